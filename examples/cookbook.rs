@@ -0,0 +1,86 @@
+use std::{collections::BTreeMap, process::ExitCode};
+
+use pareg::{DupPolicy, FromArg, Pareg, Result};
+
+#[derive(FromArg, Debug)]
+enum Cmd {
+    Build,
+    Clean,
+}
+
+#[derive(Debug, Default)]
+struct Args {
+    cmd: Option<Cmd>,
+    verbose: bool,
+    quiet: bool,
+    jobs: usize,
+    defines: BTreeMap<String, String>,
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    match start() {
+        Ok(args) => {
+            println!("{args:#?}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn start() -> Result<Args> {
+    let mut args = Pareg::args();
+    let mut res = Args {
+        jobs: 1,
+        ..Default::default()
+    };
+
+    while let Some(arg) = args.next() {
+        match arg {
+            // `-D NAME=VALUE`, each name may be defined only once.
+            "-D" | "--define" => args.next_key_val_insert(
+                '=',
+                &mut res.defines,
+                DupPolicy::Error,
+            )?,
+            // `-j 4` or `--jobs=4`
+            "-j" => res.jobs = args.next_arg()?,
+            a if a.starts_with("--jobs") => {
+                res.jobs = args.cur_val_or_next('=')?
+            }
+            // Everything after `--` is file.
+            "--" => {
+                res.files.extend(args.take_rest());
+                break;
+            }
+            a if a.starts_with("--") => {
+                return Err(args.err_unknown_argument())
+            }
+            // Cluster of short flags (e.g. `-vq`).
+            a if a.starts_with('-') => {
+                for (i, c) in a.char_indices().skip(1) {
+                    match c {
+                        'v' => res.verbose = true,
+                        'q' => res.quiet = true,
+                        _ => {
+                            return Err(args
+                                .err_invalid_span(i..i + c.len_utf8())
+                                .inline_msg("Unknown flag.")
+                                .hint("Valid flags are `v` and `q`."))
+                        }
+                    }
+                }
+            }
+            _ if res.cmd.is_none() => res.cmd = Some(args.cur_arg()?),
+            _ => res.files.push(arg.to_owned()),
+        }
+    }
+
+    if res.cmd.is_none() {
+        return Err(args.err_no_more_arguments());
+    }
+    Ok(res)
+}
@@ -0,0 +1,48 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use pareg::{ArgError, Deferred, Pareg, ResolvedArgs, Result};
+
+fn main() -> ExitCode {
+    match start() {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn start() -> Result<()> {
+    let mut args = Pareg::args();
+
+    // `--output` defaults to the name with the extension `.out`.
+    let mut name = Deferred::new(|_| Ok("input.txt".to_string()));
+    let mut output = Deferred::new(|r: &ResolvedArgs| {
+        let name = r.get::<String>("name").unwrap();
+        let path = PathBuf::from(name).with_extension("out");
+        if path.file_stem().is_none() {
+            // The error points to the value of `--name`.
+            return ArgError::parse_msg("Cannot derive output.", name.clone())
+                .main_msg("The name has no file stem.")
+                .err();
+        }
+        Ok(path)
+    });
+
+    while let Some(arg) = args.next() {
+        match arg {
+            "-n" | "--name" => args.set_deferred(&mut name)?,
+            "-o" | "--output" => args.set_deferred(&mut output)?,
+            _ => args.err_unknown_argument().err()?,
+        }
+    }
+
+    let mut values = ResolvedArgs::new();
+    values.add("name", name).add("output", output);
+    args.resolve_all(&mut values)?;
+
+    let name: String = values.take("name").unwrap();
+    let output: PathBuf = values.take("output").unwrap();
+    println!("Reading {name} and writing {}.", output.display());
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use std::process::ExitCode;
+use std::{io, process::ExitCode};
 
-use pareg::{starts_any, FromArg, Pareg, Result};
+use pareg::{
+    completion::{generate, CompletionSpec, Shell, ValueHint},
+    FromArg, Pareg, Result,
+};
 
 // You can define enums, and have them automaticaly derive FromArg where each
 // enum variant will be parsed from case insensitive strings of the same name
@@ -18,29 +21,43 @@ struct Args {
     name: String,
     count: usize,
     colors: ColorMode,
+    // hidden option that prints shell completion script
+    completions: Option<Shell>,
 }
 
 impl Args {
-    // create function that takes the arguments as ArgIterator
+    // create function that takes the arguments as Pareg
     pub fn parse(mut args: Pareg) -> Result<Self> {
         // initialize with default values
         let mut res = Args {
             name: "pareg".to_string(),
             count: 1,
             colors: ColorMode::Auto,
+            completions: None,
         };
 
         while let Some(arg) = args.next() {
             match arg {
                 // when there is the argument `count`, parse the next value
                 "-c" | "--count" => res.count = args.next_arg()?,
-                // if the argument starts with either `--color` or
-                // `--colour`, parse its value.
-                a if starts_any!(a, "--color=", "--colour=") => {
-                    res.colors = args.cur_val('=')?;
+                a if a.starts_with('-') => {
+                    // if the argument is either `--color` or `--colour`,
+                    // parse its value. The value may be given as
+                    // `--color=always` or `--color always`.
+                    if let Some(c) =
+                        args.opt_value_arg(&["--color", "--colour"])?
+                    {
+                        res.colors = c;
+                    } else if let Some(s) =
+                        args.opt_value_arg(&["--completions"])?
+                    {
+                        res.completions = Some(s);
+                    } else {
+                        // it seems that this is flag, but it is not
+                        // recognized
+                        Err(args.err_unknown_argument())?
+                    }
                 }
-                // it seems that this is flag, but it is not recognized
-                a if a.starts_with('-') => Err(args.err_unknown_argument())?,
                 // if the argument is unknown, just set it as name
                 _ => res.name = arg.to_string(),
             }
@@ -48,6 +65,18 @@ impl Args {
 
         Ok(res)
     }
+
+    // describe the arguments for shell completion
+    fn completion_spec() -> CompletionSpec {
+        CompletionSpec::new("doc")
+            .option(&["-c", "--count"], "Number of greetings.", ValueHint::Any)
+            .option(
+                &["--color", "--colour"],
+                "When to use color.",
+                // the values are taken from the derived `FromArg`
+                ValueHint::choice_of::<ColorMode>(),
+            )
+    }
 }
 
 // Now you can call your parse method:
@@ -55,6 +84,12 @@ fn start() -> Result<()> {
     // just pass in any iterator of string reference that has lifetime
     let args = Args::parse(Pareg::args())?;
 
+    // e.g. `doc --completions=bash > doc.bash && source doc.bash`
+    if let Some(shell) = args.completions {
+        generate(shell, &Args::completion_spec(), &mut io::stdout())?;
+        return Ok(());
+    }
+
     // Now you can use your arguments:
     for _ in 0..args.count {
         println!("Hello {}!", args.name);
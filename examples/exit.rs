@@ -0,0 +1,35 @@
+use pareg::{Exit, MainResult, Pareg, SpecialArgs};
+
+// Exits with the code 2 on invalid arguments (e.g. `-n x`) and with the code
+// 3 if the count is zero.
+fn main() -> MainResult {
+    start().into()
+}
+
+fn start() -> Result<(), Exit> {
+    let mut args = Pareg::args();
+    args.on_special(SpecialArgs {
+        help: Some(Box::new(|| println!("Usage: exit [-n <COUNT>]"))),
+        version: None,
+    });
+
+    let mut count = 1_u32;
+    while let Some(arg) = args.next() {
+        match arg {
+            "-n" | "--count" => count = args.next_arg()?,
+            _ => args.err_unknown_argument().err()?,
+        }
+    }
+
+    if args.intercepted().is_some() {
+        return Ok(());
+    }
+    if count == 0 {
+        return Err(Exit::other(3, "Nothing to do."));
+    }
+
+    for i in 1..=count {
+        println!("{i}");
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::process::ExitCode;
+
+use pareg::{Pareg, Result, SpecialArgs};
+
+fn main() -> ExitCode {
+    match start() {
+        Ok(_) => ExitCode::SUCCESS,
+        // `--help` or `--version` in value position (e.g. `-o --help`).
+        Err(e) if e.is_special() => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn start() -> Result<()> {
+    let mut args = Pareg::args();
+    args.on_special(SpecialArgs {
+        help: Some(Box::new(|| {
+            println!("Usage: special [-o <OUTPUT>] [-n <COUNT>]");
+        })),
+        version: Some(env!("CARGO_PKG_VERSION").into()),
+    });
+
+    let mut output = String::from("out.txt");
+    let mut count = 1_u32;
+
+    // `-h`, `--help`, `-V` and `--version` don't have to be handled here.
+    while let Some(arg) = args.next() {
+        match arg {
+            "-o" | "--output" => output = args.next_arg()?,
+            "-n" | "--count" => count = args.next_arg()?,
+            _ => Err(args.err_unknown_argument())?,
+        }
+    }
+
+    if args.intercepted().is_some() {
+        return Ok(());
+    }
+
+    println!("Writing {count} lines to {output}.");
+    Ok(())
+}
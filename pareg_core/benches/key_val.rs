@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use pareg_core::{key_mval_arg, Pareg};
+
+fn args() -> Pareg {
+    Pareg::new(
+        (0..100_000)
+            .map(|i| match i % 3 {
+                0 => format!("--key{i}={i}"),
+                1 => format!("value{i}"),
+                _ => format!("ключ{i}=значение"),
+            })
+            .collect(),
+    )
+}
+
+fn scan_100k(c: &mut Criterion) {
+    let mut args = args();
+    let mut g = c.benchmark_group("scan_100k");
+
+    g.bench_function("scan_key_val_spans", |b| {
+        b.iter(|| {
+            args.scan_key_val_spans(black_box('='))
+                .filter_map(|(_, _, v)| v)
+                .map(|v| v.len())
+                .sum::<usize>()
+        })
+    });
+
+    g.bench_function("key_mval_arg", |b| {
+        b.iter(|| {
+            args.reset();
+            let mut sum = 0;
+            while let Some(arg) = args.next() {
+                let (_, v) =
+                    key_mval_arg::<&str, &str>(arg, black_box('=')).unwrap();
+                sum += v.map_or(0, str::len);
+            }
+            sum
+        })
+    });
+
+    g.bench_function("cur_key_mval", |b| {
+        b.iter(|| {
+            args.reset();
+            let mut sum = 0;
+            while args.next().is_some() {
+                let (_, v) =
+                    args.cur_key_mval::<&str, &str>(black_box('=')).unwrap();
+                sum += v.map_or(0, str::len);
+            }
+            sum
+        })
+    });
+
+    g.finish();
+}
+
+criterion_group!(benches, scan_100k);
+criterion_main!(benches);
@@ -0,0 +1,205 @@
+use std::{borrow::Cow, net::Ipv4Addr};
+
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, Criterion,
+};
+use pareg_core::{key_val_arg, parsef, Pareg, ParseFArg, Reader};
+
+const N: usize = 10_000;
+
+fn pareg(f: impl Fn(usize) -> String) -> Pareg {
+    Pareg::new((0..N).map(f).collect())
+}
+
+fn next_arg(c: &mut Criterion) {
+    let mut args = pareg(|i| i.to_string());
+    let mut g = c.benchmark_group("next_arg_10k");
+
+    g.bench_function("next_arg", |b| {
+        b.iter(|| {
+            args.reset();
+            let mut sum = 0;
+            while args.peek().is_some() {
+                sum += args.next_arg::<u32>().unwrap();
+            }
+            sum
+        })
+    });
+
+    // Error that passes through `map_err` twice.
+    g.bench_function("map_err", |b| {
+        args.jump(N / 2);
+        b.iter(|| black_box(&args).map_err(args.cur_arg::<u8>()).unwrap_err())
+    });
+
+    g.finish();
+}
+
+fn error_args(c: &mut Criterion) {
+    let owned: Vec<_> = (0..N).map(|i| format!("--flag{}", i % 10)).collect();
+    let cow: Vec<Cow<'static, str>> = (0..N)
+        .map(|i| Cow::Borrowed(["--flag", "--opt"][i % 2]))
+        .collect();
+    let mut g = c.benchmark_group("error_args_10k");
+
+    // Every argument is copied into the error.
+    g.bench_function("owned", |b| {
+        let mut args = Pareg::new(owned.clone());
+        args.jump(N / 2);
+        b.iter(|| black_box(&args).cur_arg::<u8>().unwrap_err())
+    });
+
+    // Borrowed arguments are not copied into the error.
+    g.bench_function("cow", |b| {
+        let mut args = Pareg::from_cow(&cow);
+        args.jump(N / 2);
+        b.iter(|| black_box(&args).cur_arg::<u8>().unwrap_err())
+    });
+
+    g.finish();
+}
+
+fn probe(c: &mut Criterion) {
+    // Half of the arguments are numbers and half are names.
+    let mut args = pareg(|i| {
+        if i % 2 == 0 {
+            i.to_string()
+        } else {
+            format!("name{i}")
+        }
+    });
+    let mut g = c.benchmark_group("probe_10k");
+    // Creating the errors is very slow.
+    g.sample_size(10);
+
+    // Failures create errors with all the arguments.
+    g.bench_function("cur_arg", |b| {
+        b.iter(|| {
+            args.reset();
+            let mut sum = 0;
+            while args.next().is_some() {
+                match args.cur_arg::<u32>() {
+                    Ok(n) => sum += n as usize,
+                    Err(_) => sum += args.cur_arg::<&str>().unwrap().len(),
+                }
+            }
+            sum
+        })
+    });
+
+    g.bench_function("cur_try_arg", |b| {
+        b.iter(|| {
+            args.reset();
+            let mut sum = 0;
+            while args.next().is_some() {
+                match args.cur_try_arg::<u32>() {
+                    Some(n) => sum += n as usize,
+                    None => sum += args.cur_arg::<&str>().unwrap().len(),
+                }
+            }
+            sum
+        })
+    });
+
+    g.finish();
+}
+
+fn take(c: &mut Criterion) {
+    // Long paths, so that the copies are visible.
+    let f = |i| format!("/home/user/project/src/module{i}/file{i}.rs");
+    let mut g = c.benchmark_group("take_10k");
+
+    // Each argument is copied.
+    g.bench_function("next_arg_string", |b| {
+        b.iter_batched(
+            || pareg(f),
+            |mut args| {
+                let mut files = Vec::with_capacity(N);
+                while args.peek().is_some() {
+                    files.push(args.next_arg::<String>().unwrap());
+                }
+                (files, args)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    // The arguments are moved out without allocation.
+    g.bench_function("take_next", |b| {
+        b.iter_batched(
+            || pareg(f),
+            |mut args| {
+                let mut files = Vec::with_capacity(N);
+                while let Some(a) = args.take_next() {
+                    files.push(a);
+                }
+                (files, args)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    g.bench_function("take_remaining", |b| {
+        b.iter_batched(
+            || pareg(f),
+            |mut args| (args.take_remaining(), args),
+            BatchSize::LargeInput,
+        )
+    });
+
+    g.finish();
+}
+
+fn key_val(c: &mut Criterion) {
+    let args = pareg(|i| format!("-Dkey{i}={i}"));
+    c.bench_function("key_val_arg_10k", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for a in args.all_args() {
+                let (_, v) =
+                    key_val_arg::<&str, u32>(&a[2..], black_box('=')).unwrap();
+                sum += v;
+            }
+            sum
+        })
+    });
+}
+
+fn parsef_ipv4(c: &mut Criterion) {
+    let args = pareg(|i| Ipv4Addr::from(i as u32 * 7919).to_string());
+    c.bench_function("parsef_ipv4_10k", |b| {
+        b.iter(|| {
+            let mut sum = 0;
+            for a in args.all_args() {
+                let mut ip = [0_u8; 4];
+                let [x, y, z, w] = &mut ip;
+                parsef(
+                    &mut Reader::from(a.as_str()),
+                    [
+                        ParseFArg::Arg(x),
+                        ParseFArg::Str(".".into()),
+                        ParseFArg::Arg(y),
+                        ParseFArg::Str(".".into()),
+                        ParseFArg::Arg(z),
+                        ParseFArg::Str(".".into()),
+                        ParseFArg::Arg(w),
+                    ],
+                )
+                .unwrap();
+                sum += u32::from_be_bytes(ip);
+            }
+            sum
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    next_arg,
+    error_args,
+    probe,
+    take,
+    key_val,
+    parsef_ipv4
+);
+criterion_main!(benches);
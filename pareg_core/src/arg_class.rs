@@ -0,0 +1,71 @@
+/// Classification of command line argument by how it looks. See
+/// [`arg_class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgClass {
+    /// Long option (e.g. `--help`, `--count=5`).
+    Flag,
+    /// One or more short options (e.g. `-v`, `-abc`, `-o=5`).
+    ShortCluster,
+    /// Exactly `--`. Usually terminates options.
+    DoubleDash,
+    /// Exactly `-`. Usually means stdin or stdout.
+    SingleDash,
+    /// Anything else (including empty argument).
+    Positional,
+}
+
+impl ArgClass {
+    /// Checks whether the argument looks like option ([`ArgClass::Flag`] or
+    /// [`ArgClass::ShortCluster`]).
+    pub fn is_option(&self) -> bool {
+        matches!(self, Self::Flag | Self::ShortCluster)
+    }
+
+    /// Checks whether the argument may be value of option
+    /// ([`ArgClass::Positional`] or [`ArgClass::SingleDash`]).
+    pub fn is_value(&self) -> bool {
+        matches!(self, Self::Positional | Self::SingleDash)
+    }
+}
+
+/// Classifies the argument by how it looks. This is the policy that pareg
+/// uses everywhere it needs to know whether argument looks like option.
+///
+/// | argument  | class                      |
+/// |-----------|----------------------------|
+/// | `""`      | [`ArgClass::Positional`]   |
+/// | `file`    | [`ArgClass::Positional`]   |
+/// | `-`       | [`ArgClass::SingleDash`]   |
+/// | `--`      | [`ArgClass::DoubleDash`]   |
+/// | `---`     | [`ArgClass::Flag`]         |
+/// | `--=x`    | [`ArgClass::Flag`]         |
+/// | `--help`  | [`ArgClass::Flag`]         |
+/// | `-=x`     | [`ArgClass::ShortCluster`] |
+/// | `-5`      | [`ArgClass::ShortCluster`] |
+/// | `-abc`    | [`ArgClass::ShortCluster`] |
+///
+/// Argument that starts with `--` and has anything after it is long option
+/// even if the name is empty or weird. Argument that starts with single `-`
+/// followed by anything is cluster of short options. Note that this also
+/// includes negative numbers.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{arg_class, ArgClass};
+///
+/// assert_eq!(ArgClass::SingleDash, arg_class("-"));
+/// assert_eq!(ArgClass::DoubleDash, arg_class("--"));
+/// assert_eq!(ArgClass::Flag, arg_class("---"));
+/// assert_eq!(ArgClass::ShortCluster, arg_class("-=x"));
+/// assert_eq!(ArgClass::Flag, arg_class("--=x"));
+/// assert_eq!(ArgClass::Positional, arg_class("x-y"));
+/// ```
+pub fn arg_class(arg: &str) -> ArgClass {
+    match arg {
+        "-" => ArgClass::SingleDash,
+        "--" => ArgClass::DoubleDash,
+        _ if arg.starts_with("--") => ArgClass::Flag,
+        _ if arg.starts_with('-') => ArgClass::ShortCluster,
+        _ => ArgClass::Positional,
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{by_ref::ByRef, err::Result, from_arg::FromArg};
+use crate::{
+    by_ref::ByRef,
+    err::Result,
+    from_arg::{parse_hinted, FromArg},
+};
 
 /// This trait represents a string reference object that can be parsed into a
 /// type.
@@ -26,6 +30,6 @@ where
     where
         T: FromArg<'a>,
     {
-        T::from_arg(self.by_ref())
+        parse_hinted(self.by_ref())
     }
 }
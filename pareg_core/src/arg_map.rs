@@ -0,0 +1,51 @@
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+/// Determines what [`crate::Pareg::next_key_val_insert`] does if the key is
+/// already in the map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DupPolicy {
+    /// Duplicate key is [`crate::ArgError::TooManyArguments`].
+    #[default]
+    Error,
+    /// The new value replaces the old value.
+    Overwrite,
+    /// The old value is kept and the new value is ignored.
+    Keep,
+}
+
+/// Map into which values of repeated key-value options may be inserted (see
+/// [`crate::Pareg::next_key_val_insert`]). It is implemented for
+/// [`BTreeMap`] and `HashMap` (with the feature `std`).
+pub trait ArgMap<K, V> {
+    /// Checks whether the map contains the key.
+    fn contains_key(&self, key: &K) -> bool;
+
+    /// Inserts the value. Old value with the same key is replaced.
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Ord, V> ArgMap<K, V> for BTreeMap<K, V> {
+    fn contains_key(&self, key: &K) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        BTreeMap::insert(self, key, value);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V, S: BuildHasher> ArgMap<K, V> for HashMap<K, V, S> {
+    fn contains_key(&self, key: &K) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
@@ -0,0 +1,274 @@
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    cell::{Cell, OnceCell, RefCell},
+    ops::Range,
+};
+
+/// Storage of arguments that may be used by [`crate::Pareg`].
+pub trait ArgSource {
+    /// Gets the number of arguments.
+    fn len(&self) -> usize;
+
+    /// Gets the argument at the given index.
+    fn get(&self, idx: usize) -> Option<&str>;
+
+    /// Checks whether there are no arguments.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the arguments in the given range.
+    fn iter_range(&self, range: Range<usize>) -> impl Iterator<Item = &str> {
+        range.map_while(|i| self.get(i))
+    }
+
+    /// Gets the number of arguments that are available without reading any
+    /// more. This is less than [`ArgSource::len`] only for lazy sources such
+    /// as [`LazyArgs`].
+    fn loaded(&self) -> usize {
+        self.len()
+    }
+
+    /// Copies all the loaded arguments. This is used to give context to
+    /// errors.
+    fn to_args(&self) -> Vec<Cow<'static, str>> {
+        self.iter_range(0..self.loaded())
+            .map(|a| a.to_string().into())
+            .collect()
+    }
+}
+
+impl<S: AsRef<str>> ArgSource for [S] {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        <[S]>::len(self)
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> Option<&str> {
+        <[S]>::get(self, idx).map(|a| a.as_ref())
+    }
+}
+
+impl<S: AsRef<str>> ArgSource for Vec<S> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> Option<&str> {
+        self.as_slice().get(idx).map(|a| a.as_ref())
+    }
+}
+
+/// Arguments composed of several borrowed slices. The arguments are not
+/// copied, but they behave as if they were one continuous list of arguments.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{ArgSource, ChainedArgs, Pareg};
+///
+/// let defaults = ["--count", "5"];
+/// let args = ["--count", "10"];
+///
+/// let chain = ChainedArgs::new([&defaults[..], &args[..]]);
+/// assert_eq!(4, chain.len());
+/// assert_eq!(Some("10"), chain.get(3));
+///
+/// let mut args = Pareg::from(chain);
+/// let mut count = 0;
+/// while let Some(arg) = args.next() {
+///     match arg {
+///         "--count" => count = args.next_arg().unwrap(),
+///         _ => panic!("Unknown argument."),
+///     }
+/// }
+/// assert_eq!(10, count);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChainedArgs<'a, S> {
+    parts: Vec<&'a [S]>,
+    /// Index of the first argument of each part.
+    starts: Vec<usize>,
+    /// Index of the part for each argument.
+    lookup: Vec<u32>,
+}
+
+impl<'a, S: AsRef<str>> ChainedArgs<'a, S> {
+    /// Creates new chain from the given slices. The arguments are in the
+    /// same order as the slices.
+    pub fn new(parts: impl IntoIterator<Item = &'a [S]>) -> Self {
+        let parts: Vec<_> = parts.into_iter().collect();
+        let mut starts = Vec::with_capacity(parts.len());
+        let mut lookup = vec![];
+        for (i, p) in parts.iter().enumerate() {
+            starts.push(lookup.len());
+            lookup.extend((0..p.len()).map(|_| i as u32));
+        }
+
+        Self {
+            parts,
+            starts,
+            lookup,
+        }
+    }
+
+    /// Gets the slices in the chain.
+    pub fn parts(&self) -> &[&'a [S]] {
+        &self.parts
+    }
+
+    /// Iterates over all the arguments.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().flat_map(|p| p.iter().map(|a| a.as_ref()))
+    }
+}
+
+impl<S: AsRef<str>> ArgSource for ChainedArgs<'_, S> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.lookup.len()
+    }
+
+    #[inline]
+    fn get(&self, idx: usize) -> Option<&str> {
+        let part = *self.lookup.get(idx)? as usize;
+        Some(self.parts[part][idx - self.starts[part]].as_ref())
+    }
+}
+
+/// Borrowed arguments that are mostly `'static`. Unlike other sources, the
+/// arguments are not copied into errors if they are [`Cow::Borrowed`].
+///
+/// # Examples
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use pareg_core::{ArgSource, CowArgs};
+///
+/// let args: Vec<Cow<'static, str>> =
+///     vec!["--count".into(), "5".to_string().into()];
+/// let args = CowArgs(&args);
+/// assert_eq!(Some("5"), args.get(1));
+/// assert!(matches!(args.to_args()[0], Cow::Borrowed("--count")));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CowArgs<'a>(pub &'a [Cow<'static, str>]);
+
+impl ArgSource for CowArgs<'_> {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> Option<&str> {
+        self.0.get(idx).map(|a| a.as_ref())
+    }
+
+    fn to_args(&self) -> Vec<Cow<'static, str>> {
+        self.0.to_vec()
+    }
+}
+
+/// Number of arguments in the first chunk of [`LazyArgs`].
+const FIRST_CHUNK: usize = 8;
+/// Number of chunks in [`LazyArgs`]. This is enough for any index.
+const CHUNKS: usize = usize::BITS as usize - 2;
+
+/// Arguments that are read from iterator only when they are needed. Already
+/// read arguments are kept, so it is possible to jump back to them and they
+/// are shown in errors. Errors show only the arguments that were already read
+/// followed by `...`.
+///
+/// Note that [`ArgSource::len`] reads all the arguments.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{ArgSource, LazyArgs};
+///
+/// let args = LazyArgs::new((1..).map(|i| i.to_string()));
+/// assert_eq!(Some("10"), args.get(9));
+/// assert_eq!(10, args.loaded());
+/// ```
+pub struct LazyArgs<I> {
+    /// The iterator is [`None`] if it is exhausted.
+    iter: RefCell<Option<I>>,
+    /// Chunk `i` has capacity `FIRST_CHUNK << i`. Chunks are never moved, so
+    /// the arguments may be borrowed while new arguments are read.
+    chunks: [OnceCell<Box<[OnceCell<String>]>>; CHUNKS],
+    loaded: Cell<usize>,
+}
+
+impl<I: Iterator<Item = String>> LazyArgs<I> {
+    /// Creates lazy arguments from the iterator. Nothing is read yet.
+    pub fn new(args: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            iter: RefCell::new(Some(args.into_iter())),
+            chunks: core::array::from_fn(|_| OnceCell::new()),
+            loaded: Cell::new(0),
+        }
+    }
+
+    /// Gets the slot for the argument at the given index.
+    fn slot(&self, idx: usize) -> &OnceCell<String> {
+        let chunk = (idx / FIRST_CHUNK + 1).ilog2() as usize;
+        let start = FIRST_CHUNK * ((1 << chunk) - 1);
+        let chunk = self.chunks[chunk].get_or_init(|| {
+            (0..FIRST_CHUNK << chunk).map(|_| OnceCell::new()).collect()
+        });
+        &chunk[idx - start]
+    }
+
+    /// Reads arguments until there are more than `idx` of them or the
+    /// iterator is exhausted.
+    fn load(&self, idx: usize) {
+        let mut iter = self.iter.borrow_mut();
+        while self.loaded.get() <= idx {
+            let Some(arg) = iter.as_mut().and_then(|i| i.next()) else {
+                *iter = None;
+                return;
+            };
+            _ = self.slot(self.loaded.get()).set(arg);
+            self.loaded.set(self.loaded.get() + 1);
+        }
+    }
+}
+
+impl<I: Iterator<Item = String>> ArgSource for LazyArgs<I> {
+    fn len(&self) -> usize {
+        self.load(usize::MAX - 1);
+        self.loaded.get()
+    }
+
+    fn get(&self, idx: usize) -> Option<&str> {
+        self.load(idx);
+        if idx < self.loaded.get() {
+            self.slot(idx).get().map(|a| a.as_str())
+        } else {
+            None
+        }
+    }
+
+    fn loaded(&self) -> usize {
+        self.loaded.get()
+    }
+
+    fn to_args(&self) -> Vec<Cow<'static, str>> {
+        let mut res: Vec<_> = (0..self.loaded.get())
+            .map_while(|i| self.slot(i).get())
+            .map(|a| a.clone().into())
+            .collect();
+        if self.iter.borrow().is_some() {
+            res.push("...".into());
+        }
+        res
+    }
+}
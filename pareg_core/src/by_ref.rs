@@ -1,4 +1,4 @@
-use std::{borrow::Cow, rc::Rc, sync::Arc};
+use alloc::{borrow::Cow, rc::Rc, string::String, sync::Arc};
 
 use crate::impl_all::impl_all;
 
@@ -0,0 +1,287 @@
+use alloc::{format, string::ToString, vec::Vec};
+use core::{
+    fmt::{Display, Formatter, Write},
+    ops::Range,
+    str::FromStr,
+};
+
+use crate::{ArgError, FromArgStr, Result};
+
+/// Bytes written in hexadecimal (e.g. `0xdeadbeef` or `DEADBEEF`). The
+/// prefix `0x` is optional and the digits are case insensitive. There must be
+/// even number of digits.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{FromArg, HexBytes};
+///
+/// let bytes = HexBytes::from_arg("0xdeadBEEF").unwrap();
+/// assert_eq!(vec![0xde, 0xad, 0xbe, 0xef], bytes.0);
+/// assert_eq!("deadbeef", bytes.to_string());
+/// assert!(HexBytes::from_arg("abc").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexBytes(pub Vec<u8>);
+
+/// Bytes encoded in base64 (e.g. `3q2+7w==`). The prefix `base64:` is
+/// optional and the padding with `=` may be omitted. If `URL_SAFE` is `true`,
+/// the URL safe alphabet (`-` and `_` instead of `+` and `/`) is used (see
+/// [`B64UrlBytes`]).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{B64Bytes, FromArg};
+///
+/// let bytes: B64Bytes = B64Bytes::from_arg("base64:3q2+7w==").unwrap();
+/// assert_eq!(vec![0xde, 0xad, 0xbe, 0xef], bytes.0);
+/// assert_eq!(bytes, B64Bytes::from_arg("3q2+7w").unwrap());
+/// assert_eq!("3q2+7w==", bytes.to_string());
+/// assert!(<B64Bytes>::from_arg("3q2-7w").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct B64Bytes<const URL_SAFE: bool = false>(pub Vec<u8>);
+
+/// Bytes encoded in base64 with the URL safe alphabet (e.g. `3q2-7w`).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{B64UrlBytes, FromArg};
+///
+/// let bytes = B64UrlBytes::from_arg("3q2-7w").unwrap();
+/// assert_eq!(vec![0xde, 0xad, 0xbe, 0xef], bytes.0);
+/// assert!(B64UrlBytes::from_arg("3q2+7w").is_err());
+/// ```
+pub type B64UrlBytes = B64Bytes<true>;
+
+const B64_STD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const B64_URL: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl FromStr for HexBytes {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let start = if s.starts_with("0x") || s.starts_with("0X") {
+            2
+        } else {
+            0
+        };
+        let digits = &s[start..];
+
+        let mut res = Vec::with_capacity(digits.len() / 2);
+        let mut high = None;
+        for (i, c) in digits.char_indices() {
+            let Some(d) = c.to_digit(16) else {
+                let pos = start + i;
+                return Err(invalid_char(s, c, pos..pos + c.len_utf8())
+                    .hint("Hex digits are `0`-`9` and `a`-`f`."));
+            };
+            match high.take() {
+                Some(h) => res.push((h << 4 | d) as u8),
+                None => high = Some(d),
+            }
+        }
+
+        if high.is_some() {
+            return ArgError::parse_msg(
+                "Odd number of hex digits.",
+                s.to_string(),
+            )
+            .spanned(s.len() - 1..s.len())
+            .inline_msg("Missing digit after this.")
+            .hint("Each byte is written as two hex digits (e.g. `0a`).")
+            .err();
+        }
+        Ok(Self(res))
+    }
+}
+
+impl FromArgStr for HexBytes {}
+
+impl Display for HexBytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+impl<const URL_SAFE: bool> B64Bytes<URL_SAFE> {
+    const ALPHABET: &[u8; 64] = if URL_SAFE { B64_URL } else { B64_STD };
+}
+
+impl<const URL_SAFE: bool> FromStr for B64Bytes<URL_SAFE> {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let start = if s.starts_with("base64:") { 7 } else { 0 };
+        let data = s[start..].trim_end_matches('=');
+        let padding = s.len() - start - data.len();
+
+        let mut res = Vec::with_capacity(data.len() * 3 / 4);
+        let mut bits = 0_u32;
+        let mut cnt = 0;
+        for (i, c) in data.char_indices() {
+            let Some(d) = Self::ALPHABET.iter().position(|a| *a as char == c)
+            else {
+                let pos = start + i;
+                let hint = if URL_SAFE {
+                    "Base64 digits are `A`-`Z`, `a`-`z`, `0`-`9`, `-` and \
+                    `_`."
+                } else {
+                    "Base64 digits are `A`-`Z`, `a`-`z`, `0`-`9`, `+` and \
+                    `/`."
+                };
+                return Err(
+                    invalid_char(s, c, pos..pos + c.len_utf8()).hint(hint)
+                );
+            };
+            bits = bits << 6 | d as u32;
+            cnt += 6;
+            if cnt >= 8 {
+                cnt -= 8;
+                res.push((bits >> cnt) as u8);
+                bits &= (1 << cnt) - 1;
+            }
+        }
+
+        let rem = data.len() % 4;
+        if rem == 1 || padding > 2 || (padding != 0 && rem + padding != 4) {
+            return ArgError::parse_msg(
+                "Invalid length of base64.",
+                s.to_string(),
+            )
+            .spanned(start..s.len())
+            .hint(
+                "The last group of base64 digits must have 2, 3 or 4 \
+                digits. The padding with `=` may be omitted.",
+            )
+            .err();
+        }
+        // The unused bits of the last digit must be zero, otherwise more
+        // inputs would decode to the same bytes.
+        if bits != 0 {
+            let end = start + data.len();
+            return ArgError::parse_msg(
+                "Invalid last digit of base64.",
+                s.to_string(),
+            )
+            .spanned(end - 1..end)
+            .hint(format!(
+                "The last {cnt} bits of the last base64 digit must be zero."
+            ))
+            .err();
+        }
+        Ok(Self(res))
+    }
+}
+
+impl<const URL_SAFE: bool> FromArgStr for B64Bytes<URL_SAFE> {}
+
+impl<const URL_SAFE: bool> Display for B64Bytes<URL_SAFE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        for chunk in self.0.chunks(3) {
+            let mut bits = 0_u32;
+            for (i, b) in chunk.iter().enumerate() {
+                bits |= (*b as u32) << (16 - 8 * i);
+            }
+            for i in 0..4 {
+                let c = if i <= chunk.len() {
+                    Self::ALPHABET[(bits >> (18 - 6 * i) & 63) as usize]
+                } else {
+                    b'='
+                };
+                f.write_char(c as char)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Creates error for invalid character `c` at `span` in `arg`.
+fn invalid_char(arg: &str, c: char, span: Range<usize>) -> ArgError {
+    ArgError::parse_msg(format!("Invalid digit `{c}`."), arg.to_string())
+        .spanned(span)
+        .inline_msg("Invalid digit.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromArg;
+
+    fn span<T: for<'a> FromArg<'a>>(s: &str) -> Range<usize> {
+        match T::from_arg(s) {
+            Err(e) => e.span().unwrap(),
+            Ok(_) => panic!("{s} is valid."),
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        for len in 0..40 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 37 + 5) as u8).collect();
+            let hex = HexBytes(data.clone()).to_string();
+            assert_eq!(HexBytes::from_arg(&hex).unwrap().0, data);
+            let upper = "0X".to_string() + &hex.to_uppercase();
+            assert_eq!(HexBytes::from_arg(&upper).unwrap().0, data);
+        }
+        assert_eq!(HexBytes::from_arg("0x").unwrap().0, []);
+    }
+
+    #[test]
+    fn hex_errors() {
+        assert_eq!(span::<HexBytes>("0xdeaxbeef"), 5..6);
+        assert_eq!(span::<HexBytes>("dea beef"), 3..4);
+        assert_eq!(span::<HexBytes>("00ž0"), 2..4);
+        assert_eq!(span::<HexBytes>("0xabc"), 4..5);
+        assert_eq!(span::<HexBytes>("x0"), 0..1);
+    }
+
+    #[test]
+    fn b64_round_trip() {
+        for len in 0..40 {
+            let data: Vec<u8> = (0..len).map(|i| (i * 97 + 3) as u8).collect();
+            let std = B64Bytes::<false>(data.clone()).to_string();
+            let url = B64Bytes::<true>(data.clone()).to_string();
+            assert_eq!(std.len() % 4, 0);
+            assert_eq!(B64Bytes::<false>::from_arg(&std).unwrap().0, data);
+            assert_eq!(B64UrlBytes::from_arg(&url).unwrap().0, data);
+
+            let unpadded = url.trim_end_matches('=');
+            assert_eq!(B64UrlBytes::from_arg(unpadded).unwrap().0, data);
+        }
+        assert_eq!(
+            B64Bytes::<false>(b"foobar".to_vec()).to_string(),
+            "Zm9vYmFy"
+        );
+        assert_eq!(B64Bytes::<false>(b"fo".to_vec()).to_string(), "Zm8=");
+        assert_eq!(
+            B64Bytes::<false>::from_arg("base64:Zg==").unwrap().0,
+            b"f"
+        );
+        let all: Vec<u8> = (0..=255).collect();
+        let url = B64Bytes::<true>(all.clone()).to_string();
+        assert!(url.contains('-') && url.contains('_'));
+        assert_eq!(B64UrlBytes::from_arg(&url).unwrap().0, all);
+    }
+
+    #[test]
+    fn b64_errors() {
+        assert_eq!(span::<B64Bytes>("Zm9v-mFy"), 4..5);
+        assert_eq!(span::<B64Bytes>("base64:Zm9v_mFy"), 11..12);
+        assert_eq!(span::<B64UrlBytes>("Zm9v+mFy"), 4..5);
+        assert_eq!(span::<B64Bytes>("Zm=9"), 2..3);
+        assert_eq!(span::<B64Bytes>("Zm9vY"), 0..5);
+        assert_eq!(span::<B64Bytes>("Zg==="), 0..5);
+        assert_eq!(span::<B64Bytes>("Zm9=="), 0..5);
+        // Nonzero unused bits.
+        assert_eq!(B64Bytes::<false>::from_arg("QQ==").unwrap().0, b"A");
+        assert_eq!(span::<B64Bytes>("QR=="), 1..2);
+        assert_eq!(span::<B64Bytes>("base64:QR"), 8..9);
+        assert_eq!(span::<B64Bytes>("Zm9"), 2..3);
+        assert!(B64Bytes::<false>::from_arg("").unwrap().0.is_empty());
+        let err = B64Bytes::<false>::from_arg("Zm9v€").unwrap_err();
+        assert_eq!(err.span(), Some(4..7));
+        assert!(err.hint_str().unwrap().contains('/'));
+    }
+}
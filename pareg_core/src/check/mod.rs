@@ -0,0 +1,41 @@
+//! Arguments that are checked while they are parsed.
+//!
+//! - Values with units ([`Unit`] and [`Measured`]).
+//! - Path arguments that are checked in the filesystem (e.g. that the file
+//!   exists). These are available with the feature `fs-checks`.
+//!
+//! The types may be parsed as arguments ([`crate::FromArg`]) and also in
+//! [`crate::parsef`]. Failed path checks are [`crate::ArgError::InvalidValue`]
+//! with the path in the message and the reason from the filesystem in the
+//! hint.
+//!
+//! The filesystem is accessed by `stat`. Use `PathCheck::check_with` to check
+//! paths with other function (e.g. in tests).
+//!
+//! # Examples
+//! ```rust
+//! # #[cfg(feature = "fs-checks")]
+//! # {
+//! use pareg_core::{check::ExistingDir, Pareg};
+//!
+//! let dir = std::env::temp_dir().to_string_lossy().into_owned();
+//! let mut args = Pareg::new(vec!["--dir".into(), dir]);
+//! args.next();
+//! let dir = args.next_arg::<ExistingDir>().unwrap();
+//! assert!(dir.0.is_dir());
+//!
+//! let args = ["--dir", "pareg-missing-dir"];
+//! let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+//! args.next();
+//! let err = args.next_arg::<ExistingDir>().unwrap_err();
+//! assert!(err.is_invalid_value());
+//! # }
+//! ```
+
+#[cfg(feature = "fs-checks")]
+mod path;
+mod unit;
+
+#[cfg(feature = "fs-checks")]
+pub use self::path::*;
+pub use self::unit::*;
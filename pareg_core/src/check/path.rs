@@ -0,0 +1,426 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    ArgErrCtx, ArgError, FromArgStr, FromRead, MaybeQuoted, ParseResult,
+    ReadFmt, Reader, Result,
+};
+
+/// What is required from path (see [`PathCheck::check`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathCheck {
+    /// The path must exist.
+    Exists,
+    /// The path must be existing file (or other path that is not
+    /// directory).
+    File,
+    /// The path must be existing directory.
+    Dir,
+    /// The file may be created or overwritten. The path must not be
+    /// directory, existing file must not be read-only and the parent
+    /// directory of new file must exist and must not be read-only.
+    Creatable,
+}
+
+/// Information about path that is needed by the checks (see [`stat`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PathInfo {
+    /// The path is directory.
+    pub is_dir: bool,
+    /// The path is read-only. This is only heuristic based on the
+    /// permissions in the metadata.
+    pub readonly: bool,
+}
+
+/// Path that exists (see [`PathCheck::Exists`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExistingPath(pub PathBuf);
+
+/// Path to existing file (see [`PathCheck::File`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExistingFile(pub PathBuf);
+
+/// Path to existing directory (see [`PathCheck::Dir`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExistingDir(pub PathBuf);
+
+/// Path to file that may be created or overwritten (see
+/// [`PathCheck::Creatable`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreatableFile(pub PathBuf);
+
+/// Gets the information about the path from its metadata.
+pub fn stat(path: &Path) -> io::Result<PathInfo> {
+    let meta = fs::metadata(path)?;
+    Ok(PathInfo {
+        is_dir: meta.is_dir(),
+        readonly: meta.permissions().readonly(),
+    })
+}
+
+impl PathCheck {
+    /// Checks the path `arg` in the filesystem.
+    pub fn check(self, arg: &str) -> Result<PathBuf> {
+        self.check_with(arg, stat)
+    }
+
+    /// Checks the path `arg`. Information about paths is get with `stat`
+    /// instead of the filesystem.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::io;
+    ///
+    /// use pareg_core::check::{PathCheck, PathInfo};
+    ///
+    /// let stat = |p: &std::path::Path| match p.to_str() {
+    ///     Some("out") => Ok(PathInfo::default()),
+    ///     _ => Err(io::Error::new(io::ErrorKind::NotFound, "not found")),
+    /// };
+    ///
+    /// assert!(PathCheck::File.check_with("out", stat).is_ok());
+    /// assert!(PathCheck::Dir.check_with("out", stat).is_err());
+    /// let err = PathCheck::Exists.check_with("in", stat).unwrap_err();
+    /// assert_eq!(Some("Not found."), err.hint_str());
+    /// ```
+    pub fn check_with(
+        self,
+        arg: &str,
+        stat: impl Fn(&Path) -> io::Result<PathInfo>,
+    ) -> Result<PathBuf> {
+        let path = Path::new(arg);
+        let info = match (self, stat(path)) {
+            (_, Ok(info)) => info,
+            (Self::Creatable, Err(e))
+                if e.kind() == io::ErrorKind::NotFound =>
+            {
+                return check_parent(arg, &stat).map(|_| path.into());
+            }
+            (_, Err(e)) => return Err(access_err(arg, &e)),
+        };
+
+        match self {
+            Self::File | Self::Creatable if info.is_dir => Err(path_err(
+                arg,
+                "Expected file.",
+                format!("`{arg}` is directory, but file is expected."),
+            )),
+            Self::Dir if !info.is_dir => Err(path_err(
+                arg,
+                "Expected directory.",
+                format!("`{arg}` is not directory."),
+            )),
+            Self::Creatable if info.readonly => Err(path_err(
+                arg,
+                "File is read-only.",
+                format!("Cannot write to `{arg}` because it is read-only."),
+            )),
+            _ => Ok(path.into()),
+        }
+    }
+}
+
+/// Checks that new file may be created at `arg`.
+fn check_parent(
+    arg: &str,
+    stat: impl Fn(&Path) -> io::Result<PathInfo>,
+) -> Result<()> {
+    let Some(parent) = Path::new(arg).parent() else {
+        return Err(path_err(
+            arg,
+            "Expected file.",
+            format!("Cannot create file at `{arg}`."),
+        ));
+    };
+    let (parent, shown) = if parent.as_os_str().is_empty() {
+        (Path::new("."), ".".into())
+    } else {
+        (parent, parent.to_string_lossy())
+    };
+
+    let err = |msg, reason: &str| {
+        path_err(
+            arg,
+            msg,
+            format!("Cannot create `{arg}` because `{shown}` {reason}."),
+        )
+    };
+    match stat(parent) {
+        Ok(info) if !info.is_dir => {
+            Err(err("Parent is not directory.", "is not directory"))
+        }
+        Ok(info) if info.readonly => {
+            Err(err("Directory is read-only.", "is read-only"))
+        }
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(err("Directory doesn't exist.", "doesn't exist")
+                .hint(io_hint(&e)))
+        }
+        Err(e) => Err(err("Cannot access directory.", "cannot be accessed")
+            .hint(io_hint(&e))),
+    }
+}
+
+/// Creates error for path that cannot be accessed.
+fn access_err(arg: &str, e: &io::Error) -> ArgError {
+    let err = if e.kind() == io::ErrorKind::NotFound {
+        path_err(
+            arg,
+            "Path doesn't exist.",
+            format!("`{arg}` doesn't exist."),
+        )
+    } else {
+        path_err(
+            arg,
+            "Cannot access path.",
+            format!("Failed to access `{arg}`."),
+        )
+    };
+    err.hint(io_hint(e))
+}
+
+fn path_err(arg: &str, msg: &'static str, long: String) -> ArgError {
+    ArgError::InvalidValue(
+        ArgErrCtx::from_msg(msg, arg.to_string())
+            .main_msg(long)
+            .into(),
+    )
+}
+
+/// Gets the message of the IO error as sentence (e.g. `No such file or
+/// directory.`).
+fn io_hint(e: &io::Error) -> String {
+    let msg = e.to_string();
+    let msg = msg.split(" (os error").next().unwrap_or_default().trim();
+    let mut chars = msg.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let mut res: String = first.to_uppercase().chain(chars).collect();
+    if !res.ends_with('.') {
+        res.push('.');
+    }
+    res
+}
+
+macro_rules! impl_path_check {
+    ($($t:ident => $check:ident),* $(,)?) => {
+        $(
+        impl FromStr for $t {
+            type Err = ArgError;
+
+            fn from_str(s: &str) -> Result<Self> {
+                PathCheck::$check.check(s).map(Self)
+            }
+        }
+
+        impl FromArgStr for $t {}
+
+        /// Reads the path in the same way as [`MaybeQuoted`] and checks it.
+        impl FromRead for $t {
+            fn from_read(r: &mut Reader) -> ParseResult<Self> {
+                Self::from_read_fmt(r, &ReadFmt::default())
+            }
+
+            fn from_read_fmt(
+                r: &mut Reader,
+                fmt: &ReadFmt,
+            ) -> ParseResult<Self> {
+                if let Err(e) = r.peek() {
+                    return ParseResult { err: Some(e), res: None };
+                }
+                let start = r.pos().unwrap_or_default();
+                let res = r.parse::<MaybeQuoted>(fmt).and_then(|p| {
+                    PathCheck::$check
+                        .check(&p.0)
+                        .map_err(|e| r.map_err(e).span_start(start))
+                });
+                ParseResult {
+                    res: res.as_ref().ok().map(|p| Self(p.clone())),
+                    err: res.err(),
+                }
+            }
+        }
+
+        impl AsRef<Path> for $t {
+            fn as_ref(&self) -> &Path {
+                &self.0
+            }
+        }
+
+        impl From<$t> for PathBuf {
+            fn from(value: $t) -> Self {
+                value.0
+            }
+        }
+        )*
+    };
+}
+
+impl_path_check!(
+    ExistingPath => Exists,
+    ExistingFile => File,
+    ExistingDir => Dir,
+    CreatableFile => Creatable,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parsef, Pareg, ParseFArg};
+
+    /// Fake filesystem with the directories `dir` and `ro` (read-only), the
+    /// file `dir/a.txt`, read-only file `ro.txt` and path `secret` that
+    /// cannot be accessed.
+    fn fake(path: &Path) -> io::Result<PathInfo> {
+        let info = |is_dir, readonly| Ok(PathInfo { is_dir, readonly });
+        match path.to_str().unwrap() {
+            "." | "dir" => info(true, false),
+            "ro" => info(true, true),
+            "dir/a.txt" => info(false, false),
+            "ro.txt" => info(false, true),
+            "secret" => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "permission denied",
+            )),
+            _ => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No such file or directory (os error 2)",
+            )),
+        }
+    }
+
+    fn check(c: PathCheck, arg: &str) -> std::result::Result<(), String> {
+        c.check_with(arg, fake).map(|_| ()).map_err(|e| {
+            let ctx = e.ctx().unwrap();
+            assert!(e.is_invalid_value());
+            assert_eq!(ctx.error_span, 0..arg.len());
+            format!(
+                "{} {}",
+                ctx.long_message.as_deref().unwrap(),
+                ctx.hint.as_deref().unwrap_or("-")
+            )
+        })
+    }
+
+    #[test]
+    fn checks() {
+        use PathCheck::*;
+
+        assert_eq!(check(Exists, "dir"), Ok(()));
+        assert_eq!(check(Exists, "ro.txt"), Ok(()));
+        assert_eq!(
+            check(Exists, "x"),
+            Err("`x` doesn't exist. No such file or directory.".into())
+        );
+        assert_eq!(
+            check(Exists, "secret"),
+            Err("Failed to access `secret`. Permission denied.".into())
+        );
+
+        assert_eq!(check(File, "dir/a.txt"), Ok(()));
+        assert_eq!(
+            check(File, "dir"),
+            Err("`dir` is directory, but file is expected. -".into())
+        );
+        assert_eq!(check(Dir, "ro"), Ok(()));
+        assert_eq!(
+            check(Dir, "ro.txt"),
+            Err("`ro.txt` is not directory. -".into())
+        );
+
+        assert_eq!(check(Creatable, "dir/a.txt"), Ok(()));
+        assert_eq!(check(Creatable, "dir/new.txt"), Ok(()));
+        assert_eq!(check(Creatable, "new.txt"), Ok(()));
+        assert_eq!(
+            check(Creatable, "ro.txt"),
+            Err("Cannot write to `ro.txt` because it is read-only. -".into())
+        );
+        assert_eq!(
+            check(Creatable, "ro/new.txt"),
+            Err("Cannot create `ro/new.txt` because `ro` is read-only. -"
+                .into())
+        );
+        assert_eq!(
+            check(Creatable, "x/new.txt"),
+            Err("Cannot create `x/new.txt` because `x` doesn't exist. \
+                No such file or directory."
+                .into())
+        );
+        assert_eq!(
+            check(Creatable, "dir/a.txt/b"),
+            Err("Cannot create `dir/a.txt/b` because `dir/a.txt` is not \
+                directory. -"
+                .into())
+        );
+        assert_eq!(
+            check(Creatable, "dir"),
+            Err("`dir` is directory, but file is expected. -".into())
+        );
+        assert_eq!(
+            check(Creatable, ""),
+            Err("Cannot create file at ``. -".into())
+        );
+    }
+
+    #[test]
+    fn real_fs() {
+        let dir = std::env::temp_dir().join("pareg_check_real_fs");
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("a.txt");
+        fs::write(&file, "a").unwrap();
+        let new = dir.join("new.txt");
+        let (dir_s, file_s, new_s) = (
+            dir.to_str().unwrap().to_string(),
+            file.to_str().unwrap().to_string(),
+            new.to_str().unwrap().to_string(),
+        );
+
+        let mut args = Pareg::new(vec![
+            dir_s.clone(),
+            file_s.clone(),
+            new_s.clone(),
+            new_s.clone(),
+        ]);
+        assert_eq!(args.next_arg::<ExistingDir>().unwrap().0, dir);
+        assert_eq!(args.next_arg::<ExistingFile>().unwrap().0, file);
+        assert_eq!(args.next_arg::<CreatableFile>().unwrap().0, new);
+        let err = args.next_arg::<ExistingPath>().unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            (ctx.error_idx, ctx.error_span.clone()),
+            (3, 0..new_s.len())
+        );
+        assert!(ctx.long_message.as_deref().unwrap().contains(&new_s));
+
+        let mut a = ExistingDir(PathBuf::new());
+        let mut b = ExistingFile(PathBuf::new());
+        parsef(
+            &mut Reader::from(format!("{dir_s}:\"{file_s}\"").as_str()),
+            [
+                ParseFArg::FmtArg(&mut a, ":".into()),
+                ParseFArg::Str(":".into()),
+                ParseFArg::Arg(&mut b),
+            ],
+        )
+        .unwrap();
+        assert_eq!((&a.0, &b.0), (&dir, &file));
+
+        let input = format!("x={new_s}");
+        let err = parsef(
+            &mut Reader::from(input.as_str()),
+            [ParseFArg::Str("x=".into()), ParseFArg::Arg(&mut b)],
+        )
+        .unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_invalid_value());
+        assert_eq!(ctx.args, [input.as_str()]);
+        assert_eq!(ctx.error_span, 2..input.len());
+
+        _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,242 @@
+use alloc::{format, string::String};
+
+use crate::{
+    from_read::read_elem, ArgError, FromArgStr, FromRead, ParseF, ParseResult,
+    ReadFmt, Reader, Result, Tuple,
+};
+
+/// Number followed by unit that must be one of the allowed units (e.g.
+/// `10px` or `50%`). The number is parsed into the first field and the unit
+/// is stored in the second field. The unit is the letters and `%` after the
+/// number. Empty string in the allowed units means that the unit may be
+/// omitted. Note that units that start with `e` cannot follow floating
+/// point number, because the `e` is read as exponent.
+///
+/// This is used as argument of [`crate::parsef`] where the allowed units
+/// differ for each option.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{check::Unit, parsef, ParseFArg};
+///
+/// let mut width = 0_u32;
+/// let mut unit = String::new();
+/// parsef(
+///     &mut "50%".into(),
+///     [ParseFArg::Arg(&mut Unit(&mut width, &mut unit, &["px", "%"]))],
+/// )?;
+/// assert_eq!((50, "%"), (width, unit.as_str()));
+///
+/// let err = parsef(
+///     &mut "50em".into(),
+///     [ParseFArg::Arg(&mut Unit(&mut width, &mut unit, &["px", "%"]))],
+/// )
+/// .unwrap_err();
+/// assert_eq!(Some(2..4), err.span());
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+#[derive(Debug)]
+pub struct Unit<'a, T: ParseF + ?Sized>(
+    pub &'a mut T,
+    pub &'a mut String,
+    pub &'static [&'static str],
+);
+
+/// Number with any unit (e.g. `3s` or `10px`). The unit is the letters and
+/// `%` after the number and it may be empty. Use [`Unit`] to allow only some
+/// units.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{check::Measured, Pareg};
+///
+/// let args = ["--delay", "3s", "--width", "10.5"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+///
+/// args.next();
+/// let delay = args.next_arg::<Measured<u32>>()?;
+/// assert_eq!((3, "s"), (delay.value, delay.unit.as_str()));
+/// args.next();
+/// let width = args.next_arg::<Measured<f64>>()?;
+/// assert_eq!((10.5, ""), (width.value, width.unit.as_str()));
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Measured<T> {
+    /// The number.
+    pub value: T,
+    /// The unit. Empty if there was no unit.
+    pub unit: String,
+}
+
+impl<T: ParseF + ?Sized> ParseF for Unit<'_, T> {
+    fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        self.set_from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn set_from_read_fmt(
+        &mut self,
+        r: &mut Reader,
+        fmt: &ReadFmt,
+    ) -> Result<Option<ArgError>> {
+        self.0.set_from_read_fmt(r, fmt)?;
+        *self.1 = read_unit(r, Some(self.2))?;
+        Ok(None)
+    }
+}
+
+impl<T: FromRead> FromRead for Measured<T> {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        let res = (|| {
+            let value = read_elem(r)?;
+            let unit = read_unit(r, None)?;
+            Ok(Self { value, unit })
+        })();
+        match res {
+            Ok(v) => ParseResult {
+                res: Some(v),
+                err: None,
+            },
+            Err(e) => ParseResult {
+                res: None,
+                err: Some(e),
+            },
+        }
+    }
+}
+
+impl<T: FromRead> core::str::FromStr for Measured<T> {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl<T: FromRead> FromArgStr for Measured<T> {}
+
+/// Reads the unit (letters and `%`) and checks that it is one of the
+/// `allowed` units.
+fn read_unit(r: &mut Reader, allowed: Option<&[&str]>) -> Result<String> {
+    _ = r.peek();
+    let start = r.read_end();
+    let mut unit = String::new();
+    while let Some(c) = r.peek()?.filter(|c| c.is_alphabetic() || *c == '%') {
+        unit.push(c);
+        _ = r.next();
+    }
+
+    let Some(allowed) = allowed else {
+        return Ok(unit);
+    };
+    if allowed.contains(&unit.as_str()) {
+        return Ok(unit);
+    }
+    let hint = units_hint(allowed);
+    if unit.is_empty() {
+        return r
+            .err_parse("Expected unit.")
+            .spanned(start..start)
+            .main_msg("Missing unit.")
+            .hint(hint)
+            .err();
+    }
+    r.err_parse("Unknown unit.")
+        .spanned(start..r.read_end())
+        .main_msg(format!("Unknown unit `{unit}`."))
+        .hint(hint)
+        .err()
+}
+
+/// Creates hint that lists the allowed units.
+fn units_hint(allowed: &[&str]) -> String {
+    let units: alloc::vec::Vec<_> = allowed
+        .iter()
+        .filter(|u| !u.is_empty())
+        .map(|u| format!("`{u}`"))
+        .collect();
+    let mut res = match units.as_slice() {
+        [] => "The value must not have unit.".into(),
+        [u] => format!("The unit must be {u}."),
+        [u @ .., last] => {
+            format!("Valid units are {} and {last}.", u.join(", "))
+        }
+    };
+    if !units.is_empty() && allowed.contains(&"") {
+        res += " The unit may be omitted.";
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+    use crate::{parsef, Pareg, ParseFArg};
+
+    fn unit(
+        s: &str,
+        allowed: &'static [&'static str],
+    ) -> Result<(f64, String)> {
+        let mut value = 0.;
+        let mut unit = String::new();
+        parsef(
+            &mut s.into(),
+            [ParseFArg::Arg(&mut Unit(&mut value, &mut unit, allowed))],
+        )?;
+        Ok((value, unit))
+    }
+
+    #[test]
+    fn allowed_units() {
+        const LEN: &[&str] = &["px", "%"];
+        assert_eq!(unit("10px", LEN).unwrap(), (10., "px".into()));
+        assert_eq!(unit("2.5%", LEN).unwrap(), (2.5, "%".into()));
+        assert_eq!(unit("3", &["", "s"]).unwrap(), (3., "".into()));
+        assert_eq!(unit("3s", &["", "s"]).unwrap(), (3., "s".into()));
+
+        let err = unit("10", LEN).unwrap_err();
+        assert_eq!(err.message(), Some("Expected unit."));
+        assert_eq!(err.long_message(), Some("Missing unit."));
+        assert_eq!(err.span(), Some(2..2));
+        assert_eq!(err.hint_str(), Some("Valid units are `px` and `%`."));
+
+        let err = unit("10cm", LEN).unwrap_err();
+        assert_eq!(err.span(), Some(2..4));
+        assert_eq!(err.long_message(), Some("Unknown unit `cm`."));
+        let err = unit("1ms", &["", "s", "min", "h"]).unwrap_err();
+        assert_eq!(err.span(), Some(1..3));
+        assert_eq!(
+            err.hint_str(),
+            Some(
+                "Valid units are `s`, `min` and `h`. The unit may be omitted."
+            )
+        );
+        let err = unit("1x", &[""]).unwrap_err();
+        assert_eq!(err.hint_str(), Some("The value must not have unit."));
+        assert!(unit("5px!", LEN).is_err());
+        assert!(unit("px", LEN).is_err());
+    }
+
+    #[test]
+    fn measured() {
+        let m = |s: &str| s.parse::<Measured<i32>>();
+        assert_eq!(
+            m("-10px").unwrap(),
+            Measured {
+                value: -10,
+                unit: "px".to_string()
+            }
+        );
+        assert_eq!(m("7").unwrap().unit, "");
+        assert_eq!(m("50%").unwrap().unit, "%");
+        assert!(m("px").is_err());
+        assert!(m("5 px").is_err());
+
+        let mut args = Pareg::new(["--delay", "3x5"].map(Into::into).to_vec());
+        args.next();
+        let err = args.next_arg::<Measured<u32>>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 1);
+    }
+}
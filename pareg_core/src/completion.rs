@@ -0,0 +1,553 @@
+//! Generation of shell completion scripts.
+//!
+//! Describe the arguments with [`CompletionSpec`] and use [`generate`] to
+//! write the completion script for the given [`Shell`]. Values of options may
+//! be completed as files, directories or one of choices that may be taken
+//! from [`FromArg::arg_variants`].
+//!
+//! # Examples
+//! ```rust
+//! use pareg_core::completion::{generate, CompletionSpec, Shell, ValueHint};
+//!
+//! let spec = CompletionSpec::new("my-program")
+//!     .flag(&["-h", "--help"], "Show help.")
+//!     .option(&["-c", "--count"], "Number of greetings.", ValueHint::Any)
+//!     .option(
+//!         &["--color"],
+//!         "Color mode.",
+//!         ValueHint::choice(["auto", "always", "never"]),
+//!     )
+//!     .positional(ValueHint::File);
+//!
+//! let mut script = vec![];
+//! generate(Shell::Bash, &spec, &mut script).unwrap();
+//! let script = String::from_utf8(script).unwrap();
+//! assert!(script.contains("complete -F _my_program 'my-program'"));
+//! ```
+
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+use crate::{ArgErrCtx, ArgError, FromArg, Result};
+
+/// Shell for which the completion script is generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+impl FromArg<'_> for Shell {
+    fn from_arg(arg: &str) -> Result<Self> {
+        match arg.trim().to_lowercase().as_str() {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            _ => Err(ArgError::FailedToParse(
+                ArgErrCtx {
+                    hint: Self::hint(),
+                    ..ArgErrCtx::from_msg("Unknown shell.", arg.to_string())
+                        .main_msg(format!("Unknown shell `{arg}`."))
+                }
+                .into(),
+            )),
+        }
+    }
+
+    fn arg_variants() -> &'static [&'static str] {
+        &["bash", "zsh"]
+    }
+
+    fn hint() -> Option<Cow<'static, str>> {
+        Some("Valid shells are: `bash`, `zsh`.".into())
+    }
+}
+
+/// Determines how values are completed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ValueHint {
+    /// Any value. There is no completion.
+    #[default]
+    Any,
+    /// Path to file.
+    File,
+    /// Path to directory.
+    Dir,
+    /// One of the given values.
+    Choice(Vec<String>),
+}
+
+impl ValueHint {
+    /// Creates [`ValueHint::Choice`] from the given values.
+    pub fn choice(
+        values: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        Self::Choice(values.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates [`ValueHint::Choice`] from [`FromArg::arg_variants`] of `T`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::completion::ValueHint;
+    ///
+    /// assert_eq!(
+    ///     ValueHint::choice(["false", "true"]),
+    ///     ValueHint::choice_of::<bool>()
+    /// );
+    /// ```
+    pub fn choice_of<'a, T: FromArg<'a>>() -> Self {
+        Self::choice(T::arg_variants().iter().copied())
+    }
+}
+
+/// Option in [`CompletionSpec`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionOpt {
+    /// All the names of the option (e.g. `-h` and `--help`).
+    pub names: Vec<String>,
+    /// Short description of the option.
+    pub help: String,
+    /// Value of the option or [`None`] if the option is flag.
+    pub value: Option<ValueHint>,
+}
+
+/// Description of arguments of program or subcommand.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompletionSpec {
+    /// Name of the program or subcommand.
+    pub name: String,
+    /// Short description. It is used for subcommands.
+    pub help: String,
+    /// The options.
+    pub opts: Vec<CompletionOpt>,
+    /// Positional arguments. They are not completed if this is [`None`] or
+    /// if there are subcommands.
+    pub positional: Option<ValueHint>,
+    /// The subcommands.
+    pub subcommands: Vec<CompletionSpec>,
+}
+
+impl CompletionSpec {
+    /// Creates spec for program or subcommand with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the description.
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = help.into();
+        self
+    }
+
+    /// Adds option without value.
+    pub fn flag(mut self, names: &[&str], help: impl Into<String>) -> Self {
+        self.opts.push(CompletionOpt {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            help: help.into(),
+            value: None,
+        });
+        self
+    }
+
+    /// Adds option with value. The value may be given as `--opt=value` or
+    /// `--opt value`.
+    pub fn option(
+        mut self,
+        names: &[&str],
+        help: impl Into<String>,
+        value: ValueHint,
+    ) -> Self {
+        self.opts.push(CompletionOpt {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            help: help.into(),
+            value: Some(value),
+        });
+        self
+    }
+
+    /// Sets how positional arguments are completed.
+    pub fn positional(mut self, value: ValueHint) -> Self {
+        self.positional = Some(value);
+        self
+    }
+
+    /// Adds subcommand.
+    pub fn subcommand(mut self, sub: CompletionSpec) -> Self {
+        self.subcommands.push(sub);
+        self
+    }
+}
+
+/// Writes completion script for the given shell to `w`.
+///
+/// For bash, the script may be sourced. For zsh, the script should be saved
+/// as `_<name>` in directory in `$fpath`, or it may be also sourced.
+pub fn generate(
+    shell: Shell,
+    spec: &CompletionSpec,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    match shell {
+        Shell::Bash => bash(spec, w),
+        Shell::Zsh => zsh(spec, w),
+    }
+}
+
+fn bash(spec: &CompletionSpec, w: &mut impl Write) -> io::Result<()> {
+    let func = func_name(&spec.name);
+    writeln!(w, "{func}() {{")?;
+    writeln!(w, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"")?;
+    writeln!(w, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"")?;
+    // `=` is word break by default, so `--opt=val` is three words.
+    writeln!(w, "    if [[ \"$cur\" == = ]]; then")?;
+    writeln!(w, "        cur=")?;
+    writeln!(w, "    elif [[ \"$prev\" == = ]]; then")?;
+    writeln!(w, "        prev=\"${{COMP_WORDS[COMP_CWORD-2]}}\"")?;
+    writeln!(w, "    fi")?;
+
+    writeln!(w, "    local cmd={}", quote(&func))?;
+    if !spec.subcommands.is_empty() {
+        writeln!(w, "    local i")?;
+        writeln!(w, "    for ((i = 1; i < COMP_CWORD; i++)); do")?;
+        writeln!(w, "        case \"$cmd ${{COMP_WORDS[i]}}\" in")?;
+        bash_subcommands(&func, spec, w)?;
+        writeln!(w, "        esac")?;
+        writeln!(w, "    done")?;
+    }
+
+    writeln!(w, "    case \"$cmd\" in")?;
+    bash_command(&func, spec, w)?;
+    writeln!(w, "    esac")?;
+    writeln!(w, "}}")?;
+    writeln!(w, "complete -F {func} {}", quote(&spec.name))
+}
+
+fn bash_subcommands(
+    id: &str,
+    spec: &CompletionSpec,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    for sub in &spec.subcommands {
+        let sub_id = format!("{id}__{}", ident(&sub.name));
+        writeln!(
+            w,
+            "            {}) cmd={} ;;",
+            quote(&format!("{id} {}", sub.name)),
+            quote(&sub_id)
+        )?;
+        bash_subcommands(&sub_id, sub, w)?;
+    }
+    Ok(())
+}
+
+fn bash_command(
+    id: &str,
+    spec: &CompletionSpec,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(w, "        {})", quote(id))?;
+
+    let valued: Vec<_> = spec
+        .opts
+        .iter()
+        .filter_map(|o| o.value.as_ref().map(|v| (o, v)))
+        .collect();
+    if !valued.is_empty() {
+        writeln!(w, "            case \"$prev\" in")?;
+        for (opt, value) in valued {
+            let names: Vec<_> = opt.names.iter().map(|n| quote(n)).collect();
+            writeln!(w, "                {})", names.join("|"))?;
+            writeln!(
+                w,
+                "                    COMPREPLY=({})",
+                bash_value(value)
+            )?;
+            writeln!(w, "                    return")?;
+            writeln!(w, "                    ;;")?;
+        }
+        writeln!(w, "            esac")?;
+    }
+
+    let names: Vec<_> =
+        spec.opts.iter().flat_map(|o| o.names.iter()).collect();
+    let opts = words(names);
+    let subs = words(spec.subcommands.iter().map(|s| &s.name));
+    writeln!(w, "            if [[ \"$cur\" == -* ]]; then")?;
+    writeln!(
+        w,
+        "                COMPREPLY=($(compgen -W {opts} -- \"$cur\"))"
+    )?;
+    writeln!(w, "            else")?;
+    if !spec.subcommands.is_empty() {
+        writeln!(
+            w,
+            "                COMPREPLY=($(compgen -W {subs} -- \"$cur\"))"
+        )?;
+    } else if let Some(value) = &spec.positional {
+        writeln!(w, "                COMPREPLY=({})", bash_value(value))?;
+    } else {
+        writeln!(w, "                COMPREPLY=()")?;
+    }
+    writeln!(w, "            fi")?;
+    writeln!(w, "            ;;")?;
+
+    for sub in &spec.subcommands {
+        bash_command(&format!("{id}__{}", ident(&sub.name)), sub, w)?;
+    }
+    Ok(())
+}
+
+/// Gets the contents of `COMPREPLY` for the value.
+fn bash_value(value: &ValueHint) -> String {
+    match value {
+        ValueHint::Any => String::new(),
+        ValueHint::File => "$(compgen -f -- \"$cur\")".to_string(),
+        ValueHint::Dir => "$(compgen -d -- \"$cur\")".to_string(),
+        ValueHint::Choice(c) => {
+            format!("$(compgen -W {} -- \"$cur\")", words(c))
+        }
+    }
+}
+
+fn zsh(spec: &CompletionSpec, w: &mut impl Write) -> io::Result<()> {
+    let func = func_name(&spec.name);
+    writeln!(w, "#compdef {}", spec.name)?;
+    zsh_command(&func, spec, w)?;
+    writeln!(w)?;
+    writeln!(w, "if [ \"$funcstack[1]\" = {} ]; then", quote(&func))?;
+    writeln!(w, "    {func} \"$@\"")?;
+    writeln!(w, "else")?;
+    writeln!(w, "    compdef {func} {}", quote(&spec.name))?;
+    writeln!(w, "fi")
+}
+
+fn zsh_command(
+    func: &str,
+    spec: &CompletionSpec,
+    w: &mut impl Write,
+) -> io::Result<()> {
+    writeln!(w)?;
+    writeln!(w, "{func}() {{")?;
+    writeln!(w, "    local context state state_descr line")?;
+    writeln!(w, "    typeset -A opt_args")?;
+    writeln!(w, "    _arguments -s -C \\")?;
+
+    for opt in &spec.opts {
+        let excl = opt.names.join(" ");
+        for name in &opt.names {
+            let eq = if name.starts_with("--") && opt.value.is_some() {
+                "="
+            } else {
+                ""
+            };
+            let mut arg =
+                format!("({excl}){name}{eq}[{}]", zsh_escape(&opt.help));
+            if let Some(value) = &opt.value {
+                arg += &zsh_value(value);
+            }
+            writeln!(w, "        {} \\", quote(&arg))?;
+        }
+    }
+
+    if !spec.subcommands.is_empty() {
+        let subs: Vec<_> = spec
+            .subcommands
+            .iter()
+            .map(|s| {
+                format!(
+                    "{}\\:{}",
+                    zsh_escape(&s.name),
+                    zsh_escape(&s.help).replace(' ', "\\ ")
+                )
+            })
+            .collect();
+        let subs = format!("1:command:(({}))", subs.join(" "));
+        writeln!(w, "        {} \\", quote(&subs))?;
+        writeln!(w, "        '*:: :->args'")?;
+        writeln!(w, "    case $state in")?;
+        writeln!(w, "        args)")?;
+        writeln!(w, "            case $line[1] in")?;
+        for sub in &spec.subcommands {
+            writeln!(
+                w,
+                "                {}) {func}__{} ;;",
+                quote(&sub.name),
+                ident(&sub.name)
+            )?;
+        }
+        writeln!(w, "            esac")?;
+        writeln!(w, "            ;;")?;
+        writeln!(w, "    esac")?;
+    } else if let Some(value) = &spec.positional {
+        writeln!(w, "        {}", quote(&format!("*{}", zsh_value(value))))?;
+    } else {
+        writeln!(w, "        && return 0")?;
+    }
+    writeln!(w, "}}")?;
+
+    for sub in &spec.subcommands {
+        let sub_func = format!("{func}__{}", ident(&sub.name));
+        zsh_command(&sub_func, sub, w)?;
+    }
+    Ok(())
+}
+
+/// Gets the `_arguments` message and action for the value.
+fn zsh_value(value: &ValueHint) -> String {
+    match value {
+        ValueHint::Any => ":value:".to_string(),
+        ValueHint::File => ":file:_files".to_string(),
+        ValueHint::Dir => ":directory:_files -/".to_string(),
+        ValueHint::Choice(c) => {
+            let c: Vec<_> = c.iter().map(|c| zsh_escape(c)).collect();
+            format!(":value:({})", c.join(" "))
+        }
+    }
+}
+
+/// Escapes characters that have special meaning in `_arguments` specs.
+fn zsh_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '[' | ']' | ':' | '(' | ')' | '\\') {
+            res.push('\\');
+        }
+        res.push(c);
+    }
+    res
+}
+
+/// Gets name of shell function for program with the given name.
+fn func_name(name: &str) -> String {
+    format!("_{}", ident(name))
+}
+
+/// Replaces characters that may not be in shell identifiers with `_`.
+fn ident(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Joins the words with space and quotes them as single argument.
+fn words<S: AsRef<str>>(words: impl IntoIterator<Item = S>) -> String {
+    let words: Vec<_> =
+        words.into_iter().map(|w| w.as_ref().to_string()).collect();
+    quote(&words.join(" "))
+}
+
+/// Quotes the string for shell with single quotes.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> CompletionSpec {
+        CompletionSpec::new("doc")
+            .flag(&["-h", "--help"], "Show help.")
+            .option(&["-c", "--count"], "Count.", ValueHint::Any)
+            .option(
+                &["--color"],
+                "Color [mode].",
+                ValueHint::choice_of::<bool>(),
+            )
+            .positional(ValueHint::File)
+            .subcommand(CompletionSpec::new("run").help("Run it.").option(
+                &["-d"],
+                "Directory.",
+                ValueHint::Dir,
+            ))
+    }
+
+    fn gen(shell: Shell) -> String {
+        let mut res = vec![];
+        generate(shell, &spec(), &mut res).unwrap();
+        String::from_utf8(res).unwrap()
+    }
+
+    #[test]
+    fn bash_script() {
+        const SCRIPT: &str = r#"_doc() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    if [[ "$cur" == = ]]; then
+        cur=
+    elif [[ "$prev" == = ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+    local cmd='_doc'
+    local i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "$cmd ${COMP_WORDS[i]}" in
+            '_doc run') cmd='_doc__run' ;;
+        esac
+    done
+    case "$cmd" in
+        '_doc')
+            case "$prev" in
+                '-c'|'--count')
+                    COMPREPLY=()
+                    return
+                    ;;
+                '--color')
+                    COMPREPLY=($(compgen -W 'false true' -- "$cur"))
+                    return
+                    ;;
+            esac
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W '-h --help -c --count --color' -- "$cur"))
+            else
+                COMPREPLY=($(compgen -W 'run' -- "$cur"))
+            fi
+            ;;
+        '_doc__run')
+            case "$prev" in
+                '-d')
+                    COMPREPLY=($(compgen -d -- "$cur"))
+                    return
+                    ;;
+            esac
+            if [[ "$cur" == -* ]]; then
+                COMPREPLY=($(compgen -W '-d' -- "$cur"))
+            else
+                COMPREPLY=()
+            fi
+            ;;
+    esac
+}
+complete -F _doc 'doc'
+"#;
+        assert_eq!(gen(Shell::Bash), SCRIPT);
+    }
+
+    #[test]
+    fn zsh_script() {
+        let script = gen(Shell::Zsh);
+        assert!(script.starts_with("#compdef doc\n"));
+        assert!(script.contains("'(-h --help)--help[Show help.]' \\\n"));
+        assert!(script.contains("'(-c --count)--count=[Count.]:value:' \\\n"));
+        assert!(script.contains(
+            "'(--color)--color=[Color \\[mode\\].]:value:(false true)' \\\n"
+        ));
+        assert!(script.contains("'1:command:((run\\:Run\\ it.))' \\\n"));
+        assert!(script.contains("'run') _doc__run ;;\n"));
+        assert!(script.contains("_doc__run() {\n"));
+        assert!(script.contains("'(-d)-d[Directory.]:directory:_files -/'"));
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("it's"), "'it'\\''s'");
+        assert_eq!(func_name("my-prog.sh"), "_my_prog_sh");
+        assert!(Shell::from_arg("fish").is_err());
+        assert_eq!(Shell::from_arg("ZSH").unwrap(), Shell::Zsh);
+    }
+}
@@ -0,0 +1,232 @@
+use alloc::{format, string::String};
+use core::{
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use crate::{
+    from_read::read_elem, match_prefix, ArgErrCtx, ArgError, FromArgStr,
+    FromRead, ParseResult, Reader, Result, Tuple,
+};
+
+/// Date in the format `YYYY-MM-DD` (e.g. `2024-01-15`). The day is
+/// validated against the month and leap years.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{FromArg, SimpleDate};
+///
+/// let date = SimpleDate::from_arg("2024-02-29").unwrap();
+/// assert_eq!((2024, 2, 29), (date.year, date.month, date.day));
+/// assert_eq!("2024-02-29", date.to_string());
+/// assert!(SimpleDate::from_arg("2023-02-29").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimpleDate {
+    /// Year in range from 0 to 9999.
+    pub year: u16,
+    /// Month in range from 1 to 12.
+    pub month: u8,
+    /// Day of the month starting from 1.
+    pub day: u8,
+}
+
+/// Time of day in the format `HH:MM:SS` or `HH:MM` (e.g. `13:45:30`). The
+/// seconds are zero if they are not given.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{FromArg, SimpleTime};
+///
+/// let time = SimpleTime::from_arg("13:45:30").unwrap();
+/// assert_eq!((13, 45, 30), (time.hour, time.min, time.sec));
+/// assert_eq!("08:05:00", SimpleTime::from_arg("8:05").unwrap().to_string());
+/// assert!(SimpleTime::from_arg("24:00").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SimpleTime {
+    /// Hour in range from 0 to 23.
+    pub hour: u8,
+    /// Minute in range from 0 to 59.
+    pub min: u8,
+    /// Second in range from 0 to 59.
+    pub sec: u8,
+}
+
+impl FromRead for SimpleDate {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        let date = (|| {
+            let year = read_component(r, "year", 0..=9999)?;
+            match_prefix("-", r)?;
+            let month = read_component(r, "month", 1..=12)?;
+            match_prefix("-", r)?;
+            let day =
+                read_component(r, "day", 1..=days_in_month(year, month))?;
+            Ok(Self { year, month, day })
+        })();
+        match date {
+            Ok(d) => ParseResult {
+                err: None,
+                res: Some(d),
+            },
+            Err(e) => ParseResult {
+                err: Some(e),
+                res: None,
+            },
+        }
+    }
+}
+
+impl FromRead for SimpleTime {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        let time = (|| {
+            let hour = read_component(r, "hour", 0..=23)?;
+            match_prefix(":", r)?;
+            let min = read_component(r, "minute", 0..=59)?;
+            let mut sec = 0;
+            if r.peek()? == Some(':') {
+                _ = r.next();
+                sec = read_component(r, "second", 0..=59)?;
+            }
+            Ok(Self { hour, min, sec })
+        })();
+        match time {
+            Ok(t) => ParseResult {
+                err: None,
+                res: Some(t),
+            },
+            Err(e) => ParseResult {
+                err: Some(e),
+                res: None,
+            },
+        }
+    }
+}
+
+impl FromStr for SimpleDate {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for SimpleDate {}
+
+impl FromStr for SimpleTime {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for SimpleTime {}
+
+impl Display for SimpleDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Display for SimpleTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.min, self.sec)
+    }
+}
+
+/// Reads one component of date or time. Value out of `range` is
+/// [`ArgError::InvalidValue`] with span on the component.
+fn read_component<T>(
+    r: &mut Reader,
+    name: &str,
+    range: RangeInclusive<T>,
+) -> Result<T>
+where
+    T: FromRead + PartialOrd + Display,
+{
+    _ = r.peek();
+    let start = r.pos().unwrap_or_default();
+    let v = read_elem::<T>(r)?;
+    if range.contains(&v) {
+        return Ok(v);
+    }
+
+    let end = r.read_end();
+    let err = ArgError::InvalidValue(
+        ArgErrCtx::from_msg(format!("Invalid {name}."), String::new()).into(),
+    );
+    Err(r
+        .map_err(err)
+        .spanned(start..end)
+        .main_msg(format!("Invalid {name} `{v}`."))
+        .hint(format!(
+            "The {name} must be in range from {} to {}.",
+            range.start(),
+            range.end()
+        )))
+}
+
+/// Gets the number of days in the given month of the given year.
+fn days_in_month(year: u16, month: u8) -> u8 {
+    let leap = year.is_multiple_of(4)
+        && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromArg;
+
+    fn invalid<T: for<'a> FromArg<'a>>(s: &str) -> ArgErrCtx {
+        match T::from_arg(s) {
+            Err(ArgError::InvalidValue(ctx)) => *ctx,
+            Err(e) => panic!("{e:?}"),
+            Ok(_) => panic!("{s} is valid."),
+        }
+    }
+
+    #[test]
+    fn leap_years() {
+        assert!(SimpleDate::from_arg("2024-02-29").is_ok());
+        assert!(SimpleDate::from_arg("2000-02-29").is_ok());
+        let ctx = invalid::<SimpleDate>("1900-02-29");
+        assert_eq!(ctx.error_span, 8..10);
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some("The day must be in range from 1 to 28.")
+        );
+        assert_eq!(invalid::<SimpleDate>("2023-04-31").error_span, 8..10);
+        assert!(SimpleDate::from_arg("2023-12-31").is_ok());
+    }
+
+    #[test]
+    fn component_spans() {
+        let ctx = invalid::<SimpleDate>("2024-13-01");
+        assert_eq!(
+            (ctx.args[0].as_ref(), ctx.error_span),
+            ("2024-13-01", 5..7)
+        );
+        assert!(ctx
+            .hint
+            .unwrap()
+            .contains("month must be in range from 1 to 12"));
+        assert_eq!(invalid::<SimpleDate>("2024-00-01").error_span, 5..7);
+
+        assert_eq!(invalid::<SimpleTime>("24:00").error_span, 0..2);
+        assert_eq!(invalid::<SimpleTime>("13:60:00").error_span, 3..5);
+        assert_eq!(invalid::<SimpleTime>("13:45:075").error_span, 6..9);
+
+        let err = SimpleDate::from_arg("2024-1x-01").unwrap_err();
+        assert!(matches!(err, ArgError::FailedToParse(_)));
+        assert!(SimpleTime::from_arg("13:45:").is_err());
+        assert!(SimpleTime::from_arg("13:45:30 ").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::{any::Any, cell::RefCell, mem, ops::Range};
+
+use crate::{ArgError, Result, Spanned};
+
+/// Function that computes default value from the other values.
+type DefaultFn<T> = Box<dyn FnOnce(&ResolvedArgs) -> Result<T>>;
+
+/// Index of the argument with value and span of the value in the argument.
+type Location = (usize, Range<usize>);
+
+/// Value of argument whose default depends on other arguments (e.g.
+/// `--output` that defaults to `<name>.out`). The value is set with
+/// [`crate::Pareg::set_deferred`] and the default is computed by
+/// [`crate::Pareg::resolve_all`] after all the arguments are parsed.
+pub enum Deferred<T> {
+    /// Value given in the arguments together with its location.
+    Set(Spanned<T>),
+    /// Default that is computed from the values in [`ResolvedArgs`].
+    Default(DefaultFn<T>),
+}
+
+/// Values of arguments with deferred defaults (see [`Deferred`]). The
+/// defaults are computed by [`crate::Pareg::resolve_all`] in the order in
+/// which the values were added, so default may use only the values added
+/// before it.
+///
+/// If default fails and it used value that was given in the arguments, the
+/// error points to that argument.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{Deferred, Pareg, ResolvedArgs};
+///
+/// let args = ["--name", "report"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+///
+/// let mut name = Deferred::new(|_| Ok("a".to_string()));
+/// let mut output = Deferred::new(|r| {
+///     Ok(format!("{}.out", r.get::<String>("name").unwrap()))
+/// });
+/// while let Some(arg) = args.next() {
+///     match arg {
+///         "--name" => args.set_deferred(&mut name)?,
+///         "--output" => args.set_deferred(&mut output)?,
+///         _ => args.err_unknown_argument().err()?,
+///     }
+/// }
+///
+/// let mut values = ResolvedArgs::new();
+/// values.add("name", name).add("output", output);
+/// args.resolve_all(&mut values)?;
+/// assert_eq!(Some("report.out".to_string()), values.take("output"));
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+#[derive(Default)]
+pub struct ResolvedArgs {
+    values: Vec<(Cow<'static, str>, Slot)>,
+    /// Location of the first value with location that was used by the
+    /// default that is being computed.
+    used: RefCell<Option<Location>>,
+}
+
+/// State of value in [`ResolvedArgs`].
+enum Slot {
+    /// The value and its location. Computed defaults have the location of
+    /// the first value that they used.
+    Value(Box<dyn Any>, Option<Location>),
+    /// Default that was not computed yet.
+    Pending(DefaultFn<Box<dyn Any>>),
+    /// Default that is being computed or that failed.
+    Resolving,
+}
+
+impl<T> Deferred<T> {
+    /// Creates value that is not set. If it is not set by
+    /// [`crate::Pareg::set_deferred`], it is computed by `default`.
+    pub fn new(
+        default: impl FnOnce(&ResolvedArgs) -> Result<T> + 'static,
+    ) -> Self {
+        Self::Default(Box::new(default))
+    }
+
+    /// Checks whether the value was given in the arguments.
+    pub fn is_set(&self) -> bool {
+        matches!(self, Self::Set(_))
+    }
+}
+
+impl ResolvedArgs {
+    /// Creates empty set of values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds value with the given name. Its default is computed after the
+    /// defaults of the values that were added before it.
+    pub fn add<T: 'static>(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: Deferred<T>,
+    ) -> &mut Self {
+        let slot = match value {
+            Deferred::Set(s) => {
+                Slot::Value(Box::new(s.value), Some((s.idx, s.span)))
+            }
+            Deferred::Default(f) => Slot::Pending(Box::new(|r| {
+                f(r).map(|v| Box::new(v) as Box<dyn Any>)
+            })),
+        };
+        self.values.push((name.into(), slot));
+        self
+    }
+
+    /// Gets the value with the given name. Returns [`None`] if there is no
+    /// such value, if it has different type or if its default was not
+    /// computed yet.
+    ///
+    /// When used from default, errors of the default will point to the
+    /// first used value that was given in the arguments.
+    pub fn get<T: 'static>(&self, name: &str) -> Option<&T> {
+        let (value, loc) = match self.slot(name)? {
+            Slot::Value(v, loc) => (v.downcast_ref()?, loc),
+            _ => return None,
+        };
+        let mut used = self.used.borrow_mut();
+        if used.is_none() {
+            *used = loc.clone();
+        }
+        Some(value)
+    }
+
+    /// Removes the value with the given name and returns it. Returns
+    /// [`None`] and keeps the value if it has different type.
+    pub fn take<T: 'static>(&mut self, name: &str) -> Option<T> {
+        let i = self.values.iter().position(|(n, s)| {
+            n == name && matches!(s, Slot::Value(v, _) if v.is::<T>())
+        })?;
+        match self.values.remove(i).1 {
+            Slot::Value(v, _) => v.downcast().ok().map(|v| *v),
+            _ => None,
+        }
+    }
+
+    /// Computes all the defaults in order. Errors of defaults that used
+    /// value with location are mapped by `attach` (name of the value, index
+    /// of the argument and span).
+    pub(crate) fn resolve(
+        &mut self,
+        attach: impl Fn(ArgError, &str, usize, Range<usize>) -> ArgError,
+    ) -> Result<()> {
+        for i in 0..self.values.len() {
+            let f = match mem::replace(&mut self.values[i].1, Slot::Resolving)
+            {
+                Slot::Pending(f) => f,
+                s => {
+                    self.values[i].1 = s;
+                    continue;
+                }
+            };
+
+            *self.used.get_mut() = None;
+            let res = f(self);
+            let loc = self.used.get_mut().take();
+            match (res, loc) {
+                (Ok(v), loc) => self.values[i].1 = Slot::Value(v, loc),
+                (Err(e), Some((idx, span))) => {
+                    return Err(attach(e, &self.values[i].0, idx, span));
+                }
+                (Err(e), None) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn slot(&self, name: &str) -> Option<&Slot> {
+        self.values.iter().find(|(n, _)| n == name).map(|(_, s)| s)
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::{format, string::String, vec::Vec};
+
+/// Determines how environment variables are converted to arguments (see
+/// [`crate::Pareg::from_env_vars`]). The prefix is removed from the name of
+/// the variable and the rest is converted to option with the value (e.g.
+/// `APP_COLOR=never` is `--color=never`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvMapping {
+    /// Prefix of the created options.
+    pub option_prefix: &'static str,
+    /// Convert the names to lowercase.
+    pub lowercase: bool,
+    /// Character that replaces `_` in the names. If [`None`], `_` is kept.
+    pub underscore: Option<char>,
+}
+
+impl Default for EnvMapping {
+    fn default() -> Self {
+        Self {
+            option_prefix: "--",
+            lowercase: true,
+            underscore: Some('-'),
+        }
+    }
+}
+
+impl EnvMapping {
+    /// Converts the environment variable to argument. Returns [`None`] if
+    /// the name doesn't start with `prefix` or if it is just the prefix.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::EnvMapping;
+    ///
+    /// let map = EnvMapping::default();
+    /// assert_eq!(
+    ///     Some("--max-count=3".to_string()),
+    ///     map.arg("APP_", "APP_MAX_COUNT", "3")
+    /// );
+    /// assert_eq!(None, map.arg("APP_", "HOME", "/root"));
+    ///
+    /// let map = EnvMapping { option_prefix: "-", underscore: None, ..map };
+    /// assert_eq!(Some("-a_b=".to_string()), map.arg("X", "XA_B", ""));
+    /// ```
+    pub fn arg(
+        &self,
+        prefix: &str,
+        name: &str,
+        value: &str,
+    ) -> Option<String> {
+        let name = name.strip_prefix(prefix).filter(|n| !n.is_empty())?;
+        let mut name: String = name
+            .chars()
+            .map(|c| match (c, self.underscore) {
+                ('_', Some(u)) => u,
+                _ => c,
+            })
+            .collect();
+        if self.lowercase {
+            name = name.to_lowercase();
+        }
+        Some(format!("{}{name}={value}", self.option_prefix))
+    }
+
+    /// Converts the variables that start with `prefix` to arguments. The
+    /// arguments are sorted by the names of the variables and they are
+    /// returned together with the names.
+    pub(crate) fn args(
+        &self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Vec<(String, String)> {
+        let mut res: Vec<_> = vars
+            .into_iter()
+            .filter_map(|(n, v)| {
+                let arg = self.arg(prefix, &n, &v)?;
+                Some((n, arg))
+            })
+            .collect();
+        res.sort();
+        res
+    }
+}
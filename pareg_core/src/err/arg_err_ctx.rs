@@ -1,27 +1,86 @@
-use std::{borrow::Cow, collections::VecDeque, fmt::Display, ops::Range};
+#[cfg(not(feature = "minimal-errors"))]
+use alloc::collections::VecDeque;
+use alloc::{
+    borrow::Cow,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Write},
+    ops::Range,
+};
 
-use termal::{writemc, writemcln};
-
-use super::ColorMode;
+#[cfg(not(feature = "minimal-errors"))]
+use super::width::{UnicodeWidthChar, UnicodeWidthStr};
+use super::{default_anounce, default_color_mode, ColorMode, Messages};
 
 /// Information about error in command line arguments. Implements [`Display`]
-/// with user friendly error messages.
-#[derive(Debug)]
+/// with user friendly error messages. The alternate form (`{:#}`) doesn't
+/// start with `argument error:`.
+#[derive(Debug, Clone)]
 pub struct ArgErrCtx {
     /// All command line arguments.
-    pub args: Vec<String>,
+    pub args: Vec<Cow<'static, str>>,
     /// Index of the errornous argument in [`Self::args`]
     pub error_idx: usize,
+    /// Index of the first argument in [`Self::args`] that is given by the
+    /// user. Arguments before it (the program name) are dimmed and they are
+    /// not counted in the location of the error, so the first user argument
+    /// is always `arg1`.
+    pub args_start_index: usize,
+    /// Whether [`Self::program`] and the arguments before
+    /// [`Self::args_start_index`] are shown in the command line.
+    pub show_program: bool,
     /// Range within the argument that is invalid.
     pub error_span: Range<usize>,
+    /// Whether [`Self::error_span`] is already positioned within the whole
+    /// argument (e.g. by [`Self::shift_span`]) and not only within the part
+    /// of it that was parsed. [`Self::add_args`] and [`Self::part_of`] move
+    /// such span only if the position of the argument in the new argument is
+    /// unambiguous, so it is not moved again to other occurence of the same
+    /// text (e.g. `ab` in `ab=ab`).
+    pub positioned: bool,
     /// Simple error message describing the kind of the problem.
     pub message: Cow<'static, str>,
     /// More descriptive message describing the problem in detail.
     pub long_message: Option<Cow<'static, str>>,
     /// Hint about how to fix the error.
     pub hint: Option<Cow<'static, str>>,
+    /// Values that may replace [`Self::error_span`] to fix the error (e.g.
+    /// valid names of misspelled value). They are not rendered, so
+    /// [`Self::hint`] should mention them.
+    pub suggestions: Vec<String>,
     /// Determines when color should be used.
     pub color: ColorMode,
+    /// Whether the rendered error starts with `argument error:`. The
+    /// alternate form (`{:#}`) never starts with it.
+    pub anounce: bool,
+    /// Maximum width of the rendered error. If [`None`], terminal width is
+    /// used when the color mode is auto and the output is terminal.
+    /// Otherwise the width is 80.
+    pub max_width: Option<usize>,
+    /// Where the arguments in [`Self::args`] come from (e.g. `config`).
+    /// Arguments without origin (or out of range of this vector) were given
+    /// on the command line.
+    pub origins: Vec<Option<Cow<'static, str>>>,
+    /// Option whose value is the errornous argument (e.g. `--count`). It is
+    /// mentioned in the long message.
+    pub owner: Option<Cow<'static, str>>,
+    /// Index of the argument with [`Self::owner`] if the errornous value is
+    /// separate argument (e.g. `--count x`). The owner is always shown in
+    /// the command line, even if other arguments must be left out.
+    pub value_of: Option<usize>,
+    /// Name of the program. It is shown at the start of the command line if
+    /// the first argument is not already the program.
+    pub program: Option<Cow<'static, str>>,
+    /// Usage of the program (e.g. `my-program [OPTIONS] <NAME>`). It is
+    /// shown at the end of the error.
+    pub usage: Option<Cow<'static, str>>,
+    /// Texts used when rendering the error. If [`None`],
+    /// [`Messages::ENGLISH`] is used.
+    pub messages: Option<&'static Messages>,
 }
 
 impl ArgErrCtx {
@@ -36,51 +95,81 @@ impl ArgErrCtx {
     ) -> Self {
         Self {
             error_span: 0..arg.len(),
-            args: vec![arg],
+            positioned: false,
+            args: vec![arg.into()],
             error_idx: 0,
+            args_start_index: 0,
+            show_program: true,
             long_message: None,
             message: message.into(),
             hint: None,
-            color: ColorMode::default(),
+            suggestions: vec![],
+            color: default_color_mode(),
+            anounce: default_anounce(),
+            max_width: None,
+            origins: vec![],
+            owner: None,
+            value_of: None,
+            program: None,
+            usage: None,
+            messages: None,
         }
     }
 
     /// Moves the span in the error message by `cnt` and changes the
-    /// errornous argument to `new_arg`.
+    /// errornous argument to `new_arg`. The span is then positioned (see
+    /// [`Self::positioned`]).
     pub fn shift_span(mut self, cnt: usize, new_arg: String) -> Self {
-        self.error_span.start += cnt;
-        self.error_span.end += cnt;
-        self.args[self.error_idx] = new_arg;
+        self.error_span.start = self.error_span.start.saturating_add(cnt);
+        self.error_span.end = self.error_span.end.saturating_add(cnt);
+        self.positioned = true;
+        self.set_err_arg(new_arg);
         self
     }
 
     /// Sets new argument. If the original argument is substring of this,
     /// span will be adjusted.
     pub fn part_of(mut self, arg: String) -> Self {
-        if self.args[self.error_idx].len() == arg.len() {
+        let cur = self.args.get(self.error_idx).map_or("", |a| a.as_ref());
+        if cur == arg {
+            // The span is already in the argument.
+        } else if cur.len() == arg.len() {
             self.error_span = 0..arg.len();
-            self.args[self.error_idx] = arg;
-            return self;
+        } else if let Some(shift) = self.part_offset(&arg) {
+            self.error_span.start =
+                self.error_span.start.saturating_add(shift);
+            self.error_span.end = self.error_span.end.saturating_add(shift);
         }
-        if let Some(shift) = arg.find(&self.args[self.error_idx]) {
-            self.error_span.start += shift;
-            self.error_span.end += shift;
-        }
-        self.args[self.error_idx] = arg;
+        self.set_err_arg(arg);
         self
     }
 
     /// Add arguments to the error so that it may have better error message.
-    /// Mostly useful internaly in pareg.
-    pub fn add_args(mut self, args: Vec<String>, idx: usize) -> Self {
-        if self.args[self.error_idx].len() != args[idx].len() {
-            if let Some(shift) = args[idx].find(&self.args[self.error_idx]) {
-                self.error_span.start += shift;
-                self.error_span.end += shift;
+    /// The span is then positioned (see [`Self::positioned`]). Mostly useful
+    /// internaly in pareg.
+    ///
+    /// If `idx` is out of bounds of `args`, the error is not changed. If the
+    /// error has no argument (or it is empty), the span is kept as is.
+    pub fn add_args(
+        mut self,
+        args: Vec<Cow<'static, str>>,
+        idx: usize,
+    ) -> Self {
+        let Some(new) = args.get(idx) else {
+            return self;
+        };
+        let cur = self.args.get(self.error_idx).map_or("", |a| a.as_ref());
+        if cur.len() != new.len() {
+            if let Some(shift) = self.part_offset(new) {
+                self.error_span.start =
+                    self.error_span.start.saturating_add(shift);
+                self.error_span.end =
+                    self.error_span.end.saturating_add(shift);
             }
         }
         self.args = args;
         self.error_idx = idx;
+        self.positioned = true;
         self
     }
 
@@ -114,6 +203,54 @@ impl ArgErrCtx {
         self
     }
 
+    /// Hides the given range of the argument at the index `idx`. The range is
+    /// replaced with placeholder and the span is adjusted. If the span is
+    /// inside the redacted range, it will point to the placeholder. The
+    /// redacted text is also removed from the code spans (text between
+    /// backticks) in the messages that echo the argument.
+    pub fn redact(mut self, idx: usize, range: Range<usize>) -> Self {
+        let Some(arg) = self.args.get_mut(idx) else {
+            return self;
+        };
+        let orig = arg.to_string();
+        let Some(Range { start, end }) = redact_str(arg, range) else {
+            return self;
+        };
+
+        if idx == self.error_idx {
+            let shift = |p: usize| {
+                if p <= start {
+                    p
+                } else if p >= end {
+                    p - (end - start) + REDACTED.len()
+                } else {
+                    start + REDACTED.len()
+                }
+            };
+            self.error_span.start = if self.error_span.start < end {
+                self.error_span.start.min(start)
+            } else {
+                shift(self.error_span.start)
+            };
+            self.error_span.end = shift(self.error_span.end);
+        }
+
+        let hide = |s: &mut Cow<'static, str>| {
+            if let Some(h) = redact_code_spans(s, &orig, start..end) {
+                *s = h.into();
+            }
+        };
+        hide(&mut self.message);
+        if let Some(msg) = &mut self.long_message {
+            hide(msg);
+        }
+        if let Some(hint) = &mut self.hint {
+            hide(hint);
+        }
+
+        self
+    }
+
     /// Set the color mode.
     pub fn color_mode(mut self, mode: ColorMode) -> Self {
         self.color = mode;
@@ -125,54 +262,314 @@ impl ArgErrCtx {
         self.color_mode(ColorMode::Never)
     }
 
+    /// Sets whether the rendered error starts with `argument error:`.
+    pub fn anounce(mut self, anounce: bool) -> Self {
+        self.anounce = anounce;
+        self
+    }
+
+    /// Set the maximum width of the rendered error message.
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Sets the origins of the arguments. Errors in argument with origin are
+    /// annotated with it and the other arguments with origin are dimmed.
+    pub fn origins(mut self, origins: Vec<Option<Cow<'static, str>>>) -> Self {
+        self.origins = origins;
+        self
+    }
+
+    /// Sets the option whose value is the errornous argument. The long
+    /// message will say that the value of the option is invalid.
+    pub fn owner(mut self, owner: impl Into<Cow<'static, str>>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Sets the index of the argument with the owner of the errornous value.
+    /// See [`Self::value_of`].
+    pub fn value_of(mut self, idx: usize) -> Self {
+        self.value_of = Some(idx);
+        self
+    }
+
+    /// Sets the index of the first argument given by the user. See
+    /// [`Self::args_start_index`].
+    pub fn args_start_index(mut self, idx: usize) -> Self {
+        self.args_start_index = idx;
+        self
+    }
+
+    /// Sets whether the program name is shown in the command line. See
+    /// [`Self::show_program`].
+    pub fn show_program(mut self, show: bool) -> Self {
+        self.show_program = show;
+        self
+    }
+
+    /// Sets the values that may replace the errornous part of the argument
+    /// (see [`Self::suggestions`]).
+    pub fn suggest<I>(mut self, suggestions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.suggestions = suggestions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Gets all the arguments with the errornous part replaced by the
+    /// suggestion (see [`Self::suggestions`]). Returns [`None`] if there is
+    /// not exactly one suggestion.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::ArgErrCtx;
+    ///
+    /// let ctx = ArgErrCtx::from_msg("Unknown option.", "--color=alwyas".into())
+    ///     .spanned(8..14)
+    ///     .suggest(["always"]);
+    /// assert_eq!(
+    ///     Some(vec!["--color=always".to_string()]),
+    ///     ctx.replacement_args()
+    /// );
+    /// assert_eq!(None, ctx.suggest(["always", "auto"]).replacement_args());
+    /// ```
+    pub fn replacement_args(&self) -> Option<Vec<String>> {
+        let [sug] = self.suggestions.as_slice() else {
+            return None;
+        };
+        let arg = self.args.get(self.error_idx)?;
+        let pre = arg.get(..self.error_span.start.min(arg.len()))?;
+        let post = arg.get(self.error_span.end.min(arg.len())..)?;
+        let mut res: Vec<_> =
+            self.args.iter().map(|a| a.to_string()).collect();
+        res[self.error_idx] = format!("{pre}{sug}{post}");
+        Some(res)
+    }
+
+    /// Sets the name of the program shown in the command line.
+    pub fn program(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.program = Some(name.into());
+        self
+    }
+
+    /// Sets the usage of the program shown at the end of the error.
+    pub fn usage(mut self, usage: impl Into<Cow<'static, str>>) -> Self {
+        self.usage = Some(usage.into());
+        self
+    }
+
+    /// Sets the texts used when rendering the error. See [`Messages`].
+    pub fn messages(mut self, messages: &'static Messages) -> Self {
+        self.messages = Some(messages);
+        self
+    }
+
+    /// Gets the width to which the error message will be rendered. The
+    /// terminal size is used only with the feature `term-width`. With the
+    /// feature `minimal-errors`, the error is rendered to single line and the
+    /// terminal size is never queried.
+    pub fn render_width(&self) -> usize {
+        if let Some(w) = self.max_width {
+            return w.max(MIN_WIDTH);
+        }
+
+        #[cfg(all(feature = "term-width", not(feature = "minimal-errors")))]
+        if matches!(self.color, ColorMode::AutoStderr | ColorMode::AutoStdout)
+            && self.color.use_color()
+        {
+            if let Ok(size) = termal::raw::term_size() {
+                if size.char_width != 0 {
+                    return size.char_width.max(MIN_WIDTH);
+                }
+            }
+        }
+
+        DEFAULT_WIDTH
+    }
+
     /// Changes the current argument to be postfix of this whole argument.
     pub fn postfix_of(mut self, arg: String) -> Self {
-        let al = self.args[self.error_idx].len();
+        let al = self.args.get(self.error_idx).map_or(0, |a| a.len());
         match al.cmp(&arg.len()) {
-            std::cmp::Ordering::Less => self.shift_span(arg.len() - al, arg),
-            std::cmp::Ordering::Equal => self,
-            std::cmp::Ordering::Greater => {
+            core::cmp::Ordering::Less => self.shift_span(arg.len() - al, arg),
+            core::cmp::Ordering::Equal => self,
+            core::cmp::Ordering::Greater => {
                 let d = al - arg.len();
-                self.error_span.start += d;
-                self.error_span.end += d;
+                self.error_span.start =
+                    self.error_span.start.saturating_add(d);
+                self.error_span.end = self.error_span.end.saturating_add(d);
                 self
             }
         }
     }
+
+    /// Finds the position of the errornous argument in `arg`. Positioned
+    /// argument (see [`Self::positioned`]) is found only if it is in `arg`
+    /// exactly once.
+    fn part_offset(&self, arg: &str) -> Option<usize> {
+        let cur = self.args.get(self.error_idx).map_or("", |a| a.as_ref());
+        if !self.positioned {
+            return part_offset(arg, cur);
+        }
+        let pos = arg.find(cur)?;
+        arg[pos + cur.len()..].find(cur).is_none().then_some(pos)
+    }
+
+    /// Sets the errornous argument. If [`Self::error_idx`] is out of bounds,
+    /// the argument is added to the end and the index is moved to it.
+    fn set_err_arg(&mut self, arg: String) {
+        if let Some(a) = self.args.get_mut(self.error_idx) {
+            *a = arg.into();
+        } else {
+            self.error_idx = self.args.len();
+            self.args.push(arg.into());
+        }
+    }
+}
+
+/// Finds the position of `part` in `arg`. Values split from the argument
+/// (e.g. `--opt=value`) are at its end, so suffix is preferred over the first
+/// occurence (`a` in `--aa=a` is at position 5 and not 2).
+fn part_offset(arg: &str, part: &str) -> Option<usize> {
+    if !part.is_empty() && arg.ends_with(part) {
+        Some(arg.len() - part.len())
+    } else {
+        arg.find(part)
+    }
 }
 
 impl Display for ArgErrCtx {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        const MAX_WIDTH: usize = 80;
-        const WIDTH: usize = MAX_WIDTH - 11;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let color = self.color.use_color();
+        let alternate = f.alternate();
+        self.render(f, color, alternate, false)
+    }
+}
 
-        let args = vec!["".to_string()];
+impl ArgErrCtx {
+    /// Renders the error to `f`. Colors are used only if `color` is `true`
+    /// regardless of [`Self::color`]. If `alternate` is `true`, the header
+    /// is not shown (as with `{:#}`). If `warning` is `true`, it is rendered
+    /// as warning with yellow `argument warning:` header.
+    #[cfg(not(feature = "minimal-errors"))]
+    pub(crate) fn render(
+        &self,
+        f: &mut dyn Write,
+        color: bool,
+        alternate: bool,
+        warning: bool,
+    ) -> core::fmt::Result {
+        let max_width = self.render_width();
+        let mut arg_width = max_width - 11;
+        let s = Style::new(color);
+        let m = self.messages.unwrap_or(&Messages::ENGLISH);
+        let (mut announce, accent) = if warning {
+            (m.warning, s.yellow)
+        } else {
+            (m.error, s.red)
+        };
+        // Width of text after label. Long labels may not leave too little
+        // space.
+        let text_width = |label_width: usize| {
+            max_width.saturating_sub(label_width).max(MIN_WIDTH / 2)
+        };
+
+        let args = vec!["".into()];
         let args = if self.args.is_empty() {
             &args
         } else {
             &self.args
         };
+
+        // The program is shown only if it is not already the first argument.
+        let program = self
+            .program
+            .as_ref()
+            .filter(|p| self.show_program && args[0] != *p.as_ref())
+            .map(|p| truncate_middle(p, arg_width / 2));
+        if let Some(p) = &program {
+            arg_width =
+                arg_width.saturating_sub(p.width() + 1).max(arg_width / 2);
+        }
         let error_idx = self.error_idx.clamp(0, args.len() - 1);
+        // The first argument that may be shown.
+        let first = if self.show_program {
+            0
+        } else {
+            self.args_start_index.min(error_idx)
+        };
+        let owner_idx = self
+            .value_of
+            .filter(|&o| o >= first && o < args.len() && o != error_idx);
+
+        // Leave space for the owner if it is next to the errornous argument.
+        let err_width = match owner_idx {
+            Some(o) if o.abs_diff(error_idx) == 1 => arg_width
+                .saturating_sub(args[o].width() + 1)
+                .max(arg_width / 2),
+            _ => arg_width,
+        };
+        let (err_arg, err_start, err_len) =
+            visible_arg(&args[error_idx], &self.error_span, err_width);
+        // Convert the chars to terminal columns (e.g. CJK is two columns).
+        let err_len = width(err_arg.chars().skip(err_start).take(err_len));
+        let err_start = width(err_arg.chars().take(err_start));
+        // Empty span would have invisible caret.
+        let err_len = err_len.max(1);
 
-        let lengths: Vec<_> = args.iter().map(|a| a.chars().count()).collect();
+        let mut lengths: Vec<_> = args.iter().map(|a| a.width()).collect();
+        lengths[error_idx] = err_arg.width();
 
         let long_message = self.long_message.as_ref().unwrap_or(&self.message);
+        let long_message: Cow<str> = match &self.owner {
+            Some(o) => {
+                format!("{} {long_message}", m.value_of_prefix(o)).into()
+            }
+            None => long_message.as_ref().into(),
+        };
 
-        writemcln!(
+        if alternate || !self.anounce {
+            announce = "";
+        } else {
+            write!(f, "{accent}{announce}{} ", s.reset)?;
+        }
+        let announce = if announce.is_empty() {
+            0
+        } else {
+            announce.width() + 1
+        };
+        let mut lines = wrap(&long_message, text_width(announce));
+        writeln!(
             f,
-            color,
-            "{'r}argument error:{'_ bold} {long_message}{'_}"
+            "{}{}{}",
+            s.bold,
+            lines.next().unwrap_or_default(),
+            s.reset
         )?;
-        writemcln!(
+        for l in lines {
+            writeln!(f, "{: >announce$}{}{l}{}", "", s.bold, s.reset)?;
+        }
+        let origin = |i: usize| self.origins.get(i).and_then(|o| o.as_ref());
+        let from = origin(error_idx)
+            .map(|o| format!(" (from {o})"))
+            .unwrap_or_default();
+        writeln!(
             f,
-            color,
-            "{'b}--> {'_}arg{}:{}..{}",
-            error_idx,
+            "{}--> {}arg{}:{}..{}{}{}{}",
+            s.blue,
+            s.reset,
+            (error_idx + 1).saturating_sub(self.args_start_index),
             self.error_span.start,
-            self.error_span.end
+            self.error_span.end,
+            s.gray,
+            from,
+            s.reset
         )?;
-        writemcln!(f, color, "{'b} |{'_}")?;
+        writeln!(f, "{} |{}", s.blue, s.reset)?;
 
         let mut to_print = VecDeque::new();
         to_print.push_back(error_idx);
@@ -180,12 +577,35 @@ impl Display for ArgErrCtx {
         let mut start_idx = error_idx;
         let mut end_idx = error_idx;
 
+        // The owner is added first so that it is not left out because of
+        // the arguments on the other side.
+        if let Some(o) = owner_idx {
+            while start_idx > o {
+                let ad_len = lengths[start_idx - 1] + 1;
+                if width + ad_len > arg_width {
+                    break;
+                }
+                start_idx -= 1;
+                width += ad_len;
+                to_print.push_front(start_idx);
+            }
+            while end_idx < o {
+                let ad_len = lengths[end_idx + 1] + 1;
+                if width + ad_len > arg_width {
+                    break;
+                }
+                end_idx += 1;
+                width += ad_len;
+                to_print.push_back(end_idx);
+            }
+        }
+
         loop {
             let mut start_end = false;
-            if start_idx > 0 {
+            if start_idx > first {
                 start_idx -= 1;
-                let ad_len = args[start_idx].len() + 1;
-                if width + ad_len > WIDTH {
+                let ad_len = lengths[start_idx] + 1;
+                if width + ad_len > arg_width {
                     start_idx += 1;
                     break;
                 }
@@ -197,8 +617,8 @@ impl Display for ArgErrCtx {
 
             if end_idx + 1 < args.len() {
                 end_idx += 1;
-                let ad_len = args[end_idx].len() + 1;
-                if width + ad_len > WIDTH {
+                let ad_len = lengths[end_idx] + 1;
+                if width + ad_len > arg_width {
                     end_idx -= 1;
                     break;
                 }
@@ -209,53 +629,782 @@ impl Display for ArgErrCtx {
             }
         }
 
-        let mut err_pos = if start_idx == 0 {
-            writemc!(f, color, " {'b}${'_} ")?;
-            3
-        } else {
-            writemc!(f, color, " {'b}$ {'gr}...{'_} ")?;
-            7
-        };
+        write!(f, " {}${} ", s.blue, s.reset)?;
+        let mut err_pos = 3;
+        if let Some(p) = &program {
+            write!(f, "{p} ")?;
+            err_pos += p.width() + 1;
+        }
+        if start_idx != first {
+            write!(f, "{}...{} ", s.gray, s.reset)?;
+            err_pos += 4;
+        }
 
         for &i in &to_print {
+            // Arguments that were not given on the command line and the
+            // program are dimmed.
+            let dim = Style::new(
+                color && (origin(i).is_some() || i < self.args_start_index),
+            );
             match i {
                 i if i < error_idx => {
-                    write!(f, "{} ", args[i])?;
+                    write!(f, "{}{}{} ", dim.gray, args[i], dim.reset)?;
                     err_pos += lengths[i] + 1;
                 }
                 i if i == error_idx => {
-                    write!(f, "{}", args[i])?;
-                    let arg = &args[i];
-                    err_pos += arg[..self.error_span.start.min(arg.len())]
-                        .chars()
-                        .count();
+                    write!(f, "{err_arg}")?;
+                    err_pos += err_start;
                 }
                 i => {
-                    write!(f, " {}", args[i])?;
+                    write!(f, " {}{}{}", dim.gray, args[i], dim.reset)?;
                 }
             }
         }
 
         if end_idx != args.len() - 1 {
-            writemcln!(f, color, " {'gr}...{'_}")?;
+            writeln!(f, " {}...{}", s.gray, s.reset)?;
         } else {
             writeln!(f)?;
         }
 
         err_pos -= 2;
-        let err_len = self.error_span.len();
-        writemcln!(
+        writeln!(
             f,
-            color,
-            " {'b}|{: >err_pos$}{'r}{:^>err_len$} {}{'_}",
-            ' ',
-            '^',
-            self.message
+            " {}|{: >err_pos$}{}{:^>err_len$} {}{}",
+            s.blue, ' ', accent, '^', self.message, s.reset
         )?;
-        let Some(hint) = &self.hint else {
-            return Ok(());
+        for (label, text) in [(m.hint, &self.hint), (m.usage, &self.usage)] {
+            let Some(text) = text else {
+                continue;
+            };
+            let indent = label.width() + 1;
+            let mut lines = wrap(text, text_width(indent));
+            writeln!(
+                f,
+                "{}{label}{} {}",
+                s.cyan,
+                s.reset,
+                lines.next().unwrap_or_default()
+            )?;
+            for l in lines {
+                writeln!(f, "{: >indent$}{}", "", l)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders the error to `f` as single line
+    /// `error: <long_message> (arg N, bytes A..B)`. This is used instead of
+    /// the full renderer with the feature `minimal-errors`, so colors, width,
+    /// hint and usage are ignored. If `alternate` is `true`, the header is
+    /// not shown (as with `{:#}`). If `warning` is `true`, the header is
+    /// `warning:`. The texts are taken from [`Messages`].
+    #[cfg(feature = "minimal-errors")]
+    pub(crate) fn render(
+        &self,
+        f: &mut dyn Write,
+        _color: bool,
+        alternate: bool,
+        warning: bool,
+    ) -> core::fmt::Result {
+        let m = self.messages.unwrap_or(&Messages::ENGLISH);
+        if !alternate && self.anounce {
+            let header = if warning {
+                m.minimal_warning
+            } else {
+                m.minimal_error
+            };
+            write!(f, "{header} ")?;
+        }
+        if let Some(o) = &self.owner {
+            write!(f, "{} ", m.value_of_prefix(o))?;
+        }
+        let long_message = self.long_message.as_ref().unwrap_or(&self.message);
+        writeln!(
+            f,
+            "{long_message} (arg {}, bytes {}..{})",
+            self.error_idx
+                .saturating_add(1)
+                .saturating_sub(self.args_start_index),
+            self.error_span.start,
+            self.error_span.end,
+        )
+    }
+}
+
+/// Terminal colors used in the rendered error.
+#[cfg(not(feature = "minimal-errors"))]
+struct Style {
+    red: &'static str,
+    yellow: &'static str,
+    blue: &'static str,
+    cyan: &'static str,
+    gray: &'static str,
+    bold: &'static str,
+    reset: &'static str,
+}
+
+#[cfg(not(feature = "minimal-errors"))]
+impl Style {
+    /// Gets the colors. If `color` is `false`, all the colors are empty.
+    fn new(color: bool) -> Self {
+        if color {
+            Self {
+                red: "\x1b[31m",
+                yellow: "\x1b[33m",
+                blue: "\x1b[34m",
+                cyan: "\x1b[36m",
+                gray: "\x1b[90m",
+                bold: "\x1b[1m",
+                reset: "\x1b[0m",
+            }
+        } else {
+            Self {
+                red: "",
+                yellow: "",
+                blue: "",
+                cyan: "",
+                gray: "",
+                bold: "",
+                reset: "",
+            }
+        }
+    }
+}
+
+/// Placeholder for redacted text.
+const REDACTED: &str = "<redacted>";
+
+/// Default width of the rendered error message.
+const DEFAULT_WIDTH: usize = 80;
+/// Minimal width of the rendered error message. Smaller widths are clamped to
+/// this value.
+const MIN_WIDTH: usize = 30;
+
+/// Gets the visible part of the argument so that it fits into `width`
+/// terminal columns. Returns the visible text, char position of the span and
+/// char length of the span within the visible text.
+#[cfg(not(feature = "minimal-errors"))]
+fn visible_arg<'a>(
+    arg: &'a str,
+    span: &Range<usize>,
+    width: usize,
+) -> (Cow<'a, str>, usize, usize) {
+    const DOTS: &str = "...";
+    // The program and the owner may leave too little space. At least one
+    // char must fit between the dots.
+    let width = width.max(2 * DOTS.len() + 1);
+
+    let widths: Vec<_> = arg.chars().map(|c| c.width().unwrap_or(0)).collect();
+    let len = widths.len();
+    let start = char_pos(arg, span.start);
+    let end = char_pos(arg, span.end).max(start);
+
+    if widths.iter().sum::<usize>() <= width {
+        return (arg.into(), start, end - start);
+    }
+
+    // Number of chars from `from` that fit into `cols` columns.
+    let fwd = |from: usize, cols: usize| -> usize {
+        widths[from..]
+            .iter()
+            .scan(0, |w, c| {
+                *w += c;
+                (*w <= cols).then_some(())
+            })
+            .count()
+    };
+    // Number of chars before `to` that fit into `cols` columns.
+    let back = |to: usize, cols: usize| -> usize {
+        widths[..to]
+            .iter()
+            .rev()
+            .scan(0, |w, c| {
+                *w += c;
+                (*w <= cols).then_some(())
+            })
+            .count()
+    };
+    let chars = |r: Range<usize>| -> String {
+        arg.chars().skip(r.start).take(r.len()).collect()
+    };
+
+    let keep = width - DOTS.len();
+
+    // Cut out the middle of the argument if the span is close enough to one
+    // of its ends.
+    let tail = len - back(len, keep / 3);
+    let head = fwd(0, keep - keep / 3);
+    if end <= head {
+        let res = chars(0..head) + DOTS + &chars(tail..len);
+        return (res.into(), start, end - start);
+    }
+
+    let head = fwd(0, keep / 3);
+    let tail = len - back(len, keep - keep / 3);
+    if start >= tail {
+        let res = chars(0..head) + DOTS + &chars(tail..len);
+        let vis_start = start - tail + head + DOTS.len();
+        return (res.into(), vis_start, end - start);
+    }
+
+    // The span is in the middle, show only the part around it.
+    let keep = width - 2 * DOTS.len();
+    let win_start = start - back(start, keep / 4);
+    let win_end = win_start + fwd(win_start, keep);
+    let res = DOTS.to_string() + &chars(win_start..win_end) + DOTS;
+    let vis_start = start - win_start + DOTS.len();
+    (res.into(), vis_start, end.min(win_end) - start)
+}
+
+/// Gets the number of terminal columns occupied by the chars.
+#[cfg(not(feature = "minimal-errors"))]
+fn width(chars: impl Iterator<Item = char>) -> usize {
+    chars.collect::<String>().width()
+}
+
+/// Converts byte position in `s` to char position.
+#[cfg(not(feature = "minimal-errors"))]
+fn char_pos(s: &str, pos: usize) -> usize {
+    s.char_indices().take_while(|(i, _)| *i < pos).count()
+}
+
+/// Replaces the `range` of `s` with placeholder. Returns the replaced range
+/// or [`None`] if it is empty or not at char boundaries.
+pub(crate) fn redact_str(
+    s: &mut Cow<'_, str>,
+    range: Range<usize>,
+) -> Option<Range<usize>> {
+    let end = range.end.min(s.len());
+    let start = range.start.min(end);
+    if start == end || !s.is_char_boundary(start) || !s.is_char_boundary(end) {
+        return None;
+    }
+    s.to_mut().replace_range(start..end, REDACTED);
+    Some(start..end)
+}
+
+/// Redacts the code spans (text between backticks) in `msg` that echo the
+/// whole range `secret` of `arg` (e.g. ``Invalid value `secret`.`` from
+/// parsers). Returns [`None`] if nothing is redacted.
+fn redact_code_spans(
+    msg: &str,
+    arg: &str,
+    secret: Range<usize>,
+) -> Option<String> {
+    let mut res = String::with_capacity(msg.len());
+    let mut changed = false;
+    for (i, part) in msg.split('`').enumerate() {
+        if i != 0 {
+            res.push('`');
+        }
+        let code = (i % 2 == 1 && !part.is_empty()).then_some(part);
+        let hidden = code.and_then(|c| {
+            arg.match_indices(c).find_map(|(p, _)| {
+                (p <= secret.start && secret.end <= p + c.len()).then(|| {
+                    let mut c = c.to_string();
+                    c.replace_range(
+                        secret.start - p..secret.end - p,
+                        REDACTED,
+                    );
+                    c
+                })
+            })
+        });
+        match hidden {
+            Some(h) => {
+                res += &h;
+                changed = true;
+            }
+            None => res += part,
+        }
+    }
+    changed.then_some(res)
+}
+
+/// Shortens the text to at most `width` chars by replacing its middle with
+/// `...`.
+pub(crate) fn truncate_middle(s: &str, width: usize) -> Cow<'_, str> {
+    const DOTS: &str = "...";
+    let len = s.chars().count();
+    if len <= width || width <= DOTS.len() {
+        return s.into();
+    }
+    let keep = width - DOTS.len();
+    let head: String = s.chars().take(keep - keep / 2).collect();
+    let tail: String = s.chars().skip(len - keep / 2).collect();
+    (head + DOTS + &tail).into()
+}
+
+/// Gets the english ordinal number (e.g. `1st`, `12th`, `23rd`).
+pub(crate) fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// Splits the text into lines so that each of them has at most `width` chars.
+/// Lines are split on whitespace if possible.
+#[cfg(not(feature = "minimal-errors"))]
+fn wrap(text: &str, width: usize) -> impl Iterator<Item = &str> {
+    text.split('\n').flat_map(move |mut line| {
+        let mut res = vec![];
+        while line.chars().count() > width {
+            let split = line.char_indices().nth(width).unwrap().0;
+            let (l, r) = if line[split..].starts_with(' ') {
+                (&line[..split], &line[split + 1..])
+            } else {
+                match line[..split].rfind(' ') {
+                    Some(p) if p != 0 => (&line[..p], &line[p + 1..]),
+                    _ => (&line[..split], &line[split..]),
+                }
+            };
+            res.push(l);
+            line = r;
+        }
+        res.push(line);
+        res
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "minimal-errors"))]
+    use super::super::width::UnicodeWidthStr;
+
+    use super::*;
+
+    #[cfg(not(feature = "minimal-errors"))]
+    fn long_arg_err(span: Range<usize>) -> String {
+        let arg = "a".repeat(100) + "XYZ" + &"b".repeat(97);
+        ArgErrCtx::from_msg("Invalid value.", arg.clone())
+            .spanned(span)
+            .add_args(vec!["prog".into(), "--val".into(), arg.into()], 2)
+            .main_msg(
+                "This is a very long message that should be wrapped to the \
+                width of the terminal so it doesn't look ugly.",
+            )
+            .hint(
+                "Try using some value that is shorter and doesn't contain \
+                the invalid sequence of characters in the middle.",
+            )
+            .no_color()
+            .max_width(60)
+            .to_string()
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    const MSG: &str = "\
+argument error: This is a very long message that should be
+                wrapped to the width of the terminal so it
+                doesn't look ugly.
+";
+
+    #[cfg(not(feature = "minimal-errors"))]
+    const HINT: &str = "\
+hint: Try using some value that is shorter and doesn't
+      contain the invalid sequence of characters in the
+      middle.
+";
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn wrap_long_arg_start() {
+        let ctx = "\
+--> arg3:0..5
+ |
+ $ ... aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa...bbbbbbbbbbbbbbb
+ |     ^^^^^ Invalid value.
+";
+        assert_eq!(long_arg_err(0..5), MSG.to_string() + ctx + HINT);
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn wrap_long_arg_middle() {
+        let ctx = "\
+--> arg3:100..103
+ |
+ $ ... ...aaaaaaaaaaXYZbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb...
+ |                  ^^^ Invalid value.
+";
+        assert_eq!(long_arg_err(100..103), MSG.to_string() + ctx + HINT);
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn wrap_long_arg_end() {
+        let ctx = "\
+--> arg3:195..200
+ |
+ $ ... aaaaaaaaaaaaaaa...bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb
+ |                                                 ^^^^^ Invalid value.
+";
+        assert_eq!(long_arg_err(195..200), MSG.to_string() + ctx + HINT);
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn owner_in_window() {
+        let render = |args: &[&str], idx, owner: Option<usize>| {
+            let args = args.iter().map(|a| a.to_string().into()).collect();
+            let mut ctx = ArgErrCtx::from_msg("Invalid value.", String::new())
+                .add_args(args, idx)
+                .no_color()
+                .max_width(50);
+            ctx.value_of = owner;
+            let s = ctx.to_string();
+            s.lines().find(|l| l.starts_with(" $")).unwrap().to_string()
+        };
+
+        let long = "y".repeat(34);
+        let args = ["prog", "--count", "-v", "x", &long, &long];
+        assert_eq!(render(&args, 3, None), format!(" $ ... -v x {long} ..."));
+        assert_eq!(render(&args, 3, Some(1)), " $ prog --count -v x ...");
+
+        let long = "y".repeat(60);
+        let args = ["prog", "--name", &long];
+        assert!(!render(&args, 2, None).contains("--name"));
+        let line = render(&args, 2, Some(1));
+        assert!(line.starts_with(" $ ... --name yyy"), "{line}");
+        assert!(line.width() <= 50, "{line}");
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn narrow_owner_and_program() {
+        // The program and the owner both shrink the space for the value.
+        let owner = "--".to_string() + &"o".repeat(40);
+        let ctx = ArgErrCtx::from_msg("Invalid value.", String::new())
+            .spanned(20..22)
+            .add_args(vec![owner.into(), "v".repeat(60).into()], 1)
+            .program("p".repeat(40))
+            .value_of(0)
+            .no_color()
+            .max_width(30);
+        let s = ctx.to_string();
+        let line = s.lines().find(|l| l.starts_with(" $")).unwrap();
+        // At least part of the span is visible.
+        assert!(line.ends_with("...v..."), "{line}");
+    }
+
+    #[test]
+    fn ordinals() {
+        let ords: Vec<_> = [1, 2, 3, 4, 11, 12, 13, 21, 22, 23, 111, 112]
+            .into_iter()
+            .map(ordinal)
+            .collect();
+        assert_eq!(
+            ords,
+            [
+                "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st",
+                "22nd", "23rd", "111th", "112th"
+            ]
+        );
+    }
+
+    #[test]
+    fn truncate_middle_width() {
+        assert_eq!(truncate_middle("abcdef", 6), "abcdef");
+        assert_eq!(truncate_middle("abcdefghij", 7), "ab...ij");
+        assert_eq!(truncate_middle("abcdefghij", 8), "abc...ij");
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    fn value_err(args: &[&str], span: Range<usize>) -> String {
+        let args: Vec<_> = args.iter().map(|a| a.to_string().into()).collect();
+        let idx = args.len() - 1;
+        ArgErrCtx::from_msg("Invalid value.", String::new())
+            .spanned(span)
+            .add_args(args, idx)
+            .no_color()
+            .to_string()
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn caret_czech() {
+        let err = value_err(&["přehraj", "--název=štěstí"], 9..18);
+        assert_eq!(
+            err,
+            "\
+argument error: Invalid value.
+--> arg2:9..18
+ |
+ $ přehraj --název=štěstí
+ |                 ^^^^^^ Invalid value.
+"
+        );
+    }
+
+    #[cfg(all(feature = "unicode-width", not(feature = "minimal-errors")))]
+    #[test]
+    fn caret_cjk() {
+        let err = value_err(&["日本", "--名前=値x"], 12..13);
+        assert_eq!(
+            err,
+            "\
+argument error: Invalid value.
+--> arg2:12..13
+ |
+ $ 日本 --名前=値x
+ |               ^ Invalid value.
+"
+        );
+
+        let err = value_err(&["日本", "--名前=値x"], 9..12);
+        assert!(err.ends_with(" |             ^^ Invalid value.\n"));
+    }
+
+    #[cfg(all(feature = "unicode-width", not(feature = "minimal-errors")))]
+    #[test]
+    fn caret_emoji() {
+        let err = value_err(&["🎵", "play=🎸x"], 9..10);
+        assert!(err.ends_with(
+            "\
+ $ 🎵 play=🎸x
+ |           ^ Invalid value.
+"
+        ));
+
+        let err = value_err(&["🎵", "play=🎸x"], 5..9);
+        assert!(err.ends_with(" |         ^^ Invalid value.\n"));
+    }
+
+    #[cfg(all(feature = "unicode-width", not(feature = "minimal-errors")))]
+    #[test]
+    fn wrap_long_cjk() {
+        let arg = "日".repeat(40) + "X" + &"本".repeat(40);
+        let err = |span: Range<usize>| {
+            ArgErrCtx::from_msg("Invalid value.", arg.clone())
+                .spanned(span)
+                .add_args(vec!["prog".into(), arg.clone().into()], 1)
+                .no_color()
+                .max_width(60)
+                .to_string()
         };
 
-        writemcln!(f, color, "{'c}hint:{'_} {hint}")
+        assert!(err(0..3).ends_with(
+            "\
+ $ ... 日日日日日日日日日日日日日日日...本本本本本本本
+ |     ^^ Invalid value.
+"
+        ));
+        assert!(err(120..121).ends_with(
+            "\
+ $ ... ...日日日日日X本本本本本本本本本本本本本本本本...
+ |                  ^ Invalid value.
+"
+        ));
+        assert!(err(238..241).ends_with(
+            "\
+ $ ... 日日日日日日日...本本本本本本本本本本本本本本本
+ |                                                  ^^ Invalid value.
+"
+        ));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn program_usage() {
+        let err = ArgErrCtx::from_msg("Invalid value.", String::new())
+            .spanned(8..10)
+            .add_args(vec!["--color=no".into()], 0)
+            .program("my-program")
+            .usage("my-program [OPTIONS] <NAME>")
+            .hint("Use `auto`.")
+            .no_color()
+            .to_string();
+        assert_eq!(
+            err,
+            "\
+argument error: Invalid value.
+--> arg1:8..10
+ |
+ $ my-program --color=no
+ |                    ^^ Invalid value.
+hint: Use `auto`.
+usage: my-program [OPTIONS] <NAME>
+"
+        );
+
+        // The program is not repeated if it is the first argument.
+        let err = ArgErrCtx::from_msg("Invalid value.", String::new())
+            .add_args(vec!["my-program".into(), "x".into()], 1)
+            .program("my-program")
+            .usage("my-program <N>")
+            .color_mode(ColorMode::Always)
+            .to_string();
+        assert!(err.contains("$\x1b[0m my-program x\n"));
+        assert!(err.ends_with("\x1b[36musage:\x1b[0m my-program <N>\n"));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn render_explicit_color() {
+        use crate::{ArgError, Special};
+
+        let ctx = ArgErrCtx::from_msg("Invalid value.", "--n=x".into())
+            .spanned(4..5)
+            .hint("Use number.")
+            .color_mode(ColorMode::Always);
+        let err = ArgError::InvalidValue(ctx.clone().into());
+
+        let plain = err.render(false);
+        assert!(!plain.contains('\x1b'));
+        assert_eq!(plain, ctx.clone().no_color().to_string());
+        assert_eq!(err.render(true), ctx.to_string());
+        let never = ArgError::InvalidValue(ctx.no_color().into());
+        assert_eq!(never.render(true), err.render(true));
+
+        #[cfg(feature = "std")]
+        {
+            let mut buf = vec![];
+            err.write_to(&mut buf, false).unwrap();
+            assert_eq!(buf, plain.as_bytes());
+            buf.clear();
+            err.write_to(&mut buf, true).unwrap();
+            assert_eq!(buf, err.render(true).as_bytes());
+
+            let mut small = [0; 8];
+            let res = err.write_to(&mut &mut small[..], false);
+            assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::WriteZero);
+            assert_eq!(&small, b"argument");
+        }
+
+        let special = ArgError::Special(Special::Help);
+        assert_eq!(special.render(true), special.to_string());
+    }
+
+    #[test]
+    fn mismatched_args() {
+        use crate::ArgError;
+
+        let ctx = |e: ArgError| {
+            let c = e.ctx().unwrap().clone();
+            (c.args, c.error_idx, c.error_span)
+        };
+        let args =
+            |a: &[&'static str]| a.iter().map(|a| (*a).into()).collect();
+
+        // Error without context gets the argument with empty span.
+        let e = ArgError::parse_msg("x", String::new())
+            .add_args(args(&["p", "abc"]), 1);
+        assert_eq!(ctx(e), (args(&["p", "abc"]), 1, 0..0));
+
+        let e = ArgError::parse_msg("x", "5x".into())
+            .spanned(1..2)
+            .part_of("--n=5x".into())
+            .add_args(args(&["p", "--n=5x"]), 1);
+        assert_eq!(ctx(e), (args(&["p", "--n=5x"]), 1, 5..6));
+
+        // Invalid index keeps the original context.
+        let e =
+            ArgError::custom("t", "x", "a".into()).add_args(args(&["p"]), 5);
+        assert_eq!(ctx(e), (args(&["a"]), 0, 0..1));
+
+        let e =
+            ArgError::parse_msg("x", String::new()).postfix_of("--n=".into());
+        assert_eq!(ctx(e), (args(&["--n="]), 0, 4..4));
+
+        // Context without arguments and with index out of bounds.
+        let mut empty = ArgErrCtx::from_msg("x", "abc".into());
+        empty.args.clear();
+        empty.error_idx = 5;
+        empty.error_span = 7..9;
+        let e = ArgError::InvalidValue(Box::new(empty.clone()))
+            .part_of("abc".into());
+        assert_eq!(ctx(e), (args(&["abc"]), 0, 7..9));
+        let e = ArgError::InvalidValue(Box::new(empty.clone()))
+            .add_args(args(&["p", "abc"]), 1);
+        assert_eq!(ctx(e), (args(&["p", "abc"]), 1, 7..9));
+
+        let e = ArgError::InvalidValue(Box::new(empty))
+            .shift_span(usize::MAX, "abc".into())
+            .postfix_of("--abc".into())
+            .add_args(args(&["--abc"]), 0);
+        _ = e.to_string();
+        assert_eq!(ctx(e).2, usize::MAX..usize::MAX);
+
+        let errors = [
+            || ArgError::parse_msg("x", String::new()),
+            || ArgError::custom("t", "x", "ab".into()),
+            || {
+                let ctx = ArgErrCtx::from_inner("x", "ž".into());
+                ArgError::FailedToParse(Box::new(ctx))
+            },
+        ];
+        for e in errors {
+            for idx in [0, 1, 2, usize::MAX] {
+                let e = e()
+                    .part_of("ažb".into())
+                    .postfix_of("-ažb".into())
+                    .add_args(args(&["p", "", "-ažb"]), idx)
+                    .part_of(String::new())
+                    .add_args(vec![], idx);
+                _ = e.to_string();
+            }
+        }
+        assert!(ArgError::no_last_argument().ctx().is_none());
+    }
+
+    #[cfg(feature = "minimal-errors")]
+    #[test]
+    fn minimal_render() {
+        use crate::ArgWarning;
+
+        let err = || {
+            ArgErrCtx::from_msg("Invalid value.", String::new())
+                .spanned(8..10)
+                .add_args(vec!["prog".into(), "--color=no".into()], 1)
+                .args_start_index(1)
+                .main_msg("Invalid color mode `no`.")
+                .hint("Use `auto`.")
+                .color_mode(ColorMode::Always)
+                .max_width(40)
+        };
+        assert_eq!(
+            err().to_string(),
+            "error: Invalid color mode `no`. (arg 1, bytes 8..10)\n"
+        );
+        assert_eq!(
+            format!("{:#}", err()),
+            "Invalid color mode `no`. (arg 1, bytes 8..10)\n"
+        );
+        assert_eq!(
+            ArgWarning::from(err()).to_string(),
+            "warning: Invalid color mode `no`. (arg 1, bytes 8..10)\n"
+        );
+
+        let err = ArgErrCtx::from_msg("Expected number.", "-x".into())
+            .owner("--count")
+            .anounce(false);
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for `--count`: Expected number. (arg 1, bytes \
+            0..2)\n"
+        );
+
+        static CZECH: Messages = Messages {
+            invalid_value_for: "Neplatná hodnota `{owner}`:",
+            minimal_error: "chyba:",
+            minimal_warning: "varování:",
+            ..Messages::ENGLISH
+        };
+        let err = ArgErrCtx::from_msg("Expected number.", "-x".into())
+            .owner("--count")
+            .messages(&CZECH);
+        assert_eq!(
+            err.clone().to_string(),
+            "chyba: Neplatná hodnota `--count`: Expected number. (arg 1, \
+            bytes 0..2)\n"
+        );
+        assert!(ArgWarning::from(err).to_string().starts_with("varování: "));
     }
 }
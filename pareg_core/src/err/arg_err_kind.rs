@@ -0,0 +1,27 @@
+/// Kind of [`super::ArgError`] without its payload (see
+/// [`super::ArgError::kind`]). It may be compared without matching the
+/// error.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArgErrKind {
+    /// [`super::ArgError::UnknownArgument`]
+    UnknownArgument,
+    /// [`super::ArgError::NoMoreArguments`]
+    NoMoreArguments,
+    /// [`super::ArgError::FailedToParse`]
+    FailedToParse,
+    /// [`super::ArgError::NoValue`]
+    NoValue,
+    /// [`super::ArgError::InvalidValue`]
+    InvalidValue,
+    /// [`super::ArgError::TooManyArguments`]
+    TooManyArguments,
+    /// IO error. It may happen only with the feature `std`.
+    Io,
+    /// [`super::ArgError::NoLastArgument`]
+    NoLastArgument,
+    /// [`super::ArgError::Custom`]
+    Custom,
+    /// [`super::ArgError::Special`]
+    Special,
+}
@@ -1,51 +1,146 @@
-use std::{borrow::Cow, ops::Range};
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+use core::{
+    fmt::{Display, Write},
+    ops::Range,
+    panic::Location,
+};
 
-use thiserror::Error;
+use crate::Special;
 
-use super::{ArgErrCtx, ColorMode, Result};
+use super::{ArgErrCtx, ArgErrKind, ColorMode, ErrLocation, Messages, Result};
 
 /// Errors thrown when parsing arguments.
-#[derive(Debug, Error)]
+///
+/// New kinds of errors may be added. Use [`ArgError::kind`] and the other
+/// accessors to inspect the error without matching all the variants.
+#[non_exhaustive]
+#[derive(Debug)]
 pub enum ArgError {
     /// There was an unknown argument.
-    #[error("{0}")]
     UnknownArgument(Box<ArgErrCtx>),
     /// Expected another argument but there were no more arguments.
-    #[error("{0}")]
     NoMoreArguments(Box<ArgErrCtx>),
     /// Failed to parse a string value into a type.
-    #[error("{0}")]
     FailedToParse(Box<ArgErrCtx>),
     /// There was no value in a key-value pair.
-    #[error("{0}")]
     NoValue(Box<ArgErrCtx>),
     /// The value of argument was invalid.
-    #[error("{0}")]
     InvalidValue(Box<ArgErrCtx>),
     /// Argument is specified too many times.
-    #[error("{0}")]
     TooManyArguments(Box<ArgErrCtx>),
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
+    /// IO error (e.g. when prompting for value). Requires the feature `std`.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
     /// This error happens when you call any of the `cur_*` methods on
     /// [`crate::Pareg`]. It is not ment to happen in argument parsing and it
     /// may indicate that you have bug in your parsing.
     ///
-    /// Prints the message: `"There was no last argument when it was expected.
-    /// If you see this error, it is propably a bug."`
-    #[error(
-        "There was no last argument when it was expected. \
-        If you see this error, it is propably a bug."
-    )]
-    NoLastArgument,
+    /// The location is the call that caused the error (e.g. the call to
+    /// [`crate::Pareg::cur_arg`]). With [`crate::Pareg::strict_usage`], debug
+    /// builds panic instead of returning this error.
+    ///
+    /// Prints the message: `"There was no last argument when it was expected
+    /// at `<location>`. If you see this error, it is propably a bug."`
+    NoLastArgument(&'static Location<'static>),
+    /// Custom error from user code (e.g. [`crate::FromArg`] implementation).
+    /// The tag identifies the kind of the problem so that the application
+    /// may react to it (see [`ArgError::custom_tag`]).
+    Custom {
+        ctx: Box<ArgErrCtx>,
+        /// Identifies the kind of the error (e.g. `not-found`).
+        tag: Cow<'static, str>,
+        /// The underlying error (see [`core::error::Error::source`]).
+        source: Option<Box<dyn core::error::Error + Send + Sync>>,
+    },
+    /// Special argument (e.g. `--help`) was intercepted, so the parsing
+    /// stopped (see [`crate::Pareg::on_special`]). This is not real error
+    /// and the program should usually exit successfully.
+    Special(Special),
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ArgError::Io(e) => e.fmt(f),
+            ArgError::NoLastArgument(loc) => write!(
+                f,
+                "There was no last argument when it was expected at `{loc}`. \
+                If you see this error, it is propably a bug."
+            ),
+            ArgError::Special(s) => {
+                write!(f, "Parsing was stopped by `{s}`.")
+            }
+            e => e.ctx().map_or(Ok(()), |c| write!(f, "{c}")),
+        }
+    }
+}
+
+impl core::error::Error for ArgError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ArgError::Io(e) => e.source(),
+            ArgError::Custom {
+                source: Some(s), ..
+            } => Some(s.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Adapter that writes formatted text to [`std::io::Write`] and keeps the
+/// IO error.
+#[cfg(feature = "std")]
+struct IoWriter<'a> {
+    w: &'a mut dyn std::io::Write,
+    err: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl Write for IoWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.w.write_all(s.as_bytes()).map_err(|e| {
+            self.err = Some(e);
+            core::fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ArgError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
 }
 
 impl ArgError {
+    /// Creates [`ArgError::NoLastArgument`] with the location of the caller.
+    #[track_caller]
+    pub fn no_last_argument() -> Self {
+        Self::NoLastArgument(Location::caller())
+    }
+
     /// Shortcut for creating parse error.
     pub fn parse_msg(msg: impl Into<Cow<'static, str>>, arg: String) -> Self {
         Self::FailedToParse(Box::new(ArgErrCtx::from_msg(msg, arg)))
     }
 
+    /// Creates [`ArgError::Custom`] with the given tag. The error is rendered
+    /// the same way as the other kinds, the tag is only for the application
+    /// (see [`ArgError::custom_tag`]).
+    pub fn custom(
+        tag: impl Into<Cow<'static, str>>,
+        msg: impl Into<Cow<'static, str>>,
+        arg: String,
+    ) -> Self {
+        Self::Custom {
+            ctx: Box::new(ArgErrCtx::from_msg(msg, arg)),
+            tag: tag.into(),
+            source: None,
+        }
+    }
+
     /// Moves the span in the error message by `cnt` and changes the
     /// errornous argument to `new_arg`.
     pub fn shift_span(self, cnt: usize, new_arg: String) -> Self {
@@ -54,7 +149,7 @@ impl ArgError {
 
     /// Add arguments to the error so that it may have better error message.
     /// Mostly useful internaly in pareg.
-    pub fn add_args(self, args: Vec<String>, idx: usize) -> Self {
+    pub fn add_args(self, args: Vec<Cow<'static, str>>, idx: usize) -> Self {
         self.map_ctx(|c| c.add_args(args, idx))
     }
 
@@ -83,6 +178,12 @@ impl ArgError {
         self.map_ctx(|c| c.main_msg(msg))
     }
 
+    /// Hides the given range of the argument at the index `idx`. The range is
+    /// replaced with placeholder and the span is adjusted.
+    pub fn redact(self, idx: usize, range: Range<usize>) -> Self {
+        self.map_ctx(|c| c.redact(idx, range))
+    }
+
     /// Set the color mode.
     pub fn color_mode(self, mode: ColorMode) -> Self {
         self.map_ctx(|c| c.color_mode(mode))
@@ -93,6 +194,128 @@ impl ArgError {
         self.map_ctx(|c| c.no_color())
     }
 
+    /// Renders the error the same way as [`Display`], but colors are used
+    /// only if `color` is `true` regardless of the color mode of the error
+    /// (see [`ArgError::color_mode`]).
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+    /// use pareg_core::{ArgError, ColorMode};
+    ///
+    /// let err = ArgError::parse_msg("Invalid value.", "x".into())
+    ///     .color_mode(ColorMode::Always);
+    /// assert!(!err.render(false).contains('\x1b'));
+    /// assert!(err.render(true).contains('\x1b'));
+    /// assert_eq!(err.render(false), err.no_color().to_string());
+    /// ```
+    pub fn render(&self, color: bool) -> String {
+        let mut res = String::new();
+        _ = self.render_to(&mut res, color);
+        res
+    }
+
+    /// Same as [`ArgError::render`], but the error is written to `w` without
+    /// rendering it to string first.
+    #[cfg(feature = "std")]
+    pub fn write_to(
+        &self,
+        w: &mut dyn std::io::Write,
+        color: bool,
+    ) -> std::io::Result<()> {
+        let mut w = IoWriter { w, err: None };
+        match (self.render_to(&mut w, color), w.err) {
+            (Ok(()), _) => Ok(()),
+            (Err(_), Some(e)) => Err(e),
+            (Err(_), None) => {
+                Err(std::io::Error::other("Failed to render the error."))
+            }
+        }
+    }
+
+    fn render_to(&self, w: &mut dyn Write, color: bool) -> core::fmt::Result {
+        match self.ctx() {
+            Some(c) => c.render(w, color, false, false),
+            None => write!(w, "{self}"),
+        }
+    }
+
+    /// Sets whether the rendered error starts with `argument error:`.
+    pub fn anounce(self, anounce: bool) -> Self {
+        self.map_ctx(|c| c.anounce(anounce))
+    }
+
+    /// Set the maximum width of the rendered error message.
+    pub fn max_width(self, width: usize) -> Self {
+        self.map_ctx(|c| c.max_width(width))
+    }
+
+    /// Sets the origins of the arguments. See [`ArgErrCtx::origins`].
+    pub fn origins(self, origins: Vec<Option<Cow<'static, str>>>) -> Self {
+        self.map_ctx(|c| c.origins(origins))
+    }
+
+    /// Sets the option whose value is the errornous argument. See
+    /// [`ArgErrCtx::owner`].
+    pub fn owner(self, owner: impl Into<Cow<'static, str>>) -> Self {
+        self.map_ctx(|c| c.owner(owner))
+    }
+
+    /// Sets the index of the argument with the owner of the errornous value.
+    /// See [`ArgErrCtx::value_of`].
+    pub fn value_of(self, idx: usize) -> Self {
+        self.map_ctx(|c| c.value_of(idx))
+    }
+
+    /// Sets the index of the first argument given by the user. See
+    /// [`ArgErrCtx::args_start_index`].
+    pub fn args_start_index(self, idx: usize) -> Self {
+        self.map_ctx(|c| c.args_start_index(idx))
+    }
+
+    /// Sets whether the program name is shown in the command line. See
+    /// [`ArgErrCtx::show_program`].
+    pub fn show_program(self, show: bool) -> Self {
+        self.map_ctx(|c| c.show_program(show))
+    }
+
+    /// Sets the name of the program. See [`ArgErrCtx::program`].
+    pub fn program(self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.map_ctx(|c| c.program(name))
+    }
+
+    /// Sets the values that may replace the errornous part of the argument.
+    /// See [`ArgErrCtx::suggest`].
+    pub fn suggest<I>(self, suggestions: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.map_ctx(|c| c.suggest(suggestions))
+    }
+
+    /// Sets the usage of the program. See [`ArgErrCtx::usage`].
+    pub fn usage(self, usage: impl Into<Cow<'static, str>>) -> Self {
+        self.map_ctx(|c| c.usage(usage))
+    }
+
+    /// Sets the texts used when rendering the error. If the error has the
+    /// English default message of its kind, the message is replaced with the
+    /// default message from `messages`. See [`Messages`].
+    pub fn messages(self, messages: &'static Messages) -> Self {
+        let default = Messages::ENGLISH.kind_message(self.kind());
+        let localized = messages.kind_message(self.kind());
+        self.map_ctx(|mut c| {
+            if let (Some(d), Some(l)) = (default, localized) {
+                if c.message == d {
+                    c.message = l.into();
+                    c.long_message = None;
+                }
+            }
+            c.messages(messages)
+        })
+    }
+
     /// Sets new argument. If the original argument is substring of this,
     /// span will be adjusted.
     pub fn part_of(self, arg: String) -> Self {
@@ -108,6 +331,203 @@ impl ArgError {
         Err(self)
     }
 
+    /// Gets the kind of the error.
+    pub fn kind(&self) -> ArgErrKind {
+        match self {
+            ArgError::UnknownArgument(_) => ArgErrKind::UnknownArgument,
+            ArgError::NoMoreArguments(_) => ArgErrKind::NoMoreArguments,
+            ArgError::FailedToParse(_) => ArgErrKind::FailedToParse,
+            ArgError::NoValue(_) => ArgErrKind::NoValue,
+            ArgError::InvalidValue(_) => ArgErrKind::InvalidValue,
+            ArgError::TooManyArguments(_) => ArgErrKind::TooManyArguments,
+            #[cfg(feature = "std")]
+            ArgError::Io(_) => ArgErrKind::Io,
+            ArgError::NoLastArgument(_) => ArgErrKind::NoLastArgument,
+            ArgError::Custom { .. } => ArgErrKind::Custom,
+            ArgError::Special(_) => ArgErrKind::Special,
+        }
+    }
+
+    /// Checks whether this is [`ArgError::UnknownArgument`].
+    pub fn is_unknown_argument(&self) -> bool {
+        self.kind() == ArgErrKind::UnknownArgument
+    }
+
+    /// Checks whether this is [`ArgError::NoMoreArguments`].
+    pub fn is_no_more_arguments(&self) -> bool {
+        self.kind() == ArgErrKind::NoMoreArguments
+    }
+
+    /// Checks whether this is [`ArgError::FailedToParse`].
+    pub fn is_failed_to_parse(&self) -> bool {
+        self.kind() == ArgErrKind::FailedToParse
+    }
+
+    /// Checks whether this is [`ArgError::NoValue`].
+    pub fn is_no_value(&self) -> bool {
+        self.kind() == ArgErrKind::NoValue
+    }
+
+    /// Checks whether this is [`ArgError::InvalidValue`].
+    pub fn is_invalid_value(&self) -> bool {
+        self.kind() == ArgErrKind::InvalidValue
+    }
+
+    /// Checks whether this is [`ArgError::TooManyArguments`].
+    pub fn is_too_many_arguments(&self) -> bool {
+        self.kind() == ArgErrKind::TooManyArguments
+    }
+
+    /// Checks whether this is IO error.
+    pub fn is_io(&self) -> bool {
+        self.kind() == ArgErrKind::Io
+    }
+
+    /// Checks whether this is [`ArgError::NoLastArgument`].
+    pub fn is_no_last_argument(&self) -> bool {
+        self.kind() == ArgErrKind::NoLastArgument
+    }
+
+    /// Checks whether this is [`ArgError::Custom`].
+    pub fn is_custom(&self) -> bool {
+        self.kind() == ArgErrKind::Custom
+    }
+
+    /// Checks whether this is [`ArgError::Special`].
+    pub fn is_special(&self) -> bool {
+        self.kind() == ArgErrKind::Special
+    }
+
+    /// Gets the tag of [`ArgError::Custom`]. The tag is kept when the error
+    /// is passed through pareg (e.g. when its span is moved), so the
+    /// application may react to errors from its [`crate::FromArg`]
+    /// implementations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ArgError, FromArg, Pareg, Result};
+    ///
+    /// struct Config;
+    ///
+    /// impl FromArg<'_> for Config {
+    ///     fn from_arg(arg: &str) -> Result<Self> {
+    ///         Err(ArgError::custom(
+    ///             "not-found",
+    ///             "Config file doesn't exist.",
+    ///             arg.to_string(),
+    ///         ))
+    ///     }
+    /// }
+    ///
+    /// let mut args = Pareg::new(vec!["--config=a.toml".into()]);
+    /// args.next();
+    /// match args.cur_val::<Config>('=') {
+    ///     Err(e) if e.custom_tag() == Some("not-found") => {
+    ///         // Create the default config ...
+    ///         assert_eq!(Some(9..15), e.span());
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn custom_tag(&self) -> Option<&str> {
+        match self {
+            ArgError::Custom { tag, .. } => Some(tag),
+            _ => None,
+        }
+    }
+
+    /// Gets all the arguments. Empty if the error has no context.
+    pub fn args(&self) -> &[Cow<'static, str>] {
+        self.ctx().map_or(&[], |c| &c.args)
+    }
+
+    /// Gets the index of the errornous argument in [`ArgError::args`].
+    pub fn error_idx(&self) -> Option<usize> {
+        self.ctx().map(|c| c.error_idx)
+    }
+
+    /// Gets the errornous argument.
+    pub fn arg(&self) -> Option<&str> {
+        self.ctx()
+            .and_then(|c| c.args.get(c.error_idx))
+            .map(|a| a.as_ref())
+    }
+
+    /// Gets the range within the errornous argument that is invalid.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.ctx().map(|c| c.error_span.clone())
+    }
+
+    /// Gets the location of the error in the arguments. This may be mapped
+    /// to the original command line with [`ErrLocation::offset_in`].
+    pub fn location(&self) -> Option<ErrLocation> {
+        self.ctx().map(|c| ErrLocation {
+            arg_idx: c.error_idx,
+            span: c.error_span.clone(),
+        })
+    }
+
+    /// Gets the simple error message.
+    pub fn message(&self) -> Option<&str> {
+        self.ctx().map(|c| c.message.as_ref())
+    }
+
+    /// Gets the descriptive error message.
+    pub fn long_message(&self) -> Option<&str> {
+        self.ctx().and_then(|c| c.long_message.as_deref())
+    }
+
+    /// Gets the hint about how to fix the error.
+    pub fn hint_str(&self) -> Option<&str> {
+        self.ctx().and_then(|c| c.hint.as_deref())
+    }
+
+    /// Gets the values that may replace the errornous part of the argument
+    /// (see [`ArgErrCtx::suggestions`]). Empty if the error has no context.
+    pub fn suggestions(&self) -> &[String] {
+        self.ctx().map_or(&[], |c| &c.suggestions)
+    }
+
+    /// Gets all the arguments corrected with the only suggestion. See
+    /// [`ArgErrCtx::replacement_args`].
+    pub fn replacement_args(&self) -> Option<Vec<String>> {
+        self.ctx().and_then(|c| c.replacement_args())
+    }
+
+    /// Gets the context of the error. Returns [`None`] if the error has no
+    /// context (IO error, [`ArgError::NoLastArgument`] and
+    /// [`ArgError::Special`]).
+    pub fn into_ctx(self) -> Option<ArgErrCtx> {
+        match self {
+            ArgError::UnknownArgument(ctx)
+            | ArgError::NoMoreArguments(ctx)
+            | ArgError::FailedToParse(ctx)
+            | ArgError::NoValue(ctx)
+            | ArgError::InvalidValue(ctx)
+            | ArgError::TooManyArguments(ctx)
+            | ArgError::Custom { ctx, .. } => Some(*ctx),
+            #[cfg(feature = "std")]
+            ArgError::Io(_) => None,
+            ArgError::NoLastArgument(_) | ArgError::Special(_) => None,
+        }
+    }
+
+    /// Gets the context of the error if it has any.
+    pub fn ctx(&self) -> Option<&ArgErrCtx> {
+        match self {
+            ArgError::UnknownArgument(ctx)
+            | ArgError::NoMoreArguments(ctx)
+            | ArgError::FailedToParse(ctx)
+            | ArgError::NoValue(ctx)
+            | ArgError::InvalidValue(ctx)
+            | ArgError::TooManyArguments(ctx)
+            | ArgError::Custom { ctx, .. } => Some(ctx),
+            #[cfg(feature = "std")]
+            ArgError::Io(_) => None,
+            ArgError::NoLastArgument(_) | ArgError::Special(_) => None,
+        }
+    }
+
     pub fn map_ctx(self, f: impl FnOnce(ArgErrCtx) -> ArgErrCtx) -> Self {
         match self {
             ArgError::UnknownArgument(mut ctx) => {
@@ -134,6 +554,14 @@ impl ArgError {
                 *ctx = f(*ctx);
                 ArgError::TooManyArguments(ctx)
             }
+            ArgError::Custom {
+                mut ctx,
+                tag,
+                source,
+            } => {
+                *ctx = f(*ctx);
+                ArgError::Custom { ctx, tag, source }
+            }
             v => v,
         }
     }
@@ -0,0 +1,94 @@
+use alloc::{string::ToString, vec, vec::Vec};
+use core::fmt::Display;
+
+use super::ArgError;
+
+/// Multiple errors in arguments. They may be collected with
+/// [`crate::Pareg::collect_errors`] so that all the errors are reported at
+/// once.
+///
+/// When displayed, the errors are separated by blank line and followed by
+/// summary with the number of errors. Single error is displayed without the
+/// summary.
+#[derive(Debug, Default)]
+pub struct ArgErrors(pub Vec<ArgError>);
+
+impl ArgErrors {
+    /// Adds error.
+    pub fn push(&mut self, err: ArgError) {
+        self.0.push(err);
+    }
+
+    /// Gets the number of errors.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether there are no errors.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the errors.
+    pub fn iter(&self) -> core::slice::Iter<'_, ArgError> {
+        self.0.iter()
+    }
+
+    /// Disable color in all the errors.
+    pub fn no_color(self) -> Self {
+        Self(self.0.into_iter().map(|e| e.no_color()).collect())
+    }
+}
+
+impl Display for ArgErrors {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i != 0 {
+                writeln!(f)?;
+            }
+            let e = e.to_string();
+            f.write_str(&e)?;
+            if !e.ends_with('\n') {
+                writeln!(f)?;
+            }
+        }
+
+        match self.0.len() {
+            1 => Ok(()),
+            0 => writeln!(f, "0 argument errors"),
+            n => writeln!(f, "\n{n} argument errors"),
+        }
+    }
+}
+
+impl core::error::Error for ArgErrors {}
+
+impl From<ArgError> for ArgErrors {
+    fn from(value: ArgError) -> Self {
+        Self(vec![value])
+    }
+}
+
+impl From<Vec<ArgError>> for ArgErrors {
+    fn from(value: Vec<ArgError>) -> Self {
+        Self(value)
+    }
+}
+
+impl IntoIterator for ArgErrors {
+    type Item = ArgError;
+    type IntoIter = vec::IntoIter<ArgError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ArgErrors {
+    type Item = &'a ArgError;
+    type IntoIter = core::slice::Iter<'a, ArgError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
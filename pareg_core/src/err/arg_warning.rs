@@ -0,0 +1,50 @@
+use alloc::boxed::Box;
+use core::fmt::Display;
+
+use super::{ArgErrCtx, ColorMode};
+
+/// Warning about arguments (e.g. use of deprecated argument). It has the same
+/// context as [`crate::ArgError`] and it is rendered the same way, but with
+/// yellow `argument warning:` header. The alternate form (`{:#}`) doesn't
+/// start with the header.
+///
+/// Warnings may be collected with [`crate::Pareg::warn`].
+#[derive(Debug, Clone)]
+pub struct ArgWarning(pub Box<ArgErrCtx>);
+
+impl ArgWarning {
+    /// Gets the context of the warning.
+    pub fn ctx(&self) -> &ArgErrCtx {
+        &self.0
+    }
+
+    /// Gets the message of the warning.
+    pub fn message(&self) -> &str {
+        self.0.long_message.as_ref().unwrap_or(&self.0.message)
+    }
+
+    /// Sets the color mode. See [`ArgErrCtx::color_mode`].
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.0.color = mode;
+        self
+    }
+
+    /// Disables color. See [`ArgErrCtx::no_color`].
+    pub fn no_color(self) -> Self {
+        self.color_mode(ColorMode::Never)
+    }
+}
+
+impl Display for ArgWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let color = self.0.color.use_color();
+        let alternate = f.alternate();
+        self.0.render(f, color, alternate, true)
+    }
+}
+
+impl From<ArgErrCtx> for ArgWarning {
+    fn from(value: ArgErrCtx) -> Self {
+        Self(Box::new(value))
+    }
+}
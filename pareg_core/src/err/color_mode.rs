@@ -1,3 +1,4 @@
+#[cfg(feature = "std")]
 use std::io::{stderr, stdout, IsTerminal};
 
 #[cfg(any(
@@ -63,12 +64,18 @@ pub enum ColorMode {
 }
 
 impl ColorMode {
+    /// Checks whether color should be used. Without the feature `std`, the
+    /// automatic modes never use color.
     pub fn use_color(&self) -> bool {
         match self {
             ColorMode::Always => true,
             ColorMode::Never => false,
+            #[cfg(feature = "std")]
             ColorMode::AutoStderr => stderr().is_terminal(),
+            #[cfg(feature = "std")]
             ColorMode::AutoStdout => stdout().is_terminal(),
+            #[cfg(not(feature = "std"))]
+            ColorMode::AutoStderr | ColorMode::AutoStdout => false,
         }
     }
 }
@@ -0,0 +1,182 @@
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use super::ColorMode;
+
+/// Default color mode. [`UNSET`] if [`ColorMode::default`] should be used.
+#[cfg(not(test))]
+static COLOR_MODE: AtomicU8 = AtomicU8::new(UNSET);
+/// Default value of [`crate::ArgErrCtx::anounce`].
+#[cfg(not(test))]
+static ANOUNCE: AtomicBool = AtomicBool::new(true);
+
+// Unit tests run in parallel, so the defaults are per thread in tests. Tests
+// that change them don't affect the other tests.
+#[cfg(test)]
+std::thread_local! {
+    static COLOR_MODE: AtomicU8 = const { AtomicU8::new(UNSET) };
+    static ANOUNCE: AtomicBool = const { AtomicBool::new(true) };
+}
+
+const UNSET: u8 = u8::MAX;
+
+/// Gets the default color mode. It is per thread in tests.
+fn color_mode<R>(f: impl FnOnce(&AtomicU8) -> R) -> R {
+    #[cfg(not(test))]
+    return f(&COLOR_MODE);
+    #[cfg(test)]
+    COLOR_MODE.with(f)
+}
+
+/// Gets the default anounce. It is per thread in tests.
+fn anounce<R>(f: impl FnOnce(&AtomicBool) -> R) -> R {
+    #[cfg(not(test))]
+    return f(&ANOUNCE);
+    #[cfg(test)]
+    ANOUNCE.with(f)
+}
+
+/// Sets the color mode of all errors that are created after this call. It is
+/// useful when the color is chosen by argument (e.g. `--color=never`), so
+/// that also errors created by other crates that use pareg respect it.
+/// Explicit [`crate::ArgErrCtx::color_mode`] still overrides it.
+///
+/// The default is read when the error is created, so errors created before
+/// this call (possibly in other threads) keep the previous color mode.
+///
+/// # Examples
+#[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+#[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+/// use pareg_core::{set_default_color_mode, ColorMode, FromArg};
+///
+/// set_default_color_mode(ColorMode::Always);
+/// let err = std::thread::spawn(|| u32::from_arg("x").unwrap_err())
+///     .join()
+///     .unwrap();
+/// assert!(err.to_string().contains('\x1b'));
+/// assert!(!err.no_color().to_string().contains('\x1b'));
+/// ```
+pub fn set_default_color_mode(mode: ColorMode) {
+    color_mode(|m| m.store(mode as u8, Ordering::Relaxed));
+}
+
+/// Gets the color mode that is used by new errors. It is
+/// [`ColorMode::default`] unless it was changed with
+/// [`set_default_color_mode`].
+pub fn default_color_mode() -> ColorMode {
+    match color_mode(|m| m.load(Ordering::Relaxed)) {
+        m if m == ColorMode::Always as u8 => ColorMode::Always,
+        m if m == ColorMode::Never as u8 => ColorMode::Never,
+        m if m == ColorMode::AutoStderr as u8 => ColorMode::AutoStderr,
+        m if m == ColorMode::AutoStdout as u8 => ColorMode::AutoStdout,
+        _ => ColorMode::default(),
+    }
+}
+
+/// Sets whether errors that are created after this call start with the
+/// header `argument error:` (see [`crate::ArgErrCtx::anounce`]). Explicit
+/// [`crate::ArgErrCtx::anounce`] still overrides it.
+///
+/// The default is read when the error is created, so errors created before
+/// this call (possibly in other threads) keep the previous value.
+///
+/// # Examples
+#[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+#[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+/// use pareg_core::{set_default_anounce, FromArg};
+///
+/// let before = u32::from_arg("x").unwrap_err();
+/// set_default_anounce(false);
+/// let err = std::thread::spawn(|| u32::from_arg("x").unwrap_err())
+///     .join()
+///     .unwrap();
+/// assert!(!err.to_string().starts_with("argument error:"));
+/// assert!(before.to_string().starts_with("argument error:"));
+/// assert!(err.anounce(true).to_string().starts_with("argument error:"));
+/// ```
+pub fn set_default_anounce(anounce: bool) {
+    self::anounce(|a| a.store(anounce, Ordering::Relaxed));
+}
+
+/// Gets whether new errors start with the header `argument error:`. It is
+/// `true` unless it was changed with [`set_default_anounce`].
+pub fn default_anounce() -> bool {
+    anounce(|a| a.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArgErrCtx;
+
+    /// Sets the defaults and restores the previous values when dropped.
+    struct Defaults {
+        color: ColorMode,
+        anounce: bool,
+    }
+
+    impl Defaults {
+        fn set(color: ColorMode, anounce: bool) -> Self {
+            let prev = Self {
+                color: default_color_mode(),
+                anounce: default_anounce(),
+            };
+            set_default_color_mode(color);
+            set_default_anounce(anounce);
+            prev
+        }
+    }
+
+    impl Drop for Defaults {
+        fn drop(&mut self) {
+            set_default_color_mode(self.color);
+            set_default_anounce(self.anounce);
+        }
+    }
+
+    fn is_default(mode: ColorMode) -> bool {
+        mode as u8 == ColorMode::default() as u8
+    }
+
+    fn ctx() -> ArgErrCtx {
+        ArgErrCtx::from_msg("Invalid.", "x".into())
+    }
+
+    #[test]
+    fn changed_defaults() {
+        let before = ctx();
+        {
+            let _guard = Defaults::set(ColorMode::Always, false);
+            assert!(matches!(default_color_mode(), ColorMode::Always));
+            assert!(!default_anounce());
+
+            let err = ctx();
+            assert!(matches!(err.color, ColorMode::Always));
+            assert!(!err.anounce);
+
+            // Errors created before keep the previous defaults.
+            assert!(is_default(before.color));
+            assert!(before.anounce);
+        }
+
+        assert!(is_default(default_color_mode()));
+        assert!(default_anounce());
+        let err = ctx();
+        assert!(is_default(err.color));
+        assert!(err.anounce);
+    }
+
+    #[test]
+    fn nested_defaults() {
+        let outer = Defaults::set(ColorMode::Never, false);
+        {
+            let _inner = Defaults::set(ColorMode::Always, true);
+            assert!(matches!(default_color_mode(), ColorMode::Always));
+            assert!(default_anounce());
+        }
+        assert!(matches!(default_color_mode(), ColorMode::Never));
+        assert!(!default_anounce());
+        drop(outer);
+        assert!(is_default(default_color_mode()));
+        assert!(default_anounce());
+    }
+}
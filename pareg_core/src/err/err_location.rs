@@ -0,0 +1,20 @@
+use core::ops::Range;
+
+use crate::JoinedArgs;
+
+/// Location of error in the arguments (see [`crate::ArgError::location`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ErrLocation {
+    /// Index of the errornous argument.
+    pub arg_idx: usize,
+    /// Span of the error within the argument.
+    pub span: Range<usize>,
+}
+
+impl ErrLocation {
+    /// Gets the range of the error in the command line from which the
+    /// arguments were split. See [`JoinedArgs::offset_of`].
+    pub fn offset_in(&self, joined: &JoinedArgs) -> Range<usize> {
+        joined.offset_of(self.arg_idx, self.span.clone())
+    }
+}
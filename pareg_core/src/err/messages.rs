@@ -0,0 +1,184 @@
+use alloc::string::{String, ToString};
+
+use super::{ordinal, ArgErrKind};
+
+/// Texts used in the rendered errors. This is the hook for localizing the
+/// errors. The default is [`Messages::ENGLISH`].
+///
+/// The default messages of the error kinds replace the message of errors
+/// that have the English default message of their kind (e.g. the error from
+/// [`crate::Pareg::err_unknown_argument`]). Messages that describe the
+/// specific problem are not changed.
+///
+/// # Examples
+#[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+#[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+/// use pareg_core::{Messages, Pareg};
+///
+/// static GERMAN: Messages = Messages {
+///     error: "Argumentfehler:",
+///     hint: "Hinweis:",
+///     unknown_argument: "Unbekanntes Argument.",
+///     ..Messages::ENGLISH
+/// };
+///
+/// let args = ["prog", "--foo"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+/// args.set_messages(&GERMAN);
+///
+/// args.next();
+/// args.next();
+/// let err = args.err_unknown_argument().no_color().to_string();
+/// assert!(err.starts_with("Argumentfehler: Unbekanntes Argument.\n"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Messages {
+    /// Header of errors (`argument error:`).
+    pub error: &'static str,
+    /// Header of warnings (`argument warning:`).
+    pub warning: &'static str,
+    /// Label of the hint (`hint:`).
+    pub hint: &'static str,
+    /// Label of the usage (`usage:`).
+    pub usage: &'static str,
+    /// Prefix of the message of errors in value of option. `{owner}` is
+    /// replaced with the option (``Invalid value for `{owner}`:``).
+    pub invalid_value_for: &'static str,
+    /// Header of errors rendered with the feature `minimal-errors`
+    /// (`error:`).
+    pub minimal_error: &'static str,
+    /// Header of warnings rendered with the feature `minimal-errors`
+    /// (`warning:`).
+    pub minimal_warning: &'static str,
+    /// Default message of [`ArgErrKind::UnknownArgument`].
+    pub unknown_argument: &'static str,
+    /// Default message of [`ArgErrKind::NoMoreArguments`].
+    pub no_more_arguments: &'static str,
+    /// Default message of [`ArgErrKind::FailedToParse`].
+    pub failed_to_parse: &'static str,
+    /// Default message of [`ArgErrKind::NoValue`].
+    pub no_value: &'static str,
+    /// Default message of [`ArgErrKind::InvalidValue`].
+    pub invalid_value: &'static str,
+    /// Default message of [`ArgErrKind::TooManyArguments`].
+    pub too_many_arguments: &'static str,
+    /// Message that more arguments are expected after the last argument.
+    /// `{n}` is replaced with the position of the argument, `{nth}` with its
+    /// English ordinal and `{arg}` with the argument
+    /// (``Expected more arguments after the {nth} argument `{arg}`.``).
+    pub more_arguments_after: &'static str,
+    /// Same as [`Self::more_arguments_after`], but the last argument is the
+    /// program (``Expected more arguments after `{arg}`.``).
+    pub more_arguments_after_program: &'static str,
+    /// Message that `{count}` values of the option `{opt}` are missing when
+    /// `{count}` is one (``Expected {count} more value for `{opt}`.``).
+    pub missing_value_of: &'static str,
+    /// Same as [`Self::missing_value_of`], but `{count}` is more than one
+    /// (``Expected {count} more values for `{opt}`.``).
+    pub missing_values_of: &'static str,
+    /// Message that `{count}` values are missing when `{count}` is one
+    /// (`Expected {count} more value.`).
+    pub missing_value: &'static str,
+    /// Same as [`Self::missing_value`], but `{count}` is more than one
+    /// (`Expected {count} more values.`).
+    pub missing_values: &'static str,
+    /// Suffix of the message of errors in one of several values of option.
+    /// `{i}` is replaced with the position of the value and `{n}` with the
+    /// number of the values (`(value {i} of {n})`).
+    pub value_i_of_n: &'static str,
+}
+
+impl Messages {
+    /// The default English messages.
+    pub const ENGLISH: Self = Self {
+        error: "argument error:",
+        warning: "argument warning:",
+        hint: "hint:",
+        usage: "usage:",
+        invalid_value_for: "Invalid value for `{owner}`:",
+        minimal_error: "error:",
+        minimal_warning: "warning:",
+        unknown_argument: "Unknown argument.",
+        no_more_arguments: "Expected more arguments.",
+        failed_to_parse: "Failed to parse the argument.",
+        no_value: "Missing value.",
+        invalid_value: "Invalid value for argument.",
+        too_many_arguments: "Unexpected argument.",
+        more_arguments_after:
+            "Expected more arguments after the {nth} argument `{arg}`.",
+        more_arguments_after_program: "Expected more arguments after `{arg}`.",
+        missing_value_of: "Expected {count} more value for `{opt}`.",
+        missing_values_of: "Expected {count} more values for `{opt}`.",
+        missing_value: "Expected {count} more value.",
+        missing_values: "Expected {count} more values.",
+        value_i_of_n: "(value {i} of {n})",
+    };
+
+    /// Gets the prefix of the message of errors in value of `owner` (see
+    /// [`Self::invalid_value_for`]).
+    pub(crate) fn value_of_prefix(&self, owner: &str) -> String {
+        self.invalid_value_for.replace("{owner}", owner)
+    }
+
+    /// Gets the message that more arguments are expected after `arg`. `n` is
+    /// the position of `arg` or [`None`] if it is the program (see
+    /// [`Self::more_arguments_after`]).
+    pub(crate) fn more_arguments_msg(
+        &self,
+        n: Option<usize>,
+        arg: &str,
+    ) -> String {
+        match n {
+            Some(n) => self
+                .more_arguments_after
+                .replace("{nth}", &ordinal(n))
+                .replace("{n}", &n.to_string()),
+            None => self.more_arguments_after_program.to_string(),
+        }
+        .replace("{arg}", arg)
+    }
+
+    /// Gets the message that `count` values of `opt` are missing (see
+    /// [`Self::missing_values_of`] and [`Self::missing_values`]).
+    pub(crate) fn missing_values_msg(
+        &self,
+        count: usize,
+        opt: Option<&str>,
+    ) -> String {
+        let msg = match (count == 1, opt) {
+            (true, Some(_)) => self.missing_value_of,
+            (false, Some(_)) => self.missing_values_of,
+            (true, None) => self.missing_value,
+            (false, None) => self.missing_values,
+        };
+        msg.replace("{count}", &count.to_string())
+            .replace("{opt}", opt.unwrap_or_default())
+    }
+
+    /// Gets the suffix of errors in the `i`th of `n` values (see
+    /// [`Self::value_i_of_n`]).
+    pub(crate) fn value_i_of_n_msg(&self, i: usize, n: usize) -> String {
+        self.value_i_of_n
+            .replace("{i}", &i.to_string())
+            .replace("{n}", &n.to_string())
+    }
+
+    /// Gets the default message of the given kind of error.
+    pub fn kind_message(&self, kind: ArgErrKind) -> Option<&'static str> {
+        match kind {
+            ArgErrKind::UnknownArgument => Some(self.unknown_argument),
+            ArgErrKind::NoMoreArguments => Some(self.no_more_arguments),
+            ArgErrKind::FailedToParse => Some(self.failed_to_parse),
+            ArgErrKind::NoValue => Some(self.no_value),
+            ArgErrKind::InvalidValue => Some(self.invalid_value),
+            ArgErrKind::TooManyArguments => Some(self.too_many_arguments),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
@@ -1,8 +1,20 @@
 mod arg_err_ctx;
+mod arg_err_kind;
 mod arg_error;
+mod arg_errors;
+mod arg_warning;
 mod color_mode;
+mod defaults;
+mod err_location;
+mod messages;
+#[cfg(not(feature = "minimal-errors"))]
+mod width;
 
-pub use self::{arg_err_ctx::*, arg_error::*, color_mode::*};
+pub(crate) use self::arg_err_ctx::{ordinal, redact_str, truncate_middle};
+pub use self::{
+    arg_err_ctx::*, arg_err_kind::*, arg_error::*, arg_errors::*,
+    arg_warning::*, color_mode::*, defaults::*, err_location::*, messages::*,
+};
 
-/// Pareg result type. It is [`std::result::Result<T, ArgError<'a>>`]
-pub type Result<T> = std::result::Result<T, ArgError>;
+/// Pareg result type. It is [`core::result::Result<T, ArgError<'a>>`]
+pub type Result<T> = core::result::Result<T, ArgError>;
@@ -0,0 +1,35 @@
+//! Display width of the text in the rendered errors. With the feature
+//! `unicode-width`, this is the width from the crate `unicode-width`.
+//! Without it, every char is one column wide, so that wide chars (e.g. CJK
+//! or emoji) misalign the carets, but the dependency is not needed.
+
+#[cfg(feature = "unicode-width")]
+pub(crate) use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Fallback for [`unicode_width::UnicodeWidthStr`].
+#[cfg(not(feature = "unicode-width"))]
+pub(crate) trait UnicodeWidthStr {
+    /// Number of chars in the string.
+    fn width(&self) -> usize;
+}
+
+#[cfg(not(feature = "unicode-width"))]
+impl UnicodeWidthStr for str {
+    fn width(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+/// Fallback for [`unicode_width::UnicodeWidthChar`].
+#[cfg(not(feature = "unicode-width"))]
+pub(crate) trait UnicodeWidthChar {
+    /// `1`, or [`None`] for control chars.
+    fn width(self) -> Option<usize>;
+}
+
+#[cfg(not(feature = "unicode-width"))]
+impl UnicodeWidthChar for char {
+    fn width(self) -> Option<usize> {
+        (!self.is_control()).then_some(1)
+    }
+}
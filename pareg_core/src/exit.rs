@@ -0,0 +1,153 @@
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    process::{ExitCode, Termination},
+};
+
+use crate::ArgError;
+
+/// Error that ends the program. Argument errors are printed to stderr and
+/// the exit code is [`Exit::ARG_ERROR_CODE`]. Other errors have the exit code
+/// that was given to [`Exit::other`].
+///
+/// Errors caused by special arguments (see [`crate::Pareg::on_special`]) are
+/// not printed and the exit code is `0`.
+#[derive(Debug)]
+pub enum Exit {
+    /// Error in the arguments.
+    Arg(ArgError),
+    /// Other error with its message and exit code.
+    Other {
+        /// Exit code of the program.
+        code: u8,
+        /// Message that is printed to stderr. Nothing is printed if it is
+        /// empty.
+        msg: Cow<'static, str>,
+    },
+}
+
+/// Result of `main` that prints the error in readable form. Unlike
+/// [`Result`] returned from `main`, argument errors are printed with
+/// [`Display`] (with colors if they are enabled) and the exit code is
+/// [`Exit::ARG_ERROR_CODE`].
+///
+/// `?` cannot be used directly in function that returns [`MainResult`]
+/// (that would require the unstable trait `FromResidual`), so move the code
+/// into function that returns [`crate::Result`] or [`Result`] with [`Exit`]
+/// and convert its result with [`Into::into`].
+///
+/// # Examples
+/// ```rust,no_run
+/// use pareg_core::{Exit, MainResult, Pareg};
+///
+/// fn main() -> MainResult {
+///     start().into()
+/// }
+///
+/// fn start() -> Result<(), Exit> {
+///     let mut args = Pareg::args();
+///     let mut file = String::new();
+///     while let Some(arg) = args.next() {
+///         match arg {
+///             "-f" | "--file" => file = args.next_arg()?,
+///             _ => args.err_unknown_argument().err()?,
+///         }
+///     }
+///
+///     let text = std::fs::read_to_string(&file).map_err(|e| {
+///         Exit::other(1, format!("Failed to read `{file}`: {e}"))
+///     })?;
+///     println!("{text}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MainResult(pub Result<(), Exit>);
+
+impl Exit {
+    /// Exit code of argument errors. This is the code used for invalid
+    /// usage by most programs.
+    pub const ARG_ERROR_CODE: u8 = 2;
+
+    /// Creates error with the given exit code and message.
+    pub fn other(code: u8, msg: impl Display) -> Self {
+        Self::Other {
+            code,
+            msg: msg.to_string().into(),
+        }
+    }
+
+    /// Gets the exit code of the error.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Arg(e) if e.is_special() => 0,
+            Self::Arg(_) => Self::ARG_ERROR_CODE,
+            Self::Other { code, .. } => *code,
+        }
+    }
+}
+
+impl Termination for Exit {
+    fn report(self) -> ExitCode {
+        let msg = match &self {
+            Self::Arg(e) if e.is_special() => String::new(),
+            Self::Arg(e) => e.to_string(),
+            Self::Other { msg, .. } => msg.to_string(),
+        };
+        if !msg.is_empty() {
+            eprintln!("{}", msg.trim_end_matches('\n'));
+        }
+        self.code().into()
+    }
+}
+
+impl Termination for MainResult {
+    fn report(self) -> ExitCode {
+        match self.0 {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => e.report(),
+        }
+    }
+}
+
+impl Display for Exit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Arg(e) => e.fmt(f),
+            Self::Other { msg, .. } => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for Exit {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Arg(e) => Some(e),
+            Self::Other { .. } => None,
+        }
+    }
+}
+
+impl From<ArgError> for Exit {
+    fn from(value: ArgError) -> Self {
+        Self::Arg(value)
+    }
+}
+
+impl<E: Into<Exit>> From<Result<(), E>> for MainResult {
+    fn from(value: Result<(), E>) -> Self {
+        Self(value.map_err(Into::into))
+    }
+}
+
+impl From<ArgError> for MainResult {
+    fn from(value: ArgError) -> Self {
+        Self(Err(value.into()))
+    }
+}
+
+impl From<Exit> for MainResult {
+    fn from(value: Exit) -> Self {
+        Self(Err(value))
+    }
+}
@@ -1,13 +1,20 @@
-use std::{
+use alloc::{
     borrow::Cow,
-    ffi::{OsStr, OsString},
+    boxed::Box,
+    rc::Rc,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::{
     net::{
         IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
     },
-    path::{Path, PathBuf},
-    rc::Rc,
     str::FromStr,
-    sync::Arc,
+};
+#[cfg(feature = "std")]
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
 };
 
 use crate::{
@@ -31,6 +38,100 @@ pub trait FromArg<'a>: Sized {
     /// assert_eq!(5, i32::from_arg("5").unwrap());
     /// ```
     fn from_arg(arg: &'a str) -> Result<Self>;
+
+    /// Parses the string into `Self` without creating error if it fails. This
+    /// is used to check whether the value is valid (e.g.
+    /// [`crate::Pareg::cur_try_arg`]). The default implementation uses
+    /// [`FromArg::from_arg`], implement it if creating the error is costly.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::FromArg;
+    ///
+    /// assert_eq!(Some(5), i32::try_from_arg("5"));
+    /// assert_eq!(None, i32::try_from_arg("five"));
+    /// ```
+    fn try_from_arg(arg: &'a str) -> Option<Self> {
+        Self::from_arg(arg).ok()
+    }
+
+    /// Gets the value that is used when the argument value is missing (e.g.
+    /// empty argument with [`crate::Normalize::empty_is_missing`]). If this
+    /// returns [`None`] (default), missing value is error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::FromArg;
+    ///
+    /// assert_eq!(None, i32::from_missing());
+    /// assert_eq!(Some(None), Option::<i32>::from_missing());
+    /// ```
+    fn from_missing() -> Option<Self> {
+        None
+    }
+
+    /// Gets all the values that are valid for this type if there is finite
+    /// number of them (e.g. enums that derive [`FromArg`]). The default
+    /// implementation returns empty slice. This is used for shell
+    /// completion (see [`crate::completion`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::FromArg;
+    ///
+    /// assert_eq!(["false", "true"], bool::arg_variants());
+    /// assert!(i32::arg_variants().is_empty());
+    /// ```
+    fn arg_variants() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Gets hint for errors from parsing this type (e.g. description of the
+    /// format). When pareg parses the type (e.g. [`crate::Pareg::next_arg`]
+    /// or [`crate::key_val_arg`]), the hint is added to errors that have no
+    /// hint yet. The default implementation returns [`None`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::borrow::Cow;
+    ///
+    /// use pareg_core::{key_val_arg, ArgError, FromArg, Result};
+    ///
+    /// #[derive(Debug)]
+    /// struct When;
+    ///
+    /// impl FromArg<'_> for When {
+    ///     fn from_arg(arg: &str) -> Result<Self> {
+    ///         u32::from_arg(arg).map(|_| Self)
+    ///     }
+    ///
+    ///     fn hint() -> Option<Cow<'static, str>> {
+    ///         Some("See `--help` for the format of `--when`.".into())
+    ///     }
+    /// }
+    ///
+    /// let err = key_val_arg::<&str, When>("when=x", '=').unwrap_err();
+    /// let ArgError::FailedToParse(ctx) = err else {
+    ///     panic!("Unexpected error.");
+    /// };
+    /// assert_eq!(When::hint(), ctx.hint);
+    /// ```
+    fn hint() -> Option<Cow<'static, str>> {
+        None
+    }
+}
+
+/// Parses the argument with [`FromArg::from_arg`]. If it fails and the error
+/// has no hint, the hint from [`FromArg::hint`] is added.
+#[inline(always)]
+pub(crate) fn parse_hinted<'a, T>(arg: &'a str) -> Result<T>
+where
+    T: FromArg<'a>,
+{
+    T::from_arg(arg).map_err(|e| match T::hint() {
+        Some(h) if e.ctx().is_some_and(|c| c.hint.is_none()) => e.hint(h),
+        _ => e,
+    })
 }
 
 /// Default implementation for [`FromArg`] for types that implement [`FromStr`]
@@ -46,19 +147,54 @@ where
     }
 }
 
-impl_all! { impl<'a> FromArg<'a>:
-    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64, usize, isize,
-    bool, char, String, PathBuf, OsString, IpAddr, SocketAddr, Ipv4Addr,
-    Ipv6Addr, SocketAddrV4, SocketAddrV6,
-    => {
+macro_rules! from_str_arg {
+    () => {
         #[inline(always)]
         fn from_arg(arg: &'a str) -> Result<Self> {
             Self::from_str(arg).map_err(|e| {
-                ArgError::FailedToParse(Box::new(
-                    ArgErrCtx::from_inner(e, arg.to_string())
-                ))
+                ArgError::FailedToParse(Box::new(ArgErrCtx::from_inner(
+                    e,
+                    arg.to_string(),
+                )))
             })
         }
+
+        #[inline(always)]
+        fn try_from_arg(arg: &'a str) -> Option<Self> {
+            Self::from_str(arg).ok()
+        }
+    };
+}
+
+impl_all! { impl<'a> FromArg<'a>:
+    u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64, usize, isize,
+    char, String, IpAddr, SocketAddr, Ipv4Addr, Ipv6Addr, SocketAddrV4,
+    SocketAddrV6,
+    => { from_str_arg!(); }
+}
+
+#[cfg(feature = "std")]
+impl_all! { impl<'a> FromArg<'a>: PathBuf, OsString => { from_str_arg!(); } }
+
+impl FromArg<'_> for bool {
+    #[inline(always)]
+    fn from_arg(arg: &str) -> Result<Self> {
+        Self::from_str(arg).map_err(|e| {
+            ArgError::FailedToParse(Box::new(ArgErrCtx::from_inner(
+                e,
+                arg.to_string(),
+            )))
+        })
+    }
+
+    #[inline(always)]
+    fn try_from_arg(arg: &str) -> Option<Self> {
+        Self::from_str(arg).ok()
+    }
+
+    #[inline(always)]
+    fn arg_variants() -> &'static [&'static str] {
+        &["false", "true"]
     }
 }
 
@@ -69,6 +205,7 @@ impl<'a> FromArg<'a> for &'a str {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> FromArg<'a> for &'a Path {
     #[inline(always)]
     fn from_arg(arg: &'a str) -> Result<Self> {
@@ -76,6 +213,7 @@ impl<'a> FromArg<'a> for &'a Path {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> FromArg<'a> for &'a OsStr {
     #[inline(always)]
     fn from_arg(arg: &'a str) -> Result<Self> {
@@ -104,4 +242,28 @@ where
             Ok(Some(T::from_arg(arg)?))
         }
     }
+
+    #[inline]
+    fn try_from_arg(arg: &'a str) -> Option<Self> {
+        if arg.is_empty() {
+            Some(None)
+        } else {
+            T::try_from_arg(arg).map(Some)
+        }
+    }
+
+    #[inline(always)]
+    fn from_missing() -> Option<Self> {
+        Some(None)
+    }
+
+    #[inline(always)]
+    fn arg_variants() -> &'static [&'static str] {
+        T::arg_variants()
+    }
+
+    #[inline(always)]
+    fn hint() -> Option<Cow<'static, str>> {
+        T::hint()
+    }
 }
@@ -1,4 +1,14 @@
-use crate::{reader::Reader, ArgError};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
+
+use crate::{
+    match_prefix, reader::Reader, ArgError, FromArgStr, ReadFmt, Result,
+    SignPolicy,
+};
 
 pub struct ParseResult<T> {
     pub err: Option<ArgError>,
@@ -7,15 +17,54 @@ pub struct ParseResult<T> {
 
 pub trait FromRead: Sized {
     fn from_read(r: &mut Reader) -> ParseResult<Self>;
+
+    /// Reads the value with the given format. The default implementation
+    /// ignores the format.
+    fn from_read_fmt(r: &mut Reader, fmt: &ReadFmt) -> ParseResult<Self> {
+        _ = fmt;
+        Self::from_read(r)
+    }
 }
 
 macro_rules! impl_from_read {
     ($($(-$it:ident)? $($ut:ident)?),* $(,)?) => {
-        $(impl FromRead for $($it)? $($ut)? {
+        $(
+        /// Reads integer. The format may contain sign policy (see
+        /// [`ReadFmt::sign`]) and width (see [`ReadFmt::length_range`])
+        /// followed by the base: `x` (16), `o` (8), `b` (2) or `d` (10).
+        /// Sign counts toward the width. `+` is accepted also for unsigned
+        /// integers.
+        impl FromRead for $($it)? $($ut)? {
             fn from_read(r: &mut Reader) -> ParseResult<Self> {
-                const RADIX: u32 = 10;
+                Self::from_read_fmt(r, &ReadFmt::default())
+            }
+
+            fn from_read_fmt(
+                r: &mut Reader,
+                fmt: &ReadFmt,
+            ) -> ParseResult<Self> {
+                read_width(r, fmt, |r| {
+                    Self::read_radix(r, radix(fmt), fmt.sign())
+                })
+            }
+        }
+
+        impl ReadRadix for $($it)? $($ut)? {
+            fn read_radix(
+                r: &mut Reader,
+                radix: u32,
+                sign: SignPolicy,
+            ) -> ParseResult<Self> {
                 let mut res: Self = 0;
+                let mut digits = false;
+                if let Err(e) = r.peek() {
+                    return ParseResult { err: Some(e), res: None };
+                }
                 let start_pos = r.pos().unwrap_or_default();
+                let neg = match read_sign(r, sign, Self::MIN != 0) {
+                    Ok(n) => n,
+                    Err(e) => return ParseResult { err: Some(e), res: None },
+                };
 
                 macro_rules! unwrap_or_exit {
                     ($v:expr, $msg:literal) => {
@@ -23,7 +72,7 @@ macro_rules! impl_from_read {
                             Some(v) => v,
                             None => return ParseResult {
                                 err: Some(r.err_parse($msg)),
-                                res: Some(res),
+                                res: digits.then_some(res),
                             }
                         }
                     };
@@ -42,13 +91,13 @@ macro_rules! impl_from_read {
                 }
 
                 macro_rules! loop_signed {
-                    ($op:ident, $ignore:ident) => {
+                    ($op:ident) => {
 
                         while let Some(c) = r.peek().transpose() {
-                            let r2 = res.checked_mul(RADIX as Self);
+                            let r2 = res.checked_mul(radix as Self);
                             let d = pass_or_exit!(c);
                             let d = unwrap_or_exit!(
-                                d.to_digit(RADIX),
+                                d.to_digit(radix),
                                 "Invalid digit in string."
                             );
                             res = pass_or_exit!(
@@ -64,26 +113,24 @@ macro_rules! impl_from_read {
                                         ))
                                 )
                             );
+                            digits = true;
                             _ = r.next();
                         }
                     };
                 }
 
-                $(
-                    if matches!(pass_or_exit!(r.peek()), Some('-')) {
-                        pass_or_exit!(r.next().transpose());
-                        loop_signed!(checked_sub, $it);
-                    } else {
-                        loop_signed!(checked_add, $it);
-                    }
-                )?
-
-                $(loop_signed!(checked_add, $ut);)?
+                if neg {
+                    loop_signed!(checked_sub);
+                } else {
+                    loop_signed!(checked_add);
+                }
 
                 ParseResult {
-                    err: None,
-                    res: (start_pos != r.pos().unwrap_or_default())
-                        .then_some(res)
+                    err: (!digits).then(|| {
+                        r.err_parse("Expected at least one digit.")
+                            .main_msg("Unexpected end of string.")
+                    }),
+                    res: digits.then_some(res)
                 }
             }
         })*
@@ -91,3 +138,724 @@ macro_rules! impl_from_read {
 }
 
 impl_from_read!(u8, u16, u32, u64, usize, -i8, -i16, -i32, -i64, -isize);
+
+/// Reads integer with the given radix.
+trait ReadRadix: Sized {
+    fn read_radix(
+        r: &mut Reader,
+        radix: u32,
+        sign: SignPolicy,
+    ) -> ParseResult<Self>;
+}
+
+/// Reads the sign of number if it is allowed by `policy`. Returns whether the
+/// number is negative. `-` is not sign if the number is not `signed`.
+fn read_sign(
+    r: &mut Reader,
+    policy: SignPolicy,
+    signed: bool,
+) -> Result<bool> {
+    let next = r.peek()?;
+    let sign = next.filter(|&c| c == '+' || signed && c == '-');
+    match (sign, policy) {
+        (Some(c), SignPolicy::Forbid) => Err(r
+            .err_parse("Unexpected sign.")
+            .main_msg(format!("Sign `{c}` is not allowed."))
+            .hint("Remove the sign.")),
+        (None, SignPolicy::Require) => {
+            let signs = if signed { "`+` or `-`" } else { "`+`" };
+            let e = r
+                .err_parse("Expected sign.")
+                .main_msg(format!("Number must start with {signs}."));
+            if next.is_some() {
+                return Err(e);
+            }
+            // Point to where the sign should be.
+            let pos = r.read_end();
+            Err(e.spanned(pos..pos + 1))
+        }
+        (Some(c), _) => {
+            _ = r.next();
+            Ok(c == '-')
+        }
+        (None, _) => Ok(false),
+    }
+}
+
+/// Gets the radix of integer from the format.
+fn radix(fmt: &ReadFmt) -> u32 {
+    match fmt.spec() {
+        "x" | "X" => 16,
+        "o" => 8,
+        "b" => 2,
+        _ => 10,
+    }
+}
+
+/// Reads value with `read`. If the format has width (see
+/// [`ReadFmt::length_range`]), at most the maximum number of chars is read
+/// and at least the minimum number of chars must be read. Value that is too
+/// short is hard error that covers the whole expected field.
+fn read_width<T>(
+    r: &mut Reader,
+    fmt: &ReadFmt,
+    read: impl FnOnce(&mut Reader) -> ParseResult<T>,
+) -> ParseResult<T> {
+    let Some(range) = fmt.length_range() else {
+        return read(r);
+    };
+    if let Err(e) = r.peek() {
+        return ParseResult {
+            err: Some(e),
+            res: None,
+        };
+    }
+    let start = r.read_end();
+    let (res, cnt) = r.with_limit(*range.end(), read);
+    // Invalid value is reported by `read` unless the input just ended.
+    let at_end = matches!(r.peek(), Ok(None));
+    if cnt >= *range.start() || res.res.is_none() && !at_end {
+        return res;
+    }
+
+    // The span covers the whole expected field including the missing chars.
+    let min = *range.start();
+    let end = start + r.consumed_since(start) + (min - cnt);
+    ParseResult {
+        err: Some(
+            r.err_parse(format!("Expected {min} characters."))
+                .main_msg(format!(
+                    "Value is too short. It has only {cnt} of {min} \
+                    characters."
+                ))
+                .spanned(start..end)
+                .hint("Pad the value with leading zeros."),
+        ),
+        res: None,
+    }
+}
+
+/// Reads bool. By default accepts (case insensitive) `true`/`false`,
+/// `yes`/`no`, `on`/`off` and `1`/`0`. With the format `strict`, only exactly
+/// `true` and `false` are accepted. The flag `i` (`istrict`) makes it case
+/// insensitive (see [`ReadFmt::case_insensitive`]).
+impl FromRead for bool {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        Self::from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn from_read_fmt(r: &mut Reader, fmt: &ReadFmt) -> ParseResult<Self> {
+        const WORDS: &[(&str, bool)] = &[
+            ("true", true),
+            ("false", false),
+            ("yes", true),
+            ("no", false),
+            ("on", true),
+            ("off", false),
+            ("1", true),
+            ("0", false),
+        ];
+
+        let strict = fmt.spec() == "strict";
+        let exact = strict && !fmt.case_insensitive();
+        let words = if strict { &WORDS[..2] } else { WORDS };
+        let eq = |a: u8, b: char| {
+            if exact {
+                a as char == b
+            } else {
+                (a as char).eq_ignore_ascii_case(&b)
+            }
+        };
+
+        if let Err(e) = r.peek() {
+            return ParseResult {
+                err: Some(e),
+                res: None,
+            };
+        }
+        let start_pos = r.pos().unwrap_or_default();
+        // Bit mask of words that match the read input.
+        let mut cands = (1_u16 << words.len()) - 1;
+        let mut len = 0;
+        let next = loop {
+            let c = match r.peek() {
+                Ok(c) => c,
+                Err(e) => {
+                    return ParseResult {
+                        err: Some(e),
+                        res: None,
+                    }
+                }
+            };
+            let matching = c.map_or(0, |c| {
+                words.iter().enumerate().fold(0, |m, (i, (w, _))| {
+                    let hit = cands & (1 << i) != 0
+                        && w.as_bytes().get(len).is_some_and(|&a| eq(a, c));
+                    m | ((hit as u16) << i)
+                })
+            });
+            if matching == 0 {
+                break c;
+            }
+            cands = matching;
+            len += 1;
+            _ = r.next();
+        };
+
+        let value = words
+            .iter()
+            .enumerate()
+            .find(|(i, (w, _))| cands & (1 << i) != 0 && w.len() == len)
+            .map(|(_, (_, v))| *v);
+
+        if let Some(v) = value {
+            return ParseResult {
+                err: next.map(|c| {
+                    r.err_parse("Expected end of bool value.")
+                        .main_msg(format!("Unexpected character `{c}`."))
+                }),
+                res: Some(v),
+            };
+        }
+
+        let msg = match next {
+            Some(c) => format!("Unexpected character `{c}`."),
+            None => "Unexpected end of string.".to_string(),
+        };
+
+        if len == 0 {
+            let hint = if strict {
+                "Valid values are `true` and `false`."
+            } else {
+                "Valid values are `true`, `false`, `yes`, `no`, `on`, `off`, \
+                `1` and `0`."
+            };
+            return ParseResult {
+                err: Some(
+                    r.err_parse("Expected bool value.")
+                        .main_msg(msg)
+                        .hint(hint),
+                ),
+                res: None,
+            };
+        }
+
+        let mut expected: Vec<_> = words
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| cands & (1 << i) != 0)
+            .filter_map(|(_, (w, _))| w.get(len..=len))
+            .map(|c| format!("`{c}`"))
+            .collect();
+        expected.dedup();
+
+        ParseResult {
+            err: Some(
+                r.err_parse(format!("Expected {}.", expected.join(" or ")))
+                    .main_msg(msg)
+                    .span_start(start_pos),
+            ),
+            res: None,
+        }
+    }
+}
+
+macro_rules! impl_float_from_read {
+    ($($t:ident),* $(,)?) => {
+        $(
+        /// Reads float in the format `[+-]digits[.digits][(e|E)[+-]digits]`.
+        /// At least one digit is required in the mantissa. The value is
+        /// converted the same way as with [`str::parse`], so the result is
+        /// always correctly rounded. Values that are too large are infinity
+        /// and values that are too small are zero (keeping the sign).
+        ///
+        /// The format may contain sign policy of the mantissa (see
+        /// [`ReadFmt::sign`]) and width (see [`ReadFmt::length_range`]).
+        impl FromRead for $t {
+            fn from_read(r: &mut Reader) -> ParseResult<Self> {
+                float_from_read(r, SignPolicy::Allow)
+            }
+
+            fn from_read_fmt(
+                r: &mut Reader,
+                fmt: &ReadFmt,
+            ) -> ParseResult<Self> {
+                read_width(r, fmt, |r| float_from_read(r, fmt.sign()))
+            }
+        }
+        )*
+    };
+}
+
+impl_float_from_read!(f32, f64);
+
+fn float_from_read<T: FromStr>(
+    r: &mut Reader,
+    sign: SignPolicy,
+) -> ParseResult<T> {
+    macro_rules! pass_or_exit {
+        ($v:expr) => {
+            match $v {
+                Ok(r) => r,
+                Err(e) => {
+                    return ParseResult {
+                        err: Some(e),
+                        res: None,
+                    }
+                }
+            }
+        };
+    }
+
+    fn read_digits(r: &mut Reader, s: &mut String) -> Result<usize> {
+        let mut cnt = 0;
+        while let Some(c) = r.peek()?.filter(char::is_ascii_digit) {
+            s.push(c);
+            cnt += 1;
+            _ = r.next();
+        }
+        Ok(cnt)
+    }
+
+    let mut s = String::new();
+
+    pass_or_exit!(r.peek());
+    let start_pos = r.pos().unwrap_or_default();
+    if pass_or_exit!(read_sign(r, sign, true)) {
+        s.push('-');
+    }
+
+    let mut digits = pass_or_exit!(read_digits(r, &mut s));
+    if pass_or_exit!(r.peek()) == Some('.') {
+        s.push('.');
+        _ = r.next();
+        digits += pass_or_exit!(read_digits(r, &mut s));
+    }
+
+    if digits == 0 {
+        let msg = if pass_or_exit!(r.peek()).is_some() {
+            "Invalid digit in string."
+        } else {
+            "Unexpected end of string."
+        };
+        return ParseResult {
+            err: Some(
+                r.err_parse("Expected at least one digit.")
+                    .main_msg(msg)
+                    .span_start(start_pos),
+            ),
+            res: None,
+        };
+    }
+
+    if let Some(c @ ('e' | 'E')) = pass_or_exit!(r.peek()) {
+        s.push(c);
+        _ = r.next();
+        if let Some(c @ ('+' | '-')) = pass_or_exit!(r.peek()) {
+            s.push(c);
+            _ = r.next();
+        }
+        if pass_or_exit!(read_digits(r, &mut s)) == 0 {
+            // Point to where the digits should be.
+            let pos = r.read_end();
+            return ParseResult {
+                err: Some(
+                    r.err_parse("Expected exponent digits.")
+                        .main_msg("Missing exponent.")
+                        .spanned(pos..pos + 1),
+                ),
+                res: None,
+            };
+        }
+    }
+
+    let Ok(res) = s.parse() else {
+        return ParseResult {
+            err: Some(r.err_parse("Invalid float.").span_start(start_pos)),
+            res: None,
+        };
+    };
+
+    ParseResult {
+        err: pass_or_exit!(r.peek())
+            .map(|_| r.err_parse("Invalid digit in string.")),
+        res: Some(res),
+    }
+}
+
+macro_rules! impl_tuple_from_read {
+    ($($t:ident),+ ; $last:ident) => {
+        /// Reads tuple with the elements separated by the format string (`,`
+        /// by default).
+        #[allow(non_snake_case)]
+        impl<$($t: FromRead,)+ $last: FromRead> FromRead
+            for ($($t,)+ $last)
+        {
+            fn from_read(r: &mut Reader) -> ParseResult<Self> {
+                Self::from_read_fmt(r, &ReadFmt::default())
+            }
+
+            fn from_read_fmt(
+                r: &mut Reader,
+                fmt: &ReadFmt,
+            ) -> ParseResult<Self> {
+                let sep = if fmt.is_empty() { "," } else { fmt.custom() };
+                $(
+                    let $t = match read_elem::<$t>(r) {
+                        Ok(v) => v,
+                        Err(e) => return ParseResult {
+                            err: Some(e),
+                            res: None,
+                        },
+                    };
+                    if let Err(e) = match_prefix(sep, r) {
+                        return ParseResult { err: Some(e), res: None };
+                    }
+                )+
+
+                _ = r.peek();
+    let start = r.pos().unwrap_or_default();
+                let $last = $last::from_read(r);
+                ParseResult {
+                    err: $last.err.or_else(|| {
+                        $last.res.is_none().then(|| {
+                            r.err_parse("Failed to parse tuple element.")
+                                .span_start(start)
+                        })
+                    }),
+                    res: $last.res.map(|$last| ($($t,)+ $last)),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple_from_read!(A; B);
+impl_tuple_from_read!(A, B; C);
+impl_tuple_from_read!(A, B, C; D);
+impl_tuple_from_read!(A, B, C, D; E);
+impl_tuple_from_read!(A, B, C, D, E; F);
+
+/// Reads value that is not the last in a sequence. Soft errors are ignored.
+pub(crate) fn read_elem<T: FromRead>(r: &mut Reader) -> Result<T> {
+    _ = r.peek();
+    let start = r.pos().unwrap_or_default();
+    let res = T::from_read(r);
+    res.res.ok_or_else(|| {
+        res.err.unwrap_or_else(|| {
+            r.err_parse("Failed to parse tuple element.")
+                .span_start(start)
+        })
+    })
+}
+
+/// Bridge that implements [`FromArgStr`] for types that implement
+/// [`FromRead`] (e.g. tuples). The whole argument must be consumed.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{Pareg, Tuple};
+///
+/// let args = ["800,600", "1,2,3"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+///
+/// let Tuple(size) = args.next_arg::<Tuple<(u16, u16)>>().unwrap();
+/// assert_eq!((800, 600), size);
+/// assert!(args.next_arg::<Tuple<(u16, u16)>>().is_err());
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Tuple<T>(pub T);
+
+impl<T: FromRead> FromStr for Tuple<T> {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut r: Reader = s.into();
+        let res = T::from_read(&mut r);
+        let Some(v) = res.res else {
+            return Err(res.err.unwrap_or_else(|| {
+                r.err_parse("Failed to parse argument.").span_start(0)
+            }));
+        };
+        if r.peek()?.is_some() {
+            Err(res.err.unwrap_or_else(|| r.err_parse("Unused input.")))
+        } else {
+            Ok(Self(v))
+        }
+    }
+}
+
+impl<T: FromRead> FromArgStr for Tuple<T> {}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn read<T: FromRead>(s: &str) -> Option<T> {
+        let res = T::from_read(&mut s.into());
+        assert!(res.err.is_none(), "{s}");
+        res.res
+    }
+
+    #[test]
+    fn float_special() {
+        assert_eq!(read::<f64>("1e400"), Some(f64::INFINITY));
+        assert_eq!(read::<f64>("-1e400"), Some(f64::NEG_INFINITY));
+        assert_eq!(read::<f32>("1e39"), Some(f32::INFINITY));
+        assert_eq!(read::<f64>("1e99999999999999999999"), Some(f64::INFINITY));
+
+        let v = read::<f64>("1e-400").unwrap();
+        assert_eq!(v.to_bits(), 0.0_f64.to_bits());
+        let v = read::<f64>("-1e-400").unwrap();
+        assert_eq!(v.to_bits(), (-0.0_f64).to_bits());
+        let v = read::<f64>("-0.0").unwrap();
+        assert_eq!(v.to_bits(), (-0.0_f64).to_bits());
+        let v = read::<f32>("-0").unwrap();
+        assert_eq!(v.to_bits(), (-0.0_f32).to_bits());
+
+        let s = "0.0000000000000000000000001e25";
+        assert_eq!(read::<f64>(s), s.parse().ok());
+        assert_eq!(read::<f64>("4.9e-324"), Some(4.9e-324));
+        assert_eq!(read::<f64>(".5"), Some(0.5));
+        assert_eq!(read::<f64>("5."), Some(5.));
+    }
+
+    #[test]
+    fn float_invalid() {
+        for s in [".", "-", "", "e5", "1e", "1e+"] {
+            let res = f64::from_read(&mut s.into());
+            assert!(res.res.is_none(), "{s}");
+            assert!(res.err.is_some(), "{s}");
+        }
+
+        let res = f64::from_read(&mut "1.5x".into());
+        assert_eq!(res.res, Some(1.5));
+        assert!(res.err.is_some());
+    }
+
+    #[test]
+    fn float_invalid_spans() {
+        let span = |s: &str| {
+            let res = f64::from_read(&mut s.into());
+            assert!(res.res.is_none(), "{s}");
+            let Some(ArgError::FailedToParse(ctx)) = res.err else {
+                panic!("Invalid error kind for `{s}`.");
+            };
+            (ctx.message.into_owned(), ctx.error_span)
+        };
+        let digit = "Expected at least one digit.".to_string();
+        let exp = "Expected exponent digits.".to_string();
+
+        assert_eq!(span("."), (digit.clone(), 0..1));
+        assert_eq!(span("-"), (digit.clone(), 0..1));
+        assert_eq!(span("+.e1"), (digit.clone(), 0..3));
+        assert_eq!(span("e5"), (digit, 0..1));
+        assert_eq!(span("1e"), (exp.clone(), 2..3));
+        assert_eq!(span("1e+"), (exp.clone(), 3..4));
+        assert_eq!(span("1.5E-x"), (exp, 5..6));
+    }
+
+    #[test]
+    fn tuple() {
+        let res = <(u32, f32)>::from_read(&mut "800,0.5".into());
+        assert!(res.err.is_none());
+        assert_eq!(res.res, Some((800, 0.5)));
+
+        let mut r: Reader = "1;-2;true".into();
+        let res = r.parse::<(u8, i64, bool)>(&";".into()).unwrap();
+        assert_eq!(res, (1, -2, true));
+
+        let mut r: Reader = "1 - 2".into();
+        assert_eq!((1, 2), r.parse::<(u8, u8)>(&" - ".into()).unwrap());
+
+        let res = <(u8, u8, u8)>::from_read(&mut "1,2,3x".into());
+        assert_eq!(res.res, Some((1, 2, 3)));
+        assert!(res.err.is_some());
+        assert!("1,2,3x".parse::<Tuple<(u8, u8, u8)>>().is_err());
+        assert_eq!(
+            Tuple((1, 2, 3)),
+            "1,2,3".parse::<Tuple<(u8, u8, u8)>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn tuple_spans() {
+        let span = |s: &str| {
+            let err = s.parse::<Tuple<(u16, u16)>>().unwrap_err();
+            let ArgError::FailedToParse(ctx) = err else {
+                panic!("Invalid error kind.");
+            };
+            ctx.error_span
+        };
+
+        assert_eq!(span("800,x"), 4..5);
+        assert_eq!(span("800,"), 3..4);
+        assert_eq!(span("800,70000"), 4..9);
+        assert_eq!(span("800;600"), 3..4);
+        assert_eq!(span("800,600,"), 7..8);
+        assert_eq!(span("x,600"), 0..1);
+    }
+
+    #[test]
+    fn sign_policy() {
+        let read = |s: &str, fmt: &str| {
+            let mut r: Reader = s.into();
+            let res = r.parse::<i32>(&fmt.into());
+            res.map_err(|e| e.span().unwrap())
+        };
+        assert_eq!(read("+5", ""), Ok(5));
+        assert_eq!(read("-5", "d"), Ok(-5));
+        assert_eq!(read("+5", "+"), Ok(5));
+        assert_eq!(read("-ff", "+x"), Ok(-255));
+        assert_eq!(read("5", "+"), Err(0..1));
+        assert_eq!(read("", "+"), Err(0..1));
+        assert_eq!(read("-5", "-"), Err(0..1));
+        assert_eq!(read("+5", "-d"), Err(0..1));
+        assert_eq!(read("ff", "-x"), Ok(255));
+
+        // The sign counts toward the width.
+        assert_eq!(read("+0421", "+4"), Ok(42));
+        assert_eq!(read("0421", "-4"), Ok(421));
+        assert_eq!(read("-1011", "+..3b"), Ok(-2));
+        assert_eq!(read("12", "+..3"), Err(0..1));
+        assert_eq!(read("+12", "-..3"), Err(0..1));
+        assert_eq!(read("+7", "+4"), Err(0..4));
+
+        let mut r: Reader = "+5 -5 5".into();
+        assert_eq!(r.parse::<u8>(&"+".into()).unwrap(), 5);
+        _ = r.next();
+        let err = r.parse::<u8>(&"+".into()).unwrap_err();
+        assert_eq!(err.long_message(), Some("Number must start with `+`."));
+        assert_eq!(err.span(), Some(3..4));
+        _ = r.next();
+        _ = r.next();
+        _ = r.next();
+        assert_eq!(r.parse::<u8>(&"-".into()).unwrap(), 5);
+
+        let float = |s: &str, fmt: &str| {
+            let mut r: Reader = s.into();
+            r.parse::<f64>(&fmt.into()).map_err(|e| e.span().unwrap())
+        };
+        assert_eq!(float("-1.5e-1", "+"), Ok(-0.15));
+        assert_eq!(float("1.5e+1", "-"), Ok(15.));
+        assert_eq!(float("1.5", "+"), Err(0..1));
+        assert_eq!(float("+1.5", "-3"), Err(0..1));
+        assert_eq!(float("+1.55", "+4"), Ok(1.5));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let read = |s: &str, fmt: &str| {
+            let mut r: Reader = s.into();
+            r.parse::<bool>(&fmt.into()).ok()
+        };
+        assert_eq!(read("TRUE", "strict"), None);
+        assert_eq!(read("TRUE", "istrict"), Some(true));
+        assert_eq!(read("False", "+istrict"), Some(false));
+        assert_eq!(read("YES", "istrict"), None);
+        assert_eq!(read("YES", "i"), Some(true));
+        assert_eq!(read("Off", ""), Some(false));
+
+        // The flag doesn't change numbers.
+        let mut r: Reader = "fF1".into();
+        assert_eq!(r.parse::<u32>(&"i2x".into()).unwrap(), 255);
+        assert_eq!(r.parse::<u32>(&"i".into()).unwrap(), 1);
+    }
+
+    #[test]
+    fn width() {
+        let read = |s: &str, fmt: &str| {
+            let mut r: Reader = s.into();
+            let res = r.parse::<i32>(&fmt.into());
+            (res.map_err(|e| e.span().unwrap()), r.peek().unwrap())
+        };
+        assert_eq!(read("0420-0069", "4"), (Ok(420), Some('-')));
+        assert_eq!(read("12345", "..3"), (Ok(123), Some('4')));
+        assert_eq!(read("12", "..3"), (Ok(12), None));
+        assert_eq!(read("-042", "4"), (Ok(-42), None));
+        assert_eq!(read("-0420", "4"), (Ok(-42), Some('0')));
+        assert_eq!(read("ff7", "2x"), (Ok(255), Some('7')));
+        assert_eq!(read("-f", "2X"), (Ok(-15), None));
+        assert_eq!(read("1017", "3b"), (Ok(5), Some('7')));
+
+        // Under width. The span covers the whole expected field.
+        assert_eq!(read("42", "4"), (Err(0..4), None));
+        assert_eq!(read("-42-", "4"), (Err(0..4), Some('-')));
+        assert_eq!(read("42x5", "4"), (Err(0..4), Some('x')));
+        assert_eq!(read("-", "4").0, Err(0..4));
+        assert_eq!(read("1x", "2"), (Err(0..2), Some('x')));
+
+        let mut r: Reader = "2.71159".into();
+        assert_eq!(r.parse::<f64>(&"4".into()).unwrap(), 2.71);
+        assert_eq!(r.parse::<u8>(&"..1".into()).unwrap(), 1);
+        assert_eq!(r.parse::<u16>(&"".into()).unwrap(), 59);
+        let mut r: Reader = "-1.5".into();
+        assert_eq!(r.parse::<f32>(&"..3".into()).unwrap(), -1.);
+        assert!(r.parse::<f32>(&"2".into()).is_err());
+    }
+
+    fn float_str() -> impl Strategy<Value = String> {
+        (
+            "[+-]?",
+            "[0-9]{0,30}",
+            proptest::option::of("[0-9]{0,30}"),
+            proptest::option::of(("[eE][+-]?", -400..=400_i32)),
+        )
+            .prop_filter_map("no digits", |(sign, int, frac, exp)| {
+                if int.is_empty() && frac.as_ref().is_none_or(|f| f.is_empty())
+                {
+                    return None;
+                }
+                let mut s = sign + &int;
+                if let Some(f) = frac {
+                    s += ".";
+                    s += &f;
+                }
+                if let Some((e, v)) = exp {
+                    s += &e[..1];
+                    if e.len() == 2 && v >= 0 {
+                        s += &e[1..];
+                    }
+                    s += &v.to_string();
+                }
+                Some(s)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn float_matches_std(s in float_str()) {
+            let f = f64::from_read(&mut s.as_str().into());
+            prop_assert!(f.err.is_none());
+            prop_assert_eq!(
+                f.res.map(f64::to_bits),
+                s.parse::<f64>().ok().map(f64::to_bits)
+            );
+
+            let f = f32::from_read(&mut s.as_str().into());
+            prop_assert!(f.err.is_none());
+            prop_assert_eq!(
+                f.res.map(f32::to_bits),
+                s.parse::<f32>().ok().map(f32::to_bits)
+            );
+        }
+
+        #[test]
+        fn float_subnormal(m in 1..=9_u32, frac in "[0-9]{0,20}", e in 300..=330_u32) {
+            let s = format!("{m}.{frac}e-{e}");
+            prop_assert_eq!(
+                f64::from_read(&mut s.as_str().into()).res.map(f64::to_bits),
+                s.parse::<f64>().ok().map(f64::to_bits)
+            );
+            let s = format!("{m}.{frac}e-{}", e / 8);
+            prop_assert_eq!(
+                f32::from_read(&mut s.as_str().into()).res.map(f32::to_bits),
+                s.parse::<f32>().ok().map(f32::to_bits)
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    io::Read,
+    ops::{Range, RangeInclusive},
+};
+
+use crate::{
+    ArgErrCtx, ArgError, ByteSize, ColorMode, FromRead, Percent, Reader,
+    SimpleDate, SimpleTime,
+};
+
+/// Reads the type with [`FromRead`] and returns the error if any.
+macro_rules! read_entry {
+    ($t:ty) => {
+        |r| <$t as FromRead>::from_read(r).err
+    };
+}
+
+/// Types from pareg and std that implement [`FromRead`].
+const READ_BUILTIN: &[fn(&mut Reader) -> Option<ArgError>] = &[
+    read_entry!(u8),
+    read_entry!(i8),
+    read_entry!(u16),
+    read_entry!(i16),
+    read_entry!(u32),
+    read_entry!(i32),
+    read_entry!(u64),
+    read_entry!(i64),
+    read_entry!(usize),
+    read_entry!(isize),
+    read_entry!(f32),
+    read_entry!(f64),
+    read_entry!(bool),
+    read_entry!((i32, f64)),
+    read_entry!((bool, u8, u16)),
+    read_entry!(Range<i32>),
+    read_entry!(RangeInclusive<f64>),
+    read_entry!(SimpleDate),
+    read_entry!(SimpleTime),
+    read_entry!(Percent),
+    read_entry!(ByteSize),
+];
+
+/// Fuzz harness for [`FromRead`]. The first byte selects the type and the
+/// source of the [`Reader`] (string, bytes or chunks) and the rest is read.
+/// Invalid UTF-8 is passed to the reader from bytes as is. Errors are also
+/// rendered.
+///
+/// # Examples
+/// ```rust
+/// pareg_core::fuzz_read(b"\x00255");
+/// pareg_core::fuzz_read(b"\x01\xf4\x80");
+/// pareg_core::fuzz_read(b"");
+/// ```
+pub fn fuzz_read(data: &[u8]) {
+    let Some((sel, input)) = data.split_first() else {
+        return;
+    };
+    let sel = *sel as usize;
+    let text = String::from_utf8_lossy(input);
+    let mut r: Reader = match sel % 3 {
+        0 => text.as_ref().into(),
+        1 => (Box::new(input) as Box<dyn Read>).into(),
+        _ => Reader::from_chunks(text.split(' '), Some(' ')),
+    };
+    let read = READ_BUILTIN[sel / 3 % READ_BUILTIN.len()];
+    if let Some(e) = read(&mut r) {
+        _ = e.no_color().to_string();
+    }
+    _ = (r.pos(), r.bytes_size_hint(), r.peek());
+}
+
+/// Fuzz harness for rendering [`ArgErrCtx`]. The first 4 bytes are the index
+/// of the errornous argument, start of the span, signed length of the span
+/// and the maximum width. The rest is split by `\0` into the arguments and
+/// it is also used as the hint. The error is rendered with and without
+/// color.
+///
+/// # Examples
+/// ```rust
+/// pareg_core::fuzz_render("\x01\x02\x7f\x00prog\0--n=日本".as_bytes());
+/// pareg_core::fuzz_render(b"\xff\xff\x7f\xff");
+/// ```
+pub fn fuzz_render(data: &[u8]) {
+    let Some((&[idx, start, len, width], rest)) = data.split_first_chunk()
+    else {
+        return;
+    };
+    let text = String::from_utf8_lossy(rest);
+    let start = start as usize * 4;
+    let end = start.saturating_add_signed(len as i8 as isize * 4);
+
+    let mut ctx = ArgErrCtx::from_msg(text.to_string(), String::new());
+    ctx.args = text.split('\0').map(|a| a.to_string().into()).collect();
+    ctx.error_idx = idx as usize;
+    ctx.error_span = start..end;
+    ctx.max_width = Some(width as usize);
+    ctx.hint = Some(text.to_string().into());
+    if width % 2 == 1 {
+        ctx = ctx
+            .owner(text.to_string())
+            .value_of(len as usize)
+            .program(text.to_string())
+            .usage(text.to_string())
+            .origins(vec![None, Some(text.to_string().into())]);
+    }
+    _ = ctx.to_string();
+    _ = format!("{:#}", ctx.color_mode(ColorMode::Always));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_regressions() {
+        for sel in 0..=u8::MAX {
+            fuzz_read(&[sel]);
+            fuzz_read(&[sel, b'-']);
+            fuzz_read(&[sel, 0xc3]);
+            fuzz_read(&[sel, 0xf4, 0x80, b'1']);
+            fuzz_read(&[sel, b'1', b'e', b'-', b'9', b'9', b'9', b'9']);
+            fuzz_read(&[sel, b'9', b'9', b'9', b'9', b'-', b'0', b'2']);
+            fuzz_read(&[sel, b'2', b'.', b' ', b'.', b'3']);
+        }
+    }
+
+    #[test]
+    fn render_regressions() {
+        fuzz_render(b"");
+        fuzz_render(b"\xff\xff\x80\x00");
+        fuzz_render(b"\x00\xff\x7f\x01\xe6\x97\xa5");
+        fuzz_render(b"\x01\x00\x00\xff\0\0\0");
+        fuzz_render(
+            &[&[0, 1, 100, 31][..], "日".repeat(100).as_bytes()].concat(),
+        );
+    }
+}
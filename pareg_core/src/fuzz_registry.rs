@@ -0,0 +1,332 @@
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    net::{
+        IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6,
+    },
+    ops::{Range, RangeFrom, RangeInclusive, RangeTo},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+use crate::Result;
+
+/// One type registered for fuzzing. See [`crate::register_from_arg`].
+#[derive(Debug, Clone, Copy)]
+pub struct FuzzEntry {
+    /// Name of the type as it is written in the registration.
+    pub name: &'static str,
+    /// Gets the full name of the type ([`core::any::type_name`]). Each type
+    /// is registered only once by this name.
+    pub type_name: fn() -> &'static str,
+    /// Parses the string as the type and discards the value.
+    pub parse: fn(&str) -> Result<()>,
+}
+
+/// Creates [`FuzzEntry`] for the given type.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! fuzz_entry {
+    ($t:ty) => {
+        $crate::FuzzEntry {
+            name: stringify!($t),
+            type_name: ::core::any::type_name::<$t>,
+            parse: |s| <$t as $crate::FromArg>::from_arg(s).map(|_| ()),
+        }
+    };
+}
+
+/// Registers types that implement [`crate::FromArg`] so that they can be fuzzed with
+/// [`crate::fuzz_one`]. Registering the same type twice has no effect.
+///
+/// The registration happens when the macro is executed, so call it at the
+/// start of your fuzz target.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{fuzz_registry, register_from_arg, FromArg, Result};
+///
+/// struct Even(u32);
+///
+/// impl FromArg<'_> for Even {
+///     fn from_arg(arg: &str) -> Result<Self> {
+///         Ok(Self(u32::from_arg(arg)? & !1))
+///     }
+/// }
+///
+/// register_from_arg!(Even);
+/// let entry = fuzz_registry()
+///     .into_iter()
+///     .find(|e| e.name == "Even")
+///     .unwrap();
+/// assert!((entry.parse)("4").is_ok());
+/// assert!((entry.parse)("four").is_err());
+/// ```
+#[macro_export]
+macro_rules! register_from_arg {
+    ($($t:ty),* $(,)?) => {
+        $($crate::register_fuzz_entry($crate::fuzz_entry!($t));)*
+    };
+}
+
+static REGISTRY: Mutex<Vec<FuzzEntry>> = Mutex::new(vec![]);
+
+/// Types from pareg and std that implement [`crate::FromArg`]. Types that
+/// are not here are in `tests::NOT_FUZZED` with the reason.
+const BUILTIN: &[FuzzEntry] = &[
+    fuzz_entry!(u8),
+    fuzz_entry!(i8),
+    fuzz_entry!(u16),
+    fuzz_entry!(i16),
+    fuzz_entry!(u32),
+    fuzz_entry!(i32),
+    fuzz_entry!(u64),
+    fuzz_entry!(i64),
+    fuzz_entry!(u128),
+    fuzz_entry!(i128),
+    fuzz_entry!(usize),
+    fuzz_entry!(isize),
+    fuzz_entry!(f32),
+    fuzz_entry!(f64),
+    fuzz_entry!(bool),
+    fuzz_entry!(char),
+    fuzz_entry!(String),
+    fuzz_entry!(PathBuf),
+    fuzz_entry!(OsString),
+    fuzz_entry!(IpAddr),
+    fuzz_entry!(SocketAddr),
+    fuzz_entry!(Ipv4Addr),
+    fuzz_entry!(Ipv6Addr),
+    fuzz_entry!(SocketAddrV4),
+    fuzz_entry!(SocketAddrV6),
+    fuzz_entry!(&str),
+    fuzz_entry!(&Path),
+    fuzz_entry!(&OsStr),
+    fuzz_entry!(Arc<str>),
+    fuzz_entry!(Rc<str>),
+    fuzz_entry!(Cow<str>),
+    fuzz_entry!(Option<u32>),
+    fuzz_entry!(crate::Tuple<(i32, f64)>),
+    fuzz_entry!(crate::Tuple<(bool, u8, u16)>),
+    fuzz_entry!(crate::SimpleDate),
+    fuzz_entry!(crate::SimpleTime),
+    fuzz_entry!(crate::Percent),
+    fuzz_entry!(crate::ByteSize),
+    fuzz_entry!(crate::HexBytes),
+    fuzz_entry!(crate::B64Bytes),
+    fuzz_entry!(crate::B64UrlBytes),
+    fuzz_entry!(crate::QuotedString),
+    fuzz_entry!(crate::MaybeQuoted),
+    fuzz_entry!(Range<i32>),
+    fuzz_entry!(Range<f64>),
+    fuzz_entry!(RangeInclusive<u8>),
+    fuzz_entry!(RangeFrom<u32>),
+    fuzz_entry!(RangeTo<i64>),
+    fuzz_entry!(crate::check::Measured<f64>),
+    fuzz_entry!(crate::completion::Shell),
+];
+
+/// Adds the entry to the registry. Use [`crate::register_from_arg`] instead.
+#[doc(hidden)]
+pub fn register_fuzz_entry(entry: FuzzEntry) {
+    let name = (entry.type_name)();
+    let mut reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    if reg.iter().all(|e| (e.type_name)() != name) {
+        reg.push(entry);
+    }
+}
+
+/// Gets all the types that may be fuzzed. This contains the types from pareg
+/// followed by the types registered with [`crate::register_from_arg`].
+pub fn fuzz_registry() -> Vec<FuzzEntry> {
+    let reg = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    BUILTIN.iter().chain(reg.iter()).copied().collect()
+}
+
+/// Fuzz harness. The first byte selects the type from [`fuzz_registry`] and
+/// the rest is parsed as the type. Errors are also rendered.
+///
+/// # Examples
+/// ```rust
+/// pareg_core::fuzz_one(b"\x05-123");
+/// pareg_core::fuzz_one(b"\xff\xfe");
+/// pareg_core::fuzz_one(b"");
+/// ```
+pub fn fuzz_one(data: &[u8]) {
+    let Some((sel, input)) = data.split_first() else {
+        return;
+    };
+    let reg = fuzz_registry();
+    let entry = reg[*sel as usize % reg.len()];
+    let input = String::from_utf8_lossy(input);
+    if let Err(e) = (entry.parse)(&input) {
+        _ = e.no_color().to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sources with the implementations of [`crate::FromArg`].
+    const SOURCES: &[&str] = &[
+        include_str!("from_arg.rs"),
+        include_str!("bytes.rs"),
+        include_str!("quoted.rs"),
+        include_str!("ranges.rs"),
+        include_str!("datetime.rs"),
+        include_str!("units.rs"),
+        include_str!("from_read.rs"),
+        include_str!("stdin.rs"),
+        include_str!("completion.rs"),
+        include_str!("check/unit.rs"),
+        include_str!("check/path.rs"),
+    ];
+
+    /// Types that implement [`crate::FromArg`], but are not fuzzed and why.
+    const NOT_FUZZED: &[(&str, &str)] = &[
+        ("T", "Blanket implementation for `FromArgStr`."),
+        ("StdinOr", "Reads stdin for `-`."),
+        ("ExistingPath", "Checks the filesystem."),
+        ("ExistingFile", "Checks the filesystem."),
+        ("ExistingDir", "Checks the filesystem."),
+        ("CreatableFile", "Checks the filesystem."),
+    ];
+
+    /// Gets the name of the type without the path, references and generic
+    /// arguments (`&'a crate::Tuple<T>` is `Tuple`).
+    fn base_name(t: &str) -> &str {
+        let t = t.trim().trim_start_matches('&');
+        let t = t
+            .strip_prefix('\'')
+            .map_or(t, |t| t.split_once(' ').map_or(t, |(_, t)| t));
+        let t = t.split(['<', '>', ' ', '{']).next().unwrap_or_default();
+        t.rsplit("::").next().unwrap_or_default()
+    }
+
+    /// Finds the names of the types that implement [`crate::FromArg`] in
+    /// the sources.
+    fn implemented() -> Vec<&'static str> {
+        let mut res = vec![];
+        for src in SOURCES {
+            let mut lines = src.lines().map(str::trim);
+            while let Some(line) = lines.next() {
+                if line.starts_with("//") {
+                    continue;
+                }
+                let single = ["FromArgStr for ", "> for "]
+                    .iter()
+                    .filter(|_| line.starts_with("impl"))
+                    .filter(|_| line.contains("FromArg"))
+                    .find_map(|p| line.split_once(p).map(|(_, t)| t));
+                if let Some(t) = single {
+                    res.push(base_name(t));
+                } else if let Some((_, first)) =
+                    line.split_once("impl<'a> FromArg<'a>:")
+                {
+                    // The list in `impl_all!`.
+                    let mut line = first;
+                    loop {
+                        let list = line.split("=>").next().unwrap_or(line);
+                        res.extend(
+                            list.split(',')
+                                .map(base_name)
+                                .filter(|t| !t.is_empty()),
+                        );
+                        if line.contains("=>") {
+                            break;
+                        }
+                        let Some(l) = lines.next() else {
+                            break;
+                        };
+                        line = l;
+                    }
+                } else if line.starts_with("impl_range!(") {
+                    let mut rest =
+                        lines.by_ref().skip_while(|l| l.starts_with("///"));
+                    let t = rest.next().unwrap_or_default();
+                    res.push(base_name(t.split(',').next().unwrap_or(t)));
+                } else if line.starts_with("impl_path_check!(") {
+                    for line in lines.by_ref().take_while(|l| *l != ");") {
+                        res.extend(
+                            line.split_once("=>").map(|(t, _)| t.trim()),
+                        );
+                    }
+                }
+            }
+        }
+        res.retain(|t| !t.starts_with('$'));
+        res
+    }
+
+    #[test]
+    fn builtin_complete() {
+        let types = implemented();
+        assert!(types.contains(&"u8") && types.contains(&"RangeTo"));
+        for t in types {
+            let fuzzed = BUILTIN.iter().any(|e| base_name(e.name) == t);
+            let exempt = NOT_FUZZED.iter().any(|(n, _)| *n == t);
+            assert!(
+                fuzzed || exempt,
+                "`{t}` implements `FromArg`, but it is not in `BUILTIN`."
+            );
+        }
+    }
+
+    #[test]
+    fn builtin_smoke() {
+        let reg = fuzz_registry();
+        assert!(reg.len() >= BUILTIN.len());
+        for (i, e) in reg.iter().enumerate() {
+            _ = (e.parse)("");
+            _ = (e.parse)("1");
+            _ = (e.parse)("\u{1F600}:,x");
+            fuzz_one(&[i as u8, b'-', b'1']);
+        }
+        let i32_entry = reg.iter().find(|e| e.name == "i32").unwrap();
+        assert!((i32_entry.parse)("-5").is_ok());
+        assert!((i32_entry.parse)("five").is_err());
+    }
+
+    #[test]
+    fn register() {
+        use crate::FromArg;
+
+        struct Custom;
+
+        impl FromArg<'_> for Custom {
+            fn from_arg(arg: &str) -> Result<Self> {
+                bool::from_arg(arg).map(|_| Self)
+            }
+        }
+
+        mod other {
+            use crate::{FromArg, Result};
+
+            pub struct Custom;
+
+            impl FromArg<'_> for Custom {
+                fn from_arg(arg: &str) -> Result<Self> {
+                    u8::from_arg(arg).map(|_| Self)
+                }
+            }
+
+            pub fn register() {
+                crate::register_from_arg!(Custom);
+            }
+        }
+
+        type Alias = Custom;
+
+        crate::register_from_arg!(Custom, Alias);
+        other::register();
+        let reg = fuzz_registry();
+        let custom: Vec<_> =
+            reg.iter().filter(|e| e.name == "Custom").collect();
+        assert_eq!(custom.len(), 2);
+        assert!((custom[0].parse)("true").is_ok());
+        assert!((custom[1].parse)("true").is_err());
+        assert!((custom[1].parse)("5").is_ok());
+    }
+}
@@ -0,0 +1,270 @@
+use std::{fs, path::is_separator};
+
+/// What to do with pattern that doesn't match any file (see
+/// [`crate::Pareg::expand_globs`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GlobMode {
+    /// Keep the pattern as argument.
+    #[default]
+    Keep,
+    /// Return error that points to the pattern.
+    Error,
+    /// Remove the pattern from the arguments.
+    Remove,
+}
+
+/// Checks whether the argument contains wildcards (`*`, `?` or `[...]`).
+pub(crate) fn has_glob(arg: &str) -> bool {
+    arg.contains(['*', '?'])
+        || arg.find('[').is_some_and(|i| arg[i + 1..].contains(']'))
+}
+
+/// Expands the pattern against the filesystem. Wildcards may be in any path
+/// component. The paths are sorted in each directory and they use the same
+/// separators as the pattern. Empty if nothing matches.
+///
+/// Hidden files (starting with `.`) are matched only if the pattern
+/// component also starts with `.`. On Windows, the matching is case
+/// insensitive.
+pub(crate) fn expand_glob(pattern: &str) -> Vec<String> {
+    let mut res = vec![String::new()];
+    let mut rest = pattern;
+    loop {
+        let (part, sep) = match rest.find(is_separator) {
+            Some(i) => {
+                let sep = rest[i..].chars().next().unwrap_or('/');
+                let part = &rest[..i];
+                rest = &rest[i + sep.len_utf8()..];
+                (part, Some(sep))
+            }
+            None => (rest, None),
+        };
+
+        if has_glob(part) {
+            res = res.into_iter().flat_map(|b| matching(b, part)).collect();
+        } else {
+            res.iter_mut().for_each(|b| b.push_str(part));
+        }
+
+        match sep {
+            Some(sep) => res.iter_mut().for_each(|b| b.push(sep)),
+            None => break,
+        }
+    }
+
+    res.retain(|p| fs::symlink_metadata(p).is_ok());
+    res
+}
+
+/// Gets the entries in the directory `dir` that match `part`. The results
+/// are prefixed with `dir`.
+fn matching(dir: String, part: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(if dir.is_empty() { "." } else { &dir })
+    else {
+        return vec![];
+    };
+    let mut names: Vec<_> = entries
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .filter(|n| !n.starts_with('.') || part.starts_with('.'))
+        .filter(|n| glob_match(part, n, cfg!(windows)))
+        .collect();
+    names.sort();
+    names.into_iter().map(|n| format!("{dir}{n}")).collect()
+}
+
+/// Checks whether the name matches the pattern. `*` matches any sequence of
+/// chars, `?` matches single char and `[...]` matches one of the chars in
+/// the brackets. The brackets may contain ranges (`a-z`) and they may be
+/// negated with `!` or `^`. `[` without closing `]` is literal.
+pub(crate) fn glob_match(
+    pattern: &str,
+    name: &str,
+    case_insensitive: bool,
+) -> bool {
+    let eq = |a: char, b: char| {
+        a == b || case_insensitive && a.to_lowercase().eq(b.to_lowercase())
+    };
+    let pat: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the position in the name where it
+    // started matching.
+    let mut star = None;
+    while n < name.len() {
+        match pat.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some('[') => {
+                if let Some((matched, len)) =
+                    class_match(&pat[p..], name[n], case_insensitive)
+                {
+                    if matched {
+                        p += len;
+                        n += 1;
+                        continue;
+                    }
+                } else if eq('[', name[n]) {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+            }
+            Some(c) if eq(*c, name[n]) => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+        // Mismatch, let the last star match one more char.
+        let Some((sp, sn)) = star else {
+            return false;
+        };
+        star = Some((sp, sn + 1));
+        p = sp + 1;
+        n = sn + 1;
+    }
+    pat[p..].iter().all(|c| *c == '*')
+}
+
+/// Matches the char with class at the start of `pat` (`[...]`). Returns
+/// whether it matched and the length of the class. Returns [`None`] if the
+/// class is not closed.
+fn class_match(
+    pat: &[char],
+    c: char,
+    case_insensitive: bool,
+) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negate = matches!(pat.get(i), Some('!' | '^'));
+    if negate {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let a = *pat.get(i)?;
+        if a == ']' && !first {
+            return Some((matched != negate, i + 1));
+        }
+        first = false;
+        let (b, len) = match (pat.get(i + 1), pat.get(i + 2)) {
+            (Some('-'), Some(&b)) if b != ']' => (b, 3),
+            _ => (a, 1),
+        };
+        let in_range = |c: char| (a..=b).contains(&c);
+        matched |= in_range(c)
+            || case_insensitive
+                && (c.to_lowercase().any(in_range)
+                    || c.to_uppercase().any(in_range));
+        i += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pareg;
+
+    #[test]
+    fn matcher() {
+        let m = |p: &str, n: &str| glob_match(p, n, false);
+        assert!(m("*.txt", "a.txt"));
+        assert!(m("*.txt", ".txt"));
+        assert!(!m("*.txt", "a.txt.bak"));
+        assert!(m("a*b*c", "aXXbYYbc"));
+        assert!(!m("a*b*c", "aXXbYYb"));
+        assert!(m("?.rs", "a.rs"));
+        assert!(!m("?.rs", "ab.rs"));
+        assert!(m("*", ""));
+        assert!(!m("?", ""));
+        assert!(m("[abc].txt", "b.txt"));
+        assert!(!m("[abc].txt", "d.txt"));
+        assert!(m("[a-c]1", "c1"));
+        assert!(!m("[!a-c]1", "c1"));
+        assert!(m("[^a-c]1", "d1"));
+        assert!(m("[]]", "]"));
+        assert!(m("[a-]", "-"));
+        assert!(m("a[b", "a[b"));
+        assert!(!m("a[b", "ab"));
+        assert!(m("ž*", "žluť"));
+        assert!(!m("*.TXT", "a.txt"));
+        assert!(glob_match("*.TXT", "a.txt", true));
+        assert!(glob_match("[A-C].txt", "b.txt", true));
+
+        assert!(has_glob("*.txt") && has_glob("a?") && has_glob("[ab]"));
+        assert!(!has_glob("a.txt") && !has_glob("a[b") && !has_glob("a]b["));
+    }
+
+    #[test]
+    fn expand() {
+        let dir = std::env::temp_dir().join("pareg_glob_expand");
+        _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::create_dir_all(dir.join("other")).unwrap();
+        for f in ["b.txt", "a.txt", "c.rs", ".hidden.txt", "sub/d.txt"] {
+            fs::write(dir.join(f), "").unwrap();
+        }
+        let base = dir.to_str().unwrap().to_string() + "/";
+        let glob = |p: &str| -> Vec<String> {
+            expand_glob(&format!("{base}{p}"))
+                .into_iter()
+                .map(|p| p[base.len()..].to_string())
+                .collect()
+        };
+
+        assert_eq!(glob("*.txt"), ["a.txt", "b.txt"]);
+        assert_eq!(glob(".*.txt"), [".hidden.txt"]);
+        assert_eq!(glob("?.*"), ["a.txt", "b.txt", "c.rs"]);
+        assert_eq!(glob("*/d.txt"), ["sub/d.txt"]);
+        assert_eq!(glob("s*/*"), ["sub/d.txt"]);
+        assert_eq!(glob("*/"), ["other/", "sub/"]);
+        assert!(glob("*.md").is_empty());
+        assert!(glob("x*/a.txt").is_empty());
+
+        let mut args = Pareg::new(vec![
+            "prog".into(),
+            format!("{base}*.txt"),
+            format!("{base}*.md"),
+            "-n".into(),
+        ]);
+        args.next();
+        args.expand_globs(GlobMode::Keep).unwrap();
+        assert_eq!(args.remaining().len(), 4);
+        assert_eq!(args.remaining()[1], format!("{base}b.txt"));
+        assert_eq!(args.remaining()[2], format!("{base}*.md"));
+
+        args.next();
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 2);
+        let long = err.ctx().unwrap().long_message.as_deref().unwrap();
+        assert!(long.ends_with(&format!("(expanded from `{base}*.txt`)")));
+
+        let mut args = Pareg::new(vec![
+            format!("{base}*.md"),
+            format!("{base}c*"),
+            "x".into(),
+        ]);
+        args.expand_globs(GlobMode::Remove).unwrap();
+        assert_eq!(args.all_args(), [format!("{base}c.rs"), "x".into()]);
+        assert!(args.next_arg::<u32>().is_err());
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert!(err.ctx().unwrap().long_message.is_none());
+
+        let mut args = Pareg::new(vec!["a".into(), format!("{base}*.md")]);
+        let err = args.expand_globs(GlobMode::Error).unwrap_err();
+        assert!(err.is_invalid_value());
+        assert_eq!(err.ctx().unwrap().error_idx, 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::ops::Range;
+
+use crate::{ArgError, Result};
+
+/// Arguments split from single command line together with the position of
+/// each byte of the arguments in the original command line. This allows
+/// mapping errors back to the command line (see [`crate::ErrLocation`]).
+///
+/// The command line is split by simple shell like rules:
+///
+/// - Arguments are separated by whitespace.
+/// - Text in single quotes is taken literally.
+/// - In double quotes, backslash escapes only `"` and `\`.
+/// - Outside of quotes, backslash escapes any character.
+/// - Quotes don't split arguments (`a"b c"d` is `ab cd`) and empty quotes
+///   are empty argument.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{JoinedArgs, Pareg};
+///
+/// let joined = JoinedArgs::new(r#"prog --name "John Doe" x"#)?;
+/// assert_eq!(joined.args(), ["prog", "--name", "John Doe", "x"]);
+///
+/// let mut args = Pareg::from_joined(&joined);
+/// args.next();
+/// args.next();
+/// let err = args.next_arg::<u32>().unwrap_err();
+/// let loc = err.location().unwrap();
+/// assert_eq!(loc.offset_in(&joined), 13..21);
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JoinedArgs {
+    cmdline: String,
+    args: Vec<String>,
+    /// For each argument, position of each of its bytes in the command line
+    /// and the position after its last byte.
+    offsets: Vec<Vec<usize>>,
+}
+
+/// Argument that is being split.
+struct Part {
+    arg: String,
+    offsets: Vec<usize>,
+    /// Position after the last byte of the argument in the command line.
+    end: usize,
+}
+
+impl JoinedArgs {
+    /// Splits the command line into arguments.
+    ///
+    /// # Errors
+    /// If there is unterminated quote or the command line ends with
+    /// backslash. The error points to the problematic character in the
+    /// command line.
+    pub fn new(cmdline: impl Into<String>) -> Result<Self> {
+        let cmdline = cmdline.into();
+        let mut res = Self {
+            cmdline: String::new(),
+            args: vec![],
+            offsets: vec![],
+        };
+        let mut cur: Option<Part> = None;
+        let mut chars = cmdline.char_indices();
+        let err = |msg: &'static str, pos: usize| {
+            Err(ArgError::parse_msg(msg, cmdline.clone())
+                .spanned(pos..pos + 1))
+        };
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                c if c.is_whitespace() => res.finish(cur.take()),
+                '\'' => {
+                    let cur = Part::get(&mut cur, i + 1);
+                    loop {
+                        match chars.next() {
+                            Some((_, '\'')) => break,
+                            Some((j, c)) => cur.push(c, j),
+                            None => {
+                                return err(
+                                    "Unterminated `'` in command line.",
+                                    i,
+                                );
+                            }
+                        }
+                    }
+                }
+                '"' => {
+                    let cur = Part::get(&mut cur, i + 1);
+                    let unterminated = "Unterminated `\"` in command line.";
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((j, '\\')) => match chars.next() {
+                                Some((k, c @ ('"' | '\\'))) => cur.push(c, k),
+                                Some((k, c)) => {
+                                    cur.push('\\', j);
+                                    cur.push(c, k);
+                                }
+                                None => return err(unterminated, i),
+                            },
+                            Some((j, c)) => cur.push(c, j),
+                            None => return err(unterminated, i),
+                        }
+                    }
+                }
+                '\\' => match chars.next() {
+                    Some((j, c)) => Part::get(&mut cur, i).push(c, j),
+                    None => {
+                        return err("Command line ends with `\\`.", i);
+                    }
+                },
+                c => Part::get(&mut cur, i).push(c, i),
+            }
+        }
+        res.finish(cur);
+        res.cmdline = cmdline;
+        Ok(res)
+    }
+
+    /// Gets the original command line.
+    pub fn cmdline(&self) -> &str {
+        &self.cmdline
+    }
+
+    /// Gets the arguments split from the command line.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Maps the span within the argument at the index `idx` to the range in
+    /// the command line. The range contains the escapes and quotes between
+    /// the bytes of the span. Span that is out of the argument is clamped to
+    /// the argument and invalid index maps to empty range at the end of the
+    /// command line.
+    pub fn offset_of(&self, idx: usize, span: Range<usize>) -> Range<usize> {
+        let Some(offsets) = self.offsets.get(idx) else {
+            return self.cmdline.len()..self.cmdline.len();
+        };
+        let last = offsets.len() - 1;
+        let start = span.start.min(last);
+        let end = span.end.clamp(start, last);
+        if start == end {
+            offsets[start]..offsets[start]
+        } else {
+            offsets[start]..offsets[end - 1] + 1
+        }
+    }
+
+    fn finish(&mut self, part: Option<Part>) {
+        if let Some(mut part) = part {
+            part.offsets.push(part.end);
+            self.args.push(part.arg);
+            self.offsets.push(part.offsets);
+        }
+    }
+}
+
+impl Part {
+    /// Gets the current argument or starts new argument at `pos`.
+    fn get(part: &mut Option<Part>, pos: usize) -> &mut Part {
+        part.get_or_insert_with(|| Part {
+            arg: String::new(),
+            offsets: vec![],
+            end: pos,
+        })
+    }
+
+    /// Adds character that is at `pos` in the command line.
+    fn push(&mut self, c: char, pos: usize) {
+        self.arg.push(c);
+        let len = c.len_utf8();
+        self.offsets.extend(pos..pos + len);
+        self.end = pos + len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pareg;
+
+    fn split(s: &str) -> Vec<String> {
+        JoinedArgs::new(s).unwrap().args
+    }
+
+    #[test]
+    fn split_rules() {
+        assert_eq!(split("  a  b\tc\n"), ["a", "b", "c"]);
+        assert_eq!(split(""), [""; 0]);
+        assert_eq!(split("'a b' \"c d\""), ["a b", "c d"]);
+        assert_eq!(split("a\"b c\"d 'e'f"), ["ab cd", "ef"]);
+        assert_eq!(split("'' \"\" x''"), ["", "", "x"]);
+        assert_eq!(
+            split(r#"'\"' "\"" "\\" "\n""#),
+            [r#"\""#, "\"", "\\", r"\n"]
+        );
+        assert_eq!(split(r"a\ b \' \\ \x"), ["a b", "'", "\\", "x"]);
+        assert_eq!(split("'it'\\''s'"), ["it's"]);
+        assert_eq!(split("\"a'b\" 'a\"b'"), ["a'b", "a\"b"]);
+    }
+
+    #[test]
+    fn split_errors() {
+        let err = |s: &str| {
+            let e = JoinedArgs::new(s).unwrap_err();
+            (e.message().unwrap().to_string(), e.span().unwrap())
+        };
+        assert_eq!(
+            err("prog 'abc"),
+            ("Unterminated `'` in command line.".into(), 5..6)
+        );
+        assert_eq!(
+            err("prog \"abc\\\""),
+            ("Unterminated `\"` in command line.".into(), 5..6)
+        );
+        assert_eq!(
+            err("a \""),
+            ("Unterminated `\"` in command line.".into(), 2..3)
+        );
+        assert_eq!(
+            err("prog abc\\"),
+            ("Command line ends with `\\`.".into(), 8..9)
+        );
+    }
+
+    #[test]
+    fn shell_quote_round_trip() {
+        let args = ["a b", "", "it's", "\"q\"", "back\\slash", "new\nline"];
+        let joined = crate::shell_quote_join(&args);
+        assert_eq!(split(&joined), args);
+    }
+
+    #[test]
+    fn offsets() {
+        let joined =
+            JoinedArgs::new(r#"prog --color=no 'a b'c "x\"ý" ''"#).unwrap();
+        let cmd = joined.cmdline();
+        assert_eq!(&cmd[joined.offset_of(1, 8..10)], "no");
+        assert_eq!(&cmd[joined.offset_of(2, 0..4)], "a b'c");
+        assert_eq!(&cmd[joined.offset_of(2, 2..4)], "b'c");
+        assert_eq!(&cmd[joined.offset_of(3, 0..4)], r#"x\"ý"#);
+        assert_eq!(&cmd[joined.offset_of(3, 2..4)], "ý");
+        assert_eq!(joined.offset_of(4, 0..0), 32..32);
+        assert_eq!(joined.offset_of(1, 8..20), 13..15);
+        assert_eq!(joined.offset_of(1, 20..30), 15..15);
+        assert_eq!(joined.offset_of(5, 0..1), 33..33);
+    }
+
+    #[test]
+    fn error_location() {
+        let joined = JoinedArgs::new("prog -v --color=nope 'a b'").unwrap();
+        let mut args = Pareg::from_joined(&joined);
+        args.next();
+        args.next();
+        args.next();
+        let err = args.cur_val::<bool>('=').unwrap_err();
+        let loc = err.location().unwrap();
+        assert_eq!((loc.arg_idx, loc.span.clone()), (2, 8..12));
+        assert_eq!(loc.offset_in(&joined), 16..20);
+
+        args.next();
+        let err = args.cur_arg::<u32>().unwrap_err();
+        let loc = err.location().unwrap();
+        assert_eq!(loc.offset_in(&joined), 22..25);
+        assert_eq!(&joined.cmdline()[loc.offset_in(&joined)], "a b");
+    }
+}
@@ -1,84 +1,394 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod arg_class;
 mod arg_into;
+mod arg_map;
+mod arg_source;
 mod by_ref;
+mod bytes;
+pub mod check;
+#[cfg(feature = "std")]
+pub mod completion;
+mod datetime;
+mod deferred;
+mod env_mapping;
 mod err;
+#[cfg(feature = "std")]
+mod exit;
 mod from_arg;
 mod from_read;
+#[cfg(feature = "fuzz-registry")]
+mod fuzz;
+#[cfg(feature = "fuzz-registry")]
+mod fuzz_registry;
+#[cfg(feature = "glob")]
+mod glob;
 pub(crate) mod impl_all;
+mod joined;
+#[cfg(feature = "std")]
+pub mod man;
+mod normalize;
+#[cfg(all(test, feature = "std"))]
+mod panic_free;
+mod parse_args;
+mod parse_value;
 mod parsef;
 mod parsers;
+#[cfg(feature = "std")]
 pub mod proc;
+#[cfg(feature = "std")]
+mod prompt;
+mod quote;
+mod quoted;
+mod ranges;
+mod read_fmt;
 mod reader;
+mod set_policy;
+mod similar;
+pub mod skip;
+mod spanned;
+mod special;
 mod starts;
+#[cfg(feature = "std")]
+mod stdin;
+mod summary;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod units;
+mod unknown_sink;
 
+#[cfg(feature = "glob")]
+pub use crate::glob::GlobMode;
 pub use crate::{
-    arg_into::*, by_ref::*, err::*, from_arg::*, from_read::*, parsef::*,
-    parsers::*, reader::*,
+    arg_class::*, arg_into::*, arg_map::*, arg_source::*, by_ref::*, bytes::*,
+    datetime::*, deferred::*, env_mapping::*, err::*, from_arg::*,
+    from_read::*, joined::*, normalize::*, parse_args::*, parse_value::*,
+    parsef::*, parsers::*, quote::*, quoted::*, read_fmt::*, reader::*,
+    set_policy::*, similar::*, spanned::*, special::*, summary::*, units::*,
+    unknown_sink::*,
+};
+#[cfg(feature = "std")]
+pub use crate::{exit::*, prompt::*, stdin::*};
+#[cfg(feature = "fuzz-registry")]
+pub use crate::{fuzz::*, fuzz_registry::*};
+
+use alloc::{
+    borrow::{Cow, ToOwned},
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
 };
+use core::{
+    any::{type_name, Any},
+    fmt::Display,
+    iter, mem,
+    ops::Range,
+    panic::Location,
+};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    fs::File,
+    io::{self, BufRead, IsTerminal, Write},
+    path::PathBuf,
+};
+
+/// Registers types that implement [`FromArg`] so that they can be fuzzed
+/// with `fuzz_one`. This does nothing without the feature `fuzz-registry`.
+#[cfg(not(feature = "fuzz-registry"))]
+#[macro_export]
+macro_rules! register_from_arg {
+    ($($t:ty),* $(,)?) => {};
+}
 
-use std::{env, ops::Range};
+/// Function that determines which part of argument should be redacted in
+/// error messages. It gets the index of the argument, the previous argument
+/// and the argument.
+type Redactor = Box<
+    dyn Fn(usize, Option<&str>, &str) -> Option<Range<usize>> + Send + Sync,
+>;
+
+/// Where the argument comes from. [`None`] for arguments from the command
+/// line.
+type Origin = Option<Cow<'static, str>>;
+/// Settings that are added to all the errors created by [`Pareg`].
+#[derive(Default)]
+pub(crate) struct ErrConf {
+    /// Usage that is added to all errors.
+    usage: Option<Cow<'static, str>>,
+    /// Texts used by the errors.
+    messages: Option<&'static Messages>,
+    /// Index of the first argument given by the user (see
+    /// [`ArgErrCtx::args_start_index`]).
+    args_start: usize,
+    /// Whether the arguments were taken (see [`Pareg::take_next`]). They are
+    /// shown as [`TAKEN_ARG`] in errors. Empty if no argument was taken.
+    taken: Vec<bool>,
+    /// Whether misuse of [`Pareg`] panics in debug builds (see
+    /// [`Pareg::strict_usage`]).
+    strict_usage: bool,
+    /// Patterns from which the arguments were expanded (see
+    /// [`Pareg::expand_globs`]). Empty if no argument was expanded.
+    expanded_from: Vec<Option<Cow<'static, str>>>,
+}
+
+/// Text that is shown in errors instead of arguments that were taken (see
+/// [`Pareg::take_next`]).
+const TAKEN_ARG: &str = "<taken>";
 
 /// Helper for parsing arguments.
-pub struct Pareg {
-    args: Vec<String>,
+///
+/// The arguments are stored in `S` which is by default [`Vec<String>`], but
+/// it may be any [`ArgSource`] (e.g. [`ChainedArgs`]).
+pub struct Pareg<S = Vec<String>> {
+    args: S,
     cur: usize,
+    /// Index after the last argument that was not returned by
+    /// [`Pareg::next_back`]. [`None`] if it is the number of arguments.
+    end: Option<usize>,
+    redactors: Vec<Redactor>,
+    /// Origins of the arguments. Empty if all the arguments are from the
+    /// command line.
+    origins: Vec<Origin>,
+    unknown_sink: UnknownSink,
+    unknown: Vec<String>,
+    normalize: Normalize,
+    conf: ErrConf,
+    warnings: Vec<ArgWarning>,
+    special: Option<SpecialArgs>,
+    /// Special argument that stopped the parsing.
+    intercepted: Option<Special>,
+    /// Index of the first `--` that was returned by [`Pareg::next`]. Special
+    /// arguments after it are not intercepted.
+    double_dash: Option<usize>,
+    /// How were the arguments used (see [`Pareg::summary`]).
+    uses: ArgUses,
+    suggester: Box<dyn Suggester>,
 }
 
-impl From<Vec<String>> for Pareg {
-    fn from(value: Vec<String>) -> Self {
+impl<S: ArgSource> From<S> for Pareg<S> {
+    fn from(value: S) -> Self {
         Self {
             args: value,
             cur: 0,
+            end: None,
+            redactors: vec![],
+            origins: vec![],
+            unknown_sink: UnknownSink::default(),
+            unknown: vec![],
+            normalize: Normalize::default(),
+            conf: ErrConf::default(),
+            warnings: vec![],
+            special: None,
+            intercepted: None,
+            double_dash: None,
+            uses: ArgUses::default(),
+            suggester: Box::new(EditDistanceSuggester),
         }
     }
 }
 
 impl Pareg {
     /// Create [`Pareg`] from vector of arguments. The first argument is NOT
-    /// skipped.
+    /// skipped and errors number the arguments from 1 starting with it (see
+    /// [`ArgErrCtx::args_start_index`]).
     pub fn new(args: Vec<String>) -> Self {
         args.into()
     }
 
-    /// Create [`Pareg`] from [`env::args`], the first argument is skipped.
+    /// Create [`Pareg`] from arguments split from command line. The first
+    /// argument is NOT skipped, but it is the program in errors (the second
+    /// argument is `arg1`). Errors may be mapped back to the command line
+    /// with [`ErrLocation::offset_in`].
+    pub fn from_joined(joined: &JoinedArgs) -> Self {
+        let mut res: Self = joined.args().to_vec().into();
+        res.conf.args_start = 1;
+        res
+    }
+
+    /// Create [`Pareg`] from [`env::args_os`], the first argument is skipped.
+    /// Invalid UTF-8 in the arguments is replaced with
+    /// [`char::REPLACEMENT_CHARACTER`].
+    ///
+    /// The first argument is the program, so errors number the arguments
+    /// after it from 1 (see [`ArgErrCtx::args_start_index`]).
+    #[cfg(feature = "std")]
     pub fn args() -> Self {
-        Self {
-            args: env::args().collect(),
-            cur: 1,
+        let mut res = Self::from_os_args(env::args_os());
+        res.cur = 1;
+        res.conf.args_start = 1;
+        res
+    }
+
+    /// Same as [`Pareg::args`], but on Windows, arguments with wildcards
+    /// (`*`, `?` or `[...]`) are expanded to the matching files, because
+    /// the Windows shell doesn't do it. Patterns that don't match any file
+    /// are kept. On other platforms, this is the same as [`Pareg::args`].
+    ///
+    /// See [`Pareg::expand_globs`] for more info.
+    #[cfg(feature = "glob")]
+    pub fn args_globbed() -> Self {
+        let mut res = Self::args();
+        if cfg!(windows) {
+            // Keeping the patterns never fails.
+            _ = res.expand_globs(GlobMode::Keep);
         }
+        res
     }
 
-    /// Get the next argument
-    // Iterator impl is not possible because the returned values are borrowed.
-    #[allow(clippy::should_implement_trait)]
-    pub fn next(&mut self) -> Option<&str> {
-        next_inner(&self.args, &mut self.cur)
+    /// Same as [`Pareg::args_globbed`], but `mode` determines what happens
+    /// with patterns that don't match any file.
+    ///
+    /// # Errors
+    /// If `mode` is [`GlobMode::Error`] and some pattern doesn't match any
+    /// file.
+    #[cfg(feature = "glob")]
+    pub fn args_globbed_with(mode: GlobMode) -> Result<Self> {
+        let mut res = Self::args();
+        if cfg!(windows) {
+            res.expand_globs(mode)?;
+        }
+        Ok(res)
     }
 
-    /// Equivalent to calling next `cnt` times.
-    pub fn skip(&mut self, cnt: usize) -> Option<&str> {
-        self.jump(self.args.len().min(self.cur + cnt))
+    /// Expands the remaining arguments (including the current) that contain
+    /// wildcards (`*`, `?` or `[...]`) to the paths of the matching files.
+    /// This is done on all platforms. `mode` determines what happens with
+    /// patterns that don't match any file.
+    ///
+    /// Errors for the expanded arguments mention the pattern in their long
+    /// message (e.g. `(expanded from `*.txt`)`).
+    ///
+    /// `*` matches any sequence of characters, `?` matches single character
+    /// and `[...]` matches one of the characters in the brackets (ranges
+    /// such as `a-z` are allowed and `!` or `^` negates it). Wildcards may be
+    /// in any path component. Hidden files are matched only if the pattern
+    /// starts with `.`. On Windows, the matching is case insensitive.
+    ///
+    /// # Errors
+    /// If `mode` is [`GlobMode::Error`] and some pattern doesn't match any
+    /// file. The error points to the pattern.
+    #[cfg(feature = "glob")]
+    pub fn expand_globs(&mut self, mode: GlobMode) -> Result<()> {
+        let mut idx = self.cur;
+        while idx < self.end.unwrap_or(self.args.len()) {
+            let arg = &self.args[idx];
+            if !glob::has_glob(arg) {
+                idx += 1;
+                continue;
+            }
+            let paths = glob::expand_glob(arg);
+            if paths.is_empty() {
+                match mode {
+                    GlobMode::Keep => idx += 1,
+                    GlobMode::Error => {
+                        return Err(self
+                            .err_at(idx, 0..arg.len(), "No matching files.")
+                            .main_msg(format!(
+                                "No files match the pattern `{arg}`."
+                            ))
+                            .hint(
+                                "Check the pattern or escape the wildcards \
+                                if the argument is not a path.",
+                            ));
+                    }
+                    GlobMode::Remove => _ = self.remove_arg(idx),
+                }
+                continue;
+            }
+
+            let cnt = paths.len();
+            let pattern = self.remove_arg(idx);
+            self.insert_args_inner(idx, None, paths);
+            self.conf.expanded_from.resize(self.args.len(), None);
+            self.conf.expanded_from[idx..idx + cnt].fill(Some(pattern.into()));
+            idx += cnt;
+        }
+        Ok(())
     }
 
-    /// Skip all remaining arguments and return the last.
-    pub fn skip_all(&mut self) -> Option<&str> {
-        self.jump(self.args.len())
+    /// Removes the argument at `idx` that is not before the current
+    /// argument.
+    #[cfg(feature = "glob")]
+    fn remove_arg(&mut self, idx: usize) -> String {
+        for v in [&mut self.origins, &mut self.conf.expanded_from] {
+            if idx < v.len() {
+                v.remove(idx);
+            }
+        }
+        if idx < self.conf.taken.len() {
+            self.conf.taken.remove(idx);
+        }
+        self.uses.remove(idx);
+        if let Some(end) = &mut self.end {
+            *end -= 1;
+        }
+        self.args.remove(idx)
     }
 
-    /// Jump so that the argument at index `idx` is the next argument. Gets the
-    /// argument at `idx - 1`.
-    pub fn jump(&mut self, idx: usize) -> Option<&str> {
-        self.cur = idx;
-        self.cur()
+    /// Create [`Pareg`] from the environment variables that start with
+    /// `prefix`. They are converted to options by the default
+    /// [`EnvMapping`] (e.g. `APP_COLOR=never` is `--color=never` for prefix
+    /// `APP_`). See [`Pareg::from_env_vars`].
+    ///
+    /// Variables that are not valid UTF-8 are converted lossily.
+    #[cfg(feature = "std")]
+    pub fn from_env_prefix(prefix: &str) -> Self {
+        Self::from_env_vars(prefix, &EnvMapping::default(), env_vars())
     }
 
-    /// Jump to the zeroth argument.
-    pub fn reset(&mut self) {
-        self.jump(0);
+    /// Create [`Pareg`] from the variables in `vars` that start with `prefix`.
+    /// They are converted to options with `mapping` and sorted by their
+    /// names. Errors will say from which variable the argument comes (see
+    /// [`Pareg::merge_env_vars`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{EnvMapping, Pareg};
+    ///
+    /// let vars = [("APP_MAX_COUNT", "3"), ("APP_COLOR", "never")];
+    /// let vars = vars.map(|(n, v)| (n.to_string(), v.to_string()));
+    /// let mut args = Pareg::from_env_vars("APP_", &EnvMapping::default(), vars);
+    ///
+    /// assert_eq!(Some("--color=never"), args.next());
+    /// assert_eq!(Some("--max-count=3"), args.next());
+    /// ```
+    pub fn from_env_vars(
+        prefix: &str,
+        mapping: &EnvMapping,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let mut res = Self::new(vec![]);
+        res.merge_env_vars(prefix, mapping, vars);
+        res
     }
 
-    /// Get the last returned argument.
-    pub fn cur(&self) -> Option<&str> {
-        cur_inner(&self.args, self.cur)
+    /// Create [`Pareg`] from OS strings. Invalid UTF-8 is replaced with
+    /// [`char::REPLACEMENT_CHARACTER`]. The first argument is NOT skipped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ffi::OsString;
+    /// use pareg_core::Pareg;
+    ///
+    /// let mut args = Pareg::from_os_args([OsString::from("hello")]);
+    /// assert_eq!(Some("hello"), args.next());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_os_args(args: impl IntoIterator<Item = OsString>) -> Self {
+        args.into_iter()
+            .map(|a| {
+                a.into_string()
+                    .unwrap_or_else(|a| a.to_string_lossy().into_owned())
+            })
+            .collect::<Vec<_>>()
+            .into()
     }
 
     /// Gets all the arguments (including the first one).
@@ -88,805 +398,6882 @@ impl Pareg {
 
     /// Gets the remaining arguments (not including the current).
     pub fn remaining(&self) -> &[String] {
-        &self.args[self.cur..]
+        let end = self.end.unwrap_or(self.args.len());
+        self.args.as_slice().get(self.cur..end).unwrap_or_default()
     }
 
     /// Gets the remaining arguments (including the current).
     pub fn cur_remaining(&self) -> &[String] {
-        &self.args[self.cur.saturating_sub(1)..]
+        let end = self.end.unwrap_or(self.args.len());
+        self.args
+            .as_slice()
+            .get(self.cur.saturating_sub(1)..end)
+            .unwrap_or_default()
     }
 
-    /// Get value that will be returned with the next call to `next`.
-    pub fn peek(&self) -> Option<&str> {
-        self.get(self.cur)
+    /// Inserts the arguments at the index `at` (clamped to the number of
+    /// arguments). If they are inserted at or before the current argument,
+    /// the position is adjusted so that the current argument doesn't change.
+    /// Inserting at the index of the next argument makes the inserted
+    /// arguments the next to be parsed. Arguments inserted before the back
+    /// cursor (see [`Pareg::next_back`]) are part of the remaining
+    /// arguments.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let mut args = Pareg::new(vec!["prog".into(), "-v".into()]);
+    /// args.next();
+    /// args.insert_args(1, ["--color=never".to_string()]);
+    /// assert_eq!(Some("--color=never"), args.next());
+    /// assert_eq!(Some("-v"), args.next());
+    /// ```
+    pub fn insert_args(
+        &mut self,
+        at: usize,
+        args: impl IntoIterator<Item = String>,
+    ) {
+        self.insert_args_inner(at, None, args);
     }
 
-    /// Get the index of the next argument.
-    pub fn next_idx(&self) -> Option<usize> {
-        if self.cur >= self.args.len() {
-            None
-        } else {
-            Some(self.cur)
-        }
+    /// Same as [`Pareg::insert_args`], but errors in the inserted arguments
+    /// will say that they come from `origin` (e.g. `config`).
+    ///
+    /// This is useful for default arguments from configuration file. Insert
+    /// them before the command line arguments, so that the command line
+    /// arguments override them.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+    /// use pareg_core::Pareg;
+    ///
+    /// let mut args = Pareg::new(vec!["prog".into(), "-v".into()]);
+    /// args.next();
+    /// args.insert_args_from(1, "config", ["--count=x".to_string()]);
+    ///
+    /// args.next();
+    /// let err = args.cur_val::<u32>('=').unwrap_err().no_color();
+    /// assert!(err.to_string().contains("(from config)"));
+    /// ```
+    pub fn insert_args_from(
+        &mut self,
+        at: usize,
+        origin: impl Into<Cow<'static, str>>,
+        args: impl IntoIterator<Item = String>,
+    ) {
+        self.insert_args_inner(at, Some(origin.into()), args);
     }
 
-    /// Get index of the current argument.
-    pub fn cur_idx(&self) -> Option<usize> {
-        if self.cur == 0 {
-            None
-        } else {
-            Some(self.cur - 1)
-        }
+    /// Adds the arguments after the last remaining argument. If there are
+    /// arguments taken with [`Pareg::next_back`], the new arguments are
+    /// added before them, so that they are parsed.
+    pub fn append_args(&mut self, args: impl IntoIterator<Item = String>) {
+        self.insert_args_inner(self.back_idx(), None, args);
     }
 
-    /// Get argument at the given index.
-    pub fn get(&self, idx: usize) -> Option<&str> {
-        self.args.get(idx).map(|a| a.as_str())
+    /// Same as [`Pareg::append_args`], but errors in the added arguments will
+    /// say that they come from `origin` (e.g. `config`).
+    pub fn append_args_from(
+        &mut self,
+        origin: impl Into<Cow<'static, str>>,
+        args: impl IntoIterator<Item = String>,
+    ) {
+        self.insert_args_inner(self.back_idx(), Some(origin.into()), args);
     }
 
-    /// Perform manual parsing on the next argument. This is will make the
-    /// errors have better messages than just doing the parsing without
-    /// [`Pareg`].
+    /// Adds the environment variables that start with `prefix` after the
+    /// last argument. They are converted to options by the default
+    /// [`EnvMapping`] (e.g. `APP_COLOR=never` is `--color=never` for prefix
+    /// `APP_`). See [`Pareg::merge_env_vars`].
     ///
-    /// `pareg.next_manual(foo)` is equivalent to
-    /// `pareg.map_err(foo(pareg.next()))`, except it has no issues with
-    /// lifetimes.
+    /// Variables that are not valid UTF-8 are converted lossily.
+    #[cfg(feature = "std")]
+    pub fn merge_env(&mut self, prefix: &str) {
+        self.merge_env_vars(prefix, &EnvMapping::default(), env_vars());
+    }
+
+    /// Converts the variables in `vars` that start with `prefix` to options
+    /// with `mapping` and adds them after the last argument (before the back
+    /// cursor as in [`Pareg::append_args`]). They are added in order of
+    /// their names. Errors in them will say that they come from
+    /// the environment variable (e.g.
+    /// `(from environment variable `APP_COLOR`)`).
     ///
     /// # Examples
-    /// ```rust
-    /// use pareg_core::{Pareg, key_val_arg};
-    /// let args = ["-D10=0.25"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+    /// use pareg_core::{EnvMapping, Pareg};
     ///
-    /// let res: (usize, f32) = args.next_manual(|s| {
-    ///     key_val_arg(s.strip_prefix("-D").unwrap(), '=')
-    /// }).unwrap();
-    /// assert_eq!((10, 0.25), res);
+    /// let mut args = Pareg::new(vec!["-v".into()]);
+    /// let vars = [("APP_COUNT", "x"), ("HOME", "/root"), ("APP_COLOR", "no")];
+    /// let vars = vars.map(|(n, v)| (n.to_string(), v.to_string()));
+    /// args.merge_env_vars("APP_", &EnvMapping::default(), vars);
+    /// assert_eq!(args.all_args(), ["-v", "--color=no", "--count=x"]);
+    ///
+    /// args.jump(3);
+    /// let err = args.cur_val::<u32>('=').unwrap_err().no_color();
+    /// assert!(err
+    ///     .to_string()
+    ///     .contains("(from environment variable `APP_COUNT`)"));
     /// ```
-    pub fn next_manual<'a, T, F>(&'a mut self, f: F) -> Result<T>
-    where
-        T: 'a,
-        F: Fn(&'a str) -> Result<T>,
-    {
-        self.next();
-        self.map_err(f(self.cur_arg()?))
+    pub fn merge_env_vars(
+        &mut self,
+        prefix: &str,
+        mapping: &EnvMapping,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) {
+        for (name, arg) in mapping.args(prefix, vars) {
+            let origin = format!("environment variable `{name}`");
+            self.insert_args_inner(
+                self.back_idx(),
+                Some(origin.into()),
+                [arg],
+            );
+        }
     }
 
-    /// Perform manual parsing on the next argument. This is will make the
-    /// errors have better messages than just doing the parsing without
-    /// [`Pareg`].
+    /// Moves to the next argument and takes it out of the arguments without
+    /// copying it. The taken argument is replaced with empty string, so it
+    /// is empty when it is accessed later (e.g. by [`Pareg::cur`]). Errors
+    /// show taken arguments as `<taken>`.
     ///
-    /// `pareg.cur_manual(foo)` is equivalent to
-    /// `pareg.map_err(foo(pareg.cur()))`.
+    /// Returns [`None`] if there is no next argument.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::{Pareg, key_val_arg};
-    /// let args = ["-D10=0.25"];
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--file", "a.txt", "--count", "x"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
+    /// assert_eq!(Some("a.txt".to_string()), args.take_next());
+    /// assert_eq!(Some(""), args.cur());
     ///
-    /// let res: (usize, f32) = args.next_manual(|s| {
-    ///     key_val_arg(s.strip_prefix("-D").unwrap(), '=')
-    /// }).unwrap();
-    /// assert_eq!((10, 0.25), res);
+    /// args.next();
+    /// let err = args.next_arg::<u32>().unwrap_err();
+    /// assert_eq!("<taken>", err.args()[1]);
     /// ```
-    pub fn cur_manual<'a, T, F>(&'a self, f: F) -> Result<T>
-    where
-        T: 'a,
-        F: Fn(&'a str) -> Result<T>,
-    {
-        self.map_err(f(self.cur_arg()?))
+    pub fn take_next(&mut self) -> Option<String> {
+        self.next()?;
+        self.take_cur()
+    }
+
+    /// Takes the current argument out of the arguments without copying it
+    /// (see [`Pareg::take_next`]). Returns [`None`] if there is no current
+    /// argument or if it was already taken.
+    pub fn take_cur(&mut self) -> Option<String> {
+        let idx = self.cur.checked_sub(1)?;
+        let len = self.args.len();
+        let arg = self.args.get_mut(idx)?;
+        if self.conf.taken.get(idx) == Some(&true) {
+            return None;
+        }
+        self.conf.taken.resize(len, false);
+        self.conf.taken[idx] = true;
+        Some(mem::take(arg))
     }
 
-    /// Parses the next value in the iterator.
+    /// Skips all the remaining arguments (not including the current) and
+    /// takes them out of the arguments without copying them. This is the
+    /// same as [`Pareg::take_rest`], but the arguments are moved (see
+    /// [`Pareg::take_next`]).
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["hello", "10", "0.25", "always"];
+    /// let args = ["--files", "a.txt", "b.txt"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
-    /// assert_eq!("hello", args.next_arg::<&str>().unwrap());
-    /// assert_eq!(10, args.next_arg::<usize>().unwrap());
-    /// assert_eq!(0.25, args.next_arg::<f64>().unwrap());
+    /// args.next();
+    /// assert_eq!(vec!["a.txt", "b.txt"], args.take_remaining());
+    /// assert_eq!(None, args.next());
     /// ```
-    #[inline]
-    pub fn next_arg<'a, T>(&'a mut self) -> Result<T>
-    where
-        T: FromArg<'a>,
-    {
-        next_arg_inner(&self.args, &mut self.cur)
+    pub fn take_remaining(&mut self) -> Vec<String> {
+        let len = self.args.len();
+        let end = self.end.unwrap_or(len).min(len);
+        let start = self.cur.min(end);
+        if start == end {
+            self.skip_all();
+            return vec![];
+        }
+        self.conf.taken.resize(len, false);
+        let rest = self.args[start..end]
+            .iter_mut()
+            .zip(&mut self.conf.taken[start..end])
+            .filter_map(|(a, t)| {
+                (!mem::replace(t, true)).then(|| mem::take(a))
+            })
+            .collect();
+        self.skip_all();
+        rest
     }
 
-    /// Uses the function [`key_mval_arg`] on the next argument.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
-    /// also parsed to the given type.
-    ///
-    /// In case that there is no `'='`, value is `None`.
+    /// Gets all the arguments back without copying them. Arguments that were
+    /// taken (see [`Pareg::take_next`]) are empty.
+    pub fn into_args(self) -> Vec<String> {
+        self.args
+    }
+
+    /// Parses value from the following arguments with [`ParseArgs`] (e.g.
+    /// group of options exported by library). The parser stops at the first
+    /// argument that it doesn't know.
+    pub fn parse<T: ParseArgs>(&mut self) -> Result<T> {
+        T::parse_args(self)
+    }
+
+    /// Parses value from all the remaining arguments with [`ParseArgs`]. If
+    /// any arguments remain after the parser stops, the error is
+    /// [`ArgError::TooManyArguments`] (see [`Pareg::require_exhausted`]).
+    /// Returns the value together with the warnings (see [`Pareg::warn`]).
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::Pareg;
+    /// use pareg_core::{ArgError, Pareg, ParseArgs, Result};
     ///
-    /// let args = ["key=value", "5:0.25", "only_key"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// struct Name(String);
     ///
-    /// assert_eq!(
-    ///     ("key", Some("value")),
-    ///     args.next_key_mval::<&str, &str>('=').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     (5, Some(0.25)),
-    ///     args.next_key_mval::<i32, f64>(':').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     ("only_key".to_owned(), None),
-    ///     args.next_key_mval::<String, &str>('=').unwrap()
-    /// );
+    /// impl ParseArgs for Name {
+    ///     fn parse_args(args: &mut Pareg) -> Result<Self> {
+    ///         args.next_arg().map(Self)
+    ///     }
+    /// }
+    ///
+    /// let args = Pareg::new(vec!["pareg".into()]);
+    /// let (name, warnings) = args.parse_all::<Name>()?;
+    /// assert_eq!("pareg", name.0);
+    /// assert!(warnings.is_empty());
+    ///
+    /// let args = Pareg::new(vec!["pareg".into(), "x".into()]);
+    /// assert!(matches!(
+    ///     args.parse_all::<Name>(),
+    ///     Err(ArgError::TooManyArguments(_))
+    /// ));
+    /// # Ok::<_, ArgError>(())
     /// ```
-    #[inline(always)]
-    pub fn next_key_mval<'a, K, V>(
-        &'a mut self,
-        sep: char,
-    ) -> Result<(K, Option<V>)>
-    where
-        K: FromArg<'a>,
-        V: FromArg<'a>,
-    {
-        self.next();
-        self.map_err(key_mval_arg(self.cur_arg()?, sep))
+    pub fn parse_all<T: ParseArgs>(mut self) -> Result<(T, Vec<ArgWarning>)> {
+        let res = T::parse_args(&mut self)?;
+        self.require_exhausted()?;
+        Ok((res, self.warnings))
     }
 
-    /// Uses the function [`key_val_arg`] on the next value.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
-    /// also parsed to the given type.
-    ///
-    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    fn insert_args_inner(
+        &mut self,
+        at: usize,
+        origin: Origin,
+        args: impl IntoIterator<Item = String>,
+    ) {
+        let len = self.args.len();
+        let at = at.min(len);
+        self.args.splice(at..at, args);
+        let cnt = self.args.len() - len;
+
+        if origin.is_some() || !self.origins.is_empty() {
+            self.origins.resize(len, None);
+            self.origins.splice(at..at, iter::repeat_n(origin, cnt));
+        }
+
+        if !self.conf.taken.is_empty() {
+            self.conf.taken.resize(len, false);
+            self.conf.taken.splice(at..at, iter::repeat_n(false, cnt));
+        }
+
+        if !self.conf.expanded_from.is_empty() {
+            self.conf.expanded_from.resize(len, None);
+            self.conf
+                .expanded_from
+                .splice(at..at, iter::repeat_n(None, cnt));
+        }
+
+        self.uses.insert(at, cnt);
+
+        if at < self.cur {
+            self.cur += cnt;
+        }
+        if let Some(end) = &mut self.end {
+            if at <= *end {
+                *end += cnt;
+            }
+        }
+        if let Some(dd) = &mut self.double_dash {
+            if at <= *dd {
+                *dd += cnt;
+            }
+        }
+    }
+
+    /// Gets the index after the last remaining argument (see
+    /// [`Pareg::next_back`]).
+    fn back_idx(&self) -> usize {
+        self.end.unwrap_or(self.args.len())
+    }
+}
+
+impl<'a, S: AsRef<str>> Pareg<ChainedArgs<'a, S>> {
+    /// Create [`Pareg`] from several slices of arguments without copying
+    /// them. The first argument is NOT skipped.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// let user = ["-c", "5"];
+    /// let args = ["-c", "10", "-v"];
+    /// let mut args = Pareg::chained([&user[..], &args[..]]);
     ///
-    /// assert_eq!(
-    ///     ("key", "value"),
-    ///     args.next_key_val::<&str, &str>('=').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     (5, 0.25),
-    ///     args.next_key_val::<i32, f64>(':').unwrap()
-    /// );
+    /// args.jump(4);
+    /// assert_eq!(10, args.cur_arg::<usize>().unwrap());
+    /// assert_eq!(vec!["-v"], args.remaining().collect::<Vec<_>>());
     /// ```
-    #[inline(always)]
-    pub fn next_key_val<'a, K, V>(&'a mut self, sep: char) -> Result<(K, V)>
-    where
-        K: FromArg<'a>,
-        V: FromArg<'a>,
-    {
-        self.next();
-        self.map_err(key_val_arg(self.cur_arg()?, sep))
+    pub fn chained(parts: impl IntoIterator<Item = &'a [S]>) -> Self {
+        ChainedArgs::new(parts).into()
     }
 
-    /// Uses the function [`bool_arg`] on the next value.
-    ///
-    /// Parse bool value in a specific way. If the value of lowercase `arg` is
-    /// equal to `t` returns true, if it is equal to `f` returns false and
-    /// otherwise returns error.
+    /// Gets all the arguments (including the first one).
+    pub fn all_args(&self) -> impl Iterator<Item = &str> {
+        self.args.iter()
+    }
+
+    /// Gets the remaining arguments (not including the current).
+    pub fn remaining(&self) -> impl Iterator<Item = &str> {
+        let end = self.end.unwrap_or(self.args.len());
+        self.args.iter_range(self.cur..end)
+    }
+
+    /// Gets the remaining arguments (including the current).
+    pub fn cur_remaining(&self) -> impl Iterator<Item = &str> {
+        let end = self.end.unwrap_or(self.args.len());
+        self.args.iter_range(self.cur.saturating_sub(1)..end)
+    }
+}
+
+impl<'a> Pareg<CowArgs<'a>> {
+    /// Create [`Pareg`] from arguments that are mostly `'static`. Errors
+    /// don't copy the arguments that are [`Cow::Borrowed`]. The first
+    /// argument is NOT skipped.
     ///
     /// # Examples
     /// ```rust
+    /// use std::borrow::Cow;
+    ///
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["true", "yes", "never"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// let args: Vec<Cow<'static, str>> =
+    ///     vec!["-c".into(), "x".to_string().into()];
+    /// let mut args = Pareg::from_cow(&args);
     ///
-    /// assert_eq!(true, args.next_bool("true", "false").unwrap());
-    /// assert_eq!(true, args.next_bool("yes", "no").unwrap());
-    /// assert_eq!(false, args.next_bool("always", "never").unwrap());
+    /// args.next();
+    /// let err = args.next_arg::<usize>().unwrap_err();
+    /// assert!(matches!(err.args()[0], Cow::Borrowed("-c")));
     /// ```
-    #[inline(always)]
-    pub fn next_bool(&mut self, t: &str, f: &str) -> Result<bool> {
-        self.next();
-        self.map_err(bool_arg(t, f, self.cur_arg()?))
+    pub fn from_cow(args: &'a [Cow<'static, str>]) -> Self {
+        CowArgs(args).into()
     }
+}
 
-    /// Uses the function [`opt_bool_arg`] on the next argument.
-    ///
-    /// Parse bool value in a specific way. If the value of lowercase `arg` is
-    /// equal to `t` returns true, if it is equal to `f` returns false and
-    /// if it is equal to `n` returns [`None`]. Otherwise returns error.
+impl<I: Iterator<Item = String>> Pareg<LazyArgs<I>> {
+    /// Create [`Pareg`] from iterator that is read only when the arguments
+    /// are needed (see [`LazyArgs`]). The first argument is NOT skipped.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::Pareg;
+    /// use pareg_core::{ArgSource, Pareg};
     ///
-    /// let args = ["always", "never", "auto"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// let mut args = Pareg::lazy((1..).map(|i| format!("--n={i}")));
     ///
-    /// assert_eq!(
-    ///     Some(true),
-    ///     args.next_opt_bool("always", "never", "auto").unwrap()
-    /// );
-    /// assert_eq!(
-    ///     Some(false),
-    ///     args.next_opt_bool("always", "never", "auto").unwrap()
-    /// );
-    /// assert_eq!(
-    ///     None,
-    ///     args.next_opt_bool("always", "never", "auto").unwrap()
-    /// );
+    /// let mut sum = 0;
+    /// while let Some(arg) = args.next() {
+    ///     match arg {
+    ///         "--n=4" => break,
+    ///         a if a.starts_with("--n=") => sum += args.cur_val::<u32>('=')?,
+    ///         _ => args.err_unknown_argument().err()?,
+    ///     }
+    /// }
+    /// assert_eq!(6, sum);
+    /// assert_eq!(4, args.source().loaded());
+    /// # Ok::<_, pareg_core::ArgError>(())
     /// ```
-    #[inline(always)]
-    pub fn next_opt_bool(
-        &mut self,
-        t: &str,
-        f: &str,
-        n: &str,
-    ) -> Result<Option<bool>> {
-        self.next();
-        self.map_err(opt_bool_arg(t, f, n, self.cur_arg()?))
+    pub fn lazy(args: impl IntoIterator<IntoIter = I>) -> Self {
+        LazyArgs::new(args).into()
+    }
+}
+
+impl<S: ArgSource> Pareg<S> {
+    /// Gets the storage of the arguments.
+    pub fn source(&self) -> &S {
+        &self.args
     }
 
-    /// Uses the function [`key_arg`] on the next value.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and discards `value`.
+    /// Get the next argument
+    // Iterator impl is not possible because the returned values are borrowed.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&str> {
+        if self.intercept().is_some() {
+            return None;
+        }
+        let arg = next_inner(&self.args, &mut self.cur, self.end)?;
+        if arg == "--" && self.double_dash.is_none() {
+            self.double_dash = Some(self.cur - 1);
+        }
+        Some(arg)
+    }
+
+    /// Gets the last remaining argument and removes it from the remaining
+    /// arguments, so it will not be returned by [`Pareg::next`]. This moves
+    /// only the back cursor. The current argument (see [`Pareg::cur`] and
+    /// [`Pareg::cur_idx`]) and errors created from it are not changed.
     ///
-    /// In case that there is no `'='`, parses the whole input.
+    /// Returns [`None`] if the back cursor meets the current position. The
+    /// back cursor is reset by [`Pareg::reset`].
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25"];
+    /// let args = ["cp", "a", "b", "dest"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
-    /// assert_eq!(
-    ///     "key",
-    ///     args.next_key::<&str>('=').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     5,
-    ///     args.next_key::<i32>(':').unwrap()
-    /// );
+    /// args.next();
+    /// assert_eq!(Some("dest"), args.next_back());
+    /// assert_eq!(Some(0), args.cur_idx());
+    /// assert_eq!(vec!["a", "b"], args.remaining());
+    /// assert_eq!(Some("b"), args.skip_all());
+    /// assert_eq!(None, args.next_back());
     /// ```
-    #[inline(always)]
-    pub fn next_key<'a, T>(&'a mut self, sep: char) -> Result<T>
-    where
-        T: FromArg<'a>,
-    {
-        self.next();
-        self.map_err(key_arg(self.cur_arg()?, sep))
+    pub fn next_back(&mut self) -> Option<&str> {
+        let end = self.end.unwrap_or_else(|| self.args.len());
+        if end <= self.cur {
+            return None;
+        }
+        self.end = Some(end - 1);
+        self.args.get(end - 1)
     }
 
-    /// Uses the function [`val_arg`] on the next value.
+    /// Equivalent to calling next `cnt` times.
+    pub fn skip(&mut self, cnt: usize) -> Option<&str> {
+        let idx = self.cur.saturating_add(cnt);
+        match self.end {
+            Some(e) => self.jump(idx.min(e.max(self.cur))),
+            None if idx != 0 && self.args.get(idx - 1).is_none() => {
+                self.jump(self.args.len())
+            }
+            None => self.jump(idx),
+        }
+    }
+
+    /// Skip all remaining arguments and return the last of them. After this
+    /// [`Pareg::cur`] is the returned argument, [`Pareg::cur_idx`] is its
+    /// index and [`Pareg::next`] returns [`None`]. Arguments returned by
+    /// [`Pareg::next_back`] are not remaining, so this stops before them.
+    pub fn skip_all(&mut self) -> Option<&str> {
+        self.jump(self.end.unwrap_or_else(|| self.args.len()))
+    }
+
+    /// Skips all the remaining arguments (not including the current) and
+    /// returns them. This is useful for arguments that are passed to other
+    /// program (see also [`shell_quote_join`]).
     ///
-    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
-    /// given type.
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, shell_quote_join};
     ///
-    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    /// let args = ["run", "--", "cargo", "build", "--release"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut child = vec![];
+    /// while let Some(arg) = args.next() {
+    ///     match arg {
+    ///         "run" => {}
+    ///         "--" => child = args.take_rest(),
+    ///         _ => panic!("Unknown argument."),
+    ///     }
+    /// }
+    /// assert_eq!("cargo build --release", shell_quote_join(&child));
+    /// ```
+    pub fn take_rest(&mut self) -> Vec<String> {
+        let end = self.end.unwrap_or_else(|| self.args.len());
+        let rest = self
+            .args
+            .iter_range(self.cur..end)
+            .map(|a| a.to_owned())
+            .collect();
+        self.skip_all();
+        rest
+    }
+
+    /// Jump so that the argument at index `idx` is the next argument. Gets the
+    /// argument at `idx - 1`.
+    pub fn jump(&mut self, idx: usize) -> Option<&str> {
+        self.cur = idx;
+        self.cur()
+    }
+
+    /// Jump to the zeroth argument and reset the back cursor (see
+    /// [`Pareg::next_back`]).
+    pub fn reset(&mut self) {
+        self.end = None;
+        self.intercepted = None;
+        self.double_dash = None;
+        self.uses = ArgUses::default();
+        self.jump(0);
+    }
+
+    /// Saves the position, so that it may be restored with
+    /// [`Pareg::restore`]. This is useful for trying to parse arguments in
+    /// several ways.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25"];
+    /// let args = ["-n", "x"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
-    /// assert_eq!(
-    ///     "value",
-    ///     args.next_val::<&str>('=').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     0.25,
-    ///     args.next_val::<f64>(':').unwrap()
-    /// );
+    /// let start = args.checkpoint();
+    /// args.next();
+    /// if args.next_arg::<u32>().is_err() {
+    ///     args.restore(start);
+    /// }
+    /// assert_eq!(Some("-n"), args.next());
     /// ```
-    #[inline(always)]
-    pub fn next_val<'a, T>(&'a mut self, sep: char) -> Result<T>
-    where
-        T: FromArg<'a>,
-    {
-        self.next();
-        self.map_err(val_arg(self.cur_arg()?, sep))
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            cur: self.cur,
+            end: self.end,
+            warnings: self.warnings.len(),
+            unknown: self.unknown.len(),
+        }
     }
 
-    /// Uses the function [`mval_arg`] on the next argument.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
-    /// given type.
+    /// Restores the position saved by [`Pareg::checkpoint`]. Warnings and
+    /// unknown arguments (see [`Pareg::take_unknown`]) that were added after
+    /// the checkpoint are removed.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.cur = checkpoint.cur;
+        self.end = checkpoint.end;
+        self.warnings.truncate(checkpoint.warnings);
+        self.unknown.truncate(checkpoint.unknown);
+        self.uses.truncate(checkpoint.cur);
+    }
+
+    /// Gets summary of how were the consumed arguments used. This may be
+    /// used to collect which options are used (e.g. for telemetry). The
+    /// summary contains only the names of the options, never the values.
     ///
-    /// In case that there is no `'='`, value is `None`.
+    /// Methods that consume values record the use of the arguments (e.g.
+    /// [`Pareg::next_arg`] after option records option with value in the
+    /// next argument). Other arguments are classified by their text (see
+    /// [`arg_class`]): options are flags, or options with inline value if
+    /// there is something after the name, and the rest (including negative
+    /// numbers) is positional. Only the first option of short option cluster
+    /// is used (`-phunter2` is `-p`) and the parts hidden by the redactors
+    /// (see [`Pareg::redact_matching`]) are never included. Arguments before
+    /// the first argument given by the user (the program) are not included.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::Pareg;
+    /// use pareg_core::{JoinedArgs, Pareg, UseKind};
     ///
-    /// let args = ["key=value", "5:0.25", "only_key"];
-    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// let args = ["prog", "-v", "--color=never", "-o", "out", "in.txt"];
+    /// let joined = JoinedArgs::new(args.join(" "))?;
+    /// let mut args = Pareg::from_joined(&joined);
+    /// args.next();
     ///
+    /// while let Some(arg) = args.next() {
+    ///     match arg {
+    ///         "-v" => {}
+    ///         c if c.starts_with("--color=") => _ = args.cur_val::<&str>('=')?,
+    ///         "-o" => _ = args.next_arg::<&str>()?,
+    ///         _ => {}
+    ///     }
+    /// }
+    ///
+    /// let summary = args.summary();
+    /// let kinds: Vec<_> = summary.args.iter().map(|a| a.kind).collect();
+    /// assert_eq!(kinds, [
+    ///     UseKind::Flag,
+    ///     UseKind::OptionWithInlineValue,
+    ///     UseKind::OptionValue,
+    ///     UseKind::Positional,
+    /// ]);
     /// assert_eq!(
-    ///     Some("value"),
-    ///     args.next_mval::<&str>('=').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     Some(0.25),
-    ///     args.next_mval::<f64>(':').unwrap()
-    /// );
-    /// assert_eq!(
-    ///     None,
-    ///     args.next_mval::<&str>('=').unwrap()
+    ///     summary.options().collect::<Vec<_>>(),
+    ///     ["-v", "--color", "-o"]
     /// );
+    /// # Ok::<_, pareg_core::ArgError>(())
     /// ```
-    #[inline(always)]
-    pub fn next_mval<'a, T>(&'a mut self, sep: char) -> Result<Option<T>>
-    where
-        T: FromArg<'a>,
-    {
-        self.next();
-        self.map_err(mval_arg(self.cur_arg()?, sep))
+    pub fn summary(&self) -> ParseSummary {
+        let len = self.args.len();
+        let end = self.end.unwrap_or(len).max(self.cur).min(len);
+        let consumed =
+            (self.conf.args_start..self.cur.min(len)).chain(end..len);
+
+        let mut res = ParseSummary::default();
+        for idx in consumed {
+            let arg = self.args.get(idx).unwrap_or_default();
+            let (kind, name) = match self.uses.get(idx).cloned() {
+                Some(UseTag::Value) => continue,
+                Some(UseTag::Option(kind, Some(name))) => (kind, Some(name)),
+                Some(UseTag::Option(kind, None)) => {
+                    (kind, self.summary_name(idx, classify_arg(arg).1))
+                }
+                None => {
+                    let (kind, name) = classify_arg(arg);
+                    (kind, self.summary_name(idx, name))
+                }
+            };
+            res.args.push(UsedArg { idx, kind, name });
+        }
+        res
     }
 
-    /// Parses the last returned value from the iterator.
+    /// Gets the option name at `range` in the argument at `idx` for
+    /// [`Pareg::summary`]. The name ends where the first part hidden by the
+    /// redactors (see [`Pareg::redact_matching`]) starts.
+    fn summary_name(
+        &self,
+        idx: usize,
+        range: Option<Range<usize>>,
+    ) -> Option<String> {
+        let arg = self.args.get(idx)?;
+        let prev = idx.checked_sub(1).and_then(|i| self.args.get(i));
+        let end = self
+            .redactors
+            .iter()
+            .filter_map(|r| r(idx, prev, arg))
+            .fold(range.clone()?.end, |e, r| e.min(r.start));
+        arg.get(range?.start..end).map(|n| n.into())
+    }
+
+    /// Get the last returned argument.
+    pub fn cur(&self) -> Option<&str> {
+        cur_inner(&self.args, self.cur)
+    }
+
+    /// Get value that will be returned with the next call to `next`.
+    pub fn peek(&self) -> Option<&str> {
+        self.next_idx().and_then(|i| self.get(i))
+    }
+
+    /// Get the index of the next argument.
+    pub fn next_idx(&self) -> Option<usize> {
+        if self.end.is_some_and(|e| self.cur >= e) {
+            return None;
+        }
+        self.args.get(self.cur).map(|_| self.cur)
+    }
+
+    /// Get index of the current argument. Returns [`None`] only before the
+    /// first call to [`Self::next`] or if the position is past the end of
+    /// the arguments. After [`Self::skip_all`], it is the index of the
+    /// argument returned by it.
+    pub fn cur_idx(&self) -> Option<usize> {
+        self.cur.checked_sub(1).filter(|i| *i < self.args.len())
+    }
+
+    /// Get argument at the given index.
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.args.get(idx)
+    }
+
+    /// Gets argument at the given index with the parts hidden by the
+    /// redactors, so that it can be echoed in messages.
+    fn get_redacted(&self, idx: usize) -> Option<Cow<'_, str>> {
+        redacted_arg(&self.args, &self.redactors, idx)
+    }
+
+    /// Gets the number of all the arguments (including the first one and the
+    /// arguments that were already parsed). With [`LazyArgs`] this reads all
+    /// the arguments.
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Checks whether there are no arguments at all. Use
+    /// [`Pareg::remaining_len`] to check whether all the arguments were
+    /// parsed.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+
+    /// Gets the number of arguments that will be returned by [`Pareg::next`]
+    /// (not including the current). Arguments returned by
+    /// [`Pareg::next_back`] are not remaining.
+    pub fn remaining_len(&self) -> usize {
+        self.end
+            .unwrap_or_else(|| self.args.len())
+            .saturating_sub(self.cur)
+    }
+
+    /// Gets the number of consumed arguments and the number of all the
+    /// arguments. The consumed arguments are all the arguments that are not
+    /// remaining (see [`Pareg::remaining_len`]), so the two numbers are equal
+    /// once the parsing is done. With [`LazyArgs`] this reads all the
+    /// arguments.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["hello", "10", "0.25", "always"];
+    /// let args = ["a", "b", "c", "d"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
+    /// assert_eq!((0, 4), args.progress());
     /// args.next();
-    /// assert_eq!("hello", args.cur_arg::<&str>().unwrap());
-    /// args.next();
-    /// assert_eq!(10, args.cur_arg::<usize>().unwrap());
-    /// args.next();
-    /// assert_eq!(0.25, args.cur_arg::<f64>().unwrap());
+    /// args.next_back();
+    /// assert_eq!((2, 4), args.progress());
+    /// assert_eq!(2, args.remaining_len());
+    /// args.skip_all();
+    /// assert_eq!((4, 4), args.progress());
     /// ```
-    #[inline(always)]
-    pub fn cur_arg<'a, T>(&'a self) -> Result<T>
-    where
-        T: FromArg<'a>,
-    {
-        cur_arg_inner(&self.args, self.cur)
+    pub fn progress(&self) -> (usize, usize) {
+        let len = self.args.len();
+        (len - self.remaining_len().min(len), len)
     }
 
-    /// Uses the function [`key_mval_arg`] on the last argument. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
-    /// also parsed to the given type.
-    ///
-    /// In case that there is no `'='`, value is `None`.
+    /// Sets normalization of values. It is applied to values before they are
+    /// parsed in [`Pareg::next_arg`], [`Pareg::cur_arg`], [`Pareg::cur_val`],
+    /// [`Pareg::cur_mval`], [`Pareg::cur_val_or_next`] and similar. Errors
+    /// still refer to the original arguments.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::Pareg;
+    /// use pareg_core::{Normalize, Pareg};
     ///
-    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let args = ["--count= 10 ", ""];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.normalize(Normalize { trim: true, empty_is_missing: true });
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     ("key", Some("value")),
-    ///     args.cur_key_mval::<&str, &str>('=').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     (5, Some(0.25)),
-    ///     args.cur_key_mval::<i32, f64>(':').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     ("only_key".to_owned(), None),
-    ///     args.cur_key_mval::<String, &str>('=').unwrap()
-    /// );
+    /// assert_eq!(10, args.cur_val::<u32>('=').unwrap());
+    /// assert_eq!(None, args.next_arg::<Option<u32>>().unwrap());
     /// ```
-    #[inline(always)]
-    pub fn cur_key_mval<'a, K, V>(
-        &'a self,
-        sep: char,
-    ) -> Result<(K, Option<V>)>
-    where
-        K: FromArg<'a>,
-        V: FromArg<'a>,
-    {
-        self.map_err(key_mval_arg(self.cur_arg()?, sep))
+    pub fn normalize(&mut self, normalize: Normalize) {
+        self.normalize = normalize;
     }
 
-    /// Uses the function [`key_val_arg`] on the next value. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
-    ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
-    /// also parsed to the given type.
-    ///
-    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    /// Sets usage that is shown at the end of all errors created by this
+    /// [`Pareg`] (see [`ArgErrCtx::usage`]). Errors that already have usage
+    /// are not changed.
     ///
     /// # Examples
-    /// ```rust
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25"];
+    /// let args = ["my-program", "--count", "x"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.set_error_usage("my-program [--count <N>]");
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     ("key", "value"),
-    ///     args.cur_key_val::<&str, &str>('=').unwrap()
-    /// );
     /// args.next();
-    /// assert_eq!(
-    ///     (5, 0.25),
-    ///     args.cur_key_val::<i32, f64>(':').unwrap()
-    /// );
+    /// let err = args.next_arg::<u32>().unwrap_err().no_color();
+    /// assert!(err.to_string().ends_with("usage: my-program [--count <N>]\n"));
     /// ```
-    #[inline(always)]
-    pub fn cur_key_val<'a, K, V>(&'a self, sep: char) -> Result<(K, V)>
-    where
-        K: FromArg<'a>,
-        V: FromArg<'a>,
-    {
-        self.map_err(key_val_arg(self.cur_arg()?, sep))
+    pub fn set_error_usage(&mut self, usage: impl Into<Cow<'static, str>>) {
+        self.conf.usage = Some(usage.into());
     }
 
-    /// Uses the function [`bool_arg`] on the next value. If there is no last
-    /// argument, returns `ArgError::NoLastArgument`.
-    ///
-    /// Parse bool value in a specific way. If the value of lowercase `arg` is
-    /// equal to `t` returns true, if it is equal to `f` returns false and
-    /// otherwise returns error.
+    /// Sets the texts used by all errors and warnings created by this
+    /// [`Pareg`] (see [`Messages`]). Errors that already have messages are
+    /// not changed.
+    pub fn set_messages(&mut self, messages: &'static Messages) {
+        self.conf.messages = Some(messages);
+    }
+
+    /// Sets whether the `cur_*` methods panic in debug builds when there is
+    /// no current argument instead of returning
+    /// [`ArgError::NoLastArgument`]. This is bug in the parsing code, so the
+    /// panic shows the location of the call. In release builds, the error is
+    /// returned and its message contains the location.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["true", "yes", "never"];
+    /// let args = ["-v"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
+    /// let err = args.cur_arg::<&str>().unwrap_err();
+    /// assert!(err.is_no_last_argument());
+    /// assert!(err.to_string().contains(file!()));
+    ///
+    /// args.strict_usage(true);
     /// args.next();
-    /// assert_eq!(true, args.cur_bool("true", "false").unwrap());
-    /// args.next();
-    /// assert_eq!(true, args.cur_bool("yes", "no").unwrap());
-    /// args.next();
-    /// assert_eq!(false, args.cur_bool("always", "never").unwrap());
+    /// assert_eq!(Some("-v"), args.cur_arg().ok());
     /// ```
-    #[inline(always)]
-    pub fn cur_bool(&self, t: &str, f: &str) -> Result<bool> {
-        self.map_err(bool_arg(t, f, self.cur_arg()?))
+    pub fn strict_usage(&mut self, strict: bool) {
+        self.conf.strict_usage = strict;
     }
 
-    /// Uses the function [`opt_bool_arg`] on the next argument. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
+    /// Register predicate that determines which part of argument should be
+    /// hidden in error messages. The predicate gets the index of the argument
+    /// and the argument and returns the byte range that should be redacted.
     ///
-    /// Parse bool value in a specific way. If the value of lowercase `arg` is
-    /// equal to `t` returns true, if it is equal to `f` returns false and
-    /// if it is equal to `n` returns [`None`]. Otherwise returns error.
+    /// This is useful for arguments that contain secrets such as passwords or
+    /// tokens, so that they don't appear in logs.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["always", "never", "auto"];
+    /// let args = ["--login", "user:secret"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.redact_matching(|_, a| {
+    ///     a.find(':').map(|p| p + 1..a.len())
+    /// });
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     Some(true),
-    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     Some(false),
-    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
-    /// );
     /// args.next();
-    /// assert_eq!(
-    ///     None,
-    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
-    /// );
+    /// let err = args.err_invalid().no_color().to_string();
+    /// assert!(!err.contains("secret"));
     /// ```
-    #[inline(always)]
-    pub fn cur_opt_bool(
-        &self,
-        t: &str,
-        f: &str,
-        n: &str,
-    ) -> Result<Option<bool>> {
-        self.map_err(opt_bool_arg(t, f, n, self.cur_arg()?))
+    pub fn redact_matching(
+        &mut self,
+        pred: impl Fn(usize, &str) -> Option<Range<usize>> + Send + Sync + 'static,
+    ) {
+        self.redactors.push(Box::new(move |i, _, a| pred(i, a)));
     }
 
-    /// Uses the function [`key_arg`] on the next argument. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
+    /// Redact values of the given options in error messages. Both the forms
+    /// `--opt=value` and `--opt value` are redacted.
     ///
-    /// If sep was `'='`, parses `"key=value"` into `"key"` and discards `value`.
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
     ///
-    /// In case that there is no `'='`, parses the whole input.
+    /// let args = ["--token=secret", "--password", "secret", "--count=x"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.redact_values_of(&["--token", "--password"], '=');
+    ///
+    /// args.skip_all();
+    /// let err = args.cur_val::<usize>('=').unwrap_err();
+    /// assert!(!err.no_color().to_string().contains("secret"));
+    /// ```
+    pub fn redact_values_of(&mut self, names: &[&str], sep: char) {
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        // The options are matched when the error is created, so that also
+        // arguments inserted later are redacted.
+        self.redactors.push(Box::new(move |_, prev, arg| {
+            if prev.is_some_and(|p| names.iter().any(|n| n == p)) {
+                return Some(0..arg.len());
+            }
+            let (k, v) = key_val_spans(arg, sep)?;
+            names.iter().any(|n| *n == arg[k.clone()]).then_some(v)
+        }));
+    }
+
+    /// Perform manual parsing on the next argument. This is will make the
+    /// errors have better messages than just doing the parsing without
+    /// [`Pareg`].
+    ///
+    /// `pareg.next_manual(foo)` is equivalent to
+    /// `pareg.map_err(foo(pareg.next()))`, except it has no issues with
+    /// lifetimes.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::Pareg;
+    /// use pareg_core::{Pareg, key_val_arg};
+    /// let args = ["-D10=0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
-    /// let args = ["key=value", "5:0.25"];
+    /// let res: (usize, f32) = args.next_manual(|s| {
+    ///     key_val_arg(s.strip_prefix("-D").unwrap(), '=')
+    /// }).unwrap();
+    /// assert_eq!((10, 0.25), res);
+    /// ```
+    pub fn next_manual<'a, T, F>(&'a mut self, f: F) -> Result<T>
+    where
+        T: 'a,
+        F: Fn(&'a str) -> Result<T>,
+    {
+        self.advance()?;
+        self.map_err(f(self.cur_arg()?))
+    }
+
+    /// Perform manual parsing on the next argument. This is will make the
+    /// errors have better messages than just doing the parsing without
+    /// [`Pareg`].
+    ///
+    /// `pareg.cur_manual(foo)` is equivalent to
+    /// `pareg.map_err(foo(pareg.cur()))`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, key_val_arg};
+    /// let args = ["-D10=0.25"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     "key",
-    ///     args.cur_key::<&str>('=').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     5,
-    ///     args.cur_key::<i32>(':').unwrap()
-    /// );
+    ///
+    /// let res: (usize, f32) = args.cur_manual(|s| {
+    ///     key_val_arg(s.strip_prefix("-D").unwrap(), '=')
+    /// }).unwrap();
+    /// assert_eq!((10, 0.25), res);
     /// ```
-    #[inline(always)]
-    pub fn cur_key<'a, T>(&'a self, sep: char) -> Result<T>
+    #[track_caller]
+    pub fn cur_manual<'a, T, F>(&'a self, f: F) -> Result<T>
     where
-        T: FromArg<'a>,
+        T: 'a,
+        F: Fn(&'a str) -> Result<T>,
     {
-        self.map_err(key_arg(self.cur_arg()?, sep))
+        self.map_err(f(self.cur_arg()?))
     }
 
-    /// Uses the function [`val_arg`] on the next argument. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
+    /// Parses the next value in the iterator. If the current argument looks
+    /// like option (see [`arg_class`]), errors say that it is value of that
+    /// option (see [`Pareg::next_value_of`]).
     ///
-    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
-    /// given type.
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
     ///
-    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    /// let args = ["hello", "10", "0.25", "always"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!("hello", args.next_arg::<&str>().unwrap());
+    /// assert_eq!(10, args.next_arg::<usize>().unwrap());
+    /// assert_eq!(0.25, args.next_arg::<f64>().unwrap());
+    /// ```
+    #[inline]
+    pub fn next_arg<'a, T>(&'a mut self) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let res = next_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            &mut self.cur,
+            self.end,
+        );
+        if res.is_ok() {
+            self.uses.record_value(&self.args, self.cur);
+        }
+        res
+    }
+
+    /// Same as [`Pareg::next_arg`], but the value is returned together with
+    /// its location (see [`Spanned`]). The span doesn't contain whitespace
+    /// removed by [`Pareg::normalize`].
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25"];
+    /// let args = ["--max", "3"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     "value",
-    ///     args.cur_val::<&str>('=').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     0.25,
-    ///     args.cur_val::<f64>(':').unwrap()
-    /// );
+    /// let max = args.next_arg_spanned::<u32>().unwrap();
+    /// assert_eq!((max.value, max.idx, max.span), (3, 1, 0..1));
     /// ```
-    #[inline(always)]
-    pub fn cur_val<'a, T>(&'a self, sep: char) -> Result<T>
+    pub fn next_arg_spanned<'a, T>(&'a mut self) -> Result<Spanned<T>>
     where
         T: FromArg<'a>,
     {
-        self.map_err(val_arg(self.cur_arg()?, sep))
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let value = next_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            &mut self.cur,
+            self.end,
+        )?;
+        self.uses.record_value(&self.args, self.cur);
+        let idx = self.cur - 1;
+        let arg = self.args.get(idx).unwrap_or_default();
+        let span = self.normalize.value_span(arg, 0..arg.len());
+        Ok(Spanned { value, idx, span })
     }
 
-    /// Uses the function [`mval_arg`] on the next argument. If there is no
-    /// last argument, returns `ArgError::NoLastArgument`.
+    /// Same as [`Pareg::next_arg`], but the value is parsed with `p` (e.g.
+    /// parser of value of option registered at runtime, see
+    /// [`ValueRegistry`]). Errors have the same context as errors of
+    /// [`Pareg::next_arg`].
     ///
-    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
-    /// given type.
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, Parser};
     ///
-    /// In case that there is no `'='`, value is `None`.
+    /// let args = ["--max", "3", "--max", "x"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let max = args.next_with(&Parser::<u32>::new()).unwrap();
+    /// assert_eq!(Some(&3), max.downcast_ref::<u32>());
+    ///
+    /// args.next();
+    /// let err = args.next_with(&Parser::<u32>::new()).unwrap_err();
+    /// assert_eq!(Some("--max"), err.ctx().unwrap().owner.as_deref());
+    /// ```
+    pub fn next_with(&mut self, p: &dyn ParseValue) -> Result<Box<dyn Any>> {
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let Spanned { idx, span, .. } = self.next_arg_spanned::<&str>()?;
+        let owner = idx.checked_sub(1).and_then(|i| self.args.get(i));
+        let arg = self.args.get(idx).unwrap_or_default();
+        let res = p.parse(&arg[span.clone()]).map_err(|e| {
+            let e = if span == (0..arg.len()) {
+                e
+            } else {
+                e.shift_span(span.start, arg.to_string())
+            };
+            value_of_option(e, owner)
+        });
+        self.map_err(res)
+    }
+
+    /// Parses the next `n` arguments as values of the current option (e.g.
+    /// `--rgb 255 0 0`). If there are not enough arguments, nothing is
+    /// consumed and the error says how many values are missing. Errors of
+    /// the values say which of the `n` values is invalid.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let args = ["--rgb", "255", "0", "x", "--rgb", "1"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
+    /// let err = args.next_n::<u8>(3).unwrap_err();
     /// assert_eq!(
-    ///     Some("value"),
-    ///     args.cur_mval::<&str>('=').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     Some(0.25),
-    ///     args.cur_mval::<f64>(':').unwrap()
+    ///     Some("invalid digit found in string (value 3 of 3)"),
+    ///     err.long_message()
     /// );
+    ///
     /// args.next();
+    /// let err = args.next_n::<u8>(3).unwrap_err();
     /// assert_eq!(
-    ///     None,
-    ///     args.cur_mval::<&str>('=').unwrap()
+    ///     Some("Expected 2 more values for `--rgb`."),
+    ///     err.long_message()
     /// );
+    /// assert_eq!(vec![1], args.next_n::<u8>(1).unwrap());
     /// ```
-    #[inline(always)]
-    pub fn cur_mval<'a, T>(&'a self, sep: char) -> Result<Option<T>>
+    pub fn next_n<'a, T>(&'a mut self, n: usize) -> Result<Vec<T>>
     where
         T: FromArg<'a>,
     {
-        cur_mval_inner(&self.args, self.cur, sep)
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let end = self.end.unwrap_or(usize::MAX).min(self.args.len());
+        // Each value is intercepted as with `next_arg`. The values before
+        // the special argument are consumed.
+        let last = end.min(self.cur.saturating_add(n));
+        if let Some(i) =
+            (self.cur + 1..last).find(|i| self.special_at(*i).is_some())
+        {
+            self.cur = i;
+            if let Some(s) = self.intercept() {
+                return Err(ArgError::Special(s));
+            }
+        }
+        let missing = n.saturating_sub(end.saturating_sub(self.cur));
+        if missing != 0 {
+            return Err(self.err_missing_values(missing));
+        }
+
+        let start = self.cur;
+        self.cur += n;
+        self.uses.record_values(&self.args, start, n);
+        let this = &*self;
+        let owner = start.checked_sub(1).and_then(|i| this.args.get(i));
+        (start..start + n)
+            .map(|idx| {
+                let arg = this.args.get(idx).unwrap_or_default();
+                let res = this
+                    .normalize
+                    .parse(arg, 0..arg.len(), owner)
+                    .map_err(|e| {
+                        value_of_option(e, owner).map_ctx(|c| {
+                            let msg =
+                                c.long_message.as_ref().unwrap_or(&c.message);
+                            let suffix = this
+                                .conf
+                                .messages
+                                .unwrap_or(&Messages::ENGLISH)
+                                .value_i_of_n_msg(idx - start + 1, n);
+                            let msg = format!("{msg} {suffix}");
+                            c.main_msg(msg)
+                        })
+                    });
+                map_err_inner(
+                    &this.args,
+                    &this.redactors,
+                    &this.origins,
+                    &this.conf,
+                    idx + 1,
+                    res,
+                )
+            })
+            .collect::<Result<_>>()
     }
 
-    /// Split the current argument by the given separator and return the parsed
-    /// value after the separator or if there is no such separator, parse the
-    /// next argument.
+    /// Same as [`Pareg::next_n`], but the number of values is known at
+    /// compile time.
     ///
     /// # Examples
     /// ```rust
     /// use pareg_core::Pareg;
     ///
-    /// let args = ["--cnt", "20", "--cnt=10"];
+    /// let args = ["--point", "3", "-4"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
-    /// assert_eq!(
-    ///     20,
-    ///     args.cur_val_or_next::<u32>('=').unwrap()
-    /// );
-    /// args.next();
-    /// assert_eq!(
-    ///     10,
-    ///     args.cur_val_or_next::<u32>('=').unwrap()
-    /// );
+    /// let [x, y] = args.next_n_args::<i32, 2>().unwrap();
+    /// assert_eq!((3, -4), (x, y));
     /// ```
-    pub fn cur_val_or_next<'a, T>(&'a mut self, sep: char) -> Result<T>
+    pub fn next_n_args<'a, T, const N: usize>(&'a mut self) -> Result<[T; N]>
     where
         T: FromArg<'a>,
     {
-        if let Some(res) = cur_mval_inner(&self.args, self.cur, sep)? {
-            Ok(res)
-        } else {
-            next_arg_inner(&self.args, &mut self.cur)
-        }
+        let res = self.next_n(N)?;
+        Ok(res
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("`next_n` returns N values")))
     }
 
-    /// Creates pretty error that the last argument (cur) is unknown.
-    pub fn err_unknown_argument(&self) -> ArgError {
-        let arg = self.cur().unwrap_or("");
-        let long_message = self
-            .cur()
-            .map(|a| format!("Unknown argument `{a}`.").into());
-        let context = ArgErrCtx {
-            args: self.args.clone(),
-            error_idx: self.cur.saturating_sub(1),
-            error_span: 0..arg.len(),
-            message: "Unknown argument.".into(),
-            long_message,
-            hint: None,
-            color: ColorMode::default(),
-        };
-        ArgError::UnknownArgument(context.into())
+    /// Creates error that `missing` values of the current option are missing
+    /// (see [`Pareg::next_n`]).
+    #[cold]
+    fn err_missing_values(&self, missing: usize) -> ArgError {
+        let opt = self
+            .cur_idx()
+            .filter(|_| self.cur().is_some_and(|o| arg_class(o).is_option()))
+            .and_then(|i| self.get_redacted(i));
+        let opt = opt.as_deref().map(|o| truncate_middle(o, 40));
+        let msg = self
+            .conf
+            .messages
+            .unwrap_or(&Messages::ENGLISH)
+            .missing_values_msg(missing, opt.as_deref());
+        self.err_no_more_arguments().main_msg(msg)
     }
 
-    /// Creates pretty error that there should be more arguments but there are
-    /// no more arguments.
-    #[inline(always)]
-    pub fn err_no_more_arguments(&self) -> ArgError {
-        err_no_more_arguments_inner(&self.args)
+    /// Parses the next argument as value of the option `owner`. Errors will
+    /// say that the value of `owner` is invalid.
+    ///
+    /// [`Pareg::next_arg`] does this automatically if the current argument
+    /// looks like option (see [`arg_class`]). Use this if it doesn't (e.g.
+    /// subcommand `set count 5`) or if the value is not right after the
+    /// option.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ArgError, Pareg};
+    ///
+    /// let args = ["set", "count", "x"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.skip(2);
+    /// let Err(ArgError::FailedToParse(ctx)) = args.next_value_of::<u32>("count")
+    /// else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(ctx.owner.as_deref(), Some("count"));
+    /// ```
+    pub fn next_value_of<'a, T>(&'a mut self, owner: &str) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let Some(a) = next_inner(&self.args, &mut self.cur, self.end) else {
+            return Err(err_no_more_arguments_inner(
+                &self.args,
+                &self.redactors,
+                &self.origins,
+                &self.conf,
+                self.end,
+            ));
+        };
+        let res = self.normalize.parse(a, 0..a.len(), Some(owner));
+        let res = res.map_err(|e| value_of(e, owner));
+        let res = map_err_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            self.cur,
+            res,
+        );
+        if res.is_ok() {
+            self.uses.record_value(&self.args, self.cur);
+        }
+        res
     }
 
-    /// Creates error that says that the current argument has invalid value.
-    pub fn err_invalid(&self) -> ArgError {
-        self.err_invalid_value(self.cur().unwrap_or_default().to_owned())
+    /// Parses the next argument. If there is no next argument and stdin is
+    /// terminal, the user is prompted for the value on stderr (see
+    /// [`Pareg::next_arg_or_prompt_opts`]). If stdin is not terminal, the
+    /// error is [`ArgError::NoMoreArguments`].
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn next_arg_or_prompt<T>(&mut self, prompt: &str) -> Result<T>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        self.next_arg_or_prompt_opts(prompt, &PromptOpts::default())
     }
 
-    /// Creates error that says that the given part of the current argument has
-    /// invalid value.
-    pub fn err_invalid_value(&self, value: String) -> ArgError {
-        ArgError::InvalidValue(Box::new(ArgErrCtx::from_msg(
-            "Invalid value for argument.",
-            value,
-        )))
-        .add_args(self.args.clone(), self.cur.saturating_sub(1))
+    /// Parses the next argument. If there is no next argument and stdin is
+    /// terminal, prints `prompt: ` to stderr and parses line from stdin. If
+    /// the value is invalid, the error is printed and the user is prompted
+    /// again at most [`PromptOpts::retries`] times. If stdin is not terminal
+    /// or it ends, the error is [`ArgError::NoMoreArguments`].
+    #[cfg(feature = "std")]
+    pub fn next_arg_or_prompt_opts<T>(
+        &mut self,
+        prompt: &str,
+        opts: &PromptOpts,
+    ) -> Result<T>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        if self.peek().is_none() && !io::stdin().is_terminal() {
+            return Err(self.err_no_more_arguments());
+        }
+        self.next_arg_or_prompt_with(
+            prompt,
+            opts,
+            &mut io::stdin().lock(),
+            &mut io::stderr(),
+        )
     }
 
-    /// Creates error that says that the given part of the current argument has
-    /// invalid value.
-    pub fn err_invalid_span(&self, span: Range<usize>) -> ArgError {
-        let value = self.cur().unwrap_or_default();
-        if span.start > value.len() || span.end > value.len() {
-            self.err_invalid_value(value.to_owned())
-        } else {
-            ArgError::InvalidValue(Box::new(
-                ArgErrCtx::from_msg(
-                    "Invalid value for argument.",
-                    value[span.clone()].to_owned(),
-                )
-                .spanned(span),
-            ))
+    /// Same as [`Pareg::next_arg_or_prompt_opts`], but the prompt is written
+    /// to `output` and the value is read from `input` (regardless of whether
+    /// it is terminal).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, PromptOpts};
+    ///
+    /// let mut args = Pareg::new(vec![]);
+    /// let opts = PromptOpts {
+    ///     default: Some("8".into()),
+    ///     ..Default::default()
+    /// };
+    /// let mut out = vec![];
+    ///
+    /// let n: u32 = args
+    ///     .next_arg_or_prompt_with("Jobs", &opts, &mut "\n".as_bytes(), &mut out)
+    ///     .unwrap();
+    /// assert_eq!(8, n);
+    /// assert_eq!(b"Jobs [8]: ", out.as_slice());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn next_arg_or_prompt_with<T>(
+        &mut self,
+        prompt: &str,
+        opts: &PromptOpts,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<T>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        if self.peek().is_some() {
+            return self.next_arg();
         }
+        prompt::prompt(prompt, opts, input, output)?
+            .ok_or_else(|| self.err_no_more_arguments())
     }
 
-    /// Adds additional information to error so that it has better error
-    /// message. Consider using [`Pareg::cur_manual`] or [`Pareg::next_manual`]
-    /// instead.
+    /// Parses the next argument as path and opens the file for reading. If
+    /// the argument is `-`, the input is stdin (see [`StdinOr`]). If the file
+    /// cannot be opened, the error points to the argument.
     ///
     /// # Examples
     /// ```rust
-    /// use pareg_core::{Pareg, key_val_arg};
-    /// let args = ["-D10=0.25"];
+    /// use std::io::Read;
+    ///
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-i", "Cargo.toml", "-i", "-", "-i", "missing.txt"];
     /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
     ///
     /// args.next();
-    /// let arg: &str = args.cur_arg().unwrap();
-    /// let arg = arg.strip_prefix("-D").unwrap();
+    /// let mut input = args.next_file_or_stdin().unwrap();
+    /// let mut text = String::new();
+    /// input.read_to_string(&mut text).unwrap();
+    /// assert!(text.contains("[package]"));
     ///
-    /// let res: (usize, f32) = args.map_err(key_val_arg(arg, '=')).unwrap();
-    /// assert_eq!((10, 0.25), res);
-    /// ```
-    pub fn map_err<T>(&self, res: Result<T>) -> Result<T> {
-        map_err_inner(&self.args, self.cur, res)
+    /// args.next();
+    /// assert!(args.next_file_or_stdin().unwrap().is_stdin());
+    ///
+    /// args.next();
+    /// let err = args.next_file_or_stdin().unwrap_err();
+    /// assert_eq!(5, err.ctx().unwrap().error_idx);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn next_file_or_stdin(&mut self) -> Result<Input> {
+        let StdinOr::Value(path) = self.next_arg::<StdinOr<PathBuf>>()? else {
+            return Ok(Input::Stdin(io::stdin()));
+        };
+        match File::open(&path) {
+            Ok(f) => Ok(Input::File(path, f)),
+            Err(e) => {
+                let arg = self.cur().unwrap_or_default();
+                let err = ArgError::InvalidValue(
+                    ArgErrCtx::from_msg(
+                        "Failed to open file.",
+                        arg.to_owned(),
+                    )
+                    .main_msg(format!("Failed to open `{arg}`: {e}"))
+                    .into(),
+                );
+                let owner = self
+                    .cur_idx()
+                    .and_then(|i| i.checked_sub(1).and_then(|i| self.get(i)));
+                self.map_err(Err(value_of_option(err, owner)))
+            }
+        }
+    }
+
+    /// Parses the next argument as `T` and converts it to `U` with
+    /// [`TryFrom`]. If the conversion fails, the error is
+    /// [`ArgError::InvalidValue`] with the message of the conversion error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::num::NonZeroU8;
+    ///
+    /// use pareg_core::{ArgError, Pareg};
+    ///
+    /// let args = ["5", "0", "-1"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     NonZeroU8::new(5),
+    ///     args.next_try_into::<u8, _>().ok()
+    /// );
+    /// assert!(matches!(
+    ///     args.next_try_into::<u8, NonZeroU8>(),
+    ///     Err(ArgError::InvalidValue(_))
+    /// ));
+    /// assert!(matches!(
+    ///     args.next_try_into::<u8, NonZeroU8>(),
+    ///     Err(ArgError::FailedToParse(_))
+    /// ));
+    /// ```
+    pub fn next_try_into<'a, T, U>(&'a mut self) -> Result<U>
+    where
+        T: FromArg<'a>,
+        U: TryFrom<T>,
+        U::Error: Display,
+    {
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let val: T = next_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            &mut self.cur,
+            self.end,
+        )?;
+        self.uses.record_value(&self.args, self.cur);
+        let len = self.cur().map_or(0, str::len);
+        self.try_convert(val, 0..len)
+    }
+
+    /// Uses the function [`key_mval_arg`] on the next argument.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
+    /// also parsed to the given type.
+    ///
+    /// In case that there is no `'='`, value is `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     ("key", Some("value")),
+    ///     args.next_key_mval::<&str, &str>('=').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     (5, Some(0.25)),
+    ///     args.next_key_mval::<i32, f64>(':').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     ("only_key".to_owned(), None),
+    ///     args.next_key_mval::<String, &str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_key_mval<'a, K, V>(
+        &'a mut self,
+        sep: char,
+    ) -> Result<(K, Option<V>)>
+    where
+        K: FromArg<'a>,
+        V: FromArg<'a>,
+    {
+        self.advance()?;
+        self.map_err(key_mval_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`key_val_arg`] on the next value.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
+    /// also parsed to the given type.
+    ///
+    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     ("key", "value"),
+    ///     args.next_key_val::<&str, &str>('=').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     (5, 0.25),
+    ///     args.next_key_val::<i32, f64>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_key_val<'a, K, V>(&'a mut self, sep: char) -> Result<(K, V)>
+    where
+        K: FromArg<'a>,
+        V: FromArg<'a>,
+    {
+        self.advance()?;
+        self.map_err(key_val_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`csv_row_as`] on the next value.
+    ///
+    /// Splits the argument into fields separated by `sep` (e.g. `a,"b,c",3`)
+    /// and parses them to the given type. Fields may be quoted with `"`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--row", r#"a,"b,c",3"#];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     vec!["a", "b,c", "3"],
+    ///     args.next_csv::<String>(',').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_csv<T>(&mut self, sep: char) -> Result<Vec<T>>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        self.advance()?;
+        self.cur_csv(sep)
+    }
+
+    /// Parses the next argument and pushes it to `out`. This is useful for
+    /// options that may repeat (e.g. `-I path`).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-I", "src", "-I", "include"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut dirs: Vec<String> = vec![];
+    /// while args.next().is_some() {
+    ///     args.next_push(&mut dirs).unwrap();
+    /// }
+    /// assert_eq!(dirs, ["src", "include"]);
+    /// ```
+    #[inline]
+    pub fn next_push<'a, T>(&'a mut self, out: &mut Vec<T>) -> Result<()>
+    where
+        T: FromArg<'a>,
+    {
+        out.push(self.next_arg()?);
+        Ok(())
+    }
+
+    /// Parses the next argument with [`key_val_arg`] and inserts the pair to
+    /// `out`. `on_dup` determines what happens if the key is already in
+    /// `out`. See [`Pareg::cur_key_val_insert`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use pareg_core::{ArgError, DupPolicy, Pareg};
+    ///
+    /// let args = ["-D", "a=1", "-D", "b=2", "-D", "a=3"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut defs = BTreeMap::<String, u32>::new();
+    /// args.next();
+    /// args.next_key_val_insert('=', &mut defs, DupPolicy::Error).unwrap();
+    /// args.next();
+    /// args.next_key_val_insert('=', &mut defs, DupPolicy::Error).unwrap();
+    /// args.next();
+    /// assert!(matches!(
+    ///     args.next_key_val_insert('=', &mut defs, DupPolicy::Error),
+    ///     Err(ArgError::TooManyArguments(_))
+    /// ));
+    /// assert_eq!(defs, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+    /// ```
+    pub fn next_key_val_insert<'a, K, V, M>(
+        &'a mut self,
+        sep: char,
+        out: &mut M,
+        on_dup: DupPolicy,
+    ) -> Result<()>
+    where
+        K: FromArg<'a> + PartialEq,
+        V: FromArg<'a>,
+        M: ArgMap<K, V>,
+    {
+        self.advance()?;
+        self.cur_key_val_insert(sep, out, on_dup)
+    }
+
+    /// Uses the function [`bool_arg`] on the next value.
+    ///
+    /// Parse bool value in a specific way. If the value of lowercase `arg` is
+    /// equal to `t` returns true, if it is equal to `f` returns false and
+    /// otherwise returns error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["true", "yes", "never"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(true, args.next_bool("true", "false").unwrap());
+    /// assert_eq!(true, args.next_bool("yes", "no").unwrap());
+    /// assert_eq!(false, args.next_bool("always", "never").unwrap());
+    /// ```
+    #[inline(always)]
+    pub fn next_bool(&mut self, t: &str, f: &str) -> Result<bool> {
+        self.advance()?;
+        self.map_err(bool_arg(t, f, self.cur_arg()?))
+    }
+
+    /// Uses the function [`opt_bool_arg`] on the next argument.
+    ///
+    /// Parse bool value in a specific way. If the value of lowercase `arg` is
+    /// equal to `t` returns true, if it is equal to `f` returns false and
+    /// if it is equal to `n` returns [`None`]. Otherwise returns error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["always", "never", "auto"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     Some(true),
+    ///     args.next_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Some(false),
+    ///     args.next_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// assert_eq!(
+    ///     None,
+    ///     args.next_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_opt_bool(
+        &mut self,
+        t: &str,
+        f: &str,
+        n: &str,
+    ) -> Result<Option<bool>> {
+        self.advance()?;
+        self.map_err(opt_bool_arg(t, f, n, self.cur_arg()?))
+    }
+
+    /// Uses the function [`key_arg`] on the next value.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and discards `value`.
+    ///
+    /// In case that there is no `'='`, parses the whole input.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     "key",
+    ///     args.next_key::<&str>('=').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     5,
+    ///     args.next_key::<i32>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_key<'a, T>(&'a mut self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.advance()?;
+        self.map_err(key_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`val_arg`] on the next value.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
+    /// given type.
+    ///
+    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     "value",
+    ///     args.next_val::<&str>('=').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     0.25,
+    ///     args.next_val::<f64>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_val<'a, T>(&'a mut self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_val(sep)
+    }
+
+    /// Uses the function [`mval_arg`] on the next argument.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
+    /// given type.
+    ///
+    /// In case that there is no `'='`, value is `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     Some("value"),
+    ///     args.next_mval::<&str>('=').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Some(0.25),
+    ///     args.next_mval::<f64>(':').unwrap()
+    /// );
+    /// assert_eq!(
+    ///     None,
+    ///     args.next_mval::<&str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_mval<'a, T>(&'a mut self, sep: char) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_mval(sep)
+    }
+
+    /// Uses the function [`key_mval_arg_esc`] on the next argument. Same as
+    /// [`Pareg::next_key_mval`], but the separator may be escaped in the key
+    /// with `\`.
+    #[inline(always)]
+    pub fn next_key_mval_esc<'a, K, V>(
+        &'a mut self,
+        sep: char,
+    ) -> Result<(K, Option<V>)>
+    where
+        K: for<'k> FromArg<'k>,
+        V: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_key_mval_esc(sep)
+    }
+
+    /// Uses the function [`key_val_arg_esc`] on the next argument. Same as
+    /// [`Pareg::next_key_val`], but the separator may be escaped in the key
+    /// with `\`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--map", r"a\=b=c"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     ("a=b".to_owned(), "c"),
+    ///     args.next_key_val_esc::<String, &str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn next_key_val_esc<'a, K, V>(
+        &'a mut self,
+        sep: char,
+    ) -> Result<(K, V)>
+    where
+        K: for<'k> FromArg<'k>,
+        V: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_key_val_esc(sep)
+    }
+
+    /// Uses the function [`val_arg_esc`] on the next argument. Same as
+    /// [`Pareg::next_val`], but the separator may be escaped in the key with
+    /// `\`.
+    #[inline(always)]
+    pub fn next_val_esc<'a, T>(&'a mut self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_val_esc(sep)
+    }
+
+    /// Uses the function [`mval_arg_esc`] on the next argument. Same as
+    /// [`Pareg::next_mval`], but the separator may be escaped in the key with
+    /// `\`.
+    #[inline(always)]
+    pub fn next_mval_esc<'a, T>(&'a mut self, sep: char) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        self.advance()?;
+        self.cur_mval_esc(sep)
+    }
+
+    /// Parses the last returned value from the iterator.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["hello", "10", "0.25", "always"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!("hello", args.cur_arg::<&str>().unwrap());
+    /// args.next();
+    /// assert_eq!(10, args.cur_arg::<usize>().unwrap());
+    /// args.next();
+    /// assert_eq!(0.25, args.cur_arg::<f64>().unwrap());
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_arg<'a, T>(&'a self) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        cur_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            self.cur,
+        )
+    }
+
+    /// Parses the last returned value from the iterator, but doesn't create
+    /// error if it fails (see [`FromArg::try_from_arg`]). This is cheaper
+    /// than [`Pareg::cur_arg`] when the value is only probed (e.g. before
+    /// parsing it as different type). The result is the same as
+    /// `self.cur_arg().ok()`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["10", "name"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(Some(10), args.cur_try_arg::<u32>());
+    /// args.next();
+    /// assert_eq!(None, args.cur_try_arg::<u32>());
+    /// assert_eq!(Some("name"), args.cur_try_arg::<&str>());
+    /// ```
+    #[inline]
+    pub fn cur_try_arg<'a, T>(&'a self) -> Option<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.normalize.try_parse(self.cur()?)
+    }
+
+    /// Checks whether the last returned value from the iterator may be
+    /// parsed as `T`. No error is created (see [`Pareg::cur_try_arg`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["10", "-1"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert!(!args.cur_is::<&str>());
+    /// args.next();
+    /// assert!(args.cur_is::<u32>());
+    /// args.next();
+    /// assert!(!args.cur_is::<u32>() && args.cur_is::<i32>());
+    /// ```
+    #[inline]
+    pub fn cur_is<'a, T>(&'a self) -> bool
+    where
+        T: FromArg<'a>,
+    {
+        self.cur_try_arg::<T>().is_some()
+    }
+
+    /// Parses the argument returned by [`Pareg::next_back`]. Errors point to
+    /// that argument, but the current argument is not changed. If there are
+    /// no remaining arguments, returns [`ArgError::NoMoreArguments`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-v", "5", "x", "10"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(10, args.rlast_arg::<u32>().unwrap());
+    /// let err = args.rlast_arg::<u32>().unwrap_err();
+    /// assert_eq!(2, err.ctx().unwrap().error_idx);
+    /// assert_eq!(5, args.rlast_arg::<u32>().unwrap());
+    /// assert_eq!(Some("-v"), args.next());
+    /// assert!(args.rlast_arg::<u32>().is_err());
+    /// ```
+    pub fn rlast_arg<'a, T>(&'a mut self) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if self.next_back().is_none() {
+            return Err(self.err_no_more_arguments());
+        }
+        cur_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            self.end.map_or(0, |e| e + 1),
+        )
+    }
+
+    /// Uses the function [`key_mval_arg`] on the last argument. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
+    /// also parsed to the given type.
+    ///
+    /// In case that there is no `'='`, value is `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     ("key", Some("value")),
+    ///     args.cur_key_mval::<&str, &str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     (5, Some(0.25)),
+    ///     args.cur_key_mval::<i32, f64>(':').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     ("only_key".to_owned(), None),
+    ///     args.cur_key_mval::<String, &str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_key_mval<'a, K, V>(
+        &'a self,
+        sep: char,
+    ) -> Result<(K, Option<V>)>
+    where
+        K: FromArg<'a>,
+        V: FromArg<'a>,
+    {
+        self.map_err(key_mval_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`key_val_spans`] on the current argument. Returns
+    /// [`None`] if there is no current argument or if it has no separator.
+    ///
+    /// The ranges are relative to the current argument and nothing is parsed
+    /// or allocated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "only_key"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(Some((0..3, 4..9)), args.cur_key_val_spans('='));
+    /// args.next();
+    /// assert_eq!(None, args.cur_key_val_spans('='));
+    /// ```
+    #[inline]
+    pub fn cur_key_val_spans(
+        &self,
+        sep: char,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        key_val_spans(self.cur()?, sep)
+    }
+
+    /// Iterates over all the arguments and finds the key and value ranges as
+    /// [`key_val_spans`]. The items are the index of the argument, the range
+    /// of the key and the range of the value. If there is no separator, the
+    /// key is the whole argument and the value is [`None`].
+    ///
+    /// This doesn't move the position of [`Pareg`] and nothing is parsed or
+    /// allocated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["a=1", "bc", "d=", "=e"];
+    /// let args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let spans: Vec<_> = args.scan_key_val_spans('=').collect();
+    /// assert_eq!(
+    ///     spans,
+    ///     [
+    ///         (0, 0..1, Some(2..3)),
+    ///         (1, 0..2, None),
+    ///         (2, 0..1, Some(2..2)),
+    ///         (3, 0..0, Some(1..2)),
+    ///     ]
+    /// );
+    /// ```
+    pub fn scan_key_val_spans(
+        &self,
+        sep: char,
+    ) -> impl Iterator<Item = (usize, Range<usize>, Option<Range<usize>>)> + '_
+    {
+        self.args.iter_range(0..self.args.len()).enumerate().map(
+            move |(i, a)| match key_val_spans(a, sep) {
+                Some((k, v)) => (i, k, Some(v)),
+                None => (i, 0..a.len(), None),
+            },
+        )
+    }
+
+    /// Checks whether the current argument is option in the namespace `ns`
+    /// (e.g. `--log.level=debug` is in the namespace `log`). If it is,
+    /// returns the key without the namespace (`level`) and the value
+    /// (`debug`) together with their locations, so that errors may point to
+    /// them (see [`Pareg::err_at`]). Namespaces may be nested, so
+    /// `--a.b.c=1` is in the namespace `a` with the key `b.c` and also in the
+    /// namespace `a.b` with the key `c`.
+    ///
+    /// Returns [`None`] if the argument is not in the namespace. Option in
+    /// the namespace without value is [`ArgError::NoValue`] and option with
+    /// empty key is [`ArgError::InvalidValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--log.level=debug", "--net.timeout=5", "--log.file"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let (key, value) = args.cur_namespace("log", '=').unwrap().unwrap();
+    /// assert_eq!((key.value, key.span), ("level", 6..11));
+    /// assert_eq!((value.value, value.span), ("debug", 12..17));
+    ///
+    /// args.next();
+    /// assert!(args.cur_namespace("log", '=').unwrap().is_none());
+    ///
+    /// args.next();
+    /// assert!(args.cur_namespace("log", '=').is_err());
+    /// ```
+    #[track_caller]
+    pub fn cur_namespace<'a>(
+        &'a self,
+        ns: &str,
+        sep: char,
+    ) -> Result<Option<(Spanned<&'a str>, Spanned<&'a str>)>> {
+        match self.cur.checked_sub(1) {
+            Some(idx) => self.namespace_at(idx, ns, sep),
+            None => Ok(None),
+        }
+    }
+
+    /// Collects the keys and values of all the remaining arguments (including
+    /// the current) that are in the namespace `ns` as in
+    /// [`Pareg::cur_namespace`]. Other arguments are ignored. If key is
+    /// given more than once, the last value is used.
+    ///
+    /// This doesn't move the position of [`Pareg`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::HashMap;
+    ///
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--log.level=info", "-v", "--log.file=a", "--log.level=x"];
+    /// let args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(
+    ///     args.namespace_map("log", '=').unwrap(),
+    ///     HashMap::from([
+    ///         ("level".to_string(), "x".to_string()),
+    ///         ("file".to_string(), "a".to_string()),
+    ///     ])
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    #[track_caller]
+    pub fn namespace_map(
+        &self,
+        ns: &str,
+        sep: char,
+    ) -> Result<HashMap<String, String>> {
+        let end = self.end.unwrap_or(self.args.len());
+        let mut res = HashMap::new();
+        for idx in self.cur.saturating_sub(1)..end {
+            if let Some((k, v)) = self.namespace_at(idx, ns, sep)? {
+                res.insert(k.value.to_string(), v.value.to_string());
+            }
+        }
+        Ok(res)
+    }
+
+    /// Uses the function [`key_val_arg`] on the next value. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
+    /// also parsed to the given type.
+    ///
+    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     ("key", "value"),
+    ///     args.cur_key_val::<&str, &str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     (5, 0.25),
+    ///     args.cur_key_val::<i32, f64>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_key_val<'a, K, V>(&'a self, sep: char) -> Result<(K, V)>
+    where
+        K: FromArg<'a>,
+        V: FromArg<'a>,
+    {
+        self.map_err(key_val_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`csv_row_as`] on the last value. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["1;\"2\";3"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(vec![1, 2, 3], args.cur_csv::<u8>(';').unwrap());
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_csv<T>(&self, sep: char) -> Result<Vec<T>>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        self.map_err(csv_row_as(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`bool_arg`] on the next value. If there is no last
+    /// argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// Parse bool value in a specific way. If the value of lowercase `arg` is
+    /// equal to `t` returns true, if it is equal to `f` returns false and
+    /// otherwise returns error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["true", "yes", "never"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(true, args.cur_bool("true", "false").unwrap());
+    /// args.next();
+    /// assert_eq!(true, args.cur_bool("yes", "no").unwrap());
+    /// args.next();
+    /// assert_eq!(false, args.cur_bool("always", "never").unwrap());
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_bool(&self, t: &str, f: &str) -> Result<bool> {
+        self.map_err(bool_arg(t, f, self.cur_arg()?))
+    }
+
+    /// Uses the function [`opt_bool_arg`] on the next argument. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// Parse bool value in a specific way. If the value of lowercase `arg` is
+    /// equal to `t` returns true, if it is equal to `f` returns false and
+    /// if it is equal to `n` returns [`None`]. Otherwise returns error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["always", "never", "auto"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     Some(true),
+    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     Some(false),
+    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     None,
+    ///     args.cur_opt_bool("always", "never", "auto").unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_opt_bool(
+        &self,
+        t: &str,
+        f: &str,
+        n: &str,
+    ) -> Result<Option<bool>> {
+        self.map_err(opt_bool_arg(t, f, n, self.cur_arg()?))
+    }
+
+    /// Uses the function [`key_arg`] on the next argument. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `"key"` and discards `value`.
+    ///
+    /// In case that there is no `'='`, parses the whole input.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     "key",
+    ///     args.cur_key::<&str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     5,
+    ///     args.cur_key::<i32>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_key<'a, T>(&'a self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.map_err(key_arg(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`val_arg`] on the next argument. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
+    /// given type.
+    ///
+    /// In case that there is no `'='`, returns [`ArgError::NoValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     "value",
+    ///     args.cur_val::<&str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     0.25,
+    ///     args.cur_val::<f64>(':').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_val<'a, T>(&'a self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        match self.cur_mval(sep)? {
+            Some(v) => Ok(v),
+            None => self.map_err(val_arg(self.cur_arg()?, sep)),
+        }
+    }
+
+    /// Same as [`Pareg::cur_val`], but the value is returned together with
+    /// its location (see [`Spanned`]). The span doesn't contain whitespace
+    /// removed by [`Pareg::normalize`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--min=5"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let min = args.cur_val_spanned::<u32>('=').unwrap();
+    /// assert_eq!((min.value, min.idx, min.span), (5, 0, 6..7));
+    /// ```
+    #[track_caller]
+    pub fn cur_val_spanned<'a, T>(&'a self, sep: char) -> Result<Spanned<T>>
+    where
+        T: FromArg<'a>,
+    {
+        let value = self.cur_val(sep)?;
+        let idx = self.cur - 1;
+        let arg = self.args.get(idx).unwrap_or_default();
+        let span = key_val_spans(arg, sep).map_or(0..arg.len(), |(_, v)| v);
+        let span = self.normalize.value_span(arg, span);
+        Ok(Spanned { value, idx, span })
+    }
+
+    /// Parses the next argument and sets it as the value of `value` together
+    /// with its location, so that errors of defaults that depend on it point
+    /// to it (see [`Pareg::resolve_all`]).
+    pub fn set_deferred<'a, T>(
+        &'a mut self,
+        value: &mut Deferred<T>,
+    ) -> Result<()>
+    where
+        T: FromArg<'a>,
+    {
+        *value = Deferred::Set(self.next_arg_spanned()?);
+        Ok(())
+    }
+
+    /// Same as [`Pareg::set_deferred`], but the value is parsed from the
+    /// current argument with [`Pareg::cur_val`] (e.g. `--output=file`).
+    #[track_caller]
+    pub fn set_deferred_val<'a, T>(
+        &'a self,
+        value: &mut Deferred<T>,
+        sep: char,
+    ) -> Result<()>
+    where
+        T: FromArg<'a>,
+    {
+        *value = Deferred::Set(self.cur_val_spanned(sep)?);
+        Ok(())
+    }
+
+    /// Parses the value of the current argument with [`Pareg::cur_val`] and
+    /// pushes it to `out`. This is useful for options that may repeat (e.g.
+    /// `--include=path`).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--jobs=1", "--jobs=4"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut jobs: Vec<u32> = vec![];
+    /// while args.next().is_some() {
+    ///     args.cur_val_push('=', &mut jobs).unwrap();
+    /// }
+    /// assert_eq!(jobs, [1, 4]);
+    /// ```
+    #[inline]
+    #[track_caller]
+    pub fn cur_val_push<'a, T>(
+        &'a self,
+        sep: char,
+        out: &mut Vec<T>,
+    ) -> Result<()>
+    where
+        T: FromArg<'a>,
+    {
+        out.push(self.cur_val(sep)?);
+        Ok(())
+    }
+
+    /// Parses the current argument with [`key_val_arg`] and inserts the pair
+    /// to `out` (e.g. [`std::collections::HashMap`] or
+    /// [`alloc::collections::BTreeMap`]). If the key is already in `out`,
+    /// `on_dup` determines what happens:
+    /// - [`DupPolicy::Error`]: [`ArgError::TooManyArguments`] that points to
+    ///   the key and mentions the argument where the key was first given.
+    /// - [`DupPolicy::Overwrite`]: the value is replaced.
+    /// - [`DupPolicy::Keep`]: the new value is ignored.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    ///
+    /// use pareg_core::{DupPolicy, Pareg};
+    ///
+    /// let args = ["a=1", "a=2", "b=3"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut defs = BTreeMap::<String, u32>::new();
+    /// while args.next().is_some() {
+    ///     args.cur_key_val_insert('=', &mut defs, DupPolicy::Keep).unwrap();
+    /// }
+    /// assert_eq!(defs, BTreeMap::from([("a".into(), 1), ("b".into(), 3)]));
+    /// ```
+    #[track_caller]
+    pub fn cur_key_val_insert<'a, K, V, M>(
+        &'a self,
+        sep: char,
+        out: &mut M,
+        on_dup: DupPolicy,
+    ) -> Result<()>
+    where
+        K: FromArg<'a> + PartialEq,
+        V: FromArg<'a>,
+        M: ArgMap<K, V>,
+    {
+        let (key, value) = self.cur_key_val(sep)?;
+        if !out.contains_key(&key) {
+            out.insert(key, value);
+            return Ok(());
+        }
+        match on_dup {
+            DupPolicy::Error => Err(self.err_duplicate_key(&key, sep)),
+            DupPolicy::Overwrite => {
+                out.insert(key, value);
+                Ok(())
+            }
+            DupPolicy::Keep => Ok(()),
+        }
+    }
+
+    /// Parses the next argument as value of the current option and sets it
+    /// to `res`. If `res` already has value, the option was given more than
+    /// once and `policy` determines what happens:
+    /// - [`SetPolicy::Error`]: [`ArgError::TooManyArguments`] that points to
+    ///   the option and mentions the argument where it was first given. The
+    ///   value is not consumed.
+    /// - [`SetPolicy::FirstWins`]: the new value is ignored and warning is
+    ///   added (see [`Pareg::warnings`]).
+    /// - [`SetPolicy::LastWins`]: the value is replaced and warning is added.
+    ///
+    /// The warnings point to the repeated option and mention the argument
+    /// where it was first given. The value is parsed even if it is ignored,
+    /// so invalid value is always error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, SetPolicy};
+    ///
+    /// let args = ["--level", "1", "--level", "3"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut level: Option<u8> = None;
+    /// while args.next().is_some() {
+    ///     args.try_set_next_policy(&mut level, SetPolicy::LastWins)
+    ///         .unwrap();
+    /// }
+    /// assert_eq!(Some(3), level);
+    ///
+    /// assert!(args.warnings()[0].message().starts_with(
+    ///     "Option `--level` was already given in the 1st argument `--level`."
+    /// ));
+    /// ```
+    pub fn try_set_next_policy<T>(
+        &mut self,
+        res: &mut Option<T>,
+        policy: SetPolicy,
+    ) -> Result<()>
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        if res.is_some() && policy == SetPolicy::Error {
+            return Err(self.err_duplicate_option());
+        }
+        let opt = self.cur;
+        let value = self.next_arg()?;
+        if !policy.set(res, value) {
+            return Ok(());
+        }
+        let (arg, msg) = self.repeated_option(opt);
+        let ctx = ArgErrCtx::from_msg("Repeated option.", arg).main_msg(
+            match policy {
+                SetPolicy::FirstWins => {
+                    format!("{msg} The first value is used.")
+                }
+                _ => format!("{msg} The previous value is overridden."),
+            },
+        );
+        self.push_warning_at(opt, ctx);
+        Ok(())
+    }
+
+    /// Same as [`Pareg::cur_val`], but the value is converted to `U` with
+    /// [`TryFrom`] as in [`Pareg::next_try_into`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::num::NonZeroU8;
+    ///
+    /// use pareg_core::{ArgError, Pareg};
+    ///
+    /// let args = ["--jobs=4", "--jobs=0"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     NonZeroU8::new(4),
+    ///     args.cur_val_try_into::<u8, _>('=').ok()
+    /// );
+    /// args.next();
+    /// assert!(matches!(
+    ///     args.cur_val_try_into::<u8, NonZeroU8>('='),
+    ///     Err(ArgError::InvalidValue(_))
+    /// ));
+    /// ```
+    #[track_caller]
+    pub fn cur_val_try_into<'a, T, U>(&'a self, sep: char) -> Result<U>
+    where
+        T: FromArg<'a>,
+        U: TryFrom<T>,
+        U::Error: Display,
+    {
+        let val: T = self.cur_val(sep)?;
+        let arg = self.cur().unwrap_or_default();
+        let span = key_val_spans(arg, sep).map_or(0..arg.len(), |(_, v)| v);
+        self.try_convert(val, span)
+    }
+
+    /// Uses the function [`mval_arg`] on the next argument. If there is no
+    /// last argument, returns `ArgError::NoLastArgument`.
+    ///
+    /// If sep was `'='`, parses `"key=value"` into `value` that is parsed to the
+    /// given type.
+    ///
+    /// In case that there is no `'='`, value is `None`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["key=value", "5:0.25", "only_key"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     Some("value"),
+    ///     args.cur_mval::<&str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     Some(0.25),
+    ///     args.cur_mval::<f64>(':').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     None,
+    ///     args.cur_mval::<&str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_mval<'a, T>(&'a self, sep: char) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        cur_mval_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            self.cur,
+            sep,
+        )
+    }
+
+    /// Uses the function [`key_mval_arg_esc`] on the current argument. Same as
+    /// [`Pareg::cur_key_mval`], but the separator may be escaped in the key
+    /// with `\`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = [r"a\=b=c", r"a\\=b", r"a\=b"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     ("a=b".to_owned(), Some("c")),
+    ///     args.cur_key_mval_esc::<String, &str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     (r"a\".to_owned(), Some("b")),
+    ///     args.cur_key_mval_esc::<String, &str>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     ("a=b".to_owned(), None),
+    ///     args.cur_key_mval_esc::<String, &str>('=').unwrap()
+    /// );
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_key_mval_esc<'a, K, V>(
+        &'a self,
+        sep: char,
+    ) -> Result<(K, Option<V>)>
+    where
+        K: for<'k> FromArg<'k>,
+        V: FromArg<'a>,
+    {
+        self.map_err(key_mval_arg_esc(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`key_val_arg_esc`] on the current argument. Same as
+    /// [`Pareg::cur_key_val`], but the separator may be escaped in the key
+    /// with `\`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_key_val_esc<'a, K, V>(&'a self, sep: char) -> Result<(K, V)>
+    where
+        K: for<'k> FromArg<'k>,
+        V: FromArg<'a>,
+    {
+        self.map_err(key_val_arg_esc(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`val_arg_esc`] on the current argument. Same as
+    /// [`Pareg::cur_val`], but the separator may be escaped in the key with
+    /// `\`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_val_esc<'a, T>(&'a self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        self.map_err(val_arg_esc(self.cur_arg()?, sep))
+    }
+
+    /// Uses the function [`mval_arg_esc`] on the current argument. Same as
+    /// [`Pareg::cur_mval`], but the separator may be escaped in the key with
+    /// `\`.
+    #[inline(always)]
+    #[track_caller]
+    pub fn cur_mval_esc<'a, T>(&'a self, sep: char) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        self.map_err(mval_arg_esc(self.cur_arg()?, sep))
+    }
+
+    /// Split the current argument by the given separator and return the parsed
+    /// value after the separator or if there is no such separator, parse the
+    /// next argument.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--cnt", "20", "--cnt=10"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     20,
+    ///     args.cur_val_or_next::<u32>('=').unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     10,
+    ///     args.cur_val_or_next::<u32>('=').unwrap()
+    /// );
+    /// ```
+    #[track_caller]
+    pub fn cur_val_or_next<'a, T>(&'a mut self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if self.cur().is_some_and(|a| !a.contains(sep)) {
+            if let Some(s) = self.intercept() {
+                return Err(ArgError::Special(s));
+            }
+            let res = next_arg_inner(
+                &self.args,
+                &self.redactors,
+                &self.origins,
+                &self.conf,
+                &self.normalize,
+                &mut self.cur,
+                self.end,
+            )?;
+            self.uses.record_value(&self.args, self.cur);
+            return Ok(res);
+        }
+
+        // The current argument contains `sep` or there is no current
+        // argument, so this is never `Ok(None)`.
+        let res = cur_mval_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            self.cur,
+            sep,
+        )?;
+        self.uses.record_inline(&self.args, self.cur, sep);
+        res.ok_or_else(|| no_last_argument(&self.conf))
+    }
+
+    /// Same as [`Pareg::cur_val`], but the value must be in the current
+    /// argument after `sep` (e.g. `--out=file`). This is useful for options
+    /// where the next argument could be mistaken for the value. The next
+    /// argument is never consumed. Empty value (e.g. `--out=`) is parsed as
+    /// empty string.
+    ///
+    /// # Errors
+    /// If there is no `sep` in the current argument, returns
+    /// [`ArgError::NoValue`] that points to the option and says that the
+    /// value must be inline.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--out=a.txt", "--out", "b.txt"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!("a.txt", args.cur_val_required_inline::<&str>('=')?);
+    /// args.next();
+    /// let err = args.cur_val_required_inline::<&str>('=').unwrap_err();
+    /// assert!(err.is_no_value());
+    /// assert_eq!(
+    ///     Some("Option `--out` requires an inline value (use `--out=VALUE`)."),
+    ///     err.long_message()
+    /// );
+    /// assert_eq!(Some("b.txt"), args.next());
+    /// # Ok::<_, pareg_core::ArgError>(())
+    /// ```
+    #[track_caller]
+    pub fn cur_val_required_inline<'a, T>(&'a self, sep: char) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if let Some(res) = self.cur_mval(sep)? {
+            return Ok(res);
+        }
+        let arg = self.cur().unwrap_or_default();
+        let err = ArgError::NoValue(
+            ArgErrCtx::from_msg("Missing inline value.", arg.into())
+                .main_msg(format!(
+                    "Option `{arg}` requires an inline value (use \
+                    `{arg}{sep}VALUE`)."
+                ))
+                .hint(format!(
+                    "Use `{arg}{sep}<value>`. The value may not be in the \
+                    next argument."
+                ))
+                .into(),
+        );
+        self.map_err(Err(err))
+    }
+
+    /// Parses the next argument as value of the current option, but rejects
+    /// the current option if it has inline value after `sep` (e.g.
+    /// `--out=file`). This is the opposite of
+    /// [`Pareg::cur_val_required_inline`]. The next argument may be empty.
+    ///
+    /// # Errors
+    /// If the current argument contains `sep`, returns
+    /// [`ArgError::InvalidValue`] that points to the separator and the
+    /// inline value. Otherwise the same as [`Pareg::next_arg`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--out", "a.txt", "--out=b.txt"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!("a.txt", args.next_val_required_detached::<&str>('=')?);
+    /// args.next();
+    /// let err = args.next_val_required_detached::<&str>('=').unwrap_err();
+    /// assert_eq!(Some(5..11), err.span());
+    /// assert_eq!(None, args.next());
+    /// # Ok::<_, pareg_core::ArgError>(())
+    /// ```
+    #[track_caller]
+    pub fn next_val_required_detached<'a, T>(
+        &'a mut self,
+        sep: char,
+    ) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if let Some((name, _)) = self.cur().and_then(|a| a.split_once(sep)) {
+            let arg = self.cur().unwrap_or_default();
+            let err = ArgError::InvalidValue(
+                ArgErrCtx::from_msg("Unexpected inline value.", arg.into())
+                    .spanned(name.len()..arg.len())
+                    .main_msg(format!(
+                        "Option `{name}` doesn't accept inline value (use \
+                        `{name} VALUE`)."
+                    ))
+                    .hint(format!(
+                        "Use `{name} <value>` with the value in the next \
+                        argument."
+                    ))
+                    .into(),
+            );
+            return self.map_err(Err(err));
+        }
+        self.next_arg()
+    }
+
+    /// Parses the value attached to the short option `flag` in the current
+    /// argument (e.g. `8` in `-j8`). Error spans point to the value after
+    /// `flag`.
+    ///
+    /// If the value is empty (the argument is just `-j`), returns
+    /// [`ArgError::NoValue`]. Use [`Pareg::cur_short_val_or_next`] if the
+    /// value may also be the next argument.
+    ///
+    /// # Errors
+    /// If the current argument doesn't start with `flag`, returns
+    /// [`ArgError::InvalidValue`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-j8", "-jx", "-j"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(8, args.cur_short_val::<u32>("-j").unwrap());
+    /// args.next();
+    /// let err = args.cur_short_val::<u32>("-j").unwrap_err();
+    /// assert_eq!(err.span(), Some(2..3));
+    /// args.next();
+    /// assert!(args.cur_short_val::<u32>("-j").unwrap_err().is_no_value());
+    /// ```
+    #[track_caller]
+    pub fn cur_short_val<'a, T>(&'a self, flag: &str) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        let arg = self.cur_short_arg(flag)?;
+        let res = if arg.len() == flag.len() {
+            Err(missing_value(arg.into(), arg.len()..arg.len(), Some(flag)))
+        } else {
+            self.normalize.parse(arg, flag.len()..arg.len(), Some(flag))
+        };
+        self.map_err(res.map_err(|e| short_val_err(e, flag)))
+    }
+
+    /// Same as [`Pareg::cur_short_val`], but if the current argument is just
+    /// `flag`, the next argument is consumed and parsed as its value (e.g.
+    /// `-j 8`).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-j8", "-j", "4"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(8, args.cur_short_val_or_next::<u32>("-j").unwrap());
+    /// args.next();
+    /// assert_eq!(4, args.cur_short_val_or_next::<u32>("-j").unwrap());
+    /// assert_eq!(None, args.next());
+    /// ```
+    #[track_caller]
+    pub fn cur_short_val_or_next<'a, T>(&'a mut self, flag: &str) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        if self.cur_short_arg(flag)? != flag {
+            return self.cur_short_val(flag);
+        }
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        let res = next_arg_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            &self.normalize,
+            &mut self.cur,
+            self.end,
+        )?;
+        self.uses.record_value(&self.args, self.cur);
+        Ok(res)
+    }
+
+    /// Parses key and value separated by `sep` that are attached to the
+    /// short option `flag` in the current argument (e.g. `-DNAME=VAL`). See
+    /// [`key_val_arg`] and [`Pareg::cur_short_val`]. Error spans point to
+    /// the key or value after `flag`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-DNAME=VAL", "-Dlevel=x"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     ("NAME", "VAL"),
+    ///     args.cur_short_key_val::<&str, &str>("-D", '=').unwrap()
+    /// );
+    /// args.next();
+    /// let err = args.cur_short_key_val::<&str, u32>("-D", '=').unwrap_err();
+    /// assert_eq!(err.span(), Some(8..9));
+    /// ```
+    #[track_caller]
+    pub fn cur_short_key_val<'a, K, V>(
+        &'a self,
+        flag: &str,
+        sep: char,
+    ) -> Result<(K, V)>
+    where
+        K: FromArg<'a>,
+        V: FromArg<'a>,
+    {
+        let arg = self.cur_short_arg(flag)?;
+        let res = if arg.len() == flag.len() {
+            Err(missing_value(arg.into(), arg.len()..arg.len(), Some(flag)))
+        } else {
+            key_val_arg(&arg[flag.len()..], sep)
+                .map_err(|e| e.shift_span(flag.len(), arg.into()))
+        };
+        self.map_err(res.map_err(|e| short_val_err(e, flag)))
+    }
+
+    /// Gets the current argument and checks that it starts with the short
+    /// option `flag`.
+    #[track_caller]
+    fn cur_short_arg(&self, flag: &str) -> Result<&str> {
+        let Some(arg) = self.cur() else {
+            return Err(no_last_argument(&self.conf));
+        };
+        if arg.starts_with(flag) {
+            return Ok(arg);
+        }
+        let err = ArgError::InvalidValue(
+            ArgErrCtx::from_msg(format!("Expected `{flag}`."), arg.into())
+                .main_msg(format!("Expected option `{flag}` with value."))
+                .into(),
+        );
+        self.map_err(Err(err))
+    }
+
+    /// Checks whether the current argument is one of the options in `names`
+    /// and if so, returns its value. The value may be either in the same
+    /// argument separated by `=` (e.g. `--color=always`) or it may be the next
+    /// argument (e.g. `--color always`). In the second case, the next argument
+    /// is consumed.
+    ///
+    /// Returns [`None`] if the current argument is not any of the options.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--color=always", "--colour", "never", "--count"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     Some("always"),
+    ///     args.opt_value(&["--color", "--colour"]).unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     Some("never"),
+    ///     args.opt_value(&["--color", "--colour"]).unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(None, args.opt_value(&["--color", "--colour"]).unwrap());
+    /// assert!(args.opt_value(&["--count"]).is_err());
+    /// ```
+    #[track_caller]
+    pub fn opt_value<'a>(
+        &'a mut self,
+        names: &[&str],
+    ) -> Result<Option<&'a str>> {
+        let val = self.opt_value_span(names)?;
+        Ok(val.and_then(|(_, r)| self.cur().map(|a| &a[r])))
+    }
+
+    /// Same as [`Pareg::opt_value`], but the value is also parsed.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--count=5", "-c", "10", "--count"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(
+    ///     Some(5),
+    ///     args.opt_value_arg::<usize>(&["-c", "--count"]).unwrap()
+    /// );
+    /// args.next();
+    /// assert_eq!(
+    ///     Some(10),
+    ///     args.opt_value_arg::<usize>(&["-c", "--count"]).unwrap()
+    /// );
+    /// args.next();
+    /// assert!(args.opt_value_arg::<usize>(&["-c", "--count"]).is_err());
+    /// ```
+    #[track_caller]
+    pub fn opt_value_arg<'a, T>(
+        &'a mut self,
+        names: &[&str],
+    ) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        let Some((owner, span)) = self.opt_value_span(names)? else {
+            return Ok(None);
+        };
+        let arg = self.cur().unwrap_or_default();
+        let owner = Some(owner);
+        let res = self.normalize.parse(arg, span, owner);
+        let res = res.map_err(|e| value_of_option(e, owner));
+        self.map_err(res).map(Some)
+    }
+
+    /// Converts the value parsed from `span` of the current argument.
+    fn try_convert<T, U>(&self, val: T, span: Range<usize>) -> Result<U>
+    where
+        U: TryFrom<T>,
+        U::Error: Display,
+    {
+        U::try_from(val).map_err(|e| {
+            let ty = short_type_name::<U>();
+            self.err_invalid_span(span)
+                .inline_msg(format!("Invalid value for `{ty}`."))
+                .main_msg(e.to_string())
+                .hint(format!("The value must be valid `{ty}`."))
+        })
+    }
+
+    /// Finds the value of option with one of the names. Returns the matched
+    /// name and the span of the value in the current argument after the
+    /// value is consumed.
+    #[track_caller]
+    fn opt_value_span<'n>(
+        &mut self,
+        names: &[&'n str],
+    ) -> Result<Option<(&'n str, Range<usize>)>> {
+        let Some(arg) = self.cur() else {
+            return Err(no_last_argument(&self.conf));
+        };
+        let Some(name) = names.iter().find(|n| {
+            arg.strip_prefix(**n)
+                .is_some_and(|v| v.is_empty() || v.starts_with('='))
+        }) else {
+            return Ok(None);
+        };
+
+        let name = *name;
+        let idx = self.cur - 1;
+        let canonical = names.first().map(|n| n.to_string());
+        if arg.len() != name.len() {
+            let span = name.len() + 1..arg.len();
+            let kind = UseKind::OptionWithInlineValue;
+            self.uses.record(idx, UseTag::Option(kind, canonical));
+            return Ok(Some((name, span)));
+        }
+
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        if let Some(val) = self.next() {
+            let span = 0..val.len();
+            let kind = UseKind::OptionValue;
+            self.uses.record(idx, UseTag::Option(kind, canonical));
+            self.uses.record(idx + 1, UseTag::Value);
+            Ok(Some((name, span)))
+        } else {
+            let err = ArgError::NoValue(
+                ArgErrCtx::from_msg("Missing value.", name.to_string())
+                    .main_msg(format!("Option `{name}` requires a value."))
+                    .hint(format!("Use `{name}=<value>` or `{name} <value>`."))
+                    .into(),
+            );
+            self.map_err(Err(err))
+        }
+    }
+
+    /// Intercepts the special arguments (`-h`, `--help`, `-V` and
+    /// `--version`) that have action in `special`. When [`Pareg::next`] or
+    /// [`Pareg::next_arg`] gets to such argument, the action is run and the
+    /// parsing stops: [`Pareg::next`] returns [`None`] and
+    /// [`Pareg::next_arg`] returns [`ArgError::Special`]. The intercepted
+    /// argument is then available with [`Pareg::intercepted`]. Arguments
+    /// after `--` are not intercepted.
+    ///
+    /// This way the special arguments don't have to be handled in the
+    /// parsing loop.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, Special, SpecialArgs};
+    ///
+    /// let args = ["my-program", "--count", "--version", "x"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.on_special(SpecialArgs {
+    ///     version: Some("my-program 1.0".into()),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// args.next();
+    /// assert_eq!(Some("--count"), args.next());
+    /// assert!(args.next_arg::<u32>().unwrap_err().is_special());
+    /// assert_eq!(None, args.next());
+    /// assert_eq!(Some(Special::Version), args.intercepted());
+    /// ```
+    pub fn on_special(&mut self, special: SpecialArgs) {
+        self.special = Some(special);
+    }
+
+    /// Gets the special argument that is the current argument (see
+    /// [`Special::of`]). This doesn't require [`Pareg::on_special`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, Special};
+    ///
+    /// let args = ["my-program", "-h"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// assert_eq!(None, args.special());
+    /// args.next();
+    /// assert_eq!(Some(Special::Help), args.special());
+    /// ```
+    pub fn special(&self) -> Option<Special> {
+        self.cur().and_then(Special::of)
+    }
+
+    /// Gets the special argument that stopped the parsing (see
+    /// [`Pareg::on_special`]).
+    pub fn intercepted(&self) -> Option<Special> {
+        self.intercepted
+    }
+
+    /// Intercepts the next argument if it is special argument with action
+    /// (see [`Pareg::on_special`]). Returns the special argument if the
+    /// parsing is stopped.
+    fn intercept(&mut self) -> Option<Special> {
+        if self.intercepted.is_some() {
+            return self.intercepted;
+        }
+        let s = self.next_idx().and_then(|i| self.special_at(i))?;
+        next_inner(&self.args, &mut self.cur, self.end);
+        self.end = Some(self.cur);
+        self.intercepted = Some(s);
+        if let Some(special) = &self.special {
+            special.run(s);
+        }
+        Some(s)
+    }
+
+    /// Gets the special argument at `idx` if it would be intercepted (see
+    /// [`Pareg::on_special`]). Arguments after `--` returned by
+    /// [`Pareg::next`] are not intercepted.
+    fn special_at(&self, idx: usize) -> Option<Special> {
+        let special = self.special.as_ref()?;
+        // The `--` is not consumed if the position moved back before it.
+        let after_dd = self.double_dash.is_some_and(|d| d < self.cur);
+        if after_dd {
+            return None;
+        }
+        self.get(idx)
+            .and_then(Special::of)
+            .filter(|s| special.handles(*s))
+    }
+
+    /// Moves to the next argument that will be parsed as the current
+    /// argument. Fails if there is no next argument or if it is intercepted.
+    fn advance(&mut self) -> Result<()> {
+        if let Some(s) = self.intercept() {
+            return Err(ArgError::Special(s));
+        }
+        if next_inner(&self.args, &mut self.cur, self.end).is_none() {
+            return Err(self.err_no_more_arguments());
+        }
+        Ok(())
+    }
+
+    /// Sets what [`Pareg::handle_or_defer`] does with unknown arguments.
+    pub fn set_unknown_sink(&mut self, sink: UnknownSink) {
+        self.unknown_sink = sink;
+    }
+
+    /// Sets the algorithm that finds the suggestions in
+    /// [`Pareg::err_unknown_argument_with_suggestions`]. The default is
+    /// [`EditDistanceSuggester`].
+    pub fn set_suggester(&mut self, suggester: Box<dyn Suggester>) {
+        self.suggester = suggester;
+    }
+
+    /// Takes all the arguments collected by [`Pareg::handle_or_defer`].
+    pub fn take_unknown(&mut self) -> Vec<String> {
+        mem::take(&mut self.unknown)
+    }
+
+    /// Call this in the catch-all arm when parsing arguments. If `handled`
+    /// is `true`, this does nothing. Otherwise the current argument is
+    /// unknown and the behaviour depends on [`UnknownSink`]:
+    /// - [`UnknownSink::Error`]: returns [`Pareg::err_unknown_argument`].
+    /// - [`UnknownSink::Collect`]: the current argument (and possibly its
+    ///   value in the following argument) is consumed and saved so that it
+    ///   can be retrieved with [`Pareg::take_unknown`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, UnknownSink, UnknownValue};
+    ///
+    /// let args = ["-v", "--jobs", "4", "--out=a.txt", "file", "-q"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    /// args.set_unknown_sink(UnknownSink::Collect(UnknownValue::IfNotOption));
+    ///
+    /// let mut verbose = false;
+    /// while let Some(arg) = args.next() {
+    ///     match arg {
+    ///         "-v" => verbose = true,
+    ///         _ => args.handle_or_defer(false).unwrap(),
+    ///     }
+    /// }
+    ///
+    /// assert!(verbose);
+    /// assert_eq!(
+    ///     args.take_unknown(),
+    ///     ["--jobs", "4", "--out=a.txt", "file", "-q"]
+    /// );
+    /// ```
+    #[track_caller]
+    pub fn handle_or_defer(&mut self, handled: bool) -> Result<()> {
+        if handled {
+            return Ok(());
+        }
+        let UnknownSink::Collect(value) = self.unknown_sink else {
+            return Err(self.err_unknown_argument());
+        };
+        let Some(arg) = self.cur() else {
+            return Err(no_last_argument(&self.conf));
+        };
+
+        let take = self.peek().is_some_and(|n| value.takes(arg, n));
+        self.unknown.push(arg.to_string());
+        if take {
+            let val = self.next().unwrap_or_default().to_string();
+            self.unknown.push(val);
+        }
+        Ok(())
+    }
+
+    /// Calls `f` for each of the remaining arguments and collects all the
+    /// errors instead of stopping at the first one. `f` is called after the
+    /// argument is moved to (so use [`Pareg::cur`] to get it). If `f` fails,
+    /// parsing continues with the argument after the last consumed argument.
+    ///
+    /// Errors [`ArgError::Io`] and [`ArgError::NoLastArgument`] are not
+    /// recoverable and they stop the parsing.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["-c", "x", "--name", "pareg", "-q", "-c", "5"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut count = 0;
+    /// let errs = args
+    ///     .collect_errors(|p| {
+    ///         match p.cur().unwrap_or_default() {
+    ///             "-c" => count = p.next_arg()?,
+    ///             "--name" => _ = p.next_arg::<&str>()?,
+    ///             _ => return Err(p.err_unknown_argument()),
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(5, count);
+    /// assert_eq!(2, errs.len());
+    /// assert!(errs.to_string().ends_with("\n2 argument errors\n"));
+    /// ```
+    pub fn collect_errors(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<()>,
+    ) -> core::result::Result<(), ArgErrors> {
+        let mut errs = ArgErrors::default();
+        while self.next().is_some() {
+            match f(self) {
+                Ok(()) => {}
+                // Errors without context are not errors in the arguments.
+                Err(e) if e.ctx().is_none() => {
+                    errs.push(e);
+                    break;
+                }
+                Err(e) => errs.push(e),
+            }
+        }
+        if errs.is_empty() {
+            Ok(())
+        } else {
+            Err(errs)
+        }
+    }
+
+    /// Checks that all the arguments were consumed. If there is next
+    /// argument, returns [`Pareg::err_trailing_argument`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ArgError, Pareg};
+    ///
+    /// let args = ["add", "file", "-v"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.skip(2);
+    /// assert!(matches!(
+    ///     args.require_exhausted(),
+    ///     Err(ArgError::TooManyArguments(_))
+    /// ));
+    /// args.skip_all();
+    /// assert!(args.require_exhausted().is_ok());
+    /// ```
+    #[inline]
+    pub fn require_exhausted(&self) -> Result<()> {
+        match self.peek() {
+            Some(_) => Err(self.err_trailing_argument()),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates pretty error that the next argument is unexpected because all
+    /// the arguments should have been consumed. If the argument looks like
+    /// option (see [`arg_class`]), the hint suggests `--`.
+    #[cold]
+    pub fn err_trailing_argument(&self) -> ArgError {
+        let arg = self.peek().unwrap_or_default();
+        let shown = self.next_idx().and_then(|i| self.get_redacted(i));
+        let shown = truncate_middle(shown.as_deref().unwrap_or_default(), 40)
+            .into_owned();
+        let mut ctx = ArgErrCtx::from_msg("Unexpected argument.", arg.into())
+            .main_msg(format!("Unexpected trailing argument `{shown}`."));
+        if arg_class(arg).is_option() {
+            ctx = ctx.hint(format!(
+                "Arguments that start with `-` are options. If `{shown}` is \
+                not option, put `--` before it."
+            ));
+        }
+        attach_err(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            self.cur + 1,
+            ArgError::TooManyArguments(ctx.into()),
+        )
+    }
+
+    /// Creates pretty error that the last argument (cur) is unknown.
+    #[cold]
+    pub fn err_unknown_argument(&self) -> ArgError {
+        let arg = self.cur().unwrap_or("");
+        let long_message = self
+            .cur()
+            .map(|a| format!("Unknown argument `{a}`.").into());
+        let context = ArgErrCtx {
+            args: self.args.to_args(),
+            error_idx: self.cur.saturating_sub(1),
+            args_start_index: 0,
+            show_program: true,
+            error_span: 0..arg.len(),
+            positioned: true,
+            message: "Unknown argument.".into(),
+            long_message,
+            hint: None,
+            suggestions: vec![],
+            color: default_color_mode(),
+            anounce: default_anounce(),
+            max_width: None,
+            origins: vec![],
+            owner: None,
+            value_of: None,
+            program: None,
+            usage: None,
+            messages: None,
+        };
+        redact_err(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            ArgError::UnknownArgument(context.into()),
+        )
+    }
+
+    /// Creates pretty error that the last argument (cur) is unknown and
+    /// suggests the similar names from `candidates` (see
+    /// [`Pareg::set_suggester`]). Value of option (e.g. `always` in
+    /// `--color=always`) is not compared with the candidates and it is kept
+    /// in the suggested replacement (see [`ArgError::replacement_args`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, TokenPrefixSuggester};
+    ///
+    /// let flags = ["--p99-latency-ms", "--timeout-ms", "--verbose"];
+    /// let mut args = Pareg::new(vec!["--p99-lat=5".to_string()]);
+    /// args.next();
+    ///
+    /// let err = args.err_unknown_argument_with_suggestions(&flags);
+    /// assert_eq!(2, err.suggestions().len());
+    ///
+    /// args.set_suggester(Box::new(TokenPrefixSuggester));
+    /// let err = args.err_unknown_argument_with_suggestions(&flags);
+    /// assert_eq!(
+    ///     Some(vec!["--p99-latency-ms=5".to_string()]),
+    ///     err.replacement_args()
+    /// );
+    /// ```
+    #[cold]
+    pub fn err_unknown_argument_with_suggestions(
+        &self,
+        candidates: &[&str],
+    ) -> ArgError {
+        let arg = self.cur().unwrap_or("");
+        let name = match arg.split_once('=') {
+            Some((name, _)) if arg_class(arg).is_option() => name,
+            _ => arg,
+        };
+        let suggestions = self.suggester.suggest(name, candidates);
+        let names: Vec<_> = suggestions.iter().map(|s| s.as_str()).collect();
+        let hint = FuzzyMatch::Closest(names).hint();
+        self.err_unknown_argument().map_ctx(|c| {
+            let c = c.spanned(0..name.len()).suggest(suggestions);
+            match hint {
+                Some(h) => c.hint(h),
+                None => c,
+            }
+        })
+    }
+
+    /// Creates pretty error that there should be more arguments but there are
+    /// no more arguments.
+    #[inline(always)]
+    pub fn err_no_more_arguments(&self) -> ArgError {
+        err_no_more_arguments_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            self.end,
+        )
+    }
+
+    /// Creates error that says that the current argument has invalid value.
+    #[cold]
+    pub fn err_invalid(&self) -> ArgError {
+        self.err_invalid_value(self.cur().unwrap_or_default().to_owned())
+    }
+
+    /// Creates error that says that the given part of the current argument has
+    /// invalid value.
+    #[cold]
+    pub fn err_invalid_value(&self, value: String) -> ArgError {
+        self.map_err::<()>(
+            ArgError::InvalidValue(Box::new(ArgErrCtx::from_msg(
+                "Invalid value for argument.",
+                value,
+            )))
+            .err(),
+        )
+        .unwrap_err()
+    }
+
+    /// Creates error that says that the given part of the current argument has
+    /// invalid value.
+    #[cold]
+    pub fn err_invalid_span(&self, span: Range<usize>) -> ArgError {
+        let value = self.cur().unwrap_or_default();
+        if span.start > value.len() || span.end > value.len() {
+            self.err_invalid_value(value.to_owned())
+        } else {
+            self.map_err::<()>(
+                ArgError::InvalidValue(Box::new(
+                    ArgErrCtx::from_msg(
+                        "Invalid value for argument.",
+                        value.to_owned(),
+                    )
+                    .spanned(span),
+                ))
+                .err(),
+            )
+            .unwrap_err()
+        }
+    }
+
+    /// Creates error that says that the value at `span` of the argument at
+    /// `idx` is invalid. This is useful for errors found after the values
+    /// were parsed (see [`Spanned`]). If the span is out of the argument, the
+    /// whole argument is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--min=5", "--max", "3"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let min = args.cur_val_spanned::<u32>('=').unwrap();
+    /// args.next();
+    /// let max = args.next_arg_spanned::<u32>().unwrap();
+    ///
+    /// if min.value > max.value {
+    ///     let err = args.err_at(max.idx, max.span, "Maximum is too small.");
+    ///     assert_eq!(err.ctx().unwrap().error_idx, 2);
+    /// }
+    /// ```
+    #[cold]
+    pub fn err_at(
+        &self,
+        idx: usize,
+        span: Range<usize>,
+        msg: impl Into<Cow<'static, str>>,
+    ) -> ArgError {
+        let arg = self.get(idx).unwrap_or_default();
+        let span = if span.start > span.end || span.end > arg.len() {
+            0..arg.len()
+        } else {
+            span
+        };
+        let err = ArgError::InvalidValue(Box::new(
+            ArgErrCtx::from_msg(msg, arg.to_owned()).spanned(span),
+        ));
+        attach_err(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            idx.saturating_add(1),
+            err,
+        )
+    }
+
+    /// Computes the defaults of the values in `values` that were not set
+    /// (see [`ResolvedArgs`]). If default fails and it used value given in
+    /// the arguments, the error points to that argument.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::path::PathBuf;
+    ///
+    /// use pareg_core::{ArgError, Deferred, Pareg, ResolvedArgs};
+    ///
+    /// let args = ["--name", ""];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// let mut name = Deferred::new(|_| Ok("a".to_string()));
+    /// args.next();
+    /// args.set_deferred(&mut name).unwrap();
+    ///
+    /// let mut values = ResolvedArgs::new();
+    /// values.add("name", name).add(
+    ///     "output",
+    ///     Deferred::new(|r| {
+    ///         let name = r.get::<String>("name").unwrap();
+    ///         if name.is_empty() {
+    ///             return ArgError::parse_msg("Empty name.", name.clone())
+    ///                 .err();
+    ///         }
+    ///         Ok(PathBuf::from(format!("{name}.out")))
+    ///     }),
+    /// );
+    /// let err = args.resolve_all(&mut values).unwrap_err();
+    /// assert_eq!(1, err.ctx().unwrap().error_idx);
+    /// ```
+    pub fn resolve_all(&self, values: &mut ResolvedArgs) -> Result<()> {
+        values.resolve(|e, name, idx, span| {
+            let arg = self.get(idx).unwrap_or_default();
+            let span = if span.start > span.end || span.end > arg.len() {
+                0..arg.len()
+            } else {
+                span
+            };
+            let e = e.map_ctx(|c| {
+                let hint = c.hint.is_none().then(|| {
+                    format!(
+                        "The default value of `{name}` is derived from this \
+                        argument."
+                    )
+                });
+                let c = c.shift_span(0, arg.to_owned()).spanned(span);
+                match hint {
+                    Some(h) => c.hint(h),
+                    None => c,
+                }
+            });
+            attach_err(
+                &self.args,
+                &self.redactors,
+                &self.origins,
+                &self.conf,
+                idx.saturating_add(1),
+                e,
+            )
+        })
+    }
+
+    /// Creates error that the current option was already given. The first
+    /// argument before the current argument with the same option is
+    /// mentioned in the message.
+    #[cold]
+    fn err_duplicate_option(&self) -> ArgError {
+        let (arg, msg) = self.repeated_option(self.cur);
+        self.map_err::<()>(
+            ArgError::TooManyArguments(Box::new(
+                ArgErrCtx::from_msg("Duplicate option.", arg)
+                    .main_msg(msg)
+                    .hint("The option may be given only once."),
+            ))
+            .err(),
+        )
+        .unwrap_err()
+    }
+
+    /// Gets the option before `idx` (as in [`Pareg::cur`]) and message that
+    /// it was already given. The first argument with the same option (also
+    /// in the form `--opt=value`) is mentioned in the message.
+    fn repeated_option(&self, idx: usize) -> (String, String) {
+        let arg = idx.checked_sub(1).and_then(|i| self.get(i));
+        let arg = arg.unwrap_or_default();
+        let first = (0..idx.saturating_sub(1)).find_map(|i| {
+            let a = self.get(i)?;
+            let name = a.split_once('=').map_or(a, |(n, _)| n);
+            (name == arg).then_some(())?;
+            Some((arg_ordinal(&self.conf, i)?, self.get_redacted(i)?))
+        });
+        let name = idx.checked_sub(1).and_then(|i| self.get_redacted(i));
+        let name = truncate_middle(name.as_deref().unwrap_or_default(), 40);
+        let msg = match first {
+            Some((n, a)) => format!(
+                "Option `{name}` was already given in the {n} argument `{}`.",
+                truncate_middle(&a, 40)
+            ),
+            None => format!("Option `{name}` was already given."),
+        };
+        (arg.to_owned(), msg)
+    }
+
+    /// Creates error that the key of the current argument was already given.
+    /// The first argument before the current argument with the same key is
+    /// mentioned in the message.
+    #[cold]
+    fn err_duplicate_key<'a, K>(&'a self, key: &K, sep: char) -> ArgError
+    where
+        K: FromArg<'a> + PartialEq,
+    {
+        let arg = self.cur().unwrap_or_default();
+        let key_str = arg.split_once(sep).map_or(arg, |(k, _)| k);
+        let span = 0..key_str.len();
+        let first = (0..self.cur.saturating_sub(1)).find_map(|i| {
+            let a = self.get(i)?;
+            let (k, _) = a.split_once(sep)?;
+            K::from_arg(k).ok().filter(|k| k == key)?;
+            Some((arg_ordinal(&self.conf, i)?, self.get_redacted(i)?))
+        });
+        let shown = self.cur_idx().and_then(|i| self.get_redacted(i));
+        let shown = shown.as_deref().unwrap_or_default();
+        let shown = shown.split_once(sep).map_or(shown, |(k, _)| k);
+        let key_str = truncate_middle(shown, 40);
+        let msg = match first {
+            Some((n, a)) => format!(
+                "Key `{key_str}` was already given in the {n} argument `{}`.",
+                truncate_middle(&a, 40)
+            ),
+            None => format!("Key `{key_str}` was already given."),
+        };
+        self.map_err::<()>(
+            ArgError::TooManyArguments(Box::new(
+                ArgErrCtx::from_msg("Duplicate key.", arg.to_owned())
+                    .spanned(span)
+                    .main_msg(msg)
+                    .hint("Each key may be given only once."),
+            ))
+            .err(),
+        )
+        .unwrap_err()
+    }
+
+    /// Adds additional information to error so that it has better error
+    /// message. Consider using [`Pareg::cur_manual`] or [`Pareg::next_manual`]
+    /// instead.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{Pareg, key_val_arg};
+    /// let args = ["-D10=0.25"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let arg: &str = args.cur_arg().unwrap();
+    /// let arg = arg.strip_prefix("-D").unwrap();
+    ///
+    /// let res: (usize, f32) = args.map_err(key_val_arg(arg, '=')).unwrap();
+    /// assert_eq!((10, 0.25), res);
+    /// ```
+    pub fn map_err<T>(&self, res: Result<T>) -> Result<T> {
+        map_err_inner(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &self.conf,
+            self.cur,
+            res,
+        )
+    }
+
+    /// Adds warning about the current argument. The warning has the same
+    /// context as errors, but it doesn't stop the parsing. Use
+    /// [`Pareg::warnings`] or [`Pareg::print_warnings`] to show the warnings
+    /// after the parsing succeeds.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--jobs", "1000"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// args.next();
+    /// let jobs: usize = args.next_arg().unwrap();
+    /// if jobs > 256 {
+    ///     args.warn("Too many jobs.");
+    /// }
+    ///
+    /// let warn = args.warnings()[0].clone().no_color().to_string();
+    /// assert!(warn.starts_with("argument warning: Too many jobs."));
+    /// ```
+    #[cold]
+    pub fn warn(&mut self, msg: impl Into<Cow<'static, str>>) {
+        let arg = self.cur().unwrap_or_default().to_owned();
+        self.push_warning(ArgErrCtx::from_msg(msg, arg));
+    }
+
+    /// Adds warning that the current argument is deprecated and that
+    /// `replacement` should be used instead. If the argument is option in
+    /// the form `--opt=value`, only the option is deprecated.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+    #[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["--colour=never"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// while let Some(arg) = args.next() {
+    ///     match arg {
+    ///         v if v.starts_with("--colour=") => {
+    ///             args.warn_deprecated("--color");
+    ///         }
+    ///         _ => panic!("Unknown argument."),
+    ///     }
+    /// }
+    ///
+    /// let warn = args.warnings()[0].clone().no_color().to_string();
+    /// assert!(warn.contains("`--colour` is deprecated."));
+    /// assert!(warn.contains("hint: Use `--color` instead."));
+    /// ```
+    #[cold]
+    pub fn warn_deprecated(&mut self, replacement: &str) {
+        let arg = self.cur().unwrap_or_default();
+        let name = arg
+            .split_once('=')
+            .filter(|_| arg.starts_with('-'))
+            .map_or(arg, |(n, _)| n);
+        let shown = self.cur_idx().and_then(|i| self.get_redacted(i));
+        let shown = shown.as_deref().unwrap_or_default();
+        let shown = shown
+            .split_once('=')
+            .filter(|_| shown.starts_with('-'))
+            .map_or(shown, |(n, _)| n);
+        let ctx = ArgErrCtx::from_msg("Deprecated argument.", arg.to_owned())
+            .spanned(0..name.len())
+            .main_msg(format!(
+                "`{}` is deprecated.",
+                truncate_middle(shown, 40)
+            ))
+            .hint(format!("Use `{replacement}` instead."));
+        self.push_warning(ctx);
+    }
+
+    /// Gets the warnings in the order in which they were added.
+    pub fn warnings(&self) -> &[ArgWarning] {
+        &self.warnings
+    }
+
+    /// Prints all the warnings to stderr with the given color mode.
+    #[cfg(feature = "std")]
+    pub fn print_warnings(&self, color: ColorMode) {
+        for w in &self.warnings {
+            eprint!("{}", w.clone().color_mode(color));
+        }
+    }
+
+    /// Gets the key and value of the argument at `idx` if it is in the
+    /// namespace `ns` (see [`Pareg::cur_namespace`]).
+    #[track_caller]
+    fn namespace_at(
+        &self,
+        idx: usize,
+        ns: &str,
+        sep: char,
+    ) -> Result<Option<(Spanned<&str>, Spanned<&str>)>> {
+        let Some(arg) = self.args.get(idx) else {
+            return Ok(None);
+        };
+        let Some(rest) = arg
+            .strip_prefix("--")
+            .and_then(|a| a.strip_prefix(ns))
+            .and_then(|a| a.strip_prefix('.'))
+        else {
+            return Ok(None);
+        };
+
+        let start = arg.len() - rest.len();
+        let Some(pos) = rest.find(sep) else {
+            let err = match val_arg::<&str>(arg, sep) {
+                Err(e) => e,
+                Ok(_) => no_last_argument(&self.conf),
+            };
+            return Err(attach_err(
+                &self.args,
+                &self.redactors,
+                &self.origins,
+                &self.conf,
+                idx + 1,
+                err,
+            ));
+        };
+        if pos == 0 {
+            return Err(self
+                .err_at(idx, start - 1..start, "Missing key.")
+                .main_msg(format!("Missing key in the namespace `{ns}`."))
+                .hint(format!("Use `--{ns}.<key>{sep}<value>`.")));
+        }
+
+        let key = start..start + pos;
+        let value = key.end + sep.len_utf8()..arg.len();
+        let value = self.normalize.value_span(arg, value);
+        Ok(Some((
+            Spanned {
+                value: &arg[key.clone()],
+                idx,
+                span: key,
+            },
+            Spanned {
+                value: &arg[value.clone()],
+                idx,
+                span: value,
+            },
+        )))
+    }
+
+    /// Adds the context of the current argument to the warning and stores
+    /// it. Usage is not added to warnings.
+    fn push_warning(&mut self, ctx: ArgErrCtx) {
+        self.push_warning_at(self.cur, ctx);
+    }
+
+    /// Adds warning about the argument before `idx` (as in [`Pareg::cur`]).
+    fn push_warning_at(&mut self, idx: usize, ctx: ArgErrCtx) {
+        let err = attach_err(
+            &self.args,
+            &self.redactors,
+            &self.origins,
+            &ErrConf {
+                usage: None,
+                messages: self.conf.messages,
+                args_start: self.conf.args_start,
+                taken: self.conf.taken.clone(),
+                strict_usage: self.conf.strict_usage,
+                expanded_from: self.conf.expanded_from.clone(),
+            },
+            idx,
+            ArgError::InvalidValue(ctx.into()),
+        );
+        if let Some(ctx) = err.into_ctx() {
+            self.warnings.push(ctx.into());
+        }
+    }
+}
+
+/// Gets the environment variables. Invalid UTF-8 is replaced with
+/// [`char::REPLACEMENT_CHARACTER`].
+#[cfg(feature = "std")]
+fn env_vars() -> impl Iterator<Item = (String, String)> {
+    env::vars_os().map(|(n, v)| {
+        (
+            n.to_string_lossy().into_owned(),
+            v.to_string_lossy().into_owned(),
+        )
+    })
+}
+
+/// Gets the name of the type without the module paths (e.g. `Vec<PathBuf>`
+/// instead of `alloc::vec::Vec<std::path::PathBuf>`).
+pub(crate) fn short_type_name<T: ?Sized>() -> String {
+    let mut res = String::new();
+    let mut name = type_name::<T>();
+    while !name.is_empty() {
+        let end = name
+            .find(|c: char| !c.is_alphanumeric() && !"_:".contains(c))
+            .unwrap_or(name.len());
+        let (path, rest) = name.split_at(end);
+        res += path.rsplit("::").next().unwrap_or_default();
+        let sep = rest.chars().next().map_or(0, char::len_utf8);
+        res += &rest[..sep];
+        name = &rest[sep..];
+    }
+    res
+}
+
+#[inline(always)]
+fn cur_inner<S>(args: &S, cur: usize) -> Option<&str>
+where
+    S: ArgSource + ?Sized,
+{
+    args.get(cur.checked_sub(1)?)
+}
+
+/// Gets the argument at `idx` with the parts hidden by the redactors (see
+/// [`Pareg::redact_matching`]), so that it can be echoed in messages.
+#[cold]
+fn redacted_arg<'a, S>(
+    args: &'a S,
+    redact: &[Redactor],
+    idx: usize,
+) -> Option<Cow<'a, str>>
+where
+    S: ArgSource + ?Sized,
+{
+    let arg = args.get(idx)?;
+    let prev = idx.checked_sub(1).and_then(|i| args.get(i));
+    Some(redact.iter().filter_map(|r| r(idx, prev, arg)).fold(
+        arg.into(),
+        |mut a, range| {
+            redact_str(&mut a, range);
+            a
+        },
+    ))
+}
+
+#[inline(always)]
+#[track_caller]
+fn cur_arg_inner<'a, S, T>(
+    args: &'a S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    norm: &Normalize,
+    cur: usize,
+) -> Result<T>
+where
+    S: ArgSource + ?Sized,
+    T: FromArg<'a>,
+{
+    if let Some(arg) = cur_inner(args, cur) {
+        let owner = cur.checked_sub(1).and_then(|c| cur_inner(args, c));
+        let res = norm.parse(arg, 0..arg.len(), owner);
+        map_err_inner(args, redact, origins, conf, cur, res)
+    } else {
+        Err(no_last_argument(conf))
+    }
+}
+
+#[inline(always)]
+fn next_inner<'a, S>(
+    args: &'a S,
+    cur: &mut usize,
+    end: Option<usize>,
+) -> Option<&'a str>
+where
+    S: ArgSource + ?Sized,
+{
+    if end.is_some_and(|e| *cur >= e) {
+        return None;
+    }
+    let res = args.get(*cur)?;
+    *cur += 1;
+    Some(res)
+}
+
+#[inline(always)]
+fn next_arg_inner<'a, S, T>(
+    args: &'a S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    norm: &Normalize,
+    cur: &mut usize,
+    end: Option<usize>,
+) -> Result<T>
+where
+    S: ArgSource + ?Sized,
+    T: FromArg<'a>,
+{
+    let owner = cur_inner(args, *cur);
+    if let Some(a) = next_inner(args, cur, end) {
+        let res = norm.parse(a, 0..a.len(), owner);
+        let res = res.map_err(|e| value_of_option(e, owner));
+        map_err_inner(args, redact, origins, conf, *cur, res)
+    } else {
+        Err(err_no_more_arguments_inner(
+            args, redact, origins, conf, end,
+        ))
+    }
+}
+
+#[inline(always)]
+#[track_caller]
+pub(crate) fn cur_mval_inner<'a, S, T>(
+    args: &'a S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    norm: &Normalize,
+    cur: usize,
+    sep: char,
+) -> Result<Option<T>>
+where
+    S: ArgSource + ?Sized,
+    T: FromArg<'a>,
+{
+    let Some(arg) = cur_inner(args, cur) else {
+        return Err(no_last_argument(conf));
+    };
+    let Some((key, _)) = arg.split_once(sep) else {
+        return Ok(None);
+    };
+    let start = key.len() + sep.len_utf8();
+    let res = norm.parse(arg, start..arg.len(), Some(key));
+    let res = res.map_err(|e| value_of_option(e, Some(key)));
+    map_err_inner(args, redact, origins, conf, cur, res).map(Some)
+}
+
+/// Adds owner to error in value attached to the short option `flag`. Missing
+/// value gets hint with the valid forms.
+#[cold]
+fn short_val_err(e: ArgError, flag: &str) -> ArgError {
+    if matches!(e, ArgError::NoValue(_))
+        && e.span().is_some_and(|s| s.is_empty())
+    {
+        e.hint(format!("Use `{flag}<value>` or `{flag} <value>`."))
+    } else {
+        value_of_option(e, Some(flag))
+    }
+}
+
+/// Sets `owner` as owner of the errornous value if it looks like option
+/// without value (see [`arg_class`]).
+#[cold]
+fn value_of_option(e: ArgError, owner: Option<&str>) -> ArgError {
+    match owner {
+        Some(o) if arg_class(o).is_option() && !o.contains('=') => {
+            value_of(e, o)
+        }
+        _ => e,
+    }
+}
+
+/// Sets `owner` as owner of the errornous value. Missing values and errors
+/// that already have owner are not changed.
+#[cold]
+fn value_of(e: ArgError, owner: &str) -> ArgError {
+    if matches!(e, ArgError::NoValue(_))
+        || e.ctx().is_some_and(|c| c.owner.is_some())
+    {
+        e
+    } else {
+        e.owner(owner.to_string())
+    }
+}
+
+#[inline(always)]
+fn map_err_inner<S, T>(
+    args: &S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    cur: usize,
+    res: Result<T>,
+) -> Result<T>
+where
+    S: ArgSource + ?Sized,
+{
+    res.map_err(|e| attach_err(args, redact, origins, conf, cur, e))
+}
+
+/// Creates [`ArgError::NoLastArgument`] with the location of the caller. In
+/// debug builds with [`Pareg::strict_usage`], this panics instead.
+#[cold]
+#[track_caller]
+fn no_last_argument(conf: &ErrConf) -> ArgError {
+    if cfg!(debug_assertions) && conf.strict_usage {
+        panic!(
+            "There was no last argument when it was expected at `{}`.",
+            Location::caller()
+        );
+    }
+    ArgError::no_last_argument()
+}
+
+/// Adds the arguments to the error, redacts it and adds the origins. The
+/// arguments are not cloned if the error already has them (e.g. it already
+/// passed through [`map_err_inner`]).
+#[cold]
+fn attach_err<S>(
+    args: &S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    cur: usize,
+    e: ArgError,
+) -> ArgError
+where
+    S: ArgSource + ?Sized,
+{
+    let idx = cur.saturating_sub(1);
+    let has_args = e.ctx().is_some_and(|c| {
+        c.error_idx == idx
+            && c.args.len() == args.loaded()
+            && c.args
+                .iter()
+                .zip(args.iter_range(0..args.loaded()))
+                .enumerate()
+                .all(|(i, (a, b))| a == b || conf.taken.get(i) == Some(&true))
+    });
+    let e = if has_args {
+        e
+    } else {
+        e.add_args(args.to_args(), idx)
+    };
+    // The owner is shown in the command line if it is the previous
+    // argument.
+    let owner_idx = e.ctx().and_then(|c| {
+        let o = c.owner.as_deref()?;
+        let i = c.error_idx.checked_sub(1)?;
+        (c.value_of.is_none() && args.get(i) == Some(o)).then_some(i)
+    });
+    let e = match owner_idx {
+        Some(i) => e.value_of(i),
+        None => e,
+    };
+    redact_err(args, redact, origins, conf, e)
+}
+
+/// Adds the origins to the error and redacts all the arguments in the error
+/// based on the redactors.
+#[cold]
+fn redact_err<S>(
+    args: &S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    e: ArgError,
+) -> ArgError
+where
+    S: ArgSource + ?Sized,
+{
+    let e = if origins.is_empty() {
+        e
+    } else {
+        e.origins(origins.to_vec())
+    };
+    let e = e.args_start_index(conf.args_start);
+    let e = match &conf.usage {
+        Some(u) if e.ctx().is_some_and(|c| c.usage.is_none()) => {
+            e.usage(u.clone())
+        }
+        _ => e,
+    };
+    let e = match conf.messages {
+        Some(m) if e.ctx().is_some_and(|c| c.messages.is_none()) => {
+            e.messages(m)
+        }
+        _ => e,
+    };
+    let e = match e.ctx().and_then(|c| conf.expanded_from.get(c.error_idx)) {
+        Some(Some(pattern)) => {
+            let note = format!("(expanded from `{pattern}`)");
+            e.map_ctx(|mut c| {
+                let msg = c.long_message.take().unwrap_or(c.message.clone());
+                c.long_message = Some(if msg.ends_with(&note) {
+                    msg
+                } else {
+                    format!("{msg} {note}").into()
+                });
+                c
+            })
+        }
+        _ => e,
+    };
+    let e = if conf.taken.contains(&true) {
+        e.map_ctx(|mut c| {
+            for (a, _) in
+                c.args.iter_mut().zip(&conf.taken).filter(|(_, t)| **t)
+            {
+                *a = TAKEN_ARG.into();
+            }
+            c
+        })
+    } else {
+        e
+    };
+    if redact.is_empty() {
+        return e;
+    }
+    let mut prev = None;
+    args.iter_range(0..args.loaded())
+        .enumerate()
+        .fold(e, |e, (i, a)| {
+            let e = redact
+                .iter()
+                .filter_map(|r| r(i, prev, a))
+                .fold(e, |e, range| e.redact(i, range));
+            prev = Some(a);
+            e
+        })
+}
+
+#[cold]
+/// Gets the ordinal number of the argument at `idx` as it is counted in
+/// errors (see [`ErrConf::args_start`]). Returns [`None`] for arguments
+/// before the start (e.g. the program name).
+fn arg_ordinal(conf: &ErrConf, idx: usize) -> Option<String> {
+    idx.checked_sub(conf.args_start).map(|i| ordinal(i + 1))
+}
+
+/// Creates error that there are no more arguments. The error points after
+/// the last argument before the back cursor `end` (see
+/// [`Pareg::next_back`]).
+pub(crate) fn err_no_more_arguments_inner<S>(
+    args: &S,
+    redact: &[Redactor],
+    origins: &[Origin],
+    conf: &ErrConf,
+    end: Option<usize>,
+) -> ArgError
+where
+    S: ArgSource + ?Sized,
+{
+    let idx = end.map_or(args.len(), |e| e.min(args.len()));
+    let idx = idx.saturating_sub(1);
+    let last = args.get(idx);
+    let pos = last.map_or(0, |a| a.len());
+    let messages = conf.messages.unwrap_or(&Messages::ENGLISH);
+    let long_message = redacted_arg(args, redact, idx).map(|a| {
+        let n = idx.checked_sub(conf.args_start).map(|i| i + 1);
+        messages
+            .more_arguments_msg(n, &truncate_middle(&a, 40))
+            .into()
+    });
+    let context = ArgErrCtx {
+        args: args.to_args(),
+        error_idx: idx,
+        args_start_index: 0,
+        show_program: true,
+        // The renderer shows caret for the empty span.
+        error_span: pos..pos,
+        positioned: true,
+        message: messages.no_more_arguments.into(),
+        long_message,
+        hint: None,
+        suggestions: vec![],
+        color: default_color_mode(),
+        anounce: default_anounce(),
+        max_width: None,
+        origins: vec![],
+        owner: None,
+        value_of: None,
+        program: None,
+        usage: None,
+        // The long message is already localized.
+        messages: conf.messages,
+    };
+    redact_err(
+        args,
+        redact,
+        origins,
+        conf,
+        ArgError::NoMoreArguments(context.into()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pareg(args: &[&str]) -> Pareg {
+        Pareg::new(args.iter().map(|a| a.to_string()).collect())
+    }
+
+    #[test]
+    fn redact_error_inside() {
+        let mut args = pareg(&["prog", "--token=abc123secret"]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+        args.next();
+
+        let err = args.cur_val::<u32>('=').unwrap_err().no_color();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.args[1], "--token=<redacted>");
+        assert_eq!(ctx.error_span, 8..18);
+        assert!(!err.to_string().contains("abc123secret"));
+    }
+
+    #[test]
+    fn redact_error_before() {
+        let mut args = pareg(&["prog", "--token", "abc123secret"]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+        args.next();
+
+        let err = args.err_unknown_argument().no_color();
+        let ArgError::UnknownArgument(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.args[2], "<redacted>");
+        assert_eq!(ctx.error_span, 0..7);
+        assert!(!err.to_string().contains("abc123secret"));
+    }
+
+    #[test]
+    fn redact_error_inserted() {
+        let mut args = pareg(&["prog", "--token", "abc123secret", "x"]);
+        args.redact_values_of(&["--token"], '=');
+        args.insert_args(1, ["-v".into()]);
+        args.append_args(["--token".into(), "def456secret".into()]);
+        args.next();
+        args.next();
+        args.next();
+        args.next();
+        args.next();
+
+        let err = args.err_invalid().no_color();
+        let ArgError::InvalidValue(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.args[1], "-v");
+        assert_eq!(ctx.args[2], "--token");
+        assert_eq!(ctx.args[3], "<redacted>");
+        assert_eq!(ctx.args[4], "x");
+        assert_eq!(ctx.args[6], "<redacted>");
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn redact_error_after() {
+        let mut args = pareg(&["prog", "user:abc123secret@host"]);
+        args.redact_matching(|_, a| Some(a.find(':')? + 1..a.find('@')?));
+        args.next();
+        args.next();
+
+        let err = args.err_invalid_span(18..22).no_color();
+        let ArgError::InvalidValue(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.args[1], "user:<redacted>@host");
+        assert_eq!(&ctx.args[1][ctx.error_span.clone()], "host");
+        let msg = err.to_string();
+        assert!(!msg.contains("abc123secret"));
+        assert!(msg.contains("@host"));
+    }
+
+    #[test]
+    fn redact_long_secret_in_messages() {
+        let secret = "S3CR3T-abcdefghijklmnopqrstuvwxyz0123456789-TOKEN";
+        let mut args = pareg(&["prog", &format!("--token={secret}")]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+        args.next();
+
+        let err = args.next_arg::<String>().unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some(
+                "Expected more arguments after the 2nd argument \
+                `--token=<redacted>`."
+            )
+        );
+        assert!(!err.to_string().contains("abcd"));
+
+        let mut args = pareg(&["prog", "--token", secret]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+        args.next();
+
+        let err = args.require_exhausted().unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Unexpected trailing argument `<redacted>`.")
+        );
+        assert!(!err.to_string().contains("abcd"));
+
+        let mut args = pareg(&["prog", &format!("--token={secret}")]);
+        args.redact_matching(|_, a| Some(a.find('=')? + 1..a.len()));
+        args.next();
+        let err = args.require_exhausted().unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some(
+                "Arguments that start with `-` are options. If \
+                `--token=<redacted>` is not option, put `--` before it."
+            )
+        );
+        assert!(!err.to_string().contains("abcd"));
+    }
+
+    #[test]
+    fn redact_short_secret_in_messages() {
+        let mut args = pareg(&["prog", "--token=e"]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+
+        let err = args.require_exhausted().unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(ctx.args[1], "--token=<redacted>");
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Unexpected trailing argument `--token=<redacted>`.")
+        );
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some(
+                "Arguments that start with `-` are options. If \
+                `--token=<redacted>` is not option, put `--` before it."
+            )
+        );
+
+        let mut args = pareg(&["prog", "--token=e"]);
+        args.redact_values_of(&["--token"], '=');
+        args.next();
+        args.next();
+        let err = args.cur_manual(|a| bool_arg("yes", "no", a));
+        let err = err.unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Invalid value `--token=<redacted>`")
+        );
+        assert_eq!(ctx.hint.as_deref(), Some("Expected `yes` or `no`"));
+    }
+
+    #[test]
+    fn opt_value_spans() {
+        let mut args = pareg(&["--count=x", "--count", "y", "--count"]);
+        let names = ["-c", "--count"];
+
+        args.next();
+        let ArgError::FailedToParse(ctx) =
+            args.opt_value_arg::<usize>(&names).unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span), (0, 8..9));
+
+        args.next();
+        let ArgError::FailedToParse(ctx) =
+            args.opt_value_arg::<usize>(&names).unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span), (2, 0..1));
+
+        args.next();
+        let ArgError::NoValue(ctx) =
+            args.opt_value_arg::<usize>(&names).unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span), (3, 0..7));
+    }
+
+    #[test]
+    fn opt_value_owner() {
+        let mut args = pareg(&["--count=x", "--exclude", "-eval"]);
+
+        args.next();
+        let err = args.opt_value_arg::<u32>(&["--c", "--count"]);
+        let ctx = err.unwrap_err().into_ctx().unwrap();
+        assert_eq!(ctx.owner.as_deref(), Some("--count"));
+
+        args.next();
+        let err = args.opt_value_arg::<u32>(&["-e", "--exclude"]);
+        let ctx = err.unwrap_err().into_ctx().unwrap();
+        assert_eq!(ctx.owner.as_deref(), Some("--exclude"));
+        assert_eq!(ctx.value_of, Some(1));
+    }
+
+    #[test]
+    fn summary_unrecorded() {
+        let mut args = pareg(&[
+            "-phunter2",
+            "--token:s3cret",
+            "-5",
+            "-",
+            "-v",
+            "--key",
+            "abc",
+            "--no-x",
+            "--pass=abc",
+        ]);
+        args.redact_values_of(&["--key", "--pass"], '=');
+        args.redact_matching(|_, a| {
+            a.strip_prefix("--no-").map(|_| 2..a.len())
+        });
+        args.skip_all();
+
+        let used: Vec<_> = args
+            .summary()
+            .args
+            .into_iter()
+            .map(|a| (a.idx, a.kind, a.name))
+            .collect();
+        use UseKind::*;
+        let name = |n: &str| Some(n.to_string());
+        assert_eq!(
+            used,
+            [
+                (0, OptionWithInlineValue, name("-p")),
+                (1, OptionWithInlineValue, name("--token")),
+                (2, Positional, None),
+                (3, Positional, None),
+                (4, Flag, name("-v")),
+                (5, Flag, name("--key")),
+                (6, Positional, None),
+                (7, Flag, name("--")),
+                (8, OptionWithInlineValue, name("--pass")),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary() {
+        let mut args = pareg(&[
+            "--opt=v", "--opt", "v", "-v", "file", "--count", "5", "-j8",
+            "-c", "3", "--rgb", "1", "2", "3", "-o", "x", "--", "rest",
+        ]);
+        args.next();
+        args.cur_val::<&str>('=').unwrap();
+        args.next();
+        args.next_arg::<&str>().unwrap();
+        args.next();
+        args.next();
+        args.next();
+        args.opt_value(&["-c", "--count"]).unwrap();
+        args.next();
+        args.cur_short_val::<u32>("-j").unwrap();
+        args.next();
+        args.opt_value_arg::<u32>(&["--count", "-c"]).unwrap();
+        args.next();
+        args.next_n::<u8>(3).unwrap();
+        let start = args.checkpoint();
+        args.next();
+        args.next_arg::<u32>().unwrap_err();
+        args.restore(start);
+        args.next();
+        args.next();
+        args.next();
+        args.next();
+
+        let summary = args.summary();
+        let used: Vec<_> = summary
+            .args
+            .iter()
+            .map(|a| (a.idx, a.kind, a.name.as_deref()))
+            .collect();
+        use UseKind::*;
+        assert_eq!(
+            used,
+            [
+                (0, OptionWithInlineValue, Some("--opt")),
+                (1, OptionValue, Some("--opt")),
+                (3, Flag, Some("-v")),
+                (4, Positional, None),
+                (5, OptionValue, Some("-c")),
+                (7, OptionWithInlineValue, Some("-j")),
+                (8, OptionValue, Some("--count")),
+                (10, OptionValue, Some("--rgb")),
+                (14, Flag, Some("-o")),
+                (15, Positional, None),
+                (16, Flag, Some("--")),
+                (17, Positional, None),
+            ]
+        );
+        assert_eq!(summary.positional_count(), 3);
+        assert_eq!(summary.options().count(), 9);
+
+        // Arguments that were not consumed and the program are not included.
+        let joined = JoinedArgs::new("prog -v --n=5 x").unwrap();
+        let mut args = Pareg::from_joined(&joined);
+        args.next();
+        args.next();
+        args.next();
+        let summary = args.summary();
+        assert_eq!(summary.args.len(), 2);
+        assert_eq!(summary.args[1].kind, OptionWithInlineValue);
+        assert_eq!(summary.args[1].name.as_deref(), Some("--n"));
+    }
+
+    #[test]
+    fn required_inline_detached() {
+        let mut args = pareg(&["--out=", "--out", "file", "--n=x", "--n="]);
+        args.next();
+        assert_eq!(args.cur_val_required_inline::<&str>('=').unwrap(), "");
+        args.next();
+        let err = args.cur_val_required_inline::<&str>('=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_no_value());
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 0..5));
+        assert_eq!(ctx.hint.as_deref(), Some(
+            "Use `--out=<value>`. The value may not be in the next argument."
+        ));
+        assert_eq!(args.cur(), Some("--out"));
+        args.next();
+        args.next();
+        let err = args.cur_val_required_inline::<u32>('=').unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_span, 4..5);
+        args.next();
+        assert!(args.cur_val_required_inline::<u32>('=').is_err());
+
+        let mut args = pareg(&["--out", "", "--out=", "x", "--out:a"]);
+        args.next();
+        assert_eq!(args.next_val_required_detached::<&str>('=').unwrap(), "");
+        args.next();
+        let err = args.next_val_required_detached::<&str>('=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(matches!(err, ArgError::InvalidValue(_)));
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 5..6));
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Option `--out` doesn't accept inline value (use `--out VALUE`).")
+        );
+        assert_eq!(args.cur(), Some("--out="));
+        args.next();
+        args.next();
+        let err = args.next_val_required_detached::<&str>(':').unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_span, 5..7);
+        assert!(args.next_val_required_detached::<&str>('=').is_err());
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn chained_error_in_middle() {
+        let project = ["--count", "5"];
+        let user = ["--color=no".to_string(), "--count".to_string()];
+        let argv = ["--count", "10"];
+
+        let mut args =
+            Pareg::chained([&project[..], &["--color=no", "--count"], &argv]);
+        assert_eq!(6, args.source().len());
+        args.jump(3);
+
+        let err = args.cur_val::<bool>('=').unwrap_err().no_color();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_idx, 2);
+        assert_eq!(ctx.error_span, 8..10);
+        assert_eq!(
+            ctx.args,
+            ["--count", "5", "--color=no", "--count", "--count", "10"]
+        );
+        assert!(err
+            .to_string()
+            .contains(" $ --count 5 --color=no --count --count 10\n"));
+
+        let mut args = Pareg::chained([&user[..], &[]]);
+        args.skip_all();
+        assert_eq!(Some(1), args.cur_idx());
+        assert_eq!(vec!["--count"], args.cur_remaining().collect::<Vec<_>>());
+        let ArgError::NoMoreArguments(ctx) = args.err_no_more_arguments()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_idx, 1);
+    }
+
+    #[test]
+    fn cow_error_args() {
+        let args: Vec<Cow<'static, str>> = vec![
+            "prog".into(),
+            "--count".into(),
+            "secret-5".to_string().into(),
+        ];
+        let mut args = Pareg::from_cow(&args);
+        args.redact_matching(|_, a| a.starts_with("secret-").then_some(0..6));
+        args.jump(2);
+
+        let err = args.cur_arg::<usize>().unwrap_err();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert!(matches!(ctx.args[1], Cow::Borrowed("--count")));
+        assert!(matches!(ctx.args[2], Cow::Owned(_)));
+        assert_eq!(ctx.args, ["prog", "--count", "<redacted>-5"]);
+    }
+
+    #[test]
+    fn value_substring_of_key() {
+        let span = |e: ArgError| (e.ctx().unwrap().error_idx, e.span());
+        let mut args = pareg(&["--aa=a", "a=aa:x", r"a\=a=a", "aa=a"]);
+
+        args.next();
+        let e = args.cur_val::<u8>('=').unwrap_err();
+        assert_eq!(span(e), (0, Some(5..6)));
+        let e = args.cur_mval::<u8>('=').unwrap_err();
+        assert_eq!(span(e), (0, Some(5..6)));
+        let e = args.cur_key_val::<&str, u8>('=').unwrap_err();
+        assert_eq!(span(e), (0, Some(5..6)));
+
+        let e = args.next_val::<u8>(':').unwrap_err();
+        assert_eq!(span(e), (1, Some(5..6)));
+        let e = args.cur_key_val::<&str, u8>('=').unwrap_err();
+        assert_eq!(span(e), (1, Some(2..6)));
+
+        args.next();
+        let e = args.cur_val_esc::<u8>('=').unwrap_err();
+        assert_eq!(span(e), (2, Some(5..6)));
+        let e = args.cur_mval_esc::<u8>('=').unwrap_err();
+        assert_eq!(span(e), (2, Some(5..6)));
+
+        args.next();
+        let e = args.cur_key_val::<u8, u8>('=').unwrap_err();
+        assert_eq!(span(e), (3, Some(0..2)));
+        let e = args.cur_val::<u8>('=').unwrap_err();
+        assert_eq!(span(e), (3, Some(3..4)));
+
+        let e = val_arg::<u8>("aa=a", '=').unwrap_err();
+        assert_eq!(e.span(), Some(3..4));
+        let e = key_val_arg::<u8, u8>("a=a", '=').unwrap_err();
+        assert_eq!(e.span(), Some(0..1));
+        let e = key_val_arg::<&str, u8>("a=a", '=').unwrap_err();
+        assert_eq!(e.span(), Some(2..3));
+
+        let e = args.map_err(u8::from_arg("a")).unwrap_err();
+        assert_eq!(span(e), (3, Some(3..4)));
+        let e = ArgError::parse_msg("", "a".into()).part_of("--aa=a".into());
+        assert_eq!(e.span(), Some(5..6));
+
+        let mut args = pareg(&["--j300=300"]);
+        args.next();
+        let e = args.cur_val_try_into::<u32, u8>('=').unwrap_err();
+        assert_eq!(span(e), (0, Some(7..10)));
+    }
+
+    #[test]
+    fn positioned_span_not_shifted_again() {
+        let at = |e: &ArgError| {
+            let ctx = e.ctx().unwrap();
+            let arg = ctx.args[ctx.error_idx].to_string();
+            (arg, ctx.error_span.clone())
+        };
+        let part = |e: ArgError, arg: &str| at(&e.part_of(arg.into()));
+
+        let key = || key_arg::<u8>("ab=ab", '=').unwrap_err();
+        assert!(key().ctx().unwrap().positioned);
+        assert_eq!(part(key(), "ab=ab"), ("ab=ab".into(), 0..2));
+        assert_eq!(part(key(), "-Dab=ab"), ("-Dab=ab".into(), 2..4));
+        // The position of `ab=ab` is ambiguous, so the span is not moved.
+        assert_eq!(part(key(), "ab=ab,ab=ab"), ("ab=ab,ab=ab".into(), 0..2));
+
+        let val = || val_arg::<u8>("5:5x", ':').unwrap_err();
+        assert_eq!(part(val(), "5:5x"), ("5:5x".into(), 2..4));
+        assert_eq!(part(val(), "-D5:5x"), ("-D5:5x".into(), 4..6));
+
+        let mut args = pareg(&["ab=ab", "-Dab=ab", "5:5x"]);
+        args.next();
+        let e = args.map_err(key_arg::<u8>("ab=ab", '=')).unwrap_err();
+        assert_eq!(at(&e), ("ab=ab".into(), 0..2));
+        // Adding the arguments again doesn't move the span.
+        let e = e.add_args(vec!["x".into(), "ab=ab".into()], 1);
+        assert_eq!(at(&e), ("ab=ab".into(), 0..2));
+        let e = args.cur_key::<u8>('=').unwrap_err();
+        assert_eq!(at(&e), ("ab=ab".into(), 0..2));
+
+        args.next();
+        let arg = args.cur().unwrap()[2..].to_string();
+        let e = args.map_err(key_arg::<u8>(&arg, '=')).unwrap_err();
+        assert_eq!(at(&e), ("-Dab=ab".into(), 2..4));
+        let e = args.map_err(val_arg::<u8>(&arg, '=')).unwrap_err();
+        assert_eq!(at(&e), ("-Dab=ab".into(), 5..7));
+
+        args.next();
+        let e = args.cur_val::<u8>(':').unwrap_err();
+        assert_eq!(at(&e), ("5:5x".into(), 2..4));
+
+        // Value that is not positioned is still found at the end.
+        let e = ArgError::parse_msg("", "ab".into());
+        assert_eq!(part(e, "ab=ab"), ("ab=ab".into(), 3..5));
+    }
+
+    #[test]
+    fn lazy_args() {
+        fn parse<S: ArgSource>(mut args: Pareg<S>) -> Result<Vec<u32>> {
+            let mut res = vec![];
+            while let Some(arg) = args.next() {
+                match arg {
+                    "-n" => res.push(args.next_arg()?),
+                    "--back" => {
+                        args.jump(args.cur_idx().unwrap());
+                        res.push(args.cur_arg()?);
+                        args.skip(1);
+                    }
+                    a if a.starts_with("--n=") => res.push(args.cur_val('=')?),
+                    _ => return args.err_unknown_argument().err(),
+                }
+            }
+            Ok(res)
+        }
+
+        let input = ["-n", "1", "--n=2", "-n", "3", "--back", "--n=4"];
+        let owned = || input.iter().map(|a| a.to_string());
+        assert_eq!(
+            parse(Pareg::lazy(owned())).unwrap(),
+            parse(Pareg::new(owned().collect())).unwrap()
+        );
+        assert_eq!(parse(Pareg::lazy(owned())).unwrap(), [1, 2, 3, 3, 4]);
+
+        let pulled = core::cell::Cell::new(0);
+        let args = (1..).map(|i| format!("--n={i}"));
+        let mut args =
+            Pareg::lazy(args.inspect(|_| pulled.set(pulled.get() + 1)));
+        assert_eq!(pulled.get(), 0);
+        args.next();
+        assert_eq!(args.cur_val::<u32>('=').unwrap(), 1);
+        assert_eq!(pulled.get(), 1);
+        assert_eq!(args.peek(), Some("--n=2"));
+        assert_eq!(args.skip(8), Some("--n=9"));
+        assert_eq!((pulled.get(), args.source().loaded()), (9, 9));
+        assert_eq!(args.jump(3), Some("--n=3"));
+        assert_eq!(args.cur_val::<u8>('=').unwrap(), 3);
+
+        let err = args.cur_val::<bool>('=').unwrap_err();
+        assert_eq!(err.args().len(), 10);
+        assert_eq!(err.args()[9], "...");
+        assert_eq!(pulled.get(), 9);
+
+        let mut args = Pareg::lazy(owned().take(2));
+        args.skip(5);
+        let err = args.err_no_more_arguments();
+        assert_eq!(err.args(), ["-n", "1"]);
+    }
+
+    #[test]
+    fn back_cursor_insert() {
+        let mut args = pareg(&["a", "b", "c", "d"]);
+        args.next();
+        assert_eq!(args.next_back(), Some("d"));
+        args.insert_args(1, ["X".into(), "Y".into()]);
+        assert_eq!(args.remaining(), ["X", "Y", "b", "c"]);
+
+        // Inserting at the back cursor keeps the arguments in the window.
+        args.insert_args(5, ["Z".into()]);
+        assert_eq!(args.remaining(), ["X", "Y", "b", "c", "Z"]);
+        // Inserting after it doesn't change the window.
+        args.insert_args(10, ["W".into()]);
+        assert_eq!(args.remaining(), ["X", "Y", "b", "c", "Z"]);
+
+        args.append_args(["e".into()]);
+        args.append_args_from("config", ["f".into()]);
+        assert_eq!(args.remaining(), ["X", "Y", "b", "c", "Z", "e", "f"]);
+        assert_eq!(args.next_back(), Some("f"));
+        assert_eq!(args.skip_all(), Some("e"));
+        assert_eq!(args.next(), None);
+    }
+
+    #[test]
+    fn back_cursor() {
+        let mut args = pareg(&["prog", "-v", "in", "out", "x"]);
+
+        args.next();
+        assert_eq!(args.next_back(), Some("x"));
+        assert_eq!(args.next_back(), Some("out"));
+        assert_eq!((args.cur(), args.cur_idx()), (Some("prog"), Some(0)));
+        assert_eq!(args.remaining(), ["-v", "in"]);
+        assert_eq!(args.cur_remaining(), ["prog", "-v", "in"]);
+
+        let err = args.cur_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 0);
+        assert_eq!(err.args().len(), 5);
+
+        assert_eq!(args.next(), Some("-v"));
+        assert_eq!(args.peek(), Some("in"));
+        let err = args.rlast_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 2);
+        assert_eq!(err.span(), Some(0..2));
+        assert_eq!((args.cur(), args.cur_idx()), (Some("-v"), Some(1)));
+        assert_eq!((args.peek(), args.next_idx()), (None, None));
+        assert_eq!(args.next(), None);
+        assert_eq!(args.next_back(), None);
+        assert!(matches!(
+            args.rlast_arg::<u32>(),
+            Err(ArgError::NoMoreArguments(_))
+        ));
+
+        args.jump(1);
+        assert_eq!(args.skip(10), Some("-v"));
+        assert_eq!(args.skip_all(), Some("-v"));
+        assert_eq!(args.cur_idx(), Some(1));
+        assert!(args.take_rest().is_empty());
+
+        args.reset();
+        assert_eq!(args.skip_all(), Some("x"));
+        assert_eq!(args.cur_idx(), Some(4));
+        assert_eq!(args.next(), None);
+        assert_eq!(args.cur_arg::<&str>().unwrap(), "x");
+        assert_eq!(args.next_back(), None);
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 4);
+
+        args.jump(10);
+        assert_eq!((args.cur(), args.cur_idx()), (None, None));
+        assert!(matches!(
+            args.next_key_val::<&str, &str>('='),
+            Err(ArgError::NoMoreArguments(_))
+        ));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn spanned_values() {
+        let mut args = pareg(&["prog", "--min= 5 ", "--max", "3", "-v"]);
+        args.normalize(Normalize {
+            trim: true,
+            empty_is_missing: false,
+        });
+
+        args.next();
+        args.next();
+        let min = args.cur_val_spanned::<u32>('=').unwrap();
+        assert_eq!(
+            min,
+            Spanned {
+                value: 5,
+                idx: 1,
+                span: 7..8
+            }
+        );
+        args.next();
+        let max = args.next_arg_spanned::<u32>().unwrap();
+        assert_eq!(
+            max,
+            Spanned {
+                value: 3,
+                idx: 3,
+                span: 0..1
+            }
+        );
+        args.next();
+
+        assert!(min.value > max.value);
+        let err = args
+            .err_at(max.idx, max.span, "Maximum is smaller than minimum.")
+            .hint(format!("Minimum is `{}`.", min.value))
+            .no_color();
+        assert!(matches!(err, ArgError::InvalidValue(_)));
+        assert_eq!(
+            err.to_string(),
+            "\
+argument error: Maximum is smaller than minimum.
+--> arg4:0..1
+ |
+ $ prog --min= 5  --max 3 -v
+ |                      ^ Maximum is smaller than minimum.
+hint: Minimum is `5`.
+"
+        );
+
+        let err = args.err_at(1, 7..100, "x");
+        assert_eq!(err.span(), Some(0..9));
+        let err = args.err_at(10, 0..1, "x");
+        assert_eq!(err.span(), Some(0..0));
+        assert!(args.next_arg_spanned::<u32>().is_err());
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn warnings() {
+        let mut args =
+            pareg(&["prog", "--colour=never", "--jobs", "1000", "--tok=abc"]);
+        args.set_error_usage("prog [--color=<when>]");
+        args.redact_values_of(&["--tok"], '=');
+
+        args.next();
+        args.next();
+        args.warn_deprecated("--color");
+        args.next();
+        let jobs: usize = args.next_arg().unwrap();
+        assert_eq!(jobs, 1000);
+        args.warn("Too many jobs.");
+        args.next();
+        args.warn("Token in arguments.");
+        assert!(args.next().is_none());
+
+        let warns = args.warnings();
+        assert_eq!(warns.len(), 3);
+        assert_eq!(warns[0].message(), "`--colour` is deprecated.");
+        assert_eq!(warns[1].message(), "Too many jobs.");
+        assert_eq!(warns[2].ctx().args[4], "--tok=<redacted>");
+        assert!(warns[0].ctx().usage.is_none());
+        assert!(warns[0]
+            .clone()
+            .color_mode(ColorMode::Always)
+            .to_string()
+            .starts_with("\x1b[33margument warning:\x1b[0m "));
+        assert_eq!(
+            warns[0].clone().no_color().to_string(),
+            "\
+argument warning: `--colour` is deprecated.
+--> arg2:0..8
+ |
+ $ prog --colour=never --jobs 1000 --tok=<redacted>
+ |      ^^^^^^^^ Deprecated argument.
+hint: Use `--color` instead.
+"
+        );
+        assert_eq!(
+            format!("{:#}", warns[1].clone().no_color()),
+            "\
+Too many jobs.
+--> arg4:0..4
+ |
+ $ prog --colour=never --jobs 1000 --tok=<redacted>
+ |                            ^^^^ Too many jobs.
+"
+        );
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn set_policy() {
+        let parse = |policy| {
+            let mut args =
+                pareg(&["prog", "--level=2", "--level", "5", "--level", "7"]);
+            args.next();
+            args.next();
+            let mut level: Option<u8> = args.cur_val('=').ok();
+            let mut res = Ok(());
+            while args.next().is_some() && res.is_ok() {
+                res = args.try_set_next_policy(&mut level, policy);
+            }
+            (level, res, args.warnings().to_vec())
+        };
+
+        let (level, res, warns) = parse(SetPolicy::Error);
+        assert_eq!(level, Some(2));
+        let err = res.unwrap_err();
+        assert!(matches!(err, ArgError::TooManyArguments(_)));
+        assert!(warns.is_empty());
+        assert_eq!(
+            err.no_color().to_string(),
+            "\
+argument error: Option `--level` was already given in the 2nd argument
+                `--level=2`.
+--> arg3:0..7
+ |
+ $ prog --level=2 --level 5 --level 7
+ |                ^^^^^^^ Duplicate option.
+hint: The option may be given only once.
+"
+        );
+
+        let (level, res, warns) = parse(SetPolicy::FirstWins);
+        res.unwrap();
+        assert_eq!(level, Some(2));
+        assert_eq!(warns.len(), 2);
+        assert_eq!(
+            warns[0].message(),
+            "Option `--level` was already given in the 2nd argument \
+            `--level=2`. The first value is used."
+        );
+        assert_eq!(warns[1].ctx().error_idx, 4);
+        assert_eq!(
+            format!("{:#}", warns[0].clone().no_color()),
+            "\
+Option `--level` was already given in the 2nd argument `--level=2`. The first
+value is used.
+--> arg3:0..7
+ |
+ $ prog --level=2 --level 5 --level 7
+ |                ^^^^^^^ Repeated option.
+"
+        );
+
+        let (level, res, warns) = parse(SetPolicy::LastWins);
+        res.unwrap();
+        assert_eq!(level, Some(7));
+        assert_eq!(warns.len(), 2);
+        assert_eq!(
+            warns[1].message(),
+            "Option `--level` was already given in the 2nd argument \
+            `--level=2`. The previous value is overridden."
+        );
+
+        // Ignored value must still be valid.
+        let mut args = pareg(&["--level", "1", "--level", "x"]);
+        let mut level: Option<u8> = None;
+        args.next();
+        args.try_set_next_policy(&mut level, SetPolicy::FirstWins)
+            .unwrap();
+        args.next();
+        let err = args
+            .try_set_next_policy(&mut level, SetPolicy::FirstWins)
+            .unwrap_err();
+        assert!(matches!(err, ArgError::FailedToParse(_)));
+        assert_eq!(level, Some(1));
+        assert!(args.warnings().is_empty());
+    }
+
+    #[test]
+    fn localized_argument_messages() {
+        static FRENCH: Messages = Messages {
+            more_arguments_after:
+                "Arguments attendus après le {n}e argument `{arg}`.",
+            missing_values_of: "{count} valeurs attendues pour `{opt}`.",
+            value_i_of_n: "(valeur {i} sur {n})",
+            ..Messages::ENGLISH
+        };
+
+        let mut args = pareg(&["prog", "-n"]);
+        args.set_messages(&FRENCH);
+        args.skip(2);
+        let err = args.err_no_more_arguments();
+        assert_eq!(
+            err.long_message(),
+            Some("Arguments attendus après le 2e argument `-n`.")
+        );
+
+        let mut args = pareg(&["--rgb", "1"]);
+        args.set_messages(&FRENCH);
+        args.next();
+        let err = args.next_n::<u8>(3).unwrap_err();
+        assert_eq!(
+            err.long_message(),
+            Some("2 valeurs attendues pour `--rgb`.")
+        );
+        let err = args.next_n::<u8>(2).unwrap_err();
+        assert_eq!(
+            err.long_message(),
+            Some("Expected 1 more value for `--rgb`.")
+        );
+
+        let mut args = pareg(&["--rgb", "1", "x"]);
+        args.set_messages(&FRENCH);
+        args.next();
+        let err = args.next_n::<u8>(2).unwrap_err();
+        assert!(err.long_message().unwrap().ends_with(" (valeur 2 sur 2)"));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn localized_messages() {
+        static FRENCH: Messages = Messages {
+            error: "erreur d'argument :",
+            warning: "avertissement :",
+            hint: "astuce :",
+            usage: "utilisation :",
+            invalid_value_for: "Valeur invalide pour `{owner}` :",
+            unknown_argument: "Argument inconnu.",
+            ..Messages::ENGLISH
+        };
+
+        let mut args = pareg(&["prog", "--foo", "-n", "x"]);
+        args.set_messages(&FRENCH);
+        args.set_error_usage("prog [-n <N>]");
+
+        args.next();
+        args.next();
+        assert_eq!(
+            args.err_unknown_argument().no_color().to_string(),
+            "\
+erreur d'argument : Argument inconnu.
+--> arg2:0..5
+ |
+ $ prog --foo -n x
+ |      ^^^^^ Argument inconnu.
+utilisation : prog [-n <N>]
+"
+        );
+
+        // Messages that are not default are kept, but the labels change.
+        args.next();
+        let err = args
+            .next_arg::<usize>()
+            .map_err(|e| e.hint("Use number."))
+            .unwrap_err()
+            .no_color()
+            .to_string();
+        assert!(
+            err.starts_with(
+                "erreur d'argument : Valeur invalide pour `-n` : "
+            ),
+            "{err}"
+        );
+        assert!(err.contains("\nastuce : Use number.\n"), "{err}");
+
+        // Errors with their own messages are not changed.
+        let err = args
+            .map_err::<()>(Err(ArgError::UnknownArgument(
+                ArgErrCtx::from_msg("Unknown argument.", "x".into())
+                    .messages(&Messages::ENGLISH)
+                    .into(),
+            )))
+            .unwrap_err();
+        assert_eq!(err.message(), Some("Unknown argument."));
+
+        args.warn("Attention.");
+        let warn = args.warnings()[0].clone().no_color().to_string();
+        assert!(warn.starts_with("avertissement : Attention.\n"), "{warn}");
+    }
+
+    fn forward(args: &[&str], value: UnknownValue) -> (bool, Vec<String>) {
+        let mut args = pareg(args);
+        args.set_unknown_sink(UnknownSink::Collect(value));
+        let mut verbose = false;
+        let mut child = vec!["child".to_string()];
+        while let Some(arg) = args.next() {
+            match arg {
+                "-v" | "--verbose" => verbose = true,
+                "--" => {
+                    args.handle_or_defer(true).unwrap();
+                    break;
+                }
+                _ => args.handle_or_defer(false).unwrap(),
+            }
+        }
+        child.extend(args.take_unknown());
+        child.extend(args.remaining().iter().cloned());
+        (verbose, child)
+    }
+
+    #[test]
+    fn forward_unknown() {
+        let argv = [
+            "-v",
+            "--jobs",
+            "4",
+            "-x",
+            "--out=a.txt",
+            "-",
+            "in",
+            "--",
+            "-v",
+        ];
+        let (verbose, child) = forward(&argv, UnknownValue::IfNotOption);
+        assert!(verbose);
+        assert_eq!(
+            child,
+            ["child", "--jobs", "4", "-x", "--out=a.txt", "-", "in", "-v"]
+        );
+
+        let (_, child) = forward(&argv, UnknownValue::Never);
+        assert_eq!(
+            child,
+            ["child", "--jobs", "4", "-x", "--out=a.txt", "-", "in", "-v"]
+        );
+
+        let argv = ["--jobs", "-v", "--verbose", "-q"];
+        let (verbose, child) = forward(&argv, UnknownValue::Always);
+        assert!(verbose);
+        assert_eq!(child, ["child", "--jobs", "-v", "-q"]);
+
+        let (verbose, child) = forward(&argv, UnknownValue::IfNotOption);
+        assert!(verbose);
+        assert_eq!(child, ["child", "--jobs", "-q"]);
+    }
+
+    #[test]
+    fn unknown_error_by_default() {
+        let mut args = pareg(&["--foo"]);
+        args.next();
+        assert!(args.handle_or_defer(true).is_ok());
+        assert!(matches!(
+            args.handle_or_defer(false),
+            Err(ArgError::UnknownArgument(_))
+        ));
+        assert!(args.take_unknown().is_empty());
+    }
+
+    #[test]
+    fn normalize_trim() {
+        let mut args = pareg(&["--count= 10 ", "--count", " 5", "-c= x "]);
+        assert!(args.next().is_some());
+        assert!(args.cur_val_or_next::<u32>('=').is_err());
+
+        args.reset();
+        args.normalize(Normalize {
+            trim: true,
+            empty_is_missing: false,
+        });
+        args.next();
+        assert_eq!(10, args.cur_val_or_next::<u32>('=').unwrap());
+        args.next();
+        assert_eq!(5, args.cur_val_or_next::<u32>('=').unwrap());
+        args.next();
+        let err = args.cur_val_or_next::<u32>('=').unwrap_err();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.args[ctx.error_idx], "-c= x ");
+        assert_eq!(ctx.error_span, 4..5);
+
+        let mut args = pareg(&["-c= x "]);
+        args.next();
+        let err = args.cur_val_or_next::<u32>('=').unwrap_err();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 3..6);
+    }
+
+    #[test]
+    fn normalize_empty_is_missing() {
+        let mut args = pareg(&["--count=", "--count", " ", "--count", ""]);
+        args.normalize(Normalize {
+            trim: true,
+            empty_is_missing: true,
+        });
+
+        args.next();
+        assert_eq!(None, args.cur_val_or_next::<Option<u32>>('=').unwrap());
+        let err = args.cur_val_or_next::<u32>('=').unwrap_err();
+        let ArgError::NoValue(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 8..8);
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Option `--count` requires a value.")
+        );
+
+        args.next();
+        assert_eq!(None, args.cur_val_or_next::<Option<u32>>('=').unwrap());
+        args.next();
+        let err = args.cur_val_or_next::<String>('=').unwrap_err();
+        let ArgError::NoValue(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_idx, 4);
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Option `--count` requires a value.")
+        );
+
+        let mut args = pareg(&["--count", ""]);
+        args.next();
+        assert_eq!("", args.cur_val_or_next::<String>('=').unwrap());
+    }
+
+    #[test]
+    fn namespaces() {
+        let mut args = pareg(&[
+            "--a.b.c=1",
+            "--a.b=2",
+            "--ab.c=3",
+            "--a=4",
+            "a.b.c=5",
+            "--a.b.c",
+            "--a.=6",
+        ]);
+        let ns = |args: &Pareg, ns| {
+            args.cur_namespace(ns, '=').map(|r| {
+                r.map(|(k, v)| {
+                    (k.value.to_string(), k.span, v.value.to_string(), v.span)
+                })
+            })
+        };
+
+        args.next();
+        assert_eq!(
+            ns(&args, "a").unwrap(),
+            Some(("b.c".into(), 4..7, "1".into(), 8..9))
+        );
+        assert_eq!(
+            ns(&args, "a.b").unwrap(),
+            Some(("c".into(), 6..7, "1".into(), 8..9))
+        );
+        assert_eq!(ns(&args, "a.b.c").unwrap(), None);
+        assert_eq!(ns(&args, "b").unwrap(), None);
+        for _ in 0..4 {
+            args.next();
+            assert_eq!(ns(&args, "a.b").unwrap(), None);
+        }
+
+        args.next();
+        let err = ns(&args, "a.b").unwrap_err();
+        assert!(matches!(err, ArgError::NoValue(_)));
+        assert_eq!(err.ctx().unwrap().error_idx, 5);
+
+        args.next();
+        let err = ns(&args, "a").unwrap_err().no_color();
+        assert_eq!(err.span(), Some(3..4));
+        assert_eq!(err.ctx().unwrap().error_idx, 6);
+        assert!(err.to_string().contains("namespace `a`"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn namespace_map() {
+        let args = pareg(&["--x.k=v", "--a.b.c=1", "--a.b.d=", "--a.b.c=7"]);
+        assert_eq!(
+            args.namespace_map("a.b", '=').unwrap(),
+            HashMap::from([
+                ("c".to_string(), "7".to_string()),
+                ("d".to_string(), String::new()),
+            ])
+        );
+
+        // Missing value is error even if it is not the current argument.
+        let mut args = pareg(&["--a.b=1", "-v", "--a.c"]);
+        args.next();
+        let err = args.namespace_map("a", '=').unwrap_err();
+        assert!(matches!(err, ArgError::NoValue(_)));
+        assert_eq!(err.ctx().unwrap().error_idx, 2);
+        assert!(args.namespace_map("b", '=').unwrap().is_empty());
     }
-}
 
-#[inline(always)]
-fn cur_inner(args: &[String], cur: usize) -> Option<&str> {
-    (cur != 0).then_some(&args[cur - 1])
-}
+    #[test]
+    fn value_registry() {
+        let mut reg = ValueRegistry::new();
+        reg.register("--jobs", Parser::<u32>::new())
+            .register("--ratio", Parser::<f64>::with_description("ratio"));
+        let names: Vec<_> =
+            reg.iter().map(|(n, p)| (n, p.describe())).collect();
+        assert_eq!(names, [("--jobs", "u32"), ("--ratio", "ratio")]);
+        assert!(reg.get("--other").is_none());
 
-#[inline(always)]
-fn cur_arg_inner<'a, T>(args: &'a [String], cur: usize) -> Result<T>
-where
-    T: FromArg<'a>,
-{
-    if let Some(arg) = cur_inner(args, cur) {
-        map_err_inner(args, cur, T::from_arg(arg))
-    } else {
-        Err(ArgError::NoLastArgument)
+        let mut args = pareg(&["--ratio", "0.5", "--jobs", " 8 ", "--jobs"]);
+        args.normalize(Normalize {
+            trim: true,
+            empty_is_missing: true,
+        });
+        let mut values = vec![];
+        while let Some(arg) = args.next() {
+            let p = reg.get(arg).unwrap();
+            match args.next_with(p) {
+                Ok(v) => values.push(v),
+                Err(e) => {
+                    assert!(matches!(e, ArgError::NoMoreArguments(_)));
+                    break;
+                }
+            }
+        }
+        assert_eq!(values[0].downcast_ref::<f64>(), Some(&0.5));
+        assert_eq!(values[1].downcast_ref::<u32>(), Some(&8));
+        assert!(values[1].downcast_ref::<f64>().is_none());
+
+        let mut args = pareg(&["--jobs", " 1x"]);
+        args.normalize(Normalize {
+            trim: true,
+            empty_is_missing: true,
+        });
+        args.next();
+        let err = args.next_with(reg.get("--jobs").unwrap()).unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 1..3));
+        assert_eq!(ctx.owner.as_deref(), Some("--jobs"));
+        args.reset();
+        args.next();
+        let expected = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(
+            err.no_color().to_string(),
+            expected.no_color().to_string()
+        );
     }
-}
 
-#[inline(always)]
-fn next_inner<'a>(args: &'a [String], cur: &mut usize) -> Option<&'a str> {
-    (*cur < args.len()).then(|| {
-        let res = &args[*cur];
-        *cur += 1;
-        res.as_str()
-    })
-}
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn header_numbering() {
+        let header = |mut args: Pareg| {
+            args.next();
+            let err = args.next_arg::<u32>().unwrap_err().no_color();
+            err.to_string().lines().nth(1).unwrap().to_string()
+        };
 
-#[inline(always)]
-fn next_arg_inner<'a, T>(args: &'a [String], cur: &mut usize) -> Result<T>
-where
-    T: FromArg<'a>,
-{
-    if let Some(a) = next_inner(args, cur) {
-        map_err_inner(args, *cur, a.arg_into())
-    } else {
-        Err(err_no_more_arguments_inner(args))
+        // The first argument is the first user argument.
+        let args = pareg(&["--count", "x"]);
+        assert_eq!(header(args), "--> arg2:0..1");
+
+        // The first argument is the program like with `Pareg::args`.
+        let mut args = Pareg::from_cmdline("prog --count x");
+        args.next();
+        assert_eq!(header(args), "--> arg2:0..1");
+
+        let mut args = Pareg::from_cmdline("prog --count x");
+        args.skip_all();
+        let err = args.cur_arg::<u32>().unwrap_err().no_color();
+        assert!(err.to_string().contains(" $ prog --count x\n"));
+        assert_eq!(
+            err.show_program(false).to_string(),
+            "\
+argument error: invalid digit found in string
+--> arg2:0..1
+ |
+ $ --count x
+ |         ^ invalid digit found in string
+"
+        );
     }
-}
 
-#[inline(always)]
-pub fn cur_mval_inner<'a, T>(
-    args: &'a [String],
-    cur: usize,
-    sep: char,
-) -> Result<Option<T>>
-where
-    T: FromArg<'a>,
-{
-    map_err_inner(args, cur, mval_arg(cur_arg_inner(args, cur)?, sep))
-}
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn no_more_arguments_position() {
+        let mut args = pareg(&["prog", "--count"]);
+        args.skip_all();
+        let err = args.next_arg::<u32>().unwrap_err().no_color();
+        assert_eq!(
+            err.to_string(),
+            "\
+argument error: Expected more arguments after the 2nd argument `--count`.
+--> arg2:7..7
+ |
+ $ prog --count
+ |             ^ Expected more arguments.
+"
+        );
 
-#[inline(always)]
-fn map_err_inner<T>(args: &[String], cur: usize, res: Result<T>) -> Result<T> {
-    res.map_err(|e| e.add_args(args.into(), cur.saturating_sub(1)))
-}
+        let long = "a".repeat(30) + &"b".repeat(30);
+        let mut args = pareg(&["prog", "-x", "-y", "-z", &long]);
+        args.skip_all();
+        let err = args.next_arg::<u32>().unwrap_err();
+        let ArgError::NoMoreArguments(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 60..60);
+        assert_eq!(err.span(), Some(60..60));
+        assert_eq!(
+            ctx.long_message.as_deref().unwrap(),
+            format!(
+                "Expected more arguments after the 5th argument `{}...{}`.",
+                "a".repeat(19),
+                "b".repeat(18)
+            )
+        );
 
-pub fn err_no_more_arguments_inner(args: &[String]) -> ArgError {
-    let pos = args.last().map_or(0, |a| a.len());
-    let long_message = args.last().map(|a| {
-        format!("Expected more arguments after the argument `{a}`.").into()
-    });
-    let context = ArgErrCtx {
-        args: args.into(),
-        error_idx: args.len() - 1,
-        error_span: pos..pos,
-        message: "Expected more arguments.".into(),
-        long_message,
-        hint: None,
-        color: ColorMode::default(),
-    };
-    ArgError::NoMoreArguments(context.into())
+        // The arguments after the back cursor are not remaining.
+        let mut args = pareg(&["cp", "-t", "dest"]);
+        args.next();
+        assert_eq!(args.next_back(), Some("dest"));
+        args.next();
+        let err = args.next_arg::<&str>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 1);
+        assert_eq!(err.span(), Some(2..2));
+        assert_eq!(
+            err.long_message(),
+            Some("Expected more arguments after the 2nd argument `-t`.")
+        );
+    }
+
+    #[test]
+    fn dash_arguments() {
+        use ArgClass::*;
+
+        #[rustfmt::skip]
+        let table = [
+            // arg, class, value after `=`, takes next, is taken, is owner
+            ("-",    SingleDash,   None,      false, true,  false),
+            ("--",   DoubleDash,   None,      false, false, false),
+            ("---",  Flag,         None,      true,  false, true),
+            ("-=x",  ShortCluster, Some("x"), false, false, true),
+            ("--=x", Flag,         Some("x"), false, false, true),
+        ];
+
+        for (arg, class, val, takes, taken, owner) in table {
+            assert_eq!(arg_class(arg), class, "{arg}");
+
+            let (k, v) = key_mval_arg::<&str, &str>(arg, '=').unwrap();
+            assert_eq!(v, val, "{arg}");
+            assert_eq!(k, arg.split('=').next().unwrap(), "{arg}");
+
+            assert!(!starts_any!(arg, "-x", "--x"), "{arg}");
+            assert!(has_any_key!(arg, '=', k), "{arg}");
+
+            let value = UnknownValue::Always;
+            assert_eq!(value.takes(arg, "v"), takes, "{arg}");
+            let value = UnknownValue::IfNotOption;
+            assert_eq!(value.takes("-v", arg), taken, "{arg}");
+
+            let mut args = pareg(&["-v", arg]);
+            args.set_unknown_sink(UnknownSink::Collect(value));
+            args.next();
+            args.handle_or_defer(false).unwrap();
+            let expected: &[&str] = if taken { &["-v", arg] } else { &["-v"] };
+            assert_eq!(args.take_unknown(), expected, "{arg}");
+
+            let mut args = pareg(&[arg]);
+            args.next();
+            let ArgError::UnknownArgument(ctx) = args.err_unknown_argument()
+            else {
+                panic!("Invalid error kind.");
+            };
+            assert_eq!(ctx.error_span, 0..arg.len(), "{arg}");
+
+            let mut args = pareg(&[arg, ""]);
+            args.normalize(Normalize {
+                empty_is_missing: true,
+                ..Default::default()
+            });
+            args.next();
+            let ArgError::NoValue(ctx) =
+                args.next_arg::<String>().unwrap_err()
+            else {
+                panic!("Invalid error kind.");
+            };
+            let msg = if owner {
+                format!("Option `{arg}` requires a value.")
+            } else {
+                "Expected value.".to_string()
+            };
+            assert_eq!(ctx.long_message.as_deref(), Some(msg.as_str()));
+        }
+    }
+
+    #[test]
+    fn key_val_spans_multibyte() {
+        let args = pareg(&["日本=語", "ключ→значение", "no_sep", "→", ""]);
+        let spans: Vec<_> = args.scan_key_val_spans('→').collect();
+        assert_eq!(
+            spans,
+            [
+                (0, 0..10, None),
+                (1, 0..8, Some(11..27)),
+                (2, 0..6, None),
+                (3, 0..0, Some(3..3)),
+                (4, 0..0, None),
+            ]
+        );
+        for (i, k, v) in spans {
+            let arg = args.get(i).unwrap();
+            assert!(arg.get(k).is_some());
+            assert!(v.is_none_or(|v| arg.get(v).is_some()));
+        }
+
+        let mut args = pareg(&["日本=語", "x"]);
+        assert_eq!(args.cur_key_val_spans('='), None);
+        args.next();
+        assert_eq!(args.cur_key_val_spans('='), Some((0..6, 7..10)));
+        assert_eq!(args.cur_key_val_spans('本'), Some((0..3, 6..10)));
+        args.next();
+        assert_eq!(args.cur_key_val_spans('='), None);
+    }
+
+    #[derive(Debug)]
+    struct Even(u32);
+
+    impl TryFrom<u32> for Even {
+        type Error = String;
+
+        fn try_from(value: u32) -> std::result::Result<Self, Self::Error> {
+            if value.is_multiple_of(2) {
+                Ok(Self(value))
+            } else {
+                Err(format!("The number {value} is odd."))
+            }
+        }
+    }
+
+    #[test]
+    fn try_into_foreign() {
+        let mut args = pareg(&["4", "x4", "5"]);
+        assert_eq!(args.next_try_into::<u32, Even>().unwrap().0, 4);
+
+        let ArgError::FailedToParse(ctx) =
+            args.next_try_into::<u32, Even>().unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 0..2));
+
+        let ArgError::InvalidValue(ctx) =
+            args.next_try_into::<u32, Even>().unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 0..1));
+        assert_eq!(ctx.long_message.as_deref(), Some("The number 5 is odd."));
+        assert_eq!(ctx.message, "Invalid value for `Even`.");
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some("The value must be valid `Even`.")
+        );
+
+        let mut args = pareg(&["--n=6", "--n=x6", "--n=7", "--n"]);
+        args.next();
+        assert_eq!(args.cur_val_try_into::<u32, Even>('=').unwrap().0, 6);
+
+        args.next();
+        let ArgError::FailedToParse(ctx) =
+            args.cur_val_try_into::<u32, Even>('=').unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 4..6);
+
+        args.next();
+        let ArgError::InvalidValue(ctx) =
+            args.cur_val_try_into::<u32, Even>('=').unwrap_err()
+        else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 4..5));
+        assert_eq!(ctx.args[2], "--n=7");
+
+        args.next();
+        let err = args.cur_val_try_into::<u32, Even>('=').unwrap_err();
+        assert!(matches!(err, ArgError::NoValue(_)));
+    }
+
+    #[test]
+    fn short_type_names() {
+        assert_eq!(short_type_name::<Even>(), "Even");
+        assert_eq!(
+            short_type_name::<Vec<std::path::PathBuf>>(),
+            "Vec<PathBuf>"
+        );
+        assert_eq!(
+            short_type_name::<(Option<&str>, [u8; 2])>(),
+            "(Option<&str>, [u8; 2])"
+        );
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn collect_errors() {
+        let mut args = pareg(&["prog", "-c", "x", "--name", "-c", "5", "-q"]);
+        args.next();
+
+        let mut count = 0;
+        let mut name = String::new();
+        let errs = args
+            .collect_errors(|p| {
+                match p.cur().unwrap_or_default() {
+                    "-c" => count = p.next_arg()?,
+                    "--name" => name = p.next_arg()?,
+                    _ => return Err(p.err_unknown_argument()),
+                }
+                Ok(())
+            })
+            .unwrap_err()
+            .no_color();
+
+        // `--name` consumed `-c` so `5` is unknown.
+        assert_eq!((count, name.as_str()), (0, "-c"));
+        let spans: Vec<_> = errs
+            .iter()
+            .map(|e| match e {
+                ArgError::FailedToParse(c) => {
+                    ('p', c.error_idx, c.error_span.clone())
+                }
+                ArgError::UnknownArgument(c) => {
+                    ('u', c.error_idx, c.error_span.clone())
+                }
+                _ => panic!("Invalid error kind."),
+            })
+            .collect();
+        assert_eq!(spans, [('p', 2, 0..1), ('u', 5, 0..1), ('u', 6, 0..2)]);
+
+        let msg = errs.to_string();
+        assert!(msg.starts_with(
+            "argument error: Invalid value for `-c`: invalid digit found in \
+            string\n"
+        ));
+        assert!(msg.contains(
+            "^ invalid digit found in string\n\n\
+            argument error: Unknown argument `5`.\n"
+        ));
+        assert!(msg.ends_with("\n\n3 argument errors\n"));
+
+        let mut args = pareg(&["-c", "1"]);
+        assert!(args
+            .collect_errors(|p| {
+                p.next();
+                Ok(())
+            })
+            .is_ok());
+    }
+
+    #[test]
+    fn insert_args_cur() {
+        let mut args = pareg(&["prog", "-a", "-b"]);
+        args.next();
+        args.next();
+
+        // Before the current argument.
+        args.insert_args(0, ["x".to_string(), "y".to_string()]);
+        assert_eq!(Some("-a"), args.cur());
+        assert_eq!(Some(3), args.cur_idx());
+
+        // At the current argument.
+        args.insert_args(3, ["z".to_string()]);
+        assert_eq!(Some("-a"), args.cur());
+
+        // After the current argument.
+        args.insert_args(5, ["w".to_string()]);
+        assert_eq!(Some("-a"), args.cur());
+        assert_eq!(Some("w"), args.next());
+
+        args.append_args(["-c".to_string()]);
+        args.insert_args(usize::MAX, ["-d".to_string()]);
+        assert_eq!(
+            args.all_args(),
+            ["x", "y", "prog", "z", "-a", "w", "-b", "-c", "-d"]
+        );
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn insert_args_origin() {
+        let mut args = pareg(&["prog", "--name", "x"]);
+        args.next();
+        args.insert_args_from(1, "config", ["--count=x".to_string()]);
+        args.append_args_from("env", ["-v".to_string()]);
+
+        args.next();
+        let err = args.cur_val::<u32>('=').unwrap_err().no_color();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(
+            ctx.origins,
+            [None, Some("config".into()), None, None, Some("env".into())]
+        );
+        assert_eq!(
+            err.to_string(),
+            "argument error: Invalid value for `--count`: invalid digit found \
+            in string
+--> arg2:8..9 (from config)
+ |
+ $ prog --count=x --name x -v
+ |              ^ invalid digit found in string
+"
+        );
+
+        // Errors in arguments from the command line have no annotation.
+        args.next();
+        let err = args.err_unknown_argument().no_color().to_string();
+        assert!(err.contains("--> arg3:0..6\n"));
+
+        // Other arguments with origin are dimmed.
+        let err = args.err_unknown_argument();
+        let err = err.color_mode(ColorMode::Always).to_string();
+        assert!(err.contains("\x1b[90m--count=x\x1b[0m --name"));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    fn esc_span(args: &mut Pareg) -> Range<usize> {
+        args.next();
+        match args.cur_key_val_esc::<char, u32>('=').unwrap_err() {
+            ArgError::FailedToParse(c) => c.error_span,
+            e => panic!("Invalid error kind: {e:?}"),
+        }
+    }
+
+    #[test]
+    fn key_val_escaped() {
+        let mut args =
+            pareg(&[r"a\=b=c", r"a\\=b", r"a\\\=b=c", r"a\b=c", r"a\", r"\="]);
+
+        let mut kv = || args.next_key_mval_esc::<String, String>('=').unwrap();
+        assert_eq!(kv(), ("a=b".to_owned(), Some("c".to_owned())));
+        assert_eq!(kv(), (r"a\".to_owned(), Some("b".to_owned())));
+        assert_eq!(kv(), (r"a\=b".to_owned(), Some("c".to_owned())));
+        assert_eq!(kv(), (r"a\b".to_owned(), Some("c".to_owned())));
+        assert_eq!(kv(), (r"a\".to_owned(), None));
+        assert_eq!(kv(), ("=".to_owned(), None));
+
+        args.reset();
+        assert_eq!(Some("c"), args.next_mval_esc::<&str>('=').unwrap());
+        assert_eq!("b", args.next_val_esc::<&str>('=').unwrap());
+        args.skip(2);
+        assert!(matches!(
+            args.next_val_esc::<&str>('='),
+            Err(ArgError::NoValue(_))
+        ));
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn key_val_escaped_spans() {
+        // Error in value is after the escapes.
+        let mut args = pareg(&[r"a\\\=b=x"]);
+        let err = args.next_key_val_esc::<String, u32>('=').unwrap_err();
+        let ArgError::FailedToParse(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 7..8);
+        assert_eq!(ctx.args, [r"a\\\=b=x"]);
+
+        // Error in key covers the escapes.
+        let mut args = pareg(&[r"a\=b=1", r"\\\\=1", r"\==1"]);
+        assert_eq!(esc_span(&mut args), 0..4);
+        assert_eq!(esc_span(&mut args), 0..4);
+        args.next();
+        assert_eq!(args.cur_key_val_esc::<char, u32>('=').unwrap(), ('=', 1));
+
+        let mut args = pareg(&["--map", r"ab\=c=1"]);
+        args.next();
+        let err = args.next_key_val_esc::<char, u32>('=').unwrap_err();
+        assert_eq!(
+            err.no_color().to_string(),
+            r"argument error: too many characters in string
+--> arg2:0..5
+ |
+ $ --map ab\=c=1
+ |       ^^^^^ too many characters in string
+"
+        );
+    }
+
+    #[test]
+    fn map_err_twice() {
+        let mut args = pareg(&["prog", "--token=x", "-n", "1x"]);
+        args.jump(4);
+        let once = args.cur_arg::<u8>().unwrap_err().no_color().to_string();
+        let twice = args.map_err(args.cur_arg::<u8>());
+        assert_eq!(once, twice.unwrap_err().no_color().to_string());
+
+        // Error with different arguments still gets the arguments.
+        let err = args.map_err(u8::from_arg(&"1x"[1..])).unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!((ctx.args.len(), ctx.error_idx), (4, 3));
+        assert_eq!(ctx.error_span, 1..2);
+    }
+
+    #[cfg(feature = "std")]
+    fn prompt<T>(
+        args: &mut Pareg,
+        opts: &PromptOpts,
+        input: &str,
+    ) -> (Result<T>, String)
+    where
+        T: for<'b> FromArg<'b>,
+    {
+        let mut out = vec![];
+        let res = args.next_arg_or_prompt_with(
+            "N",
+            opts,
+            &mut input.as_bytes(),
+            &mut out,
+        );
+        (res, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", not(feature = "minimal-errors")))]
+    fn prompt_retry() {
+        let opts = PromptOpts {
+            color: ColorMode::Never,
+            ..Default::default()
+        };
+
+        // Argument is used if there is one.
+        let mut args = pareg(&["5"]);
+        let (res, out) = prompt::<u8>(&mut args, &opts, "6\n");
+        assert_eq!((res.unwrap(), out.as_str()), (5, ""));
+
+        let (res, out) = prompt::<u8>(&mut args, &opts, "x\r\n300\n7\n");
+        assert_eq!(res.unwrap(), 7);
+        assert_eq!(
+            out,
+            "N: invalid digit found in string
+--> arg1:0..1
+ |
+ $ x
+ | ^ invalid digit found in string
+N: number too large to fit in target type
+--> arg1:0..3
+ |
+ $ 300
+ | ^^^ number too large to fit in target type
+N: "
+        );
+
+        // The last error is returned.
+        let (res, out) = prompt::<u8>(&mut args, &opts, "a\nb\nc\nd\n");
+        let ctx = res.unwrap_err().ctx().unwrap().args.clone();
+        assert_eq!(ctx, ["c"]);
+        assert_eq!(out.matches("N: ").count(), 3);
+
+        // End of input.
+        let (res, out) = prompt::<u8>(&mut args, &opts, "x\n");
+        assert!(matches!(res, Err(ArgError::NoMoreArguments(_))));
+        assert!(out.ends_with("N: \n"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn prompt_default_echo() {
+        let opts = PromptOpts {
+            retries: 0,
+            echo: true,
+            default: Some("auto".into()),
+            color: ColorMode::Never,
+        };
+        let mut args = pareg(&[]);
+
+        let (res, out) = prompt::<String>(&mut args, &opts, "\n");
+        assert_eq!(res.unwrap(), "auto");
+        assert_eq!(out, "N [auto]: \n");
+
+        let (res, out) = prompt::<String>(&mut args, &opts, "never\n");
+        assert_eq!(res.unwrap(), "never");
+        assert_eq!(out, "N [auto]: never\n");
+
+        let (res, _) = prompt::<u32>(&mut args, &opts, "\n");
+        assert!(res.unwrap_err().ctx().unwrap().args == ["auto"]);
+    }
+
+    #[test]
+    fn value_owner() {
+        let mut args =
+            pareg(&["--count", "x", "--count=y", "-n", "--max=5", "z"]);
+        let owner_msg = |r: Result<u32>| {
+            let e = r.unwrap_err().no_color().to_string();
+            e.lines().next().unwrap_or_default().to_string()
+        };
+
+        args.next();
+        let msg = owner_msg(args.cur_val_or_next('='));
+        assert!(msg.contains("Invalid value for `--count`: "), "{msg}");
+        let msg = owner_msg(args.next_arg());
+        assert!(!msg.contains("Invalid value for"), "{msg}");
+
+        args.jump(3);
+        let err = args.cur_val_or_next::<u32>('=').unwrap_err();
+        let ArgError::FailedToParse(ctx) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(ctx.owner.as_deref(), Some("--count"));
+        assert_eq!(ctx.value_of, None);
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 8..9));
+
+        // `--count=y` already has value.
+        let msg = owner_msg(args.next_arg());
+        assert!(!msg.contains("Invalid value for"), "{msg}");
+
+        args.jump(5);
+        let msg = owner_msg(args.next_arg());
+        assert!(!msg.contains("Invalid value for"), "{msg}");
+        args.jump(5);
+        let msg = owner_msg(args.next_value_of("--max"));
+        assert!(msg.contains("Invalid value for `--max`: "), "{msg}");
+
+        let mut args = pareg(&["--count", "x", "--max", "y"]);
+        args.next();
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().value_of, Some(0));
+        args.next();
+        let err = args.next_value_of::<u32>("max").unwrap_err();
+        assert_eq!(err.ctx().unwrap().value_of, None);
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn env_vars() {
+        let vars = |v: &[(&str, &str)]| -> Vec<(String, String)> {
+            v.iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect()
+        };
+        let env = vars(&[
+            ("APP_COUNT", "x"),
+            ("APP_", "empty"),
+            ("PATH", "/bin"),
+            ("APP_COLOR_MODE", "never"),
+        ]);
+
+        let mut args =
+            Pareg::from_env_vars("APP_", &EnvMapping::default(), env);
+        assert_eq!(args.all_args(), ["--color-mode=never", "--count=x"]);
+        args.jump(2);
+        let err = args.cur_val::<u32>('=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 8..9));
+        assert_eq!(
+            ctx.origins[1].as_deref(),
+            Some("environment variable `APP_COUNT`")
+        );
+        let msg = err.no_color().to_string();
+        assert!(msg.contains("(from environment variable `APP_COUNT`)"));
+
+        let mut args = pareg(&["prog", "-v"]);
+        args.next();
+        let map = EnvMapping {
+            option_prefix: "-",
+            lowercase: false,
+            underscore: None,
+        };
+        args.merge_env_vars("X", &map, vars(&[("XA_B", "1"), ("Y", "2")]));
+        assert_eq!(args.all_args(), ["prog", "-v", "-A_B=1"]);
+        assert_eq!(args.next(), Some("-v"));
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().origins[1], None);
+        assert!(err.ctx().unwrap().origins[2].is_some());
+    }
+
+    #[test]
+    fn merge_env_back_cursor() {
+        let mut args = pareg(&["prog", "out.txt"]);
+        assert_eq!(args.next_back(), Some("out.txt"));
+        assert_eq!(args.next(), Some("prog"));
+        let env = [("APP_COLOR".to_string(), "never".to_string())];
+        args.merge_env_vars("APP_", &EnvMapping::default(), env);
+        assert_eq!(args.all_args(), ["prog", "--color=never", "out.txt"]);
+        assert!(args.require_exhausted().is_err());
+        assert_eq!(args.next(), Some("--color=never"));
+        assert_eq!(args.next(), None);
+    }
+
+    #[test]
+    fn short_val() {
+        let mut args = pareg(&["-j8", "-j", "4", "-jx", "-j"]);
+        args.next();
+        assert_eq!(args.cur_short_val::<u32>("-j").unwrap(), 8);
+        assert_eq!(args.cur_short_val_or_next::<u32>("-j").unwrap(), 8);
+
+        args.next();
+        let err = args.cur_short_val::<u32>("-j").unwrap_err();
+        let ArgError::NoValue(ctx) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 2..2));
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some("Use `-j<value>` or `-j <value>`.")
+        );
+        assert_eq!(args.cur_short_val_or_next::<u32>("-j").unwrap(), 4);
+
+        args.next();
+        let err = args.cur_short_val_or_next::<u32>("-j").unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_failed_to_parse());
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (3, 2..3));
+        assert_eq!(ctx.owner.as_deref(), Some("-j"));
+
+        args.next();
+        let err = args.cur_short_val_or_next::<u32>("-j").unwrap_err();
+        assert!(matches!(err, ArgError::NoMoreArguments(_)));
+
+        let err = args.cur_short_val::<u32>("-D").unwrap_err();
+        assert!(err.is_invalid_value());
+        assert_eq!(err.ctx().unwrap().error_idx, 4);
+    }
+
+    #[test]
+    fn short_key_val() {
+        let mut args = pareg(&["-DNAME=VAL", "-Dlevel=x", "-Dx", "-D"]);
+        args.next();
+        let (k, v) = args.cur_short_key_val::<&str, &str>("-D", '=').unwrap();
+        assert_eq!((k, v), ("NAME", "VAL"));
+
+        args.next();
+        let err = args.cur_short_key_val::<&str, u8>("-D", '=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 8..9));
+        assert_eq!(ctx.args[1], "-Dlevel=x");
+
+        args.next();
+        let err = args.cur_short_key_val::<&str, u8>("-D", '=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_no_value());
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 2..3));
+
+        args.next();
+        let err = args.cur_short_key_val::<&str, u8>("-D", '=').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_no_value());
+        assert_eq!(ctx.error_span, 2..2);
+        assert!(ctx.hint.as_deref().unwrap().contains("`-D <value>`"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn key_val_insert_dup() {
+        use std::collections::{BTreeMap, HashMap};
+
+        let insert = |m: &mut HashMap<String, u32>, on_dup| {
+            let mut args = pareg(&["-D", "a=1", "-D", "b=2", "-D", "a=3"]);
+            while args.next().is_some() {
+                args.next_key_val_insert('=', m, on_dup)?;
+            }
+            Ok::<_, ArgError>(())
+        };
+
+        let mut map = HashMap::new();
+        let err = insert(&mut map, DupPolicy::Error).unwrap_err();
+        let ArgError::TooManyArguments(ctx) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (5, 0..1));
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Key `a` was already given in the 2nd argument `a=1`.")
+        );
+        let pairs = |p: [(&str, u32); 2]| p.map(|(k, v)| (k.to_string(), v));
+        assert_eq!(map, HashMap::from(pairs([("a", 1), ("b", 2)])));
+
+        let mut map = HashMap::new();
+        insert(&mut map, DupPolicy::Overwrite).unwrap();
+        assert_eq!(map, HashMap::from(pairs([("a", 3), ("b", 2)])));
+
+        let mut map = HashMap::new();
+        insert(&mut map, DupPolicy::Keep).unwrap();
+        assert_eq!(map, HashMap::from(pairs([("a", 1), ("b", 2)])));
+
+        // The key was in the map before parsing.
+        let mut args = pareg(&["-x", "key:5"]);
+        args.skip(2);
+        let mut map = BTreeMap::from([("key".to_string(), 0)]);
+        let err = args
+            .cur_key_val_insert(':', &mut map, DupPolicy::Error)
+            .unwrap_err();
+        let ArgError::TooManyArguments(ctx) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 0..3));
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Key `key` was already given.")
+        );
+        args.cur_key_val_insert(':', &mut map, DupPolicy::Overwrite)
+            .unwrap();
+        assert_eq!(map["key"], 5);
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn next_n_values() {
+        let mut args = pareg(&["prog", "--rgb", "255", "x", "0", "--rgb"]);
+        args.next();
+        args.next();
+        let err = args.next_n::<u8>(3).unwrap_err();
+        assert_eq!(args.cur(), Some("0"));
+        assert_eq!(
+            err.no_color().max_width(100).to_string(),
+            "argument error: Invalid value for `--rgb`: invalid digit found \
+in string (value 2 of 3)
+--> arg4:0..1
+ |
+ $ prog --rgb 255 x 0 --rgb
+ |                ^ invalid digit found in string
+"
+        );
+
+        args.next();
+        let err = args.next_n_args::<u8, 3>().unwrap_err();
+        assert!(err.is_no_more_arguments());
+        assert_eq!(args.cur(), Some("--rgb"));
+        assert_eq!(
+            err.no_color().to_string(),
+            "argument error: Expected 3 more values for `--rgb`.
+--> arg6:5..5
+ |
+ $ prog --rgb 255 x 0 --rgb
+ |                         ^ Expected more arguments.
+"
+        );
+
+        let mut args = pareg(&["1", "2", "3", "4"]);
+        assert_eq!(args.next_n_args::<u32, 0>().unwrap(), []);
+        assert_eq!(args.next_n_args::<u32, 2>().unwrap(), [1, 2]);
+        assert_eq!(args.cur_idx(), Some(1));
+        assert_eq!(args.next_back(), Some("4"));
+        let err = args.next_n::<u32>(2).unwrap_err();
+        assert_eq!(err.long_message(), Some("Expected 1 more value."));
+        assert_eq!(args.next_n::<u32>(1).unwrap(), [3]);
+    }
+
+    #[test]
+    fn try_arg_equivalence() {
+        let inputs = [
+            "", " ", "0", " 7 ", "-1", "255", "256", "1.5", "true", "True",
+            "x", "ž", "--val=1",
+        ];
+        let mut args = pareg(&inputs);
+        for norm in [
+            Normalize::default(),
+            Normalize {
+                trim: true,
+                empty_is_missing: true,
+            },
+        ] {
+            args.normalize(norm);
+            args.reset();
+            assert_eq!(args.cur_try_arg::<u8>(), None);
+            while args.next().is_some() {
+                assert_eq!(args.cur_try_arg::<u8>(), args.cur_arg().ok());
+                assert_eq!(args.cur_try_arg::<i64>(), args.cur_arg().ok());
+                assert_eq!(args.cur_try_arg::<f32>(), args.cur_arg().ok());
+                assert_eq!(args.cur_try_arg::<bool>(), args.cur_arg().ok());
+                assert_eq!(args.cur_try_arg::<char>(), args.cur_arg().ok());
+                assert_eq!(args.cur_try_arg::<&str>(), args.cur_arg().ok());
+                assert_eq!(
+                    args.cur_try_arg::<Option<u8>>(),
+                    args.cur_arg().ok()
+                );
+                assert_eq!(
+                    args.cur_is::<Option<i8>>(),
+                    args.cur_arg::<Option<i8>>().is_ok()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn require_exhausted() {
+        let mut args = pareg(&["sub", "file", "-x", "-", "out"]);
+        let trailing = |args: &Pareg| {
+            let err = args.require_exhausted().unwrap_err();
+            let ArgError::TooManyArguments(ctx) = err else {
+                panic!("{err:?}");
+            };
+            ctx
+        };
+
+        args.next();
+        let ctx = trailing(&args);
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 0..4));
+        assert_eq!(
+            ctx.long_message.as_deref(),
+            Some("Unexpected trailing argument `file`.")
+        );
+        assert_eq!(ctx.hint, None);
+
+        args.next();
+        let ctx = trailing(&args);
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (2, 0..2));
+        assert!(ctx.hint.unwrap().contains("put `--` before it"));
+
+        args.next();
+        assert_eq!(trailing(&args).hint, None);
+
+        args.skip_all();
+        args.require_exhausted().unwrap();
+        args.reset();
+        assert!(args.require_exhausted().is_err());
+        assert!(pareg(&[]).require_exhausted().is_ok());
+    }
+
+    #[test]
+    fn fields_arg() {
+        let arg = "host=example.com,,port=8080,tls=true";
+        let mut f = parsers::fields_arg(arg, ',', '=').unwrap();
+        assert_eq!(f.require::<&str>("host").unwrap(), "example.com");
+        assert_eq!(f.take::<u16>("port").unwrap(), Some(8080));
+        assert_eq!(f.take::<u16>("port").unwrap(), None);
+        let err = f.clone().finish().unwrap_err();
+        let ArgError::InvalidValue(ctx) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(ctx.error_span, 28..31);
+        let hint = ctx.hint.unwrap();
+        assert!(hint.contains("`tls`") && hint.contains("`host`, `port`"));
+        assert_eq!(f.take::<bool>("tls").unwrap(), Some(true));
+        f.finish().unwrap();
+
+        let mut f = parsers::fields_arg("host=a,port=80x0", ',', '=').unwrap();
+        let err = f.take::<u16>("port").unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(
+            (ctx.args[0].as_ref(), ctx.error_span.clone()),
+            ("host=a,port=80x0", 12..16)
+        );
+        let err = f.require::<u16>("user").unwrap_err();
+        let ArgError::InvalidValue(ctx) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(
+            ctx.hint.as_deref(),
+            Some("Add the field as `user=<value>`.")
+        );
+
+        let err = parsers::fields_arg("a=1,b=2,a=3", ',', '=').unwrap_err();
+        let ArgError::TooManyArguments(ctx) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(ctx.error_span, 8..9);
+
+        let err = parsers::fields_arg("a=1,b", ',', '=').unwrap_err();
+        let ArgError::NoValue(ctx) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(ctx.error_span.start, 4);
+    }
+
+    #[test]
+    fn csv_row() {
+        assert_eq!(
+            parsers::csv_row(r#"a,"b,c",3"#, ',').unwrap(),
+            ["a", "b,c", "3"]
+        );
+        assert_eq!(
+            parsers::csv_row(r#""say ""hi""","""""#, ',').unwrap(),
+            ["say \"hi\"", "\""]
+        );
+        assert_eq!(parsers::csv_row(",a,,", ',').unwrap(), ["", "a", "", ""]);
+        assert_eq!(parsers::csv_row(r#""""#, ',').unwrap(), [""]);
+        assert_eq!(parsers::csv_row("", ',').unwrap(), [""]);
+        assert_eq!(parsers::csv_row("a;ž;b", ';').unwrap(), ["a", "ž", "b"]);
+
+        let span = |arg: &str| {
+            let err = parsers::csv_row(arg, ',').unwrap_err();
+            let ArgError::FailedToParse(ctx) = err else {
+                panic!("{err:?}");
+            };
+            assert_eq!(ctx.args[0], arg);
+            ctx.error_span
+        };
+        assert_eq!(span(r#"a,"b,c"#), 2..3);
+        assert_eq!(span(r#"a,b"c"#), 3..4);
+        assert_eq!(span(r#""a"b,c"#), 3..4);
+
+        assert_eq!(
+            parsers::csv_row_as::<u8>(r#"1,"2",3"#, ',').unwrap(),
+            [1, 2, 3]
+        );
+        let err = parsers::csv_row_as::<u8>(r#"1,"""x",3"#, ',').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(ctx.args[0], r#"1,"""x",3"#);
+        assert_eq!(&ctx.args[0][ctx.error_span.clone()], r#"""x"#);
+
+        let mut args = pareg(&["--row", r#"a,"b,c""#, "--row", "1,x"]);
+        args.next();
+        assert_eq!(args.next_csv::<String>(',').unwrap(), ["a", "b,c"]);
+        args.next();
+        let err = args.next_csv::<u8>(',').unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (3, 2..3));
+    }
+
+    #[test]
+    fn progress() {
+        let mut args = pareg(&["a", "b", "c", "d", "e"]);
+        let check = |args: &Pareg, done: usize| {
+            assert_eq!(args.progress(), (done, 5));
+            assert_eq!(args.remaining_len(), 5 - done);
+            assert_eq!(args.len(), 5);
+            assert!(!args.is_empty());
+        };
+        check(&args, 0);
+        args.next();
+        check(&args, 1);
+        args.next_back();
+        check(&args, 2);
+        assert_eq!(args.remaining().len(), args.remaining_len());
+        args.skip(2);
+        check(&args, 4);
+        args.skip_all();
+        check(&args, 5);
+        args.next();
+        check(&args, 5);
+        args.reset();
+        check(&args, 0);
+        args.jump(3);
+        check(&args, 3);
+        args.jump(10);
+        check(&args, 5);
+        args.reset();
+        let mut cnt = 0;
+        while args.next().is_some() {
+            cnt += 1;
+            assert_eq!(args.remaining_len(), 5 - cnt);
+        }
+        assert_eq!(cnt, 5);
+
+        let empty = pareg(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.progress(), (0, 0));
+
+        let mut args = pareg(&["--help", "x"]);
+        args.on_special(SpecialArgs {
+            help: Some(Box::new(|| {})),
+            version: None,
+        });
+        assert_eq!(args.next(), None);
+        assert_eq!(args.progress(), (2, 2));
+
+        let mut args = Pareg::lazy((0..4).map(|i| i.to_string()));
+        args.next();
+        assert_eq!(args.remaining_len(), 3);
+        assert_eq!(args.progress(), (1, 4));
+    }
+
+    #[test]
+    fn take_args() {
+        let args: Vec<String> = ["-o", "out.txt", "-n", "x", "a", "b", "c"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        let ptr = args[1].as_ptr();
+        let mut args = Pareg::new(args);
+
+        args.next();
+        let out = args.take_next().unwrap();
+        assert_eq!(out, "out.txt");
+        assert_eq!(out.as_ptr(), ptr);
+        assert_eq!(args.cur(), Some(""));
+        assert_eq!(args.take_cur(), None);
+
+        args.next();
+        let err = args.next_arg::<u32>().unwrap_err().no_color();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(ctx.args, ["-o", "<taken>", "-n", "x", "a", "b", "c"]);
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (3, 0..1));
+        assert!(!err.to_string().contains("out.txt"));
+
+        args.next_back();
+        assert_eq!(args.take_remaining(), ["a", "b"]);
+        assert_eq!(args.next(), None);
+        assert!(args.take_remaining().is_empty());
+        assert_eq!(args.take_next(), None);
+
+        args.insert_args(0, ["-v".to_string()]);
+        args.jump(3);
+        let err = args.err_invalid();
+        assert_eq!(err.ctx().unwrap().error_span, 0..0);
+        assert_eq!(err.ctx().unwrap().args[..3], ["-v", "-o", "<taken>"]);
+        assert_eq!(args.into_args(), ["-v", "-o", "", "-n", "x", "", "", "c"]);
+    }
+
+    #[test]
+    fn deferred_defaults() {
+        let stem = |r: &ResolvedArgs| {
+            let name = r.get::<String>("name").unwrap();
+            match name.split_once('.') {
+                Some((s, _)) if !s.is_empty() => Ok(s.to_string()),
+                Some(_) => ArgError::parse_msg("Empty stem.", name.clone())
+                    .spanned(0..1)
+                    .err(),
+                None => Ok(name.clone()),
+            }
+        };
+        let values = |name, output: Deferred<String>| {
+            let mut values = ResolvedArgs::new();
+            values
+                .add("name", name)
+                .add("stem", Deferred::new(stem))
+                .add("output", output)
+                .add("count", Deferred::new(|_| Ok(1_u32)));
+            values
+        };
+        let output = || {
+            Deferred::new(|r: &ResolvedArgs| {
+                Ok(format!("{}.out", r.get::<String>("stem").unwrap()))
+            })
+        };
+
+        let mut args = pareg(&["-v", "--name=data.csv", "--output", "o"]);
+        let mut name = Deferred::new(|_| Ok("a".to_string()));
+        let mut out = output();
+        args.next();
+        args.next();
+        args.set_deferred_val(&mut name, '=').unwrap();
+        assert!(name.is_set() && !out.is_set());
+        let mut v = values(name, output());
+        args.resolve_all(&mut v).unwrap();
+        assert_eq!(v.take::<u32>("output"), None);
+        assert_eq!(v.take::<String>("output").as_deref(), Some("data.out"));
+        assert_eq!(v.get::<u32>("count"), Some(&1));
+
+        args.next();
+        args.set_deferred(&mut out).unwrap();
+        let mut v = values(Deferred::new(|_| Ok("x".into())), out);
+        args.resolve_all(&mut v).unwrap();
+        assert_eq!(v.take::<String>("output").as_deref(), Some("o"));
+        assert_eq!(v.take::<String>("stem").as_deref(), Some("x"));
+
+        // The error of `stem` points to the value of `--name`.
+        let mut args = pareg(&["--name=.csv"]);
+        let mut name = Deferred::new(|_| Ok("a".to_string()));
+        args.next();
+        args.set_deferred_val(&mut name, '=').unwrap();
+        let err = args.resolve_all(&mut values(name, output())).unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert_eq!(ctx.args[ctx.error_idx], "--name=.csv");
+        assert_eq!(ctx.error_span, 7..11);
+        assert_eq!(ctx.message, "Empty stem.");
+        let hint = ctx.hint.as_deref().unwrap();
+        assert!(hint.contains("`stem`"), "{hint}");
+
+        // Defaults computed from other defaults have no location.
+        let name = Deferred::new(|_| Ok(".csv".to_string()));
+        let err = args.resolve_all(&mut values(name, output())).unwrap_err();
+        assert_eq!(err.ctx().unwrap().args[0], ".csv");
+    }
+
+    #[test]
+    fn error_usage() {
+        let mut args = pareg(&["prog", "-n", "x", "y"]);
+        args.set_error_usage("prog [-n <N>]");
+        args.next();
+        args.next();
+        let usage = |e: &ArgError| e.ctx().unwrap().usage.clone();
+        let err = args.next_arg::<u32>().unwrap_err();
+        assert_eq!(usage(&err).as_deref(), Some("prog [-n <N>]"));
+        let err = args.err_unknown_argument();
+        assert_eq!(usage(&err).as_deref(), Some("prog [-n <N>]"));
+
+        // Usage of the error is kept.
+        let err =
+            args.map_err::<()>(Err(
+                ArgError::parse_msg("x", "x".into()).usage("other")
+            ));
+        assert_eq!(usage(&err.unwrap_err()).as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn custom_error() {
+        #[derive(Debug)]
+        struct Port;
+
+        impl FromArg<'_> for Port {
+            fn from_arg(arg: &str) -> Result<Self> {
+                let src = arg.parse::<u16>().unwrap_err();
+                Err(ArgError::Custom {
+                    ctx: ArgErrCtx::from_msg("Port is in use.", arg.into())
+                        .into(),
+                    tag: "in-use".into(),
+                    source: Some(Box::new(src)),
+                })
+            }
+        }
+
+        let err = key_val_arg::<&str, Port>("http=x80", '=').unwrap_err();
+        assert_eq!(err.custom_tag(), Some("in-use"));
+        assert_eq!(err.span(), Some(5..8));
+        assert!(err.is_custom());
+        assert!(core::error::Error::source(&err).is_some());
+
+        let mut args = pareg(&["prog", "--port", "http=x80"]);
+        args.next();
+        args.next();
+        args.next();
+        let arg = args.cur_arg::<&str>().unwrap();
+        let err = args
+            .map_err(key_val_arg::<&str, Port>(arg, '='))
+            .unwrap_err();
+        assert_eq!(err.custom_tag(), Some("in-use"));
+        assert_eq!(err.error_idx(), Some(2));
+        assert_eq!(err.span(), Some(5..8));
+        assert_eq!(err.message(), Some("Port is in use."));
+        assert!(err.to_string().contains("Port is in use."));
+
+        let err = ArgError::custom("tag", "msg", "arg".into()).hint("h");
+        assert_eq!(err.kind(), ArgErrKind::Custom);
+        assert_eq!(err.hint_str(), Some("h"));
+        assert_eq!(args.err_unknown_argument().custom_tag(), None);
+    }
+
+    /// [`Pareg`] may be moved to other threads and kept in statics.
+    fn _assert_send_sync() {
+        fn check<T: Send + Sync>() {}
+        check::<Pareg>();
+    }
+
+    #[test]
+    fn special_args() {
+        use alloc::sync::Arc;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        let helped = Arc::new(AtomicUsize::new(0));
+        let special = || {
+            let helped = helped.clone();
+            SpecialArgs {
+                help: Some(Box::new(move || {
+                    helped.fetch_add(1, Ordering::Relaxed);
+                })),
+                version: None,
+            }
+        };
+
+        // As the first argument.
+        let mut args = pareg(&["prog", "--help", "-n", "5"]);
+        args.on_special(special());
+        args.next();
+        assert_eq!(args.next(), None);
+        assert_eq!(args.next(), None);
+        assert_eq!(args.intercepted(), Some(Special::Help));
+        assert_eq!(args.cur(), Some("--help"));
+        assert_eq!(helped.load(Ordering::Relaxed), 1);
+
+        // In the middle of parsing and in value position.
+        let mut args = pareg(&["prog", "-n", "5", "-o", "-h", "-n", "6"]);
+        args.on_special(special());
+        args.next();
+        let mut n = 0;
+        let err = loop {
+            match args.next() {
+                Some("-n") => n = args.next_arg::<u32>().unwrap(),
+                Some("-o") => break args.next_arg::<&str>().unwrap_err(),
+                a => panic!("{a:?}"),
+            }
+        };
+        assert_eq!(n, 5);
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert!(args.next_arg::<u32>().unwrap_err().is_special());
+        assert_eq!(args.next(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 2);
+
+        // Without action, it is not intercepted.
+        let mut args = pareg(&["prog", "-V", "-h"]);
+        args.on_special(special());
+        args.next();
+        assert_eq!(args.next(), Some("-V"));
+        assert_eq!(args.special(), Some(Special::Version));
+        assert_eq!(args.intercepted(), None);
+        assert_eq!(args.next(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 3);
+        args.reset();
+        assert_eq!(args.intercepted(), None);
+        assert_eq!(args.next(), Some("prog"));
+
+        // Values with location or with owner are intercepted too.
+        let mut args = pareg(&["prog", "-n", "-h"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.next_arg_spanned::<u32>().unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(helped.load(Ordering::Relaxed), 4);
+
+        let mut args = pareg(&["prog", "set", "n", "--help"]);
+        args.on_special(special());
+        args.skip(3);
+        let err = args.next_value_of::<u32>("n").unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(args.next(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 5);
+
+        // Values parsed by the other methods are intercepted too.
+        let mut args = pareg(&["prog", "--out", "--help"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.next_try_into::<i64, u32>().unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(args.intercepted(), Some(Special::Help));
+
+        let mut args = pareg(&["prog", "--out", "--help"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.cur_val_or_next::<&str>('=').unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(args.intercepted(), Some(Special::Help));
+
+        let mut args = pareg(&["prog", "-o", "--help"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.cur_short_val_or_next::<&str>("-o").unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(args.intercepted(), Some(Special::Help));
+        assert_eq!(args.next(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 8);
+
+        let mut args = pareg(&["prog", "--count", "--help"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.opt_value_arg::<u32>(&["--count"]).unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(helped.load(Ordering::Relaxed), 9);
+
+        // Each of the values is intercepted.
+        let mut args = pareg(&["prog", "--rgb", "1", "--help"]);
+        args.on_special(special());
+        args.skip(2);
+        let err = args.next_n_args::<String, 2>().unwrap_err();
+        assert!(matches!(err, ArgError::Special(Special::Help)));
+        assert_eq!(args.cur(), Some("--help"));
+        assert_eq!(args.next(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 10);
+
+        // Arguments after `--` are not intercepted.
+        let mut args = pareg(&["prog", "x", "--", "--help", "-h"]);
+        args.on_special(special());
+        args.next();
+        let mut rest = vec![];
+        let mut dd = false;
+        while let Some(a) = args.next() {
+            match a {
+                "--" if !dd => dd = true,
+                a => rest.push(a.to_string()),
+            }
+        }
+        assert_eq!(rest, ["x", "--help", "-h"]);
+        assert_eq!(args.intercepted(), None);
+        assert_eq!(helped.load(Ordering::Relaxed), 10);
+
+        // `--` that is value of option doesn't end the options.
+        let mut args = pareg(&["prog", "--sep", "--", "--help"]);
+        args.on_special(special());
+        args.next();
+        assert_eq!(args.next(), Some("--sep"));
+        assert_eq!(args.next_arg::<&str>().unwrap(), "--");
+        assert_eq!(args.next(), None);
+        assert_eq!(args.intercepted(), Some(Special::Help));
+        assert_eq!(helped.load(Ordering::Relaxed), 11);
+
+        // Value with owner is recorded as value of the option.
+        let mut args = pareg(&["prog", "-n", "5"]);
+        args.skip(2);
+        assert_eq!(args.next_value_of::<u32>("-n").unwrap(), 5);
+        let summary = args.summary();
+        assert_eq!(summary.args.len(), 2);
+        assert_eq!(summary.args[1].kind, UseKind::OptionValue);
+    }
+
+    #[test]
+    fn no_last_argument_location() {
+        let mut args = pareg(&["--count=x", "-o"]);
+        let line = line!() + 1;
+        let err = args.cur_val::<u32>('=').unwrap_err();
+        assert!(err.is_no_last_argument());
+        let loc = format!("{}:{line}:", file!());
+        assert!(err.to_string().contains(&loc), "{err}");
+
+        let line = line!() + 1;
+        let err = args.opt_value_arg::<u32>(&["-o"]).unwrap_err();
+        let ArgError::NoLastArgument(l) = err else {
+            panic!("{err:?}");
+        };
+        assert_eq!((l.file(), l.line()), (file!(), line));
+
+        args.strict_usage(true);
+        args.next();
+        assert!(args.cur_val::<u32>('=').unwrap_err().is_failed_to_parse());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "no last argument when it was expected at")]
+    fn strict_usage() {
+        let mut args = pareg(&["-o"]);
+        args.strict_usage(true);
+        _ = args.cur_key_val::<&str, &str>('=');
+    }
 }
@@ -0,0 +1,329 @@
+//! Generation of man pages.
+//!
+//! The man page is generated from the same [`CompletionSpec`] that is used
+//! for shell completion. Use [`render_man`] to get the page in roff.
+//!
+//! # Examples
+//! ```rust
+//! use pareg_core::{
+//!     completion::{CompletionSpec, ValueHint},
+//!     man::{render_man, ManMeta},
+//! };
+//!
+//! let spec = CompletionSpec::new("my-program")
+//!     .help("Greets the user.")
+//!     .flag(&["-h", "--help"], "Show help.")
+//!     .option(
+//!         &["--color"],
+//!         "Color mode.",
+//!         ValueHint::choice(["auto", "always", "never"]),
+//!     );
+//!
+//! let page = render_man(&spec, &ManMeta::new("my-program"));
+//! assert!(page.contains(".SH OPTIONS"));
+//! assert!(page.contains(r"\fB\-\-color\fR [\fBauto\fR|"));
+//! ```
+
+use crate::completion::{CompletionOpt, CompletionSpec, ValueHint};
+
+/// Information about the man page that is not in [`CompletionSpec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManMeta {
+    /// Title of the page. It is usually the name of the program.
+    pub name: String,
+    /// Section of the manual. Programs are in the section `1`.
+    pub section: String,
+    /// Date of the last change of the page (e.g. `2024-05-01`).
+    pub date: String,
+    /// Version of the program.
+    pub version: String,
+    /// Additional sections with their titles (e.g. `EXAMPLES`). They are
+    /// after the generated sections.
+    pub sections: Vec<(String, String)>,
+}
+
+impl ManMeta {
+    /// Creates metadata of page with the given title in the section `1`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            section: "1".into(),
+            date: String::new(),
+            version: String::new(),
+            sections: vec![],
+        }
+    }
+
+    /// Sets the date of the last change of the page.
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = date.into();
+        self
+    }
+
+    /// Sets the version of the program.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Adds section with the given title and text. Paragraphs in the text
+    /// are separated by empty line.
+    pub fn section(
+        mut self,
+        title: impl Into<String>,
+        text: impl Into<String>,
+    ) -> Self {
+        self.sections.push((title.into(), text.into()));
+        self
+    }
+}
+
+/// Renders man page of the program described by `spec` in roff.
+///
+/// Subcommands are listed in the section `COMMANDS` and their options are in
+/// subsections. Choices of values are shown as `[a|b|c]`.
+pub fn render_man(spec: &CompletionSpec, meta: &ManMeta) -> String {
+    let mut res = format!(
+        ".TH {} {} {} {} \"User Commands\"\n",
+        quoted(&meta.name.to_uppercase()),
+        quoted(&meta.section),
+        quoted(&meta.date),
+        quoted(format!("{} {}", meta.name, meta.version).trim_end()),
+    );
+
+    res += ".SH NAME\n";
+    if spec.help.is_empty() {
+        res += &format!("{}\n", escape(&spec.name));
+    } else {
+        res += &format!("{} \\- {}\n", escape(&spec.name), escape(&spec.help));
+    }
+
+    res += ".SH SYNOPSIS\n";
+    res += &synopsis(&spec.name, spec);
+
+    if !spec.opts.is_empty() {
+        res += ".SH OPTIONS\n";
+        res += &options(&spec.opts);
+    }
+
+    if !spec.subcommands.is_empty() {
+        res += ".SH COMMANDS\n";
+        res += &commands(&spec.name, spec);
+    }
+
+    for (title, text) in &meta.sections {
+        res += &format!(".SH {}\n", quoted(&title.to_uppercase()));
+        res += &paragraphs(text);
+    }
+
+    res
+}
+
+/// Renders synopsis of the command `path` (e.g. `git remote`).
+fn synopsis(path: &str, spec: &CompletionSpec) -> String {
+    let mut res = format!(".B {}\n", quoted(path));
+    let mut args = vec![];
+    if !spec.opts.is_empty() {
+        args.push("[\\fIOPTIONS\\fR]".to_string());
+    }
+    if !spec.subcommands.is_empty() {
+        args.push("\\fICOMMAND\\fR".into());
+    } else if let Some(v) = &spec.positional {
+        args.push(format!("[{}...]", value(v)));
+    }
+    if !args.is_empty() {
+        res += &format!("{}\n", args.join(" "));
+    }
+    res
+}
+
+/// Renders the options as tagged paragraphs.
+fn options(opts: &[CompletionOpt]) -> String {
+    let mut res = String::new();
+    for opt in opts {
+        let names: Vec<_> = opt
+            .names
+            .iter()
+            .map(|n| format!("\\fB{}\\fR", escape(n)))
+            .collect();
+        res += ".TP\n";
+        res += &names.join(", ");
+        if let Some(v) = &opt.value {
+            res += &format!(" {}", value(v));
+        }
+        res.push('\n');
+        res += &text(&opt.help);
+    }
+    res
+}
+
+/// Renders the list of subcommands of `spec` and subsections with their
+/// arguments. `path` is the command of `spec`.
+fn commands(path: &str, spec: &CompletionSpec) -> String {
+    let mut res = String::new();
+    for sub in &spec.subcommands {
+        res += &format!(".TP\n\\fB{}\\fR\n", escape(&sub.name));
+        res += &text(&sub.help);
+    }
+    for sub in &spec.subcommands {
+        let path = format!("{path} {}", sub.name);
+        res += &format!(".SS {}\n", quoted(&path));
+        res += &synopsis(&path, sub);
+        res += &options(&sub.opts);
+        res += &commands(&path, sub);
+    }
+    res
+}
+
+/// Renders placeholder of value.
+fn value(v: &ValueHint) -> String {
+    match v {
+        ValueHint::Any => "\\fIVALUE\\fR".into(),
+        ValueHint::File => "\\fIFILE\\fR".into(),
+        ValueHint::Dir => "\\fIDIR\\fR".into(),
+        ValueHint::Choice(c) => {
+            let c: Vec<_> =
+                c.iter().map(|c| format!("\\fB{}\\fR", escape(c))).collect();
+            format!("[{}]", c.join("|"))
+        }
+    }
+}
+
+/// Renders text with paragraphs separated by empty lines.
+fn paragraphs(s: &str) -> String {
+    let paras: Vec<_> = s
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(text)
+        .collect();
+    paras.join(".PP\n")
+}
+
+/// Escapes text so that it is shown as is and ends it with newline.
+fn text(s: &str) -> String {
+    let mut res = String::new();
+    for l in s.lines() {
+        if l.starts_with(['.', '\'']) {
+            res += "\\&";
+        }
+        res += &escape(l);
+        res.push('\n');
+    }
+    res
+}
+
+/// Escapes characters that are special in roff.
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\e").replace('-', "\\-")
+}
+
+/// Escapes and quotes macro argument.
+fn quoted(s: &str) -> String {
+    format!("\"{}\"", escape(s).replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> CompletionSpec {
+        CompletionSpec::new("doc")
+            .help("Example program.")
+            .flag(&["-h", "--help"], "Show help.")
+            .option(&["-c", "--count"], "Count.", ValueHint::Any)
+            .option(
+                &["--color"],
+                "Color [mode].\n.Dot at start of line.",
+                ValueHint::choice_of::<bool>(),
+            )
+            .positional(ValueHint::File)
+            .subcommand(
+                CompletionSpec::new("run")
+                    .help("Run it.")
+                    .option(&["-d"], r"Directory (e.g. C:\x).", ValueHint::Dir)
+                    .positional(ValueHint::choice(["a", "b"])),
+            )
+    }
+
+    fn meta() -> ManMeta {
+        ManMeta::new("doc")
+            .date("2024-05-01")
+            .version("1.0.0")
+            .section("examples", "Run \"doc\":\n  doc run -d .\n\nDone.")
+    }
+
+    #[test]
+    fn roff() {
+        const PAGE: &str = r#".TH "DOC" "1" "2024\-05\-01" "doc 1.0.0" "User Commands"
+.SH NAME
+doc \- Example program.
+.SH SYNOPSIS
+.B "doc"
+[\fIOPTIONS\fR] \fICOMMAND\fR
+.SH OPTIONS
+.TP
+\fB\-h\fR, \fB\-\-help\fR
+Show help.
+.TP
+\fB\-c\fR, \fB\-\-count\fR \fIVALUE\fR
+Count.
+.TP
+\fB\-\-color\fR [\fBfalse\fR|\fBtrue\fR]
+Color [mode].
+\&.Dot at start of line.
+.SH COMMANDS
+.TP
+\fBrun\fR
+Run it.
+.SS "doc run"
+.B "doc run"
+[\fIOPTIONS\fR] [[\fBa\fR|\fBb\fR]...]
+.TP
+\fB\-d\fR \fIDIR\fR
+Directory (e.g. C:\ex).
+.SH "EXAMPLES"
+Run "doc":
+  doc run \-d .
+.PP
+Done.
+"#;
+        assert_eq!(render_man(&spec(), &meta()), PAGE);
+    }
+
+    #[test]
+    fn minimal() {
+        assert_eq!(
+            render_man(&CompletionSpec::new("x"), &ManMeta::new("x")),
+            ".TH \"X\" \"1\" \"\" \"x\" \"User Commands\"
+.SH NAME
+x
+.SH SYNOPSIS
+.B \"x\"
+"
+        );
+    }
+
+    /// Checks that `man` can format the page. It requires `man-db`.
+    #[test]
+    #[ignore]
+    fn man_formats() {
+        use std::process::Command;
+
+        let path = std::env::temp_dir().join("pareg_man_formats.1");
+        std::fs::write(&path, render_man(&spec(), &meta())).unwrap();
+        let out = Command::new("man")
+            .arg("-l")
+            .arg(&path)
+            .env("MANPAGER", "cat")
+            .env("MANWIDTH", "80")
+            .output()
+            .unwrap();
+        _ = std::fs::remove_file(&path);
+        assert!(out.status.success());
+        let page = String::from_utf8_lossy(&out.stdout);
+        assert!(page.contains("OPTIONS"));
+        assert!(page.contains("doc run"));
+        assert!(!String::from_utf8_lossy(&out.stderr).contains("warning"));
+    }
+}
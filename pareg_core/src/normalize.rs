@@ -0,0 +1,165 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::ops::Range;
+
+use crate::{
+    arg_class, from_arg::parse_hinted, ArgErrCtx, ArgError, FromArg, Result,
+};
+
+/// Normalization of argument values before they are parsed. See
+/// [`crate::Pareg::normalize`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalize {
+    /// Remove leading and trailing whitespace from values.
+    pub trim: bool,
+    /// Empty values (after trimming) are missing. Missing values are parsed
+    /// with [`FromArg::from_missing`] (so [`Option`] will be [`None`]) and
+    /// other types will fail with [`ArgError::NoValue`].
+    pub empty_is_missing: bool,
+}
+
+impl Normalize {
+    /// Normalizes the value. Returns [`None`] if the value is missing.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Normalize;
+    ///
+    /// let norm = Normalize { trim: true, empty_is_missing: true };
+    /// assert_eq!(Some("10"), norm.apply(" 10 "));
+    /// assert_eq!(None, norm.apply("  "));
+    /// assert_eq!(Some(" 10 "), Normalize::default().apply(" 10 "));
+    /// ```
+    pub fn apply<'a>(&self, value: &'a str) -> Option<&'a str> {
+        let value = if self.trim { value.trim() } else { value };
+        (!self.empty_is_missing || !value.is_empty()).then_some(value)
+    }
+
+    /// Parses the value after normalization without creating error (see
+    /// [`FromArg::try_from_arg`]). Returns [`None`] if the value is invalid.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Normalize;
+    ///
+    /// let norm = Normalize { trim: true, empty_is_missing: true };
+    /// assert_eq!(Some(10), norm.try_parse::<u32>(" 10 "));
+    /// assert_eq!(None, norm.try_parse::<u32>(" "));
+    /// assert_eq!(Some(None), norm.try_parse::<Option<u32>>(" "));
+    /// ```
+    pub fn try_parse<'a, T>(&self, value: &'a str) -> Option<T>
+    where
+        T: FromArg<'a>,
+    {
+        match self.apply(value) {
+            Some(v) => T::try_from_arg(v),
+            None => T::from_missing(),
+        }
+    }
+
+    /// Parses value at the `span` of the argument `arg` after normalization.
+    /// Errors will refer to the original argument. `owner` is the option to
+    /// which the value belongs and it is used in error message if the value
+    /// is missing and `owner` looks like option (see [`crate::arg_class`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ArgError, Normalize};
+    ///
+    /// let norm = Normalize { trim: true, empty_is_missing: true };
+    /// assert_eq!(10, norm.parse::<u32>("--cnt= 10 ", 6..10, None).unwrap());
+    ///
+    /// let err = norm.parse::<u32>("--cnt= x ", 6..9, None).unwrap_err();
+    /// let ArgError::FailedToParse(ctx) = err else {
+    ///     panic!();
+    /// };
+    /// assert_eq!(7..8, ctx.error_span);
+    ///
+    /// assert_eq!(None, norm.parse::<Option<u32>>("", 0..0, None).unwrap());
+    /// let err = norm.parse::<u32>("", 0..0, Some("--cnt")).unwrap_err();
+    /// assert!(matches!(err, ArgError::NoValue(_)));
+    /// ```
+    pub fn parse<'a, T>(
+        &self,
+        arg: &'a str,
+        span: Range<usize>,
+        owner: Option<&str>,
+    ) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        let span = char_span(arg, span);
+        let value = &arg[span.clone()];
+        let Some(norm) = self.apply(value) else {
+            return T::from_missing()
+                .ok_or_else(|| missing_value(arg.to_string(), span, owner));
+        };
+
+        let shift =
+            span.start + norm.as_ptr() as usize - value.as_ptr() as usize;
+        parse_hinted::<T>(norm).map_err(|e| {
+            if shift == 0 && norm.len() == arg.len() {
+                e
+            } else {
+                e.shift_span(shift, arg.to_string())
+            }
+        })
+    }
+}
+
+impl Normalize {
+    /// Gets the span of the normalized value at `span` in `arg`. If the value
+    /// is missing, this is `span`.
+    pub(crate) fn value_span(
+        &self,
+        arg: &str,
+        span: Range<usize>,
+    ) -> Range<usize> {
+        let span = char_span(arg, span);
+        let value = &arg[span.clone()];
+        match self.apply(value) {
+            Some(v) => {
+                let start =
+                    span.start + v.as_ptr() as usize - value.as_ptr() as usize;
+                start..start + v.len()
+            }
+            None => span,
+        }
+    }
+}
+
+/// Creates error for value at `span` in `arg` that is missing.
+#[cold]
+pub(crate) fn missing_value(
+    arg: String,
+    span: Range<usize>,
+    owner: Option<&str>,
+) -> ArgError {
+    let main = match owner {
+        Some(o) if arg_class(o).is_option() => {
+            format!("Option `{o}` requires a value.")
+        }
+        _ => "Expected value.".to_string(),
+    };
+    ArgError::NoValue(
+        ArgErrCtx::from_msg("Missing value.", arg)
+            .spanned(span)
+            .main_msg(main)
+            .into(),
+    )
+}
+
+/// Clamps the span so that it is valid range of chars in `s`.
+fn char_span(s: &str, span: Range<usize>) -> Range<usize> {
+    let floor = |mut p: usize| {
+        p = p.min(s.len());
+        while !s.is_char_boundary(p) {
+            p -= 1;
+        }
+        p
+    };
+    let end = floor(span.end);
+    floor(span.start).min(end)..end
+}
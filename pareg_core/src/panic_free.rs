@@ -0,0 +1,1371 @@
+//! Audit that no public error producing or rendering API panics on
+//! adversarial input.
+//!
+//! Every public function in [`AUDITED_SOURCES`] must be exercised by
+//! [`audit`] (or listed in [`EXEMPT`] with a reason). This is checked by the
+//! test [`corpus_is_complete`], so new public APIs must be added here.
+
+// Reversed spans are part of the corpus.
+#![allow(clippy::reversed_empty_ranges)]
+
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+    sync::OnceLock,
+};
+
+use crate::*;
+
+/// Sources whose public functions must be covered by the audit.
+const AUDITED_SOURCES: &[(&str, &str)] = &[
+    ("lib.rs", include_str!("lib.rs")),
+    ("arg_class.rs", include_str!("arg_class.rs")),
+    ("arg_source.rs", include_str!("arg_source.rs")),
+    ("normalize.rs", include_str!("normalize.rs")),
+    ("parsef.rs", include_str!("parsef.rs")),
+    ("parsers.rs", include_str!("parsers.rs")),
+    ("read_fmt.rs", include_str!("read_fmt.rs")),
+    ("reader.rs", include_str!("reader.rs")),
+    ("similar.rs", include_str!("similar.rs")),
+    ("unknown_sink.rs", include_str!("unknown_sink.rs")),
+    ("err/arg_err_ctx.rs", include_str!("err/arg_err_ctx.rs")),
+    ("err/arg_error.rs", include_str!("err/arg_error.rs")),
+    ("err/arg_errors.rs", include_str!("err/arg_errors.rs")),
+    ("err/arg_warning.rs", include_str!("err/arg_warning.rs")),
+    ("err/color_mode.rs", include_str!("err/color_mode.rs")),
+    ("err/messages.rs", include_str!("err/messages.rs")),
+];
+
+/// Messages with labels that don't leave space for the text.
+const ODD_MESSAGES: Messages = Messages {
+    error: "long error label that is longer than the error itself ....",
+    warning: "",
+    hint: "long hint label that is longer than the error itself ......",
+    usage: "\n",
+    unknown_argument: "",
+    no_more_arguments: "\n\n",
+    invalid_value_for: "{owner}{owner}{",
+    minimal_error: "",
+    ..Messages::ENGLISH
+};
+
+/// Public functions that are not audited and why.
+#[cfg(feature = "glob")]
+const EXEMPT: &[(&str, &str)] = &[];
+/// Public functions that are not audited and why.
+#[cfg(not(feature = "glob"))]
+const EXEMPT: &[(&str, &str)] = &[
+    ("args_globbed", "Requires the feature `glob`."),
+    ("args_globbed_with", "Requires the feature `glob`."),
+    ("expand_globs", "Requires the feature `glob`."),
+];
+
+/// Adversarial argument lists.
+fn corpus_args() -> Vec<Vec<String>> {
+    let giant = "x".repeat(2_000);
+    let giant_mb = "ž".repeat(1_000) + "=" + &"日".repeat(1_000);
+    let lists: &[&[&str]] = &[
+        &[],
+        &[""],
+        &["", ""],
+        &["-"],
+        &["--"],
+        &["="],
+        &["=="],
+        &["--opt="],
+        &["--opt", ""],
+        &["--opt", "=x"],
+        &["a=b=c", "x:y"],
+        &["true", "false", "yes", "no", "maybe"],
+        &["10", "-5", "0.25", "1e400", "nan"],
+        &["ěščř", "日本語", "é=ü", "🦀=🦀"],
+        &["e\u{301}", "\u{301}", "\u{200b}=\u{200b}"],
+        &[" 10 ", "\t", " = "],
+        &["<redacted>", "--token=<redacted>"],
+        &["\n", "a\nb", "\r\n=\n"],
+        &["\\", "a\\=b=c", "\\\\=\\", "ž\\ž=\\", "x\\"],
+    ];
+    let mut res: Vec<Vec<String>> = lists
+        .iter()
+        .map(|l| l.iter().map(|a| a.to_string()).collect())
+        .collect();
+    res.push(vec![giant.clone()]);
+    res.push(vec!["--opt=".to_string() + &giant, giant.clone()]);
+    res.push(vec![giant_mb.clone(), "-".into(), giant_mb]);
+    res.push((0..200).map(|i| i.to_string()).collect());
+    res
+}
+
+/// Adversarial spans.
+const SPANS: &[std::ops::Range<usize>] = &[
+    0..0,
+    0..1,
+    1..2,
+    2..1,
+    3..100,
+    1_999..2_001,
+    usize::MAX - 1..usize::MAX,
+    0..usize::MAX,
+];
+
+/// Adversarial indexes and counts.
+const IDXS: &[usize] = &[0, 1, 2, 3, 199, 200, 2_000, usize::MAX];
+
+/// Adversarial widths.
+const WIDTHS: &[usize] = &[0, 1, 11, 29, 30, 31, 80, 10_000, usize::MAX];
+
+const COLORS: &[ColorMode] = &[
+    ColorMode::Never,
+    ColorMode::Always,
+    ColorMode::AutoStderr,
+    ColorMode::AutoStdout,
+];
+
+#[derive(Default)]
+struct Audit {
+    covered: HashSet<&'static str>,
+    failures: Vec<String>,
+}
+
+impl Audit {
+    /// Runs `f` and records failure if it panics.
+    fn check(
+        &mut self,
+        api: &'static str,
+        input: impl Debug,
+        f: impl FnOnce(),
+    ) {
+        self.covered.insert(api);
+        if panic::catch_unwind(AssertUnwindSafe(f)).is_err() {
+            let mut input = format!("{input:?}");
+            if input.len() > 200 {
+                let mut end = 200;
+                while !input.is_char_boundary(end) {
+                    end -= 1;
+                }
+                input.truncate(end);
+                input += "...";
+            }
+            self.failures.push(format!("`{api}` panicked with {input}"));
+        }
+    }
+
+    /// Runs `f` on [`Pareg`] with the arguments with all possible positions
+    /// of the cursor.
+    fn pareg(
+        &mut self,
+        api: &'static str,
+        args: &[String],
+        f: impl Fn(&mut Pareg),
+    ) {
+        for cur in 0..=args.len() + 1 {
+            self.check(api, (args, cur), || {
+                let mut p = Pareg::new(args.to_vec());
+                p.jump(cur);
+                f(&mut p);
+            });
+        }
+    }
+}
+
+/// Renders the error in all the ways.
+fn render(e: ArgError) {
+    _ = format!("{e:?}");
+    let mut e = e.no_color();
+    for w in [0, 80, usize::MAX] {
+        e = e.max_width(w);
+        _ = e.to_string();
+    }
+    _ = e.color_mode(ColorMode::Always).to_string();
+}
+
+fn render_warning(w: ArgWarning) {
+    _ = (format!("{w:?}"), w.message(), w.ctx().args.len());
+    _ = w.clone().no_color().to_string();
+    _ = format!("{:#}", w.color_mode(ColorMode::Always));
+}
+
+fn show<T>(r: Result<T>) {
+    if let Err(e) = r {
+        render(e);
+    }
+}
+
+/// Creates errors of all the kinds for the arguments.
+/// Group of arguments for [`Pareg::parse`].
+struct Group;
+
+impl ParseArgs for Group {
+    fn parse_args(args: &mut Pareg) -> Result<Self> {
+        args.next_arg::<u8>()?;
+        _ = args.cur_val_or_next::<String>('=');
+        Ok(Self)
+    }
+}
+
+fn errors(args: &[String]) -> Vec<ArgError> {
+    let arg = args.first().cloned().unwrap_or_default();
+    let ctx = || ArgErrCtx::from_msg("msg", arg.clone());
+    let mut res = vec![
+        ArgError::UnknownArgument(ctx().into()),
+        ArgError::NoMoreArguments(ctx().into()),
+        ArgError::FailedToParse(ctx().into()),
+        ArgError::NoValue(ctx().into()),
+        ArgError::InvalidValue(ctx().into()),
+        ArgError::TooManyArguments(ctx().into()),
+        ArgError::no_last_argument(),
+        ArgError::Io(std::io::Error::other("io")),
+        ArgError::parse_msg("", arg.clone()),
+        ArgError::custom(arg.clone(), arg.clone(), arg.clone()),
+        ArgError::Special(Special::Help),
+    ];
+    res.push(ArgError::FailedToParse(
+        ArgErrCtx {
+            args: vec![],
+            error_idx: usize::MAX,
+            args_start_index: 0,
+            show_program: true,
+            error_span: usize::MAX..0,
+            positioned: true,
+            message: "".into(),
+            long_message: Some("\n\n".into()),
+            hint: Some(" ".repeat(200).into()),
+            suggestions: vec!["\n".into()],
+            color: ColorMode::Never,
+            anounce: true,
+            max_width: Some(0),
+            origins: vec![],
+            owner: Some("\n".into()),
+            value_of: Some(usize::MAX),
+            program: Some("".into()),
+            usage: Some(" \n".repeat(50).into()),
+            messages: Some(&Messages::ENGLISH),
+        }
+        .into(),
+    ));
+    res.push(ArgError::FailedToParse(
+        ArgErrCtx::from_inner(
+            "long ".repeat(100) + &"x".repeat(300),
+            arg.clone(),
+        )
+        .add_args(
+            args.iter().map(|a| a.clone().into()).collect(),
+            args.len().saturating_sub(1),
+        )
+        .into(),
+    ));
+    res
+}
+
+/// Runs all the builders on all the errors.
+fn audit_errors(a: &mut Audit, args: &[String]) {
+    macro_rules! each_err {
+        ($api:literal, $input:expr, |$e:ident| $body:expr) => {
+            a.check($api, (args, &$input), || {
+                for $e in errors(args) {
+                    render($body);
+                }
+            });
+        };
+    }
+
+    for s in SPANS {
+        each_err!("spanned", s, |e| e.spanned(s.clone()));
+        each_err!("suggest", s, |e| e.spanned(s.clone()).suggest(["x", ""]));
+        each_err!("replacement_args", s, |e| {
+            let e = e.spanned(s.clone()).suggest(["ž"]);
+            _ = e.replacement_args();
+            e
+        });
+        each_err!("redact", s, |e| {
+            let mut e = e;
+            for i in IDXS {
+                e = e.redact(*i, s.clone());
+            }
+            e
+        });
+    }
+    for i in IDXS {
+        each_err!("shift_span", i, |e| e.shift_span(*i, "new".into()));
+        each_err!("span_start", i, |e| e.span_start(*i));
+        each_err!("add_args", i, |e| e
+            .add_args(args.iter().map(|a| a.clone().into()).collect(), *i));
+        each_err!("add_args", i, |e| e.add_args(vec![], *i));
+        each_err!("args_start_index", i, |e| e
+            .add_args(args.iter().map(|a| a.clone().into()).collect(), 1)
+            .args_start_index(*i)
+            .show_program(false));
+    }
+    for w in WIDTHS {
+        each_err!("max_width", w, |e| e.max_width(*w));
+    }
+    for i in IDXS {
+        each_err!("value_of", i, |e| e.value_of(*i));
+    }
+    for c in COLORS {
+        each_err!("color_mode", c, |e| e.color_mode(*c));
+    }
+    for w in WIDTHS {
+        each_err!("render", w, |e| {
+            let e = e.max_width(*w);
+            _ = (e.render(true), e.render(false));
+            e
+        });
+        each_err!("write_to", w, |e| {
+            let e = e.max_width(*w);
+            _ = e.write_to(&mut vec![], true);
+            _ = e.write_to(&mut &mut [0; 8][..], false);
+            e
+        });
+    }
+    for arg in args.iter().chain([&String::new()]) {
+        each_err!("part_of", arg, |e| e.part_of(arg.clone()));
+        each_err!("postfix_of", arg, |e| e.postfix_of(arg.clone()));
+        each_err!("hint", arg, |e| e.hint(arg.clone()));
+        each_err!("inline_msg", arg, |e| e.inline_msg(arg.clone()));
+        each_err!("main_msg", arg, |e| e.main_msg(arg.clone()));
+        each_err!("origins", arg, |e| {
+            e.origins(vec![None, Some(arg.clone().into()), Some("".into())])
+        });
+        each_err!("owner", arg, |e| e.owner(arg.clone()));
+        each_err!("program", arg, |e| e.program(arg.clone()));
+        each_err!("usage", arg, |e| e.usage(arg.clone()));
+        each_err!("parse_msg", arg, |e| {
+            _ = e;
+            ArgError::parse_msg(arg.clone(), arg.clone())
+        });
+        each_err!("custom", arg, |e| {
+            _ = e;
+            ArgError::custom(arg.clone(), arg.clone(), arg.clone())
+        });
+    }
+    each_err!("no_color", (), |e| e.no_color());
+    each_err!("anounce", (), |e| e.anounce(false));
+    each_err!("show_program", (), |e| e.show_program(false));
+    each_err!("messages", (), |e| e.messages(&ODD_MESSAGES));
+    each_err!("map_ctx", (), |e| e.map_ctx(|c| c.spanned(5..2)));
+    each_err!("err", (), |e| e.err::<()>().unwrap_err());
+    type Accessor = (&'static str, fn(&ArgError));
+    let accessors: [Accessor; 23] = [
+        ("kind", |e| _ = e.kind()),
+        ("is_unknown_argument", |e| _ = e.is_unknown_argument()),
+        ("is_no_more_arguments", |e| _ = e.is_no_more_arguments()),
+        ("is_failed_to_parse", |e| _ = e.is_failed_to_parse()),
+        ("is_no_value", |e| _ = e.is_no_value()),
+        ("is_invalid_value", |e| _ = e.is_invalid_value()),
+        ("is_too_many_arguments", |e| _ = e.is_too_many_arguments()),
+        ("is_io", |e| _ = e.is_io()),
+        ("is_no_last_argument", |e| _ = e.is_no_last_argument()),
+        ("is_custom", |e| _ = e.is_custom()),
+        ("is_special", |e| _ = e.is_special()),
+        ("custom_tag", |e| _ = e.custom_tag()),
+        ("args", |e| _ = e.args()),
+        ("error_idx", |e| _ = e.error_idx()),
+        ("arg", |e| _ = e.arg()),
+        ("span", |e| _ = e.span()),
+        ("location", |e| _ = e.location()),
+        ("no_last_argument", |e| {
+            _ = (e.is_no_last_argument(), ArgError::no_last_argument());
+        }),
+        ("message", |e| _ = e.message()),
+        ("long_message", |e| _ = e.long_message()),
+        ("hint_str", |e| _ = e.hint_str()),
+        ("suggestions", |e| _ = e.suggestions()),
+        ("ctx", |e| _ = e.ctx()),
+    ];
+    for (name, f) in accessors {
+        a.check(name, args, || errors(args).iter().for_each(f));
+    }
+    a.check("into_ctx", args, || {
+        errors(args).into_iter().for_each(|e| _ = e.into_ctx())
+    });
+    a.check("push", args, || {
+        let mut errs = ArgErrors::default();
+        _ = errs.to_string();
+        for e in errors(args) {
+            errs.push(e);
+        }
+        _ = (errs.len(), errs.is_empty(), errs.iter().count());
+        _ = errs.no_color().to_string();
+        _ = ArgErrors::from(errors(args).remove(0)).to_string();
+    });
+    a.check("len", args, || _ = ArgErrors::from(errors(args)).len());
+    a.check("kind_message", args, || {
+        for e in errors(args) {
+            _ = ODD_MESSAGES.kind_message(e.kind());
+        }
+    });
+    for name in ["message", "ctx", "color_mode", "no_color"] {
+        a.check(name, args, || {
+            for e in errors(args) {
+                if let Some(ctx) = e.into_ctx() {
+                    render_warning(ctx.into());
+                }
+            }
+        });
+    }
+
+    a.check("from_inner", args, || {
+        let arg = args.first().cloned().unwrap_or_default();
+        _ = ArgErrCtx::from_inner(&arg, arg.clone()).to_string();
+    });
+    a.check("from_msg", args, || {
+        let arg = args.concat();
+        _ = ArgErrCtx::from_msg(arg.clone(), arg).to_string();
+    });
+    a.check("render_width", args, || {
+        for e in errors(args) {
+            if let ArgError::FailedToParse(ctx) = e {
+                for w in WIDTHS {
+                    _ = ctx.render_width();
+                    let ctx = ArgErrCtx {
+                        max_width: Some(*w),
+                        origins: vec![],
+                        ..ArgErrCtx::from_msg("", String::new())
+                    };
+                    _ = ctx.render_width();
+                }
+            }
+        }
+    });
+    a.check("offset_in", args, || {
+        for cmdline in [args.concat(), shell_quote_join(args)] {
+            let Ok(joined) = JoinedArgs::new(cmdline) else {
+                continue;
+            };
+            let mut p = Pareg::from_joined(&joined);
+            for e in errors(args) {
+                if let Some(loc) = e.location() {
+                    _ = loc.offset_in(&joined);
+                }
+            }
+            while p.next().is_some() {
+                if let Some(loc) =
+                    p.cur_arg::<u8>().err().and_then(|e| e.location())
+                {
+                    _ = loc.offset_in(&joined);
+                }
+            }
+            for (i, span) in [(0, 5..2), (1, 0..usize::MAX), (99, 1..1)] {
+                _ = joined.offset_of(i, span);
+            }
+        }
+    });
+    a.check("use_color", (), || {
+        for c in COLORS {
+            _ = c.use_color();
+        }
+    });
+}
+
+/// Runs all the methods of [`Pareg`] on the arguments.
+fn audit_pareg(a: &mut Audit, args: &[String]) {
+    macro_rules! each {
+        ($($api:literal => |$p:ident| $body:expr),* $(,)?) => {
+            $(a.pareg($api, args, |$p| { _ = $body; });)*
+        };
+    }
+
+    each!(
+        "new" => |p| p.cur(),
+        "source" => |p| p.source().len(),
+        "all_args" => |p| p.all_args().len(),
+        "remaining" => |p| p.remaining().len(),
+        "cur_remaining" => |p| p.cur_remaining().len(),
+        "next" => |p| (p.next().map(str::len), p.next().map(str::len)),
+        "skip_all" => |p| p.skip_all().map(str::len),
+        "next_arg_spanned" => |p| {
+            show(p.next_arg_spanned::<u8>());
+            show(p.next_arg_spanned::<&str>());
+        },
+        "next_file_or_stdin" => |p| {
+            show(p.next_file_or_stdin().map(|i| i.is_stdin()));
+        },
+        "set_deferred" => |p| {
+            show(p.set_deferred(&mut Deferred::<u8>::new(|_| Ok(0))));
+        },
+        "set_deferred_val" => |p| {
+            show(p.set_deferred_val(&mut Deferred::<&str>::new(|_| Ok("")), 'ž'));
+        },
+        "cur_val_spanned" => |p| {
+            show(p.cur_val_spanned::<u8>('='));
+            show(p.cur_val_spanned::<&str>('ž'));
+        },
+        "next_back" => |p| {
+            _ = p.next_back().map(str::len);
+            _ = (p.skip_all().map(str::len), p.remaining().len());
+            (p.next_back().map(str::len), p.peek().map(str::len))
+        },
+        "rlast_arg" => |p| {
+            show(p.rlast_arg::<u8>());
+            show(p.next_arg::<u8>());
+            show(p.rlast_arg::<&str>());
+            render(p.err_unknown_argument());
+        },
+        "reset" => |p| p.reset(),
+        "cur" => |p| p.cur().map(str::len),
+        "peek" => |p| p.peek().map(str::len),
+        "next_idx" => |p| p.next_idx(),
+        "cur_idx" => |p| p.cur_idx(),
+        "len" => |p| p.len(),
+        "is_empty" => |p| p.is_empty(),
+        "remaining_len" => |p| p.remaining_len(),
+        "progress" => |p| p.progress(),
+        "next_n" => |p| {
+            show(p.next_n::<&str>(0));
+            show(p.next_n::<u8>(2));
+            show(p.next_n::<Option<i8>>(usize::MAX));
+        },
+        "next_n_args" => |p| {
+            show(p.next_n_args::<&str, 1>());
+            show(p.next_n_args::<u32, 3>());
+        },
+        "next_arg" => |p| {
+            show(p.next_arg::<&str>());
+            show(p.next_arg::<u32>());
+            show(p.next_arg::<f64>());
+            show(p.next_arg::<Option<i8>>());
+        },
+        "cur_namespace" => |p| {
+            for ns in ["", "a", "ž", "--"] {
+                show(p.cur_namespace(ns, '='));
+                show(p.cur_namespace(ns, 'ž'));
+            }
+        },
+        "namespace_map" => |p| {
+            show(p.namespace_map("", '='));
+            show(p.namespace_map("a", '.'));
+        },
+        "next_with" => |p| {
+            show(p.next_with(&Parser::<u8>::new()));
+            show(p.next_with(&Parser::<Option<i8>>::new()));
+        },
+        "cur_arg" => |p| {
+            show(p.cur_arg::<u32>());
+            show(p.cur_arg::<bool>());
+        },
+        "next_key_mval" => |p| show(p.next_key_mval::<u8, f32>('=')),
+        "next_key_val" => |p| show(p.next_key_val::<&str, u8>('=')),
+        "next_csv" => |p| show(p.next_csv::<String>(',')),
+        "next_push" => |p| show(p.next_push::<u8>(&mut vec![])),
+        "next_key_val_insert" => |p| {
+            let mut m = std::collections::HashMap::<String, u8>::new();
+            for d in [DupPolicy::Error, DupPolicy::Overwrite, DupPolicy::Keep] {
+                show(p.next_key_val_insert('=', &mut m, d));
+                p.reset();
+            }
+        },
+        "next_bool" => |p| show(p.next_bool("", "")),
+        "next_opt_bool" => |p| show(p.next_opt_bool("yes", "no", "")),
+        "next_key" => |p| show(p.next_key::<u8>('=')),
+        "next_val" => |p| show(p.next_val::<u8>('=')),
+        "next_mval" => |p| show(p.next_mval::<u8>('=')),
+        "cur_key_mval" => |p| show(p.cur_key_mval::<u8, f32>('=')),
+        "cur_key_val" => |p| show(p.cur_key_val::<&str, u8>(':')),
+        "cur_csv" => |p| show(p.cur_csv::<u8>('"')),
+        "next_key_mval_esc" => |p| {
+            show(p.next_key_mval_esc::<u8, f32>('='));
+        },
+        "next_key_val_esc" => |p| {
+            show(p.next_key_val_esc::<String, u8>('\\'));
+        },
+        "next_val_esc" => |p| show(p.next_val_esc::<u8>('=')),
+        "next_mval_esc" => |p| show(p.next_mval_esc::<u8>('=')),
+        "cur_key_mval_esc" => |p| {
+            show(p.cur_key_mval_esc::<char, &str>('ž'));
+        },
+        "cur_key_val_esc" => |p| {
+            show(p.cur_key_val_esc::<u8, u8>('='));
+        },
+        "cur_val_esc" => |p| show(p.cur_val_esc::<u8>('=')),
+        "cur_mval_esc" => |p| show(p.cur_mval_esc::<u8>(':')),
+        "next_try_into" => |p| {
+            show(p.next_try_into::<u16, u8>())
+        },
+        "cur_val_try_into" => |p| {
+            show(p.cur_val_try_into::<i64, u8>('='))
+        },
+        "cur_key_val_spans" => |p| {
+            p.cur_key_val_spans('=').map(|(k, v)| p.cur().map(|a| {
+                (a.get(k).is_some(), a.get(v).is_some())
+            }))
+        },
+        "scan_key_val_spans" => |p| p.scan_key_val_spans('ž').count(),
+        "cur_bool" => |p| show(p.cur_bool("true", "false")),
+        "cur_opt_bool" => |p| show(p.cur_opt_bool("", "", "")),
+        "cur_key" => |p| show(p.cur_key::<u8>('=')),
+        "cur_val" => |p| show(p.cur_val::<u8>('=')),
+        "require_exhausted" => |p| show(p.require_exhausted()),
+        "err_trailing_argument" => |p| render(p.err_trailing_argument()),
+        "cur_val_push" => |p| show(p.cur_val_push::<u8>('=', &mut vec![])),
+        "cur_key_val_insert" => |p| {
+            let mut m = std::collections::BTreeMap::<&str, u8>::new();
+            for d in [DupPolicy::Error, DupPolicy::Overwrite, DupPolicy::Keep] {
+                show(p.cur_key_val_insert(':', &mut m, d));
+            }
+        },
+        "cur_mval" => |p| show(p.cur_mval::<u8>('\n')),
+        "cur_val_or_next" => |p| show(p.cur_val_or_next::<u8>('=')),
+        "cur_val_required_inline" => |p| {
+            show(p.cur_val_required_inline::<&str>('ž'));
+        },
+        "next_val_required_detached" => |p| {
+            show(p.next_val_required_detached::<u8>('='));
+        },
+        "cur_short_val" => |p| show(p.cur_short_val::<u8>("-")),
+        "cur_short_val_or_next" => |p| {
+            show(p.cur_short_val_or_next::<u8>("-"));
+        },
+        "cur_short_key_val" => |p| {
+            show(p.cur_short_key_val::<&str, u8>("-", '='));
+        },
+        "next_value_of" => |p| show(p.next_value_of::<u8>("--opt")),
+        "next_manual" => |p| show(p.next_manual(|s| s.parse::<u8>()
+            .map_err(|e| ArgError::parse_msg(e.to_string(), s.into())))),
+        "cur_manual" => |p| show(p.cur_manual(u8::from_arg)),
+        "cur_try_arg" => |p| (p.cur_try_arg::<u8>(), p.cur_try_arg::<bool>()),
+        "cur_is" => |p| (p.cur_is::<u8>(), p.cur_is::<Option<i8>>()),
+        "opt_value" => |p| show(p.opt_value(&["--opt", "", "="]).map(|_| ())),
+        "opt_value_arg" => |p| show(p.opt_value_arg::<u8>(&["--opt", "-"])),
+        "set_unknown_sink" => |p| {
+            p.set_unknown_sink(UnknownSink::Collect(UnknownValue::Always));
+            show(p.handle_or_defer(false));
+            p.take_unknown()
+        },
+        "take_unknown" => |p| p.take_unknown(),
+        "on_special" => |p| {
+            p.on_special(SpecialArgs {
+                help: Some(Box::new(|| {})),
+                version: None,
+            });
+            let res = (p.next().map(str::len), show(p.next_arg::<u8>()));
+            (res, p.special(), p.intercepted(), p.next().is_some())
+        },
+        "special" => |p| (p.next().is_some(), p.special()),
+        "intercepted" => |p| p.intercepted(),
+        // Only with remaining arguments so that it doesn't wait for stdin.
+        "next_arg_or_prompt" => |p| {
+            if p.peek().is_some() {
+                show(p.next_arg_or_prompt::<u8>(""));
+            }
+        },
+        "next_arg_or_prompt_opts" => |p| {
+            if p.peek().is_some() {
+                show(p.next_arg_or_prompt_opts::<u8>("", &PromptOpts::default()));
+            }
+        },
+        "next_arg_or_prompt_with" => |p| {
+            p.skip_all();
+            let opts = PromptOpts {
+                default: Some("x".into()),
+                color: ColorMode::Always,
+                ..Default::default()
+            };
+            let mut input = "\n\r\n300\n".as_bytes();
+            show(p.next_arg_or_prompt_with::<u8>("", &opts, &mut input, &mut vec![]));
+        },
+        "append_args" => |p| {
+            p.append_args(["=".to_string()]);
+            p.skip_all();
+            show(p.cur_val::<u8>('='));
+        },
+        "append_args_from" => |p| {
+            p.append_args_from("cfg", ["x".to_string()]);
+            p.append_args([]);
+            render(p.err_no_more_arguments());
+        },
+        "handle_or_defer" => |p| {
+            show(p.handle_or_defer(false));
+            show(p.handle_or_defer(true));
+        },
+        "err_unknown_argument" => |p| render(p.err_unknown_argument()),
+        "err_unknown_argument_with_suggestions" => |p| {
+            let names = ["--ž-=", "", "-", "=", "--a_b"];
+            render(p.err_unknown_argument_with_suggestions(&names));
+        },
+        "try_set_next_policy" => |p| {
+            for policy in [SetPolicy::Error, SetPolicy::FirstWins] {
+                let mut res = Some(0);
+                show(p.try_set_next_policy::<u8>(&mut res, policy));
+                show(p.try_set_next_policy::<u8>(&mut None, policy));
+            }
+            p.warnings().len()
+        },
+        "set_suggester" => |p| {
+            let names = ["--ž-=", "", "-", "=", "--a_b"];
+            p.set_suggester(Box::new(TokenPrefixSuggester));
+            render(p.err_unknown_argument_with_suggestions(&names));
+        },
+        "err_no_more_arguments" => |p| render(p.err_no_more_arguments()),
+        "err_invalid" => |p| render(p.err_invalid()),
+        "err_invalid_value" => |p| {
+            render(p.err_invalid_value(String::new()));
+            render(p.err_invalid_value("ž".repeat(100)));
+        },
+        "collect_errors" => |p| {
+            _ = p.collect_errors(|p| {
+                p.next_arg::<u8>()?;
+                Err(p.err_unknown_argument())
+            }).map_err(|e| e.to_string());
+        },
+        "map_err" => |p| {
+            for e in errors(args) {
+                show(p.map_err::<()>(Err(e)));
+            }
+        },
+        "set_error_usage" => |p| {
+            p.set_error_usage("\n".repeat(100));
+            show(p.next_arg::<u8>());
+            render(p.err_unknown_argument());
+        },
+        "warn" => |p| {
+            p.warn("\n".repeat(100));
+            p.warn("");
+            p.warnings().iter().for_each(|w| render_warning(w.clone()));
+        },
+        "warn_deprecated" => |p| {
+            p.warn_deprecated("");
+            p.warn_deprecated("--new");
+            p.warnings().iter().for_each(|w| render_warning(w.clone()));
+        },
+        "warnings" => |p| p.warnings().len(),
+        "take_rest" => |p| {
+            _ = p.take_rest();
+            show(p.next_arg::<u8>());
+        },
+        "checkpoint" => |p| {
+            let c = p.checkpoint();
+            p.next_back();
+            p.skip(3);
+            p.warn("");
+            p.restore(c);
+            p.next()
+        },
+        "restore" => |p| {
+            let c = p.checkpoint();
+            p.reset();
+            p.restore(c);
+            show(p.next_arg::<u8>());
+        },
+        "summary" => |p| {
+            show(p.next_arg::<&str>());
+            show(p.cur_val_or_next::<&str>('='));
+            show(p.opt_value(&["-a", "--b"]));
+            show(p.next_n::<&str>(2));
+            p.next_back();
+            show(p.cur_short_val::<&str>("-"));
+            p.insert_args(1, ["-x".to_string()]);
+            p.summary().args.len()
+        },
+        "parse" => |p| {
+            show(p.parse::<(Group, Option<Group>)>());
+            show(p.parse::<Option<(Group, Group)>>());
+        },
+        "parse_all" => |p| {
+            let c = p.checkpoint();
+            let mut q = Pareg::new(p.all_args().to_vec());
+            q.restore(c);
+            show(q.parse_all::<(Option<Group>, Option<Group>)>());
+        },
+        "take_next" => |p| {
+            _ = (p.take_next(), p.take_next(), p.take_cur());
+            show(p.next_arg::<u8>());
+            p.jump(1);
+            show(p.cur_val::<u8>('='));
+        },
+        "take_cur" => |p| {
+            _ = p.take_cur();
+            p.next();
+            _ = (p.take_cur(), p.take_cur());
+            render(p.err_invalid_span(0..usize::MAX));
+        },
+        "take_remaining" => |p| {
+            p.next_back();
+            _ = (p.take_remaining(), p.take_remaining());
+            p.reset();
+            show(p.next_arg::<u8>());
+        },
+        "print_warnings" => |p| {
+            p.warn("x");
+            p.print_warnings(ColorMode::Never);
+        },
+        "strict_usage" => |p| {
+            p.strict_usage(true);
+            if p.next().is_some() {
+                show(p.cur_arg::<u8>());
+                show(p.cur_val::<&str>('='));
+                show(p.opt_value(&["-a"]));
+            }
+        },
+        "set_messages" => |p| {
+            p.set_messages(&ODD_MESSAGES);
+            show(p.next_arg::<u8>());
+            render(p.err_unknown_argument());
+            render(p.err_no_more_arguments());
+            p.warn("");
+            p.warnings().iter().for_each(|w| render_warning(w.clone()));
+        },
+        "normalize" => |p| {
+            p.normalize(Normalize { trim: true, empty_is_missing: true });
+            show(p.next_arg::<u8>());
+            show(p.cur_val_or_next::<Option<u8>>('='));
+            show(p.opt_value_arg::<u8>(&["--opt"]));
+        },
+        "redact_matching" => |p| {
+            p.redact_matching(|i, a| Some(i..a.len() * 2));
+            p.redact_matching(|i, _| Some(usize::MAX - i..i));
+            p.redact_matching(|_, _| Some(1..2));
+            show(p.next_arg::<u8>());
+            render(p.err_unknown_argument());
+            render(p.err_no_more_arguments());
+        },
+        "redact_values_of" => |p| {
+            p.redact_values_of(&["--opt", "", "="], '=');
+            show(p.cur_val::<u8>('='));
+            render(p.err_invalid());
+        },
+    );
+
+    for i in IDXS {
+        each!(
+            "skip" => |p| p.skip(*i).map(str::len),
+            "jump" => |p| (p.jump(*i).map(str::len), p.cur_idx(), p.next()),
+            "skip" => |p| {
+                p.next_back();
+                (p.skip(*i).map(str::len), p.next_idx(), p.take_rest())
+            },
+            "get" => |p| p.get(*i).map(str::len),
+            "insert_args" => |p| {
+                p.insert_args(*i, ["-".to_string()]);
+                show(p.cur_arg::<u8>());
+            },
+            "insert_args_from" => |p| {
+                p.insert_args_from(*i, "", ["x".to_string(), "".to_string()]);
+                show(p.next_arg::<u8>());
+                render(p.err_unknown_argument());
+            },
+        );
+    }
+    for s in SPANS {
+        each!("err_invalid_span" => |p| render(p.err_invalid_span(s.clone())));
+        for i in IDXS {
+            each!("err_at" => |p| render(p.err_at(*i, s.clone(), "")));
+            each!("resolve_all" => |p| {
+                let mut values = ResolvedArgs::new();
+                let span = s.clone();
+                values.add("a", Deferred::Set(Spanned { value: 0, idx: *i, span }));
+                values.add("b", Deferred::new(|r| {
+                    r.get::<i32>("a");
+                    ArgError::parse_msg("", "\n".into()).spanned(0..9).err::<()>()
+                }));
+                show(p.resolve_all(&mut values));
+            });
+        }
+    }
+
+    a.check("chained", args, || {
+        let (l, r) = args.split_at(args.len() / 2);
+        let mut p = Pareg::chained([l, &[], r]);
+        for i in IDXS {
+            p.jump(*i);
+            show(p.cur_arg::<u8>());
+            _ = p.all_args().count() + p.remaining().count();
+            _ = p.cur_remaining().count();
+            render(p.err_no_more_arguments());
+            render(p.err_unknown_argument());
+        }
+    });
+    a.check("from_cow", args, || {
+        let cow: Vec<Cow<'static, str>> =
+            args.iter().map(|a| a.clone().into()).collect();
+        let mut p = Pareg::from_cow(&cow);
+        for i in IDXS {
+            p.jump(*i);
+            show(p.cur_arg::<u8>());
+            render(p.err_unknown_argument());
+        }
+    });
+    a.check("lazy", args, || {
+        let mut p = Pareg::lazy(args.iter().cloned());
+        for i in IDXS {
+            p.jump(*i);
+            show(p.cur_arg::<u8>());
+            render(p.err_unknown_argument());
+            _ = p.skip(*i).map(str::len);
+            _ = (p.next_idx(), p.peek());
+        }
+        render(p.err_no_more_arguments());
+    });
+    a.check("new", args, || {
+        let l = LazyArgs::new(args.iter().cloned());
+        _ = (l.get(usize::MAX), l.loaded(), l.to_args(), l.len());
+    });
+    a.check("parts", args, || {
+        let c = ChainedArgs::new([args, args]);
+        _ = (c.parts().len(), c.iter().count(), c.get(usize::MAX));
+    });
+    a.check("iter", args, || _ = ChainedArgs::new([args]).iter().count());
+    a.check("into_args", args, || {
+        let mut p = Pareg::new(args.to_vec());
+        _ = (p.take_next(), p.into_args());
+    });
+    a.check("cur_mval_inner", args, || {
+        for i in IDXS {
+            show(cur_mval_inner::<_, u8>(
+                args,
+                &[],
+                &[],
+                &ErrConf {
+                    usage: Some("usage".into()),
+                    messages: Some(&ODD_MESSAGES),
+                    args_start: *i,
+                    taken: vec![true, false, true],
+                    strict_usage: false,
+                    expanded_from: vec![],
+                },
+                &Normalize::default(),
+                *i,
+                '=',
+            ));
+        }
+    });
+    a.check("err_no_more_arguments_inner", args, || {
+        let conf = ErrConf::default();
+        for end in [None, Some(0), Some(1), Some(usize::MAX)] {
+            render(err_no_more_arguments_inner(args, &[], &[], &conf, end));
+        }
+    });
+    a.check("from_env_vars", args, || {
+        let vars = args.iter().map(|a| (a.clone(), a.clone()));
+        let map = EnvMapping::default();
+        for prefix in ["", "-", "ž"] {
+            let mut p = Pareg::from_env_vars(prefix, &map, vars.clone());
+            while p.next().is_some() {
+                show(p.cur_val::<u8>('='));
+            }
+        }
+    });
+    a.check("merge_env_vars", args, || {
+        let vars = args.iter().map(|a| (a.clone(), a.clone()));
+        let map = EnvMapping {
+            option_prefix: "",
+            lowercase: false,
+            underscore: Some('\0'),
+        };
+        let mut p = Pareg::new(args.to_vec());
+        p.next();
+        p.merge_env_vars("", &map, vars);
+        show(p.next_arg::<u8>());
+    });
+}
+
+/// Runs the free functions and readers on the arguments.
+fn audit_parsers(a: &mut Audit, args: &[String]) {
+    for arg in args.iter().take(20).chain([&String::new()]) {
+        let s = arg.as_str();
+        a.check("key_mval_arg", s, || {
+            show(key_mval_arg::<u8, f32>(s, '='));
+            show(key_mval_arg::<&str, &str>(s, 'ž'));
+        });
+        a.check("edit_distance", s, || {
+            _ = edit_distance(s, "--ž");
+            _ = edit_distance("", s);
+        });
+        a.check("find", s, || {
+            let f = FuzzyMatch::find(s, &[&[], &["a", ""], &[s]], 2);
+            _ = f.hint();
+            _ = FuzzyMatch::find(s, &[], usize::MAX).hint();
+        });
+        a.check("strip_punct", s, || _ = strip_punct(s));
+        a.check("set_arg_with_policy", s, || {
+            let mut res = Some(0);
+            show(set_arg_with_policy::<u8>(
+                &mut res,
+                s,
+                SetPolicy::Error,
+                || (),
+            ));
+            show(set_arg_with_policy::<&str>(
+                &mut None,
+                s,
+                SetPolicy::LastWins,
+                || (),
+            ));
+        });
+        a.check("suggest", s, || {
+            _ = EditDistanceSuggester.suggest(s, &[s, "", "-ž_"]);
+            _ = TokenPrefixSuggester.suggest(s, &[s, "", "-ž_"]);
+        });
+        a.check("find_flexible_punct", s, || {
+            _ = find_flexible_punct(s, &[&[], &["-", "İ"], &[s]]);
+        });
+        a.check("prefix_matches", s, || {
+            _ = prefix_matches(s, &[&[], &["_", "İ"], &[s]]);
+        });
+        a.check("key_val_spans", s, || {
+            _ = key_val_spans(s, '=');
+            _ = key_val_spans(s, 'ž');
+        });
+        a.check("key_val_arg", s, || show(key_val_arg::<&str, u8>(s, '=')));
+        a.check("bool_arg", s, || show(bool_arg("", "", s)));
+        a.check("opt_bool_arg", s, || show(opt_bool_arg("a", "b", "", s)));
+        a.check("parse_arg", s, || show(parse_arg::<i8>(s)));
+        a.check("key_arg", s, || show(key_arg::<u8>(s, '=')));
+        a.check("val_arg", s, || show(val_arg::<u8>(s, '=')));
+        a.check("mval_arg", s, || show(mval_arg::<u8>(s, '=')));
+        a.check("key_mval_arg_esc", s, || {
+            show(key_mval_arg_esc::<u8, f32>(s, '='));
+            show(key_mval_arg_esc::<String, &str>(s, '\\'));
+        });
+        a.check("key_val_arg_esc", s, || {
+            show(key_val_arg_esc::<char, u8>(s, 'ž'));
+        });
+        a.check("val_arg_esc", s, || show(val_arg_esc::<u8>(s, '=')));
+        a.check("mval_arg_esc", s, || show(mval_arg_esc::<u8>(s, ':')));
+        a.check("csv_row", s, || {
+            show(csv_row(s, ','));
+            show(csv_row(s, '"'));
+        });
+        a.check("csv_row_as", s, || show(csv_row_as::<i8>(s, 'ž')));
+        a.check("fields_arg", s, || {
+            show(fields_arg(s, ',', '='));
+            show(fields_arg(s, 'ž', 'ž'));
+        });
+        if let Ok(f) = fields_arg(s, ',', '=') {
+            a.check("take", s, || show(f.clone().take::<u8>("")));
+            a.check("require", s, || show(f.clone().require::<&str>("a")));
+            a.check("finish", s, || {
+                let mut f = f.clone();
+                show(f.take::<&str>("a"));
+                show(f.finish());
+            });
+        }
+
+        for norm in [
+            Normalize::default(),
+            Normalize {
+                trim: true,
+                empty_is_missing: true,
+            },
+        ] {
+            a.check("apply", s, || _ = norm.apply(s));
+            a.check("try_parse", s, || _ = norm.try_parse::<u8>(s));
+            for sp in SPANS {
+                a.check("parse", (s, sp), || {
+                    show(norm.parse::<u8>(s, sp.clone(), Some(s)));
+                    show(norm.parse::<Option<&str>>(s, sp.clone(), None));
+                });
+            }
+        }
+
+        a.check("arg_class", s, || _ = arg_class(s));
+        a.check("is_option", s, || _ = arg_class(s).is_option());
+        a.check("is_value", s, || _ = arg_class(s).is_value());
+
+        a.check("takes", s, || {
+            for v in [
+                UnknownValue::Never,
+                UnknownValue::IfNotOption,
+                UnknownValue::Always,
+            ] {
+                _ = v.takes(s, s);
+            }
+        });
+
+        let fmts = [
+            "",
+            "strict",
+            s,
+            "2x",
+            "..0",
+            "99999999999999999999",
+            "+i",
+            "-",
+        ];
+        for fmt in fmts {
+            let f = ReadFmt::new(fmt);
+            a.check("sign", fmt, || _ = f.sign());
+            a.check("case_insensitive", fmt, || _ = f.case_insensitive());
+            a.check("custom", fmt, || _ = f.custom());
+            a.check("is_empty", fmt, || _ = f.is_empty());
+            a.check("length_range", fmt, || _ = f.length_range());
+            a.check("spec", fmt, || _ = f.spec());
+            a.check("parsef", (s, fmt), || {
+                let (mut b, mut n, mut x) = (false, 0_u8, 0.0_f64);
+                show(parsef(
+                    &mut s.into(),
+                    [
+                        ParseFArg::FmtArg(&mut b, f),
+                        ParseFArg::Str("=".into()),
+                        ParseFArg::FmtArg(&mut n, f),
+                        ParseFArg::FmtArg(&mut x, f),
+                    ],
+                ));
+            });
+            a.check("parsef_part", (s, fmt), || {
+                let mut n = 0_i64;
+                show(parsef_part(
+                    &mut s.into(),
+                    [ParseFArg::Arg(&mut n), ParseFArg::Str(fmt.into())],
+                ));
+            });
+            a.check("parsef_all_errors", (s, fmt), || {
+                let (mut n, mut x) = (0_u8, 0.0_f64);
+                let res = parsef_all_errors(
+                    &mut s.into(),
+                    [
+                        ParseFArg::FmtArg(&mut n, f),
+                        ParseFArg::Str(fmt.into()),
+                        ParseFArg::Str("=".into()),
+                        ParseFArg::FmtArg(&mut x, f),
+                        ParseFArg::Str("".into()),
+                    ],
+                );
+                if let Err(errs) = res {
+                    errs.0.into_iter().for_each(render);
+                }
+            });
+        }
+        a.check("match_prefix", s, || {
+            show(match_prefix(s, &mut "".into()));
+            show(match_prefix("ž", &mut s.into()));
+        });
+        a.check("match_prefix_flex", s, || {
+            for (c, ws) in [(false, false), (true, false), (true, true)] {
+                show(match_prefix_flex(s, &mut "".into(), c, ws));
+                show(match_prefix_flex(s, &mut s.into(), c, ws));
+                show(match_prefix_flex(" İ ", &mut s.into(), c, ws));
+            }
+        });
+        a.check("new", s, || _ = ReadFmt::new(s));
+
+        let reader = || -> [Reader; 5] {
+            [
+                s.into(),
+                (std::sync::Arc::<str>::from(s)).into(),
+                (Box::new(s.chars()) as Box<dyn Iterator<Item = char>>).into(),
+                (Box::new(s.as_bytes()) as Box<dyn std::io::Read>).into(),
+                s.split_whitespace().into(),
+            ]
+        };
+        a.check("from_chunks", s, || {
+            for j in [None, Some('ž')] {
+                let chunks = s.split_inclusive(|c: char| !c.is_alphabetic());
+                let mut r = Reader::from_chunks(chunks, j);
+                show(r.read_all(&mut String::new()));
+                _ = (r.pos(), r.size_hint(), r.bytes_size_hint());
+                render(r.err_parse("x"));
+            }
+        });
+        a.check("read_to", s, || {
+            for mut r in reader() {
+                for i in [0, 1, 3, usize::MAX] {
+                    show(r.read_to(&mut String::new(), i));
+                }
+            }
+        });
+        a.check("read_all", s, || {
+            for mut r in reader() {
+                show(r.read_all(&mut String::new()));
+            }
+        });
+        a.check("peek", s, || {
+            for mut r in reader() {
+                for _ in 0..16 {
+                    let Ok(Some(_)) = r.peek() else {
+                        break;
+                    };
+                    _ = (r.pos(), r.bytes_size_hint());
+                    render(r.err_parse("x"));
+                    _ = r.next();
+                }
+                while let Ok(Some(_)) = r.peek() {
+                    _ = r.next();
+                }
+                render(r.err_parse("end"));
+            }
+        });
+        a.check("with_context_window", s, || {
+            for n in [0, 1, 3] {
+                for r in reader() {
+                    let mut r = r.with_context_window(n);
+                    show(r.parse::<(u8, bool)>(&ReadFmt::default()));
+                    render(r.err_parse("x"));
+                    r = r.with_context_window(n / 2);
+                    show(r.read_all(&mut String::new()));
+                    render(r.err_parse("end"));
+                }
+            }
+        });
+        a.check("find_forward", s, || {
+            for mut r in reader() {
+                show(r.find_forward("aab"));
+                show(r.find_forward(s));
+                show(r.find_forward(""));
+            }
+        });
+        a.check("parse", s, || {
+            for mut r in reader() {
+                show(r.parse::<(u8, bool, f32)>(&"=".into()));
+                show(r.parse::<(i8, i8)>(&ReadFmt::default()));
+            }
+        });
+        a.check("pos", s, || {
+            for r in reader() {
+                _ = r.pos();
+            }
+        });
+        a.check("bytes_size_hint", s, || {
+            for r in reader() {
+                _ = r.bytes_size_hint();
+            }
+        });
+        a.check("consumed_since", s, || {
+            for mut r in reader() {
+                _ = r.consumed_since(usize::MAX);
+                _ = r.next();
+                _ = (r.consumed_since(0), r.consumed_since(1));
+            }
+        });
+        a.check("try_clone", s, || {
+            for mut r in reader() {
+                _ = r.next();
+                if let Some(mut c) = r.try_clone() {
+                    show(c.read_all(&mut String::new()));
+                }
+            }
+        });
+        a.check("slice", s, || {
+            for r in reader() {
+                for range in [0..0, 0..s.len(), 1..3, 2..1, 0..usize::MAX] {
+                    let Some(mut r) = r.slice(range) else {
+                        continue;
+                    };
+                    show(r.parse::<(u8, bool)>(&ReadFmt::default()));
+                    render(r.err_parse("x"));
+                    show(r.read_all(&mut String::new()));
+                    _ = (r.pos(), r.size_hint(), r.bytes_size_hint());
+                }
+            }
+        });
+        a.check("err_parse", s, || {
+            for mut r in reader() {
+                _ = r.next();
+                render(r.err_parse(s.to_string()));
+            }
+        });
+        a.check("map_err", s, || {
+            for mut r in reader() {
+                _ = r.next();
+                for e in errors(args) {
+                    render(r.map_err(e));
+                }
+            }
+        });
+    }
+}
+
+/// Runs the whole corpus.
+fn audit() -> Audit {
+    let mut a = Audit::default();
+    for args in corpus_args() {
+        audit_errors(&mut a, &args);
+        audit_pareg(&mut a, &args);
+        audit_parsers(&mut a, &args);
+    }
+
+    a.check("args", (), || {
+        _ = Pareg::args();
+    });
+    a.check("from_env_prefix", (), || {
+        _ = Pareg::from_env_prefix("");
+        Pareg::new(vec![]).merge_env("PAREG_");
+    });
+    a.check("merge_env", (), || Pareg::new(vec![]).merge_env(""));
+    #[cfg(feature = "glob")]
+    a.check("args_globbed", (), || {
+        _ = Pareg::args_globbed();
+    });
+    #[cfg(feature = "glob")]
+    a.check("args_globbed_with", (), || {
+        _ = Pareg::args_globbed_with(GlobMode::Error);
+    });
+    #[cfg(feature = "glob")]
+    a.check("expand_globs", "[ ] [! ?* */ ]* ** [z-a] \\", || {
+        let args = ["[", "]", "[!", "?*", "*/", "]*", "**", "[z-a]", "\\"];
+        for mode in [GlobMode::Keep, GlobMode::Error, GlobMode::Remove] {
+            let mut p = Pareg::new(args.map(|a| a.to_string()).to_vec());
+            p.next();
+            p.next_back();
+            show(p.expand_globs(mode));
+            while p.next().is_some() {
+                show(p.cur_arg::<u8>());
+            }
+        }
+    });
+
+    a.check("from_joined", "prog '' \"a b\"", || {
+        let joined = JoinedArgs::new("prog '' \"a b\"").unwrap();
+        let mut p = Pareg::from_joined(&joined);
+        p.next();
+        show(p.next_arg::<u8>());
+        render(p.err_unknown_argument());
+    });
+
+    a.check("from_os_args", (), || {
+        let mut p = Pareg::from_os_args([std::ffi::OsString::new()]);
+        show(p.next_arg::<u8>());
+    });
+    #[cfg(unix)]
+    a.check("from_os_args", "non utf-8", || {
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+        let args = [
+            OsString::from("prog"),
+            OsString::from_vec(vec![b'-', 0xff, b'=', 0xc3]),
+        ];
+        let mut p = Pareg::from_os_args(args);
+        show(p.next_arg::<&str>());
+        render(p.err_unknown_argument());
+    });
+
+    a
+}
+
+/// Gets names of all public functions in the source.
+fn public_fns(src: &str) -> impl Iterator<Item = &str> {
+    src.split("pub fn ").skip(1).filter_map(|s| {
+        let end = s.find(|c: char| !c.is_alphanumeric() && c != '_')?;
+        Some(&s[..end])
+    })
+}
+
+/// The audit is run only once for all the tests.
+fn audit_result() -> &'static Audit {
+    static AUDIT: OnceLock<Audit> = OnceLock::new();
+    AUDIT.get_or_init(audit)
+}
+
+#[test]
+fn corpus_has_no_panics() {
+    let a = audit_result();
+    assert!(a.failures.is_empty(), "{}", a.failures.join("\n"));
+}
+
+#[test]
+fn corpus_is_complete() {
+    let a = audit_result();
+    let exempt: HashSet<_> = EXEMPT.iter().map(|(n, _)| *n).collect();
+    let missing: Vec<_> = AUDITED_SOURCES
+        .iter()
+        .flat_map(|(f, src)| public_fns(src).map(move |n| (f, n)))
+        .filter(|(_, n)| !a.covered.contains(n) && !exempt.contains(n))
+        .map(|(f, n)| format!("{f}: {n}"))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "Public functions missing from the panic audit corpus:\n{}",
+        missing.join("\n")
+    );
+}
@@ -0,0 +1,213 @@
+use crate::{Pareg, Result};
+
+/// Type that is parsed from several arguments (e.g. group of options). This
+/// allows libraries to export parsers of their options that applications
+/// may embed in their own parsers (see [`Pareg::parse`]).
+///
+/// The parser should consume only the arguments that belong to it and stop
+/// before the first argument that it doesn't know (e.g. with
+/// [`Pareg::peek`]), so that the next parser may continue. Errors point to
+/// the arguments in the whole command line.
+///
+/// Tuples are parsed by parsing their elements in sequence. [`Option`] is
+/// [`None`] if the value fails to parse and the position is restored (see
+/// [`Pareg::checkpoint`]).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{ParseArgs, Pareg, Result};
+///
+/// struct Verbosity(i32);
+///
+/// impl ParseArgs for Verbosity {
+///     fn parse_args(args: &mut Pareg) -> Result<Self> {
+///         let mut res = 0;
+///         while let Some(arg) = args.peek() {
+///             match arg {
+///                 "-v" => res += 1,
+///                 "-q" => res -= 1,
+///                 _ => break,
+///             }
+///             args.next();
+///         }
+///         Ok(Self(res))
+///     }
+/// }
+///
+/// let args = ["-v", "-v", "-q", "file"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+///
+/// assert_eq!(1, args.parse::<Verbosity>()?.0);
+/// assert_eq!(Some("file"), args.next());
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+pub trait ParseArgs: Sized {
+    /// Parses the value from the arguments.
+    fn parse_args(args: &mut Pareg) -> Result<Self>;
+}
+
+/// Saved position of [`Pareg`] (see [`Pareg::checkpoint`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub(crate) cur: usize,
+    pub(crate) end: Option<usize>,
+    pub(crate) warnings: usize,
+    pub(crate) unknown: usize,
+}
+
+/// Parses the value and returns [`None`] if it fails. The position is
+/// restored if it fails, but errors caused by special arguments (see
+/// [`crate::ArgError::Special`]) are returned.
+impl<T: ParseArgs> ParseArgs for Option<T> {
+    fn parse_args(args: &mut Pareg) -> Result<Self> {
+        let start = args.checkpoint();
+        match T::parse_args(args) {
+            Ok(v) => Ok(Some(v)),
+            Err(e) if e.is_special() => Err(e),
+            Err(_) => {
+                args.restore(start);
+                Ok(None)
+            }
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident),+) => {
+        impl<$($t: ParseArgs),+> ParseArgs for ($($t,)+) {
+            fn parse_args(args: &mut Pareg) -> Result<Self> {
+                Ok(($($t::parse_args(args)?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use super::*;
+    use crate::ArgError;
+
+    /// Group of verbosity flags.
+    #[derive(Debug, PartialEq)]
+    struct Verbosity(i32);
+
+    /// Group of output options.
+    #[derive(Debug, PartialEq)]
+    struct Output {
+        file: String,
+        level: u8,
+    }
+
+    impl ParseArgs for Verbosity {
+        fn parse_args(args: &mut Pareg) -> Result<Self> {
+            let mut res = 0;
+            while let Some(arg) = args.peek() {
+                match arg {
+                    "-v" => res += 1,
+                    "-q" => res -= 1,
+                    _ => break,
+                }
+                args.next();
+            }
+            Ok(Self(res))
+        }
+    }
+
+    impl ParseArgs for Output {
+        fn parse_args(args: &mut Pareg) -> Result<Self> {
+            let mut res = Output {
+                file: "out".into(),
+                level: 0,
+            };
+            let mut any = false;
+            while let Some(arg) = args.peek() {
+                match arg {
+                    "-o" => {
+                        args.next();
+                        res.file = args.next_arg()?;
+                    }
+                    "-l" => {
+                        args.next();
+                        res.level = args.next_arg()?;
+                    }
+                    _ => break,
+                }
+                any = true;
+            }
+            if !any {
+                return Err(args.err_no_more_arguments());
+            }
+            Ok(res)
+        }
+    }
+
+    fn pareg(args: &[&str]) -> Pareg {
+        Pareg::new(args.iter().map(|a| a.to_string()).collect())
+    }
+
+    #[test]
+    fn nested_groups() {
+        let mut args = pareg(&["prog", "-v", "-o", "a.txt", "-l", "3", "-v"]);
+        args.next();
+        let (v, o, v2) =
+            args.parse::<(Verbosity, Output, Verbosity)>().unwrap();
+        assert_eq!(v, Verbosity(1));
+        assert_eq!(o.file, "a.txt");
+        assert_eq!((o.level, v2), (3, Verbosity(1)));
+
+        let mut args = pareg(&["prog", "-q", "-o", "b", "-l", "x", "-v"]);
+        args.next();
+        let err = args.parse::<(Verbosity, Output)>().unwrap_err();
+        let ctx = err.ctx().unwrap();
+        assert!(err.is_failed_to_parse());
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (5, 0..1));
+        assert_eq!(ctx.args.len(), 7);
+    }
+
+    #[test]
+    fn optional_group() {
+        let mut args = pareg(&["-v", "-l", "x", "-q"]);
+        let (v, o) = args.parse::<(Verbosity, Option<Output>)>().unwrap();
+        assert_eq!((v, o), (Verbosity(1), None));
+        assert_eq!(args.next(), Some("-l"));
+
+        let mut args = pareg(&["-v", "-l", "2"]);
+        let (_, o) = args.parse::<(Verbosity, Option<Output>)>().unwrap();
+        assert_eq!(o.map(|o| o.level), Some(2));
+
+        let (v, warnings) = pareg(&["-q", "-q"])
+            .parse_all::<(Verbosity, Option<Output>)>()
+            .unwrap();
+        assert_eq!((v.0, v.1), (Verbosity(-2), None));
+        assert!(warnings.is_empty());
+
+        let err = pareg(&["-q", "x"]).parse_all::<(Verbosity,)>().unwrap_err();
+        assert!(matches!(err, ArgError::TooManyArguments(_)));
+        assert_eq!(err.ctx().unwrap().error_idx, 1);
+    }
+
+    #[test]
+    fn checkpoint() {
+        let mut args = pareg(&["-a", "-b", "-c"]);
+        args.next();
+        let start = args.checkpoint();
+        args.next();
+        args.next_back();
+        args.warn("warning");
+        assert_eq!(args.warnings().len(), 1);
+        args.restore(start);
+        assert_eq!(args.cur(), Some("-a"));
+        assert!(args.warnings().is_empty());
+        let rest: Vec<_> = args.remaining().to_vec();
+        assert_eq!(rest, ["-b", "-c"]);
+    }
+}
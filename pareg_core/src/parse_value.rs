@@ -0,0 +1,129 @@
+use alloc::{borrow::Cow, boxed::Box, collections::BTreeMap};
+use core::{any::Any, marker::PhantomData};
+
+use crate::{from_arg::parse_hinted, short_type_name, FromArg, Result};
+
+/// Parser of values whose type is known only at runtime (e.g. values of
+/// options registered by plugins). Unlike [`FromArg`], this trait may be used
+/// as trait object. Use [`Parser`] to create it for type that implements
+/// [`FromArg`].
+pub trait ParseValue {
+    /// Parses the value. The result may be downcasted to the parsed type.
+    fn parse(&self, arg: &str) -> Result<Box<dyn Any>>;
+
+    /// Describes the parsed value (e.g. the name of the type).
+    fn describe(&self) -> &str;
+}
+
+/// [`ParseValue`] that parses values of the type `T` with [`FromArg`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{ParseValue, Parser};
+///
+/// let p = Parser::<u32>::new();
+/// assert_eq!("u32", p.describe());
+/// let value = p.parse("5").unwrap();
+/// assert_eq!(Some(&5), value.downcast_ref::<u32>());
+/// assert!(p.parse("x").is_err());
+/// ```
+pub struct Parser<T> {
+    description: Cow<'static, str>,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// Maps names of options to parsers of their values (see
+/// [`crate::Pareg::next_with`]).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{Pareg, Parser, ValueRegistry};
+///
+/// let mut reg = ValueRegistry::new();
+/// reg.register("--level", Parser::<u8>::new());
+/// reg.register("--out", Parser::<String>::new());
+///
+/// let args = ["--out", "a.txt", "--level", "3"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+/// let mut level = None;
+/// while let Some(arg) = args.next() {
+///     let p = reg.get(arg).unwrap();
+///     let value = args.next_with(p).unwrap();
+///     if let Some(l) = value.downcast_ref::<u8>() {
+///         level = Some(*l);
+///     }
+/// }
+/// assert_eq!(Some(3), level);
+/// ```
+#[derive(Default)]
+pub struct ValueRegistry {
+    parsers: BTreeMap<Cow<'static, str>, Box<dyn ParseValue>>,
+}
+
+impl<T> Parser<T>
+where
+    T: for<'a> FromArg<'a> + 'static,
+{
+    /// Creates parser described by the name of the type `T`.
+    pub fn new() -> Self {
+        Self::with_description(short_type_name::<T>())
+    }
+
+    /// Creates parser with the given description.
+    pub fn with_description(desc: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            description: desc.into(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Parser<T>
+where
+    T: for<'a> FromArg<'a> + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ParseValue for Parser<T>
+where
+    T: for<'a> FromArg<'a> + 'static,
+{
+    fn parse(&self, arg: &str) -> Result<Box<dyn Any>> {
+        parse_hinted::<T>(arg).map(|v| Box::new(v) as Box<dyn Any>)
+    }
+
+    fn describe(&self) -> &str {
+        &self.description
+    }
+}
+
+impl ValueRegistry {
+    /// Creates empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers parser for values of the option `name`. Parser that was
+    /// already registered for `name` is replaced.
+    pub fn register(
+        &mut self,
+        name: impl Into<Cow<'static, str>>,
+        parser: impl ParseValue + 'static,
+    ) -> &mut Self {
+        self.parsers.insert(name.into(), Box::new(parser));
+        self
+    }
+
+    /// Gets the parser for values of the option `name`.
+    pub fn get(&self, name: &str) -> Option<&dyn ParseValue> {
+        self.parsers.get(name).map(|p| p.as_ref())
+    }
+
+    /// Iterates over the registered names and their parsers.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &dyn ParseValue)> {
+        self.parsers.iter().map(|(n, p)| (n.as_ref(), p.as_ref()))
+    }
+}
@@ -1,15 +1,35 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, format, vec, vec::Vec};
+use core::ops::Range;
 
-use crate::{ArgError, FromRead, Reader, Result};
+use crate::{ArgError, ArgErrors, FromRead, ReadFmt, Reader, Result};
 
 pub trait ParseF {
     fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>>;
+
+    /// Sets the value from reader with the given format. The default
+    /// implementation ignores the format.
+    fn set_from_read_fmt(
+        &mut self,
+        r: &mut Reader,
+        fmt: &ReadFmt,
+    ) -> Result<Option<ArgError>> {
+        _ = fmt;
+        self.set_from_read(r)
+    }
 }
 
 impl<T: FromRead> ParseF for T {
     fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        self.set_from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn set_from_read_fmt(
+        &mut self,
+        r: &mut Reader,
+        fmt: &ReadFmt,
+    ) -> Result<Option<ArgError>> {
         let start = r.pos().unwrap_or_default();
-        let res = Self::from_read(r);
+        let res = Self::from_read_fmt(r, fmt);
         if let Some(v) = res.res {
             *self = v;
             Ok(res.err)
@@ -21,9 +41,113 @@ impl<T: FromRead> ParseF for T {
     }
 }
 
+/// Reads list of values separated by separator. The format is the separator
+/// (`,` by default). If it starts with `+`, the values are appended to the
+/// existing values, otherwise they replace them (e.g. `{list:+;}` appends
+/// values separated by `;`).
+///
+/// The list ends at the first value that isn't followed by the separator.
+/// Empty input or input that cannot start value is empty list. Separator
+/// that is not followed by value is error.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{parsef, ParseFArg};
+///
+/// let mut tags: Vec<u32> = vec![];
+/// for arg in ["1,2", "3"] {
+///     parsef(&mut arg.into(), [ParseFArg::FmtArg(&mut tags, "+".into())])?;
+/// }
+/// assert_eq!(tags, [1, 2, 3]);
+///
+/// parsef(&mut "4;5".into(), [ParseFArg::FmtArg(&mut tags, ";".into())])?;
+/// assert_eq!(tags, [4, 5]);
+///
+/// let err = parsef(&mut "1,2,".into(), [ParseFArg::Arg(&mut tags)]);
+/// assert_eq!(Some(3..4), err.unwrap_err().span());
+/// # Ok::<_, pareg_core::ArgError>(())
+/// ```
+impl<T: FromRead> ParseF for Vec<T> {
+    fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        self.set_from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn set_from_read_fmt(
+        &mut self,
+        r: &mut Reader,
+        fmt: &ReadFmt,
+    ) -> Result<Option<ArgError>> {
+        let (append, sep) = match fmt.custom().strip_prefix('+') {
+            Some(sep) => (true, sep),
+            None => (false, fmt.custom()),
+        };
+        let sep = if sep.is_empty() { "," } else { sep };
+
+        let mut values = vec![];
+        let mut soft_err = None;
+        if r.peek()?.is_some() {
+            let start = r.read_end();
+            let first = T::from_read(r);
+            soft_err = first.err;
+            match first.res {
+                Some(v) => values.push(v),
+                // Input that cannot start value is empty list.
+                None if r.consumed_since(start) == 0 => {}
+                None => {
+                    return Err(soft_err.unwrap_or_else(|| {
+                        r.err_parse("Failed to parse list element.")
+                            .span_start(start)
+                    }))
+                }
+            }
+        }
+
+        while !values.is_empty()
+            && r.peek()?.is_some_and(|c| sep.starts_with(c))
+        {
+            let sep_start = r.read_end();
+            match_prefix(sep, r)?;
+            let sep_end = r.read_end();
+            let elem = T::from_read(r);
+            let Some(v) = elem.res else {
+                return r
+                    .err_parse(format!("Expected value after `{sep}`."))
+                    .main_msg(format!(
+                        "Separator `{sep}` must be followed by value."
+                    ))
+                    .spanned(sep_start..sep_end)
+                    .hint(format!(
+                        "Remove the last `{sep}` or add value after it."
+                    ))
+                    .err();
+            };
+            values.push(v);
+            soft_err = elem.err;
+        }
+
+        if append {
+            self.extend(values);
+        } else {
+            *self = values;
+        }
+        Ok(soft_err)
+    }
+}
+
 pub enum ParseFArg<'a> {
     Str(Cow<'a, str>),
     Arg(&'a mut dyn ParseF),
+    /// Argument with format specification.
+    FmtArg(&'a mut dyn ParseF, ReadFmt<'a>),
+    /// Literal that is matched with [`match_prefix_flex`].
+    StrFlex {
+        text: Cow<'a, str>,
+        /// Letters are matched case insensitively.
+        case_insensitive: bool,
+        /// Any run of whitespace in the input matches any run of whitespace
+        /// (including none) in the literal.
+        flex_ws: bool,
+    },
 }
 
 pub fn parsef<'a>(
@@ -44,16 +168,103 @@ pub fn parsef_part<'a>(
 ) -> Result<Option<ArgError>> {
     let mut last_err = None;
     for a in args.as_mut() {
-        last_err = match a {
-            ParseFArg::Arg(a) => a.set_from_read(r)?,
-            ParseFArg::Str(a) => {
-                match_prefix(a, r)?;
-                None
+        last_err = parse_arg(r, a)?;
+    }
+
+    Ok(last_err)
+}
+
+/// Same as [`parsef`], but it doesn't stop at the first error and returns
+/// all the errors.
+///
+/// When an argument fails, the input is skipped up to the next occurence of
+/// the following literal ([`ParseFArg::Str`]) and the parsing continues
+/// after it. If the failed argument is literal, it is searched for itself.
+/// Arguments before the literal are not set and the span of the error is
+/// extended over the skipped input. If there is no such literal, the rest
+/// of the input is skipped.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{parsef_all_errors, ParseFArg};
+///
+/// let (mut a, mut b, mut c) = (0_u8, 0_u8, 0_u8);
+/// let errs = parsef_all_errors(
+///     &mut "x:1,300".into(),
+///     [
+///         ParseFArg::Arg(&mut a),
+///         ParseFArg::Str(":".into()),
+///         ParseFArg::Arg(&mut b),
+///         ParseFArg::Str(",".into()),
+///         ParseFArg::Arg(&mut c),
+///     ],
+/// )
+/// .unwrap_err();
+/// assert_eq!(2, errs.len());
+/// assert_eq!(1, b);
+/// ```
+pub fn parsef_all_errors<'a>(
+    r: &mut Reader,
+    mut args: impl AsMut<[ParseFArg<'a>]>,
+) -> core::result::Result<(), ArgErrors> {
+    let args = args.as_mut();
+    let mut errs = ArgErrors::default();
+    let mut last_err = None;
+    let mut i = 0;
+    while i < args.len() {
+        let err = match parse_arg(r, &mut args[i]) {
+            Ok(e) => {
+                last_err = e;
+                i += 1;
+                continue;
+            }
+            Err(e) => e,
+        };
+        last_err = None;
+
+        let skip_start = r.read_end();
+        let sync = i + !matches!(args[i], ParseFArg::Str(_)) as usize;
+        let lit =
+            args.iter()
+                .enumerate()
+                .skip(sync)
+                .find_map(|(j, a)| match a {
+                    ParseFArg::Str(s) => Some((j, s.as_ref())),
+                    _ => None,
+                });
+        let found = match lit.map(|(j, s)| (j, r.find_forward(s))) {
+            Some((j, Ok(Some(pos)))) => {
+                i = j + 1;
+                Some(pos)
             }
+            Some((_, Err(e))) => {
+                errs.push(err);
+                errs.push(e);
+                return Err(errs);
+            }
+            _ => None,
+        };
+
+        let Some(skip_end) = found else {
+            while let Some(Ok(_)) = r.next() {}
+            errs.push(mark_skipped(err, skip_start..r.read_end()));
+            return Err(errs);
         };
+        errs.push(mark_skipped(err, skip_start..skip_end));
     }
 
-    Ok(last_err)
+    match r.peek() {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            errs.push(last_err.unwrap_or_else(|| r.err_parse("Unused input")))
+        }
+        Err(e) => errs.push(e),
+    }
+    if errs.is_empty() {
+        Ok(())
+    } else {
+        Err(errs)
+    }
 }
 
 pub fn match_prefix(prefix: &str, r: &mut Reader) -> Result<()> {
@@ -74,3 +285,235 @@ pub fn match_prefix(prefix: &str, r: &mut Reader) -> Result<()> {
     }
     Ok(())
 }
+
+/// Same as [`match_prefix`], but the matching may be less strict.
+///
+/// If `case_insensitive` is `true`, letters are compared case insensitively.
+///
+/// If `flex_ws` is `true`, any run of whitespace in the input matches any run
+/// of whitespace in `prefix`. Input whitespace is also allowed at the start
+/// and at the end of `prefix`, so `" x "` and `"x"` are equivalent.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{match_prefix_flex, Reader};
+///
+/// let mut r: Reader = "  X  768".into();
+/// match_prefix_flex("x", &mut r, true, true).unwrap();
+/// assert_eq!(Some('7'), r.peek().unwrap());
+///
+/// let mut r: Reader = "Px".into();
+/// assert!(match_prefix_flex("p x", &mut r, false, true).is_err());
+/// ```
+pub fn match_prefix_flex(
+    prefix: &str,
+    r: &mut Reader,
+    case_insensitive: bool,
+    flex_ws: bool,
+) -> Result<()> {
+    let mut skip_ws = flex_ws;
+    for p in prefix.chars() {
+        if flex_ws && p.is_whitespace() {
+            skip_ws = true;
+            continue;
+        }
+        if skip_ws {
+            skip_whitespace(r)?;
+            skip_ws = false;
+        }
+
+        let Some(s) = r.next().transpose()? else {
+            return r
+                .err_parse("Unexpected end of string.")
+                .inline_msg(format!("Expected `{p}`"))
+                .err();
+        };
+        let eq = if case_insensitive {
+            p.to_lowercase().eq(s.to_lowercase())
+        } else {
+            p == s
+        };
+        if !eq {
+            return r
+                .err_parse(format!("Unexpected character `{s}`."))
+                .inline_msg(format!("Expected `{p}`."))
+                .err();
+        }
+    }
+
+    if flex_ws {
+        skip_whitespace(r)?;
+    }
+    Ok(())
+}
+
+/// Parses single argument of [`parsef_part`].
+fn parse_arg(r: &mut Reader, a: &mut ParseFArg) -> Result<Option<ArgError>> {
+    match a {
+        ParseFArg::Arg(a) => a.set_from_read(r),
+        ParseFArg::FmtArg(a, f) => a.set_from_read_fmt(r, f),
+        ParseFArg::Str(a) => match_prefix(a, r).map(|_| None),
+        ParseFArg::StrFlex {
+            text,
+            case_insensitive,
+            flex_ws,
+        } => match_prefix_flex(text, r, *case_insensitive, *flex_ws)
+            .map(|_| None),
+    }
+}
+
+/// Extends the span of the error over the skipped input.
+fn mark_skipped(err: ArgError, skipped: Range<usize>) -> ArgError {
+    if skipped.is_empty() {
+        return err;
+    }
+    err.map_ctx(|c| {
+        let span = c.error_span.start.min(skipped.start)
+            ..c.error_span.end.max(skipped.end);
+        c.spanned(span)
+    })
+}
+
+fn skip_whitespace(r: &mut Reader) -> Result<()> {
+    while r.peek()?.is_some_and(|c| c.is_whitespace()) {
+        r.next();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+    use crate::ParseResult;
+
+    /// Network mask with at most 32 bits.
+    #[derive(Clone, Default)]
+    struct Mask(u8);
+
+    impl FromRead for Mask {
+        fn from_read(r: &mut Reader) -> ParseResult<Self> {
+            let res = (|| {
+                r.peek()?;
+                let start = r.pos().unwrap_or_default();
+                match r.parse::<u8>(&ReadFmt::default())? {
+                    m @ 0..=32 => Ok(Self(m)),
+                    _ => r
+                        .err_parse("Mask must be at most 32.")
+                        .span_start(start)
+                        .err(),
+                }
+            })();
+            ParseResult {
+                res: res.as_ref().ok().cloned(),
+                err: res.err(),
+            }
+        }
+    }
+
+    /// Parses address in the format `a.b.c.d/mask`.
+    fn address(
+        s: &str,
+        all: bool,
+    ) -> core::result::Result<([u8; 4], u8), ArgErrors> {
+        let ([mut a, mut b, mut c, mut d], mut mask) = ([0; 4], Mask(0));
+        let args = [
+            ParseFArg::Arg(&mut a),
+            ParseFArg::Str(".".into()),
+            ParseFArg::Arg(&mut b),
+            ParseFArg::Str(".".into()),
+            ParseFArg::Arg(&mut c),
+            ParseFArg::Str(".".into()),
+            ParseFArg::Arg(&mut d),
+            ParseFArg::Str("/".into()),
+            ParseFArg::Arg(&mut mask),
+        ];
+        let mut r = s.into();
+        if all {
+            parsef_all_errors(&mut r, args)?;
+        } else {
+            parsef(&mut r, args).map_err(ArgErrors::from)?;
+        }
+        Ok(([a, b, c, d], mask.0))
+    }
+
+    fn spans(s: &str) -> Vec<Range<usize>> {
+        let errs = address(s, true).unwrap_err();
+        errs.iter().map(|e| e.span().unwrap()).collect()
+    }
+
+    fn list(
+        s: &str,
+        fmt: &str,
+        list: &mut Vec<u32>,
+    ) -> Result<Option<ArgError>> {
+        list.set_from_read_fmt(&mut s.into(), &fmt.into())
+    }
+
+    #[test]
+    fn vec_list() {
+        let mut v = vec![9];
+        assert!(list("1,2", "", &mut v).unwrap().is_none());
+        assert_eq!(v, [1, 2]);
+        list("3", "+", &mut v).unwrap();
+        assert_eq!(v, [1, 2, 3]);
+        list("4, 5", "+, ", &mut v).unwrap();
+        assert_eq!(v, [1, 2, 3, 4, 5]);
+        list("6;7", ";", &mut v).unwrap();
+        assert_eq!(v, [6, 7]);
+
+        // Empty input.
+        assert!(list("", "", &mut v).unwrap().is_none());
+        assert!(v.is_empty());
+        v.push(1);
+        list("", "+", &mut v).unwrap();
+        assert_eq!(v, [1]);
+        assert!(list("x", "+", &mut v).unwrap().is_some());
+        assert_eq!(v, [1]);
+
+        // The list ends at value without separator.
+        let mut r = "1,2;3".into();
+        v.set_from_read(&mut r).unwrap();
+        assert_eq!(
+            (v.as_slice(), r.peek().unwrap()),
+            (&[1, 2][..], Some(';'))
+        );
+    }
+
+    #[test]
+    fn vec_list_errors() {
+        let span = |s: &str, fmt: &str| {
+            let mut v = vec![1];
+            let err = list(s, fmt, &mut v).unwrap_err();
+            assert_eq!(v, [1], "{s}");
+            err.span().unwrap()
+        };
+        assert_eq!(span("1,2,", ""), 3..4);
+        assert_eq!(span("1, ", "+, "), 1..3);
+        assert_eq!(span("1,x", "+"), 1..2);
+
+        let mut v = Vec::<u32>::new();
+        let err =
+            parsef(&mut "1,2,".into(), [ParseFArg::Arg(&mut v)]).unwrap_err();
+        assert_eq!(err.message(), Some("Expected value after `,`."));
+        let arg = ParseFArg::FmtArg(&mut v, ";".into());
+        let err = parsef(&mut "1;2,3".into(), [arg]).unwrap_err();
+        assert_eq!(err.span(), Some(3..4));
+    }
+
+    #[test]
+    fn all_errors() {
+        assert_eq!(spans("1.2.x.4/99"), vec![4..5, 8..10]);
+        assert_eq!(address("1.2.x.4/99", false).unwrap_err().len(), 1);
+        assert_eq!(address("1.2.3.4/24", true).unwrap(), ([1, 2, 3, 4], 24));
+
+        // Skipped input is part of the error.
+        assert_eq!(spans("1.2-3.4/8"), vec![3..5, 7..9]);
+        assert_eq!(spans("1.300abc.3.4/8"), vec![4..8]);
+        assert_eq!(spans("1.2.3"), vec![4..5]);
+        assert_eq!(spans("1.2.3.4/1x"), vec![9..10]);
+        assert_eq!(spans("1.2.3.4/8 9.9"), vec![9..10]);
+        assert_eq!(spans("x.x.x.x/x"), vec![0..1, 2..3, 4..5, 6..7, 8..9]);
+    }
+}
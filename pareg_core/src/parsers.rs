@@ -1,8 +1,17 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::ops::Range;
+
 use crate::{
     arg_into::ArgInto,
+    default_anounce, default_color_mode,
     err::{ArgError, Result},
-    from_arg::FromArg,
-    ArgErrCtx, ColorMode,
+    from_arg::{parse_hinted, FromArg},
+    ArgErrCtx, SetPolicy,
 };
 
 /// If sep was `'='`, parses `"key=value"` into `"key"` and `value` that is
@@ -36,12 +45,12 @@ where
     V: FromArg<'a>,
 {
     let Some((k, v)) = arg.split_once(sep) else {
-        return Ok((K::from_arg(arg)?, None));
+        return Ok((parse_hinted::<K>(arg)?, None));
     };
 
     Ok((
-        K::from_arg(k).map_err(|e| e.shift_span(0, arg.to_string()))?,
-        Some(V::from_arg(v).map_err(|e| {
+        parse_hinted::<K>(k).map_err(|e| e.shift_span(0, arg.to_string()))?,
+        Some(parse_hinted::<V>(v).map_err(|e| {
             e.shift_span(k.len() + sep.len_utf8(), arg.to_string())
         })?),
     ))
@@ -71,25 +80,126 @@ where
     V: FromArg<'a>,
 {
     let Some((k, v)) = arg.split_once(sep) else {
-        return Err(ArgError::NoValue(ArgErrCtx {
-            args: vec![arg.into()],
-            error_idx: 0,
-            error_span: 0..arg.len(),
-            message: format!("Missing separator `{sep}`.").into(),
-            long_message: Some(format!("Missing separator `{sep}` for key value pair.").into()),
-            hint: Some(format!("Use the separator `{sep}` to split the argument into key and value.").into()),
-            color: ColorMode::default(),
-        }.into()));
+        return Err(no_separator(arg, sep));
     };
 
     Ok((
-        K::from_arg(k).map_err(|e| e.shift_span(0, arg.to_string()))?,
-        V::from_arg(v).map_err(|e| {
+        parse_hinted::<K>(k).map_err(|e| e.shift_span(0, arg.to_string()))?,
+        parse_hinted::<V>(v).map_err(|e| {
             e.shift_span(k.len() + sep.len_utf8(), arg.to_string())
         })?,
     ))
 }
 
+/// Same as [`key_mval_arg`], but the separator may be escaped in the key with
+/// `\`. Backslash may be escaped in the key as `\\`. The escapes are
+/// removed from the key and the first unescaped separator splits the key and
+/// value. The value is not unescaped.
+///
+/// The key is parsed from unescaped copy so it cannot borrow from `arg`
+/// (e.g. use [`String`] instead of `&str`).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::key_mval_arg_esc;
+///
+/// assert_eq!(
+///     ("a=b".to_owned(), Some("c")),
+///     key_mval_arg_esc::<String, &str>(r"a\=b=c", '=').unwrap()
+/// );
+/// assert_eq!(
+///     (r"a\".to_owned(), Some("b")),
+///     key_mval_arg_esc::<String, &str>(r"a\\=b", '=').unwrap()
+/// );
+/// assert_eq!(
+///     ("a=".to_owned(), None),
+///     key_mval_arg_esc::<String, &str>(r"a\=", '=').unwrap()
+/// );
+/// ```
+pub fn key_mval_arg_esc<'a, K, V>(
+    arg: &'a str,
+    sep: char,
+) -> Result<(K, Option<V>)>
+where
+    K: for<'k> FromArg<'k>,
+    V: FromArg<'a>,
+{
+    let (key, pos, escapes) = split_escaped(arg, sep);
+    let key =
+        parse_hinted::<K>(&key).map_err(|e| unescape_err(e, arg, &escapes))?;
+    let Some(pos) = pos else {
+        return Ok((key, None));
+    };
+
+    let start = pos + sep.len_utf8();
+    Ok((
+        key,
+        Some(
+            parse_hinted::<V>(&arg[start..])
+                .map_err(|e| e.shift_span(start, arg.to_string()))?,
+        ),
+    ))
+}
+
+/// Same as [`key_val_arg`], but the separator may be escaped in the key with
+/// `\` as in [`key_mval_arg_esc`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::key_val_arg_esc;
+///
+/// assert_eq!(
+///     ("a=b".to_owned(), 5),
+///     key_val_arg_esc::<String, i32>(r"a\=b=5", '=').unwrap()
+/// );
+/// assert!(key_val_arg_esc::<String, i32>(r"a\=b", '=').is_err());
+/// ```
+pub fn key_val_arg_esc<'a, K, V>(arg: &'a str, sep: char) -> Result<(K, V)>
+where
+    K: for<'k> FromArg<'k>,
+    V: FromArg<'a>,
+{
+    match key_mval_arg_esc(arg, sep)? {
+        (k, Some(v)) => Ok((k, v)),
+        (_, None) => Err(no_separator(arg, sep)),
+    }
+}
+
+/// Same as [`val_arg`], but the separator may be escaped in the key with `\`
+/// as in [`key_mval_arg_esc`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::val_arg_esc;
+///
+/// assert_eq!(0.25, val_arg_esc::<f64>(r"a\:b:0.25", ':').unwrap());
+/// ```
+#[inline(always)]
+pub fn val_arg_esc<'a, T>(arg: &'a str, sep: char) -> Result<T>
+where
+    T: FromArg<'a>,
+{
+    Ok(key_val_arg_esc::<String, _>(arg, sep)?.1)
+}
+
+/// Same as [`mval_arg`], but the separator may be escaped in the key with
+/// `\` as in [`key_mval_arg_esc`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::mval_arg_esc;
+///
+/// assert_eq!(Some("c"), mval_arg_esc::<&str>(r"a\=b=c", '=').unwrap());
+/// assert_eq!(None, mval_arg_esc::<&str>(r"a\=b", '=').unwrap());
+/// ```
+#[inline(always)]
+pub fn mval_arg_esc<'a, T>(arg: &'a str, sep: char) -> Result<Option<T>>
+where
+    T: FromArg<'a>,
+{
+    Ok(key_mval_arg_esc::<String, _>(arg, sep)?.1)
+}
+
 /// Parse bool value in a specific way. If the value of lowercase `arg` is
 /// equal to `t` returns true, if it is equal to `f` returns false and
 /// otherwise returns error.
@@ -111,13 +221,25 @@ pub fn bool_arg(t: &str, f: &str, arg: &str) -> Result<bool> {
     } else {
         Err(ArgError::FailedToParse(
             ArgErrCtx {
-                args: vec![arg.into()],
+                args: vec![arg.to_string().into()],
                 error_idx: 0,
+                args_start_index: 0,
+                show_program: true,
                 error_span: 0..arg.len(),
+                positioned: false,
                 message: "Invalid value.".into(),
                 long_message: Some(format!("Invalid value `{arg}`").into()),
                 hint: Some(format!("Expected `{t}` or `{f}`").into()),
-                color: ColorMode::default(),
+                suggestions: vec![],
+                color: default_color_mode(),
+                anounce: default_anounce(),
+                max_width: None,
+                origins: vec![],
+                owner: None,
+                value_of: None,
+                program: None,
+                usage: None,
+                messages: None,
             }
             .into(),
         ))
@@ -161,13 +283,25 @@ pub fn opt_bool_arg(
     } else {
         Err(ArgError::FailedToParse(
             ArgErrCtx {
-                args: vec![arg.into()],
+                args: vec![arg.to_string().into()],
                 error_idx: 0,
+                args_start_index: 0,
+                show_program: true,
                 error_span: 0..arg.len(),
+                positioned: false,
                 message: "Invalid value.".into(),
                 long_message: Some(format!("Invalid value `{arg}`").into()),
                 hint: Some(format!("Expected `{t}`, `{f}` or `{n}`").into()),
-                color: ColorMode::default(),
+                suggestions: vec![],
+                color: default_color_mode(),
+                anounce: default_anounce(),
+                max_width: None,
+                origins: vec![],
+                owner: None,
+                value_of: None,
+                program: None,
+                usage: None,
+                messages: None,
             }
             .into(),
         ))
@@ -272,3 +406,434 @@ where
 {
     Ok(key_mval_arg::<&str, _>(arg, sep)?.1)
 }
+
+/// If sep was `'='`, finds the ranges of `"key"` and `"value"` in
+/// `"key=value"`. Nothing is parsed.
+///
+/// In case that there is no `'='`, returns [`None`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::key_val_spans;
+///
+/// assert_eq!(Some((0..3, 4..9)), key_val_spans("key=value", '='));
+/// assert_eq!(Some((0..3, 6..6)), key_val_spans("ké→", '→'));
+/// assert_eq!(None, key_val_spans("only_key", '='));
+/// ```
+#[inline]
+pub fn key_val_spans(
+    arg: &str,
+    sep: char,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let pos = arg.find(sep)?;
+    Some((0..pos, pos + sep.len_utf8()..arg.len()))
+}
+
+/// Splits `arg` into fields separated by `pair_sep`. Each field is key and
+/// value separated by `kv_sep` (e.g. `host=example.com,port=8080`). Empty
+/// fields are ignored.
+///
+/// The values are parsed when they are taken from the [`FieldMap`]. Errors
+/// have spans in `arg`. Field without `kv_sep` is [`ArgError::NoValue`] and
+/// duplicate field is [`ArgError::TooManyArguments`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::fields_arg;
+///
+/// let mut f = fields_arg("host=example.com,port=8080", ',', '=').unwrap();
+/// assert_eq!("example.com", f.require::<&str>("host").unwrap());
+/// assert_eq!(Some(8080), f.take::<u16>("port").unwrap());
+/// assert_eq!(None, f.take::<bool>("tls").unwrap());
+/// f.finish().unwrap();
+/// ```
+pub fn fields_arg(
+    arg: &str,
+    pair_sep: char,
+    kv_sep: char,
+) -> Result<FieldMap<'_>> {
+    let mut fields: Vec<(&str, usize, Range<usize>)> = vec![];
+    let mut start = 0;
+    for part in arg.split(pair_sep) {
+        let part_start = start;
+        start += part.len() + pair_sep.len_utf8();
+        if part.is_empty() {
+            continue;
+        }
+
+        let Some((key, _)) = part.split_once(kv_sep) else {
+            return Err(no_separator(part, kv_sep)
+                .shift_span(part_start, arg.to_string()));
+        };
+        if fields.iter().any(|(k, _, _)| *k == key) {
+            return Err(duplicate_field(arg, key, part_start));
+        }
+        let value_start = part_start + key.len() + kv_sep.len_utf8();
+        fields.push((key, part_start, value_start..part_start + part.len()));
+    }
+
+    Ok(FieldMap {
+        arg,
+        kv_sep,
+        fields,
+        known: vec![],
+    })
+}
+
+/// Fields of argument created with [`fields_arg`]. Take the fields with
+/// [`FieldMap::take`] or [`FieldMap::require`] and check that there are no
+/// unknown fields with [`FieldMap::finish`].
+#[derive(Debug, Clone)]
+pub struct FieldMap<'a> {
+    arg: &'a str,
+    kv_sep: char,
+    /// Fields that were not taken: key, start of the field and span of the
+    /// value.
+    fields: Vec<(&'a str, usize, Range<usize>)>,
+    /// Keys that were asked for. They are listed in the hint of
+    /// [`FieldMap::finish`].
+    known: Vec<String>,
+}
+
+impl<'a> FieldMap<'a> {
+    /// Removes the field with the key `key` and parses its value. Returns
+    /// [`None`] if there is no such field.
+    pub fn take<T>(&mut self, key: &str) -> Result<Option<T>>
+    where
+        T: FromArg<'a>,
+    {
+        if !self.known.iter().any(|k| k == key) {
+            self.known.push(key.to_string());
+        }
+        let Some(i) = self.fields.iter().position(|(k, _, _)| *k == key)
+        else {
+            return Ok(None);
+        };
+
+        let (_, _, span) = self.fields.remove(i);
+        parse_hinted::<T>(&self.arg[span.clone()])
+            .map(Some)
+            .map_err(|e| e.shift_span(span.start, self.arg.to_string()))
+    }
+
+    /// Same as [`FieldMap::take`], but missing field is
+    /// [`ArgError::InvalidValue`].
+    pub fn require<T>(&mut self, key: &str) -> Result<T>
+    where
+        T: FromArg<'a>,
+    {
+        match self.take(key)? {
+            Some(v) => Ok(v),
+            None => Err(missing_field(self.arg, key, self.kv_sep)),
+        }
+    }
+
+    /// Checks that all the fields were taken. If not, returns
+    /// [`ArgError::InvalidValue`] pointing to the first field that was not
+    /// taken. The hint lists all such fields and the known fields.
+    pub fn finish(self) -> Result<()> {
+        if self.fields.is_empty() {
+            Ok(())
+        } else {
+            Err(unknown_fields(&self))
+        }
+    }
+}
+
+/// Splits `arg` into fields separated by `sep` (e.g. `a,"b,c",3`). Field may
+/// be in double quotes `"` and then it may contain `sep`. Quote inside quoted
+/// field is written as two quotes `""`. Empty `arg` is single empty field.
+///
+/// Unterminated quoted field and quote inside field that is not quoted are
+/// [`ArgError::FailedToParse`] with span in `arg`.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::csv_row;
+///
+/// assert_eq!(
+///     vec!["a", "b,c", "", "say \"hi\""],
+///     csv_row(r#"a,"b,c",,"say ""hi""""#, ',').unwrap()
+/// );
+/// assert!(csv_row(r#"a,"b"#, ',').is_err());
+/// ```
+pub fn csv_row(arg: &str, sep: char) -> Result<Vec<String>> {
+    Ok(csv_fields(arg, sep)?.into_iter().map(|f| f.0).collect())
+}
+
+/// Same as [`csv_row`], but the fields are parsed to `T`. Errors of the
+/// fields have spans in `arg`.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::csv_row_as;
+///
+/// assert_eq!(vec![1, 2, 3], csv_row_as::<u8>(r#"1,"2",3"#, ',').unwrap());
+/// let err = csv_row_as::<u8>("1;x", ';').unwrap_err();
+/// assert_eq!(2..3, err.ctx().unwrap().error_span);
+/// ```
+pub fn csv_row_as<T>(arg: &str, sep: char) -> Result<Vec<T>>
+where
+    T: for<'b> FromArg<'b>,
+{
+    csv_fields(arg, sep)?
+        .into_iter()
+        .map(|(field, start, escapes)| {
+            parse_hinted::<T>(&field).map_err(|e| {
+                unescape_err(e, &field, &escapes)
+                    .shift_span(start, arg.to_string())
+            })
+        })
+        .collect()
+}
+
+/// Parses `arg` and sets it to `res` according to `policy` if `res` already
+/// has value:
+/// - [`SetPolicy::Error`]: [`ArgError::TooManyArguments`] and `res` is not
+///   changed.
+/// - [`SetPolicy::FirstWins`]: the old value is kept.
+/// - [`SetPolicy::LastWins`]: the value is replaced.
+///
+/// `on_repeat` is called if `res` already has value and the policy is not
+/// [`SetPolicy::Error`] (e.g. to report warning). The value is parsed even
+/// if it is ignored, so invalid value is always error.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{set_arg_with_policy, SetPolicy};
+///
+/// let mut level = None;
+/// let mut repeats = 0;
+/// for arg in ["1", "2"] {
+///     set_arg_with_policy::<u8>(&mut level, arg, SetPolicy::FirstWins, || {
+///         repeats += 1
+///     })
+///     .unwrap();
+/// }
+/// assert_eq!(Some(1), level);
+/// assert_eq!(1, repeats);
+///
+/// assert!(
+///     set_arg_with_policy(&mut level, "3", SetPolicy::Error, || ()).is_err()
+/// );
+/// ```
+pub fn set_arg_with_policy<'a, T>(
+    res: &mut Option<T>,
+    arg: &'a str,
+    policy: SetPolicy,
+    on_repeat: impl FnOnce(),
+) -> Result<()>
+where
+    T: FromArg<'a>,
+{
+    if res.is_some() && policy == SetPolicy::Error {
+        return Err(ArgError::TooManyArguments(
+            ArgErrCtx::from_msg("Value was already set.", arg.to_string())
+                .hint("The value may be given only once.")
+                .into(),
+        ));
+    }
+    if policy.set(res, parse_hinted::<T>(arg)?) {
+        on_repeat();
+    }
+    Ok(())
+}
+
+/// Creates error that field with the key `key` at `start` is duplicate.
+#[cold]
+fn duplicate_field(arg: &str, key: &str, start: usize) -> ArgError {
+    ArgError::TooManyArguments(
+        ArgErrCtx::from_msg("Duplicate field.", arg.to_string())
+            .spanned(start..start + key.len())
+            .main_msg(format!("Field `{key}` is given more than once."))
+            .hint("Each field may be given only once.")
+            .into(),
+    )
+}
+
+/// Creates error that the required field `key` is missing.
+#[cold]
+fn missing_field(arg: &str, key: &str, kv_sep: char) -> ArgError {
+    ArgError::InvalidValue(
+        ArgErrCtx::from_msg("Missing field.", arg.to_string())
+            .main_msg(format!("Missing required field `{key}`."))
+            .hint(format!("Add the field as `{key}{kv_sep}<value>`."))
+            .into(),
+    )
+}
+
+/// Creates error that there are fields that were not taken.
+#[cold]
+fn unknown_fields(fields: &FieldMap) -> ArgError {
+    let list = |keys: &mut dyn Iterator<Item = &str>| {
+        keys.map(|k| format!("`{k}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let (key, start, _) = &fields.fields[0];
+    let unknown = list(&mut fields.fields.iter().map(|(k, _, _)| *k));
+    let mut hint = format!("Unknown fields: {unknown}.");
+    if !fields.known.is_empty() {
+        let known = list(&mut fields.known.iter().map(|k| k.as_str()));
+        hint += &format!(" Known fields are: {known}.");
+    }
+    ArgError::InvalidValue(
+        ArgErrCtx::from_msg("Unknown field.", fields.arg.to_string())
+            .spanned(*start..start + key.len())
+            .main_msg(format!("Unknown field `{key}`."))
+            .hint(hint)
+            .into(),
+    )
+}
+
+/// Creates error that the argument has no separator between key and value.
+#[cold]
+fn no_separator(arg: &str, sep: char) -> ArgError {
+    ArgError::NoValue(ArgErrCtx {
+        args: vec![arg.to_string().into()],
+        error_idx: 0,
+        args_start_index: 0,
+        show_program: true,
+        error_span: 0..arg.len(),
+        positioned: false,
+        message: format!("Missing separator `{sep}`.").into(),
+        long_message: Some(format!("Missing separator `{sep}` for key value pair.").into()),
+        hint: Some(format!("Use the separator `{sep}` to split the argument into key and value.").into()),
+        suggestions: vec![],
+        color: default_color_mode(),
+        anounce: default_anounce(),
+        max_width: None,
+        origins: vec![],
+        owner: None,
+        value_of: None,
+        program: None,
+        usage: None,
+        messages: None,
+    }.into())
+}
+
+/// Creates error that quoted field starting at `start` is not terminated.
+#[cold]
+fn unterminated_quote(arg: &str, start: usize) -> ArgError {
+    ArgError::FailedToParse(
+        ArgErrCtx::from_msg("Unterminated quote.", arg.to_string())
+            .spanned(start..start + 1)
+            .main_msg("Quoted field is not terminated.")
+            .hint("Add `\"` at the end of the field.")
+            .into(),
+    )
+}
+
+/// Creates error that there is unexpected quote at `pos`.
+#[cold]
+fn stray_quote(arg: &str, pos: usize) -> ArgError {
+    ArgError::FailedToParse(
+        ArgErrCtx::from_msg("Unexpected quote.", arg.to_string())
+            .spanned(pos..pos + 1)
+            .main_msg("Unexpected quote inside field.")
+            .hint(
+                "Quote the whole field and write quotes inside it as `\"\"` \
+                (e.g. `\"say \"\"hi\"\"\"`).",
+            )
+            .into(),
+    )
+}
+
+/// Creates error that there is something other than separator after quoted
+/// field at `pos`.
+#[cold]
+fn after_quote(arg: &str, pos: usize, sep: char) -> ArgError {
+    let len = arg[pos..].chars().next().map_or(0, char::len_utf8);
+    ArgError::FailedToParse(
+        ArgErrCtx::from_msg("Expected separator.", arg.to_string())
+            .spanned(pos..pos + len)
+            .main_msg(format!("Expected `{sep}` after quoted field."))
+            .hint(
+                "Write quotes inside quoted field as `\"\"` \
+                (e.g. `\"say \"\"hi\"\"\"`).",
+            )
+            .into(),
+    )
+}
+
+/// Splits `arg` into csv fields separated by `sep`. Returns the unquoted
+/// fields, their start in `arg` and positions in the fields where `"` was
+/// removed.
+fn csv_fields(
+    arg: &str,
+    sep: char,
+) -> Result<Vec<(String, usize, Vec<usize>)>> {
+    let mut res = vec![];
+    let mut chars = arg.char_indices().peekable();
+    loop {
+        let mut field = String::new();
+        let mut escapes = vec![];
+        let start;
+        if let Some((q, _)) = chars.next_if(|(_, c)| *c == '"') {
+            start = q + 1;
+            loop {
+                match chars.next() {
+                    None => return Err(unterminated_quote(arg, q)),
+                    Some((_, '"')) => {
+                        if chars.next_if(|(_, c)| *c == '"').is_none() {
+                            break;
+                        }
+                        escapes.push(field.len());
+                        field.push('"');
+                    }
+                    Some((_, c)) => field.push(c),
+                }
+            }
+            match chars.peek() {
+                Some((_, c)) if *c == sep => {}
+                Some((i, _)) => return Err(after_quote(arg, *i, sep)),
+                None => {}
+            }
+        } else {
+            start = chars.peek().map_or(arg.len(), |(i, _)| *i);
+            while let Some((i, c)) = chars.next_if(|(_, c)| *c != sep) {
+                if c == '"' {
+                    return Err(stray_quote(arg, i));
+                }
+                field.push(c);
+            }
+        }
+        res.push((field, start, escapes));
+        if chars.next().is_none() {
+            return Ok(res);
+        }
+    }
+}
+
+/// Finds the first separator that is not escaped with `\` and unescapes the
+/// key before it. Returns the unescaped key, position of the separator and
+/// positions in the unescaped key where `\` was removed.
+fn split_escaped(arg: &str, sep: char) -> (String, Option<usize>, Vec<usize>) {
+    let mut key = String::new();
+    let mut escapes = vec![];
+    let mut chars = arg.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == sep {
+            return (key, Some(i), escapes);
+        }
+        if c == '\\' {
+            if let Some((_, e)) = chars.next_if(|(_, e)| *e == sep || *e == c)
+            {
+                escapes.push(key.len());
+                key.push(e);
+                continue;
+            }
+        }
+        key.push(c);
+    }
+    (key, None, escapes)
+}
+
+/// Moves the span of error in unescaped key to the original argument.
+fn unescape_err(e: ArgError, arg: &str, escapes: &[usize]) -> ArgError {
+    let orig = |p: usize| p + escapes.iter().filter(|e| **e < p).count();
+    e.map_ctx(|c| {
+        let span = orig(c.error_span.start)..orig(c.error_span.end);
+        c.shift_span(0, arg.to_string()).spanned(span)
+    })
+}
@@ -1,6 +1,19 @@
 use proc_macro2::{Literal, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{punctuated::Punctuated, Data, DeriveInput, LitStr, Meta, Token};
+use syn::{
+    punctuated::Punctuated, Attribute, Data, DeriveInput, Expr, ExprLit,
+    ExprUnary, Fields, Ident, Lit, LitStr, Meta, MetaNameValue, Token, UnOp,
+    Visibility,
+};
+
+/// Configuration of the set from `#[arg(set)]`.
+#[derive(Debug, Default, PartialEq)]
+struct SetConf {
+    /// Value that means all the variants.
+    all: Option<String>,
+    /// Value that means no variants.
+    none: Option<String>,
+}
 
 /// Implementation of the derive proc macro for [`crate::FromArg`]
 pub fn derive_from_arg(item: TokenStream) -> TokenStream {
@@ -13,6 +26,17 @@ pub fn derive_from_arg(item: TokenStream) -> TokenStream {
 
     // Get the ident of the enum
     let ident = input.ident;
+    // Maximum distance of misspelled values from '#[arg(fuzzy)]'
+    let fuzzy = fuzzy_distance(&input.attrs);
+    // Whether values may be also given by the discriminants
+    // ('#[arg(numeric)]')
+    let numeric = has_flag(&input.attrs, "numeric");
+    // Whether the punctuation `-` and `_` is ignored
+    // ('#[arg(flexible_punct)]')
+    let flexible_punct = has_flag(&input.attrs, "flexible_punct");
+    // Set of the variants from '#[arg(set)]'
+    let set = set_conf(&input.attrs);
+    let vis = input.vis;
 
     // Check that it is enum
     let Data::Enum(input) = input.data else {
@@ -22,19 +46,64 @@ pub fn derive_from_arg(item: TokenStream) -> TokenStream {
     let mut res = TokenStream::new();
 
     let mut variants = vec![];
+    // All the names of each variant (including the aliases)
+    let mut names = vec![];
+    // Variant with the attribute '#[arg(other)]'
+    let mut other = None;
+    // Discriminants of the variants with '#[arg(numeric)]'
+    let mut nums = vec![];
+    // Identifiers of the variants (without the '#[arg(other)]' variant)
+    let mut idents = vec![];
 
     // Create match arms for all enum variants
     res.extend(input.variants.into_iter().flat_map(|v| {
+        let is_other = v.attrs.iter().any(|a| {
+            matches!(&a.meta, Meta::List(l) if l.path.is_ident("arg"))
+                && a.parse_args::<Ident>().is_ok_and(|i| i == "other")
+        });
+
+        if is_other {
+            let Fields::Unnamed(f) = v.fields else {
+                panic!("Variant with '#[arg(other)]' must have one field")
+            };
+            if f.unnamed.len() != 1 {
+                panic!("Variant with '#[arg(other)]' must have one field")
+            }
+            if other.is_some() {
+                panic!("Only one variant may have '#[arg(other)]'")
+            }
+            other = Some((v.ident, f.unnamed[0].ty.clone()));
+            return TokenStream::new().into_iter();
+        }
+
         // Ensure the enum has no fields.
         if !v.fields.is_empty() {
             panic!("Enum variants may not have any fields")
         }
 
         let ident = v.ident;
+        idents.push(ident.clone());
+
+        if numeric {
+            let Some((_, disc)) = &v.discriminant else {
+                panic!(
+                    "Variant `{ident}` must have explicit discriminant with \
+                    '#[arg(numeric)]'"
+                )
+            };
+            let num = int_discriminant(disc).unwrap_or_else(|| {
+                panic!("Discriminant of `{ident}` must be integer literal")
+            });
+            if nums.iter().any(|(n, _)| *n == num) {
+                panic!("Duplicate discriminant `{num}` of `{ident}`")
+            }
+            nums.push((num, ident.clone()));
+        }
 
         // Get the lowercase name of the enum as the first literal in the match
         let variant = ident.to_string().to_lowercase();
         let mut res = Literal::string(&variant).into_token_stream();
+        let mut var_names = vec![variant.clone()];
         variants.push(variant);
 
         // Add the variants from the '#[arg()]' attributes
@@ -51,41 +120,691 @@ pub fn derive_from_arg(item: TokenStream) -> TokenStream {
                 quote! { | }.to_tokens(&mut res);
                 vars.to_tokens(&mut res);
             }
+            var_names.extend(vars.iter().map(|v| v.value()));
         }
+        names.push(var_names);
 
         quote! { => Ok(Self::#ident), }.to_tokens(&mut res);
         res.into_iter()
     }));
 
-    let mut hint = "Valid options are: ".to_string();
+    if set.is_some() && other.is_some() {
+        panic!("'#[arg(set)]' cannot be used with '#[arg(other)]'")
+    }
+
+    // There is no hint if there are no named variants (e.g. the only variant
+    // is '#[arg(other)]').
+    let hint = (!variants.is_empty()).then(|| {
+        let mut hint = "Valid options are: ".to_string();
+        for (i, v) in variants.iter().enumerate() {
+            match nums.get(i) {
+                Some((n, _)) => hint += &format!("`{v}` ({n}), "),
+                None => hint += &format!("`{v}`, "),
+            }
+        }
+        hint.pop();
+        hint.pop();
+        if other.is_some() {
+            hint += " or any custom name";
+        }
+        hint.push('.');
+        Literal::string(&hint).to_token_stream()
+    });
+
+    let variant_names = quote! {
+        <Self as pareg::FromArg>::arg_variants()
+            .iter()
+            .map(|v| v.to_string())
+            .collect()
+    };
+
+    // All the names of all the variants as '&[&[&str]]'
+    let all_names = quote! { &[#(&[#(#names),*]),*] };
+
+    // Values that differ only by the punctuation are matched to the variant.
+    let flexible = if flexible_punct {
+        quote! {
+            if let Some(i) = pareg::find_flexible_punct(lower, #all_names) {
+                return Self::from_arg(Self::arg_variants()[i]);
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    // Misspelled values are matched to the closest variant if it is the only
+    // one within the distance.
+    let (fuzzy, fuzzy_near) = if let Some(dist) = fuzzy {
+        let dist = Literal::usize_unsuffixed(dist);
+        let fuzzy = quote! {
+            let fuzzy = pareg::FuzzyMatch::find(lower, #all_names, #dist);
+            if let pareg::FuzzyMatch::Unique(i) = fuzzy {
+                return Self::from_arg(Self::arg_variants()[i]);
+            }
+        };
+        let fuzzy_near = quote! {
+            if let pareg::FuzzyMatch::Closest(c) = fuzzy {
+                near = c;
+            }
+        };
+        (fuzzy, fuzzy_near)
+    } else {
+        (TokenStream::new(), TokenStream::new())
+    };
+
+    // Unknown values suggest the closest names with '#[arg(fuzzy)]'. If there
+    // are none, the variants that start with the value are suggested.
+    let near = quote! {
+        let mut near = vec![];
+        #fuzzy_near
+        if near.is_empty() {
+            near = pareg::prefix_matches(lower, #all_names);
+        }
+    };
+    let err_hint = match &hint {
+        Some(hint) => quote! {
+            match pareg::FuzzyMatch::Closest(near.clone()).hint() {
+                Some(h) => Some(format!("{h} {}", #hint).into()),
+                None => <Self as pareg::FromArg>::hint(),
+            }
+        },
+        None => quote! { None },
+    };
+    let err_sugg = quote! {
+        if near.is_empty() {
+            #variant_names
+        } else {
+            near.iter().map(|v| v.to_string()).collect()
+        }
+    };
+
+    // Numbers are matched to the discriminants. Unknown numbers are parsed
+    // into the '#[arg(other)]' variant if there is one.
+    let numeric = if numeric {
+        let numeric_hint = match &hint {
+            Some(hint) => quote! { .hint(#hint) },
+            None => TokenStream::new(),
+        };
+        let unknown = if other.is_some() {
+            quote! { {} }
+        } else {
+            quote! {
+                return Err(pareg::ArgError::FailedToParse(
+                    pareg::ArgErrCtx::from_msg(
+                        "Unknown number.",
+                        arg.to_string(),
+                    )
+                    .main_msg(format!("No option has the number `{n}`."))
+                    #numeric_hint
+                    .suggest(
+                        <Self as pareg::FromArg>::arg_variants()
+                            .iter()
+                            .copied()
+                    )
+                    .into()
+                ))
+            }
+        };
+        let (nums, idents): (Vec<_>, Vec<_>) = nums
+            .into_iter()
+            .map(|(n, i)| (Literal::i128_unsuffixed(n), i))
+            .unzip();
+        quote! {
+            if let Ok(n) = arg.trim().parse::<i128>() {
+                match n {
+                    #(#nums => return Ok(Self::#idents),)*
+                    _ => #unknown
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    // Unknown values are parsed into the '#[arg(other)]' variant. The value
+    // is trimmed the same way as when matching the other variants.
+    let fallback = if let Some((ident, ty)) = other {
+        let pat = if flexible_punct || !fuzzy.is_empty() {
+            quote! { lower }
+        } else {
+            quote! { _ }
+        };
+        quote! {
+            #pat => {
+                #numeric
+                #flexible
+                #fuzzy
+                let trimmed = arg.trim_start();
+                let start = arg.len() - trimmed.len();
+                <#ty as pareg::FromArg<'a>>::from_arg(trimmed.trim_end())
+                    .map(Self::#ident)
+                    .map_err(|e| e.shift_span(start, arg.into()))
+            },
+        }
+    } else {
+        quote! {
+            lower => {
+                #numeric
+                #flexible
+                #fuzzy
+                #near
+                Err(pareg::ArgError::FailedToParse(pareg::ArgErrCtx {
+                    args: vec![arg.to_string().into()],
+                    error_idx: 0,
+                    args_start_index: 0,
+                    show_program: true,
+                    error_span: 0..arg.len(),
+                    positioned: false,
+                    message: "Unknown option.".into(),
+                    long_message: Some(
+                        format!("Unknown option `{arg}`.").into()
+                    ),
+                    hint: #err_hint,
+                    suggestions: #err_sugg,
+                    color: pareg::default_color_mode(),
+                    anounce: pareg::default_anounce(),
+                    max_width: None,
+                    origins: vec![],
+                    owner: None,
+                    value_of: None,
+                    program: None,
+                    usage: None,
+                    messages: None,
+                }.into()))
+            },
+        }
+    };
+
+    let hint = match hint {
+        Some(hint) => quote! { Some(#hint.into()) },
+        None => quote! { None },
+    };
+
+    let set = if let Some(conf) = set {
+        derive_set(&ident, &vis, &idents, &variants, conf)
+    } else {
+        TokenStream::new()
+    };
+
+    quote! {
+        #set
+
+        impl<'a> pareg::FromArg<'a> for #ident {
+            fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+                match arg.trim().to_lowercase().as_str() {
+                    #res
+                    #fallback
+                }
+            }
+
+            fn arg_variants() -> &'static [&'static str] {
+                &[#(#variants),*]
+            }
+
+            fn hint() -> Option<::std::borrow::Cow<'static, str>> {
+                #hint
+            }
+        }
+    }
+}
+
+/// Generates set of the variants of the enum `ident` that is parsed from
+/// values separated by `+` or `,` (see `#[arg(set)]`).
+fn derive_set(
+    ident: &Ident,
+    vis: &Visibility,
+    idents: &[Ident],
+    variants: &[String],
+    conf: SetConf,
+) -> TokenStream {
+    if idents.len() > 64 {
+        panic!(
+            "'#[arg(set)]' may be used only on enums with up to 64 variants"
+        )
+    }
+
+    let set = Ident::new(&format!("{ident}Set"), ident.span());
+    let doc = format!(
+        "Set of the values of [`{ident}`] parsed from values separated by \
+        `+` or `,`."
+    );
+    let bits: Vec<_> =
+        (0..idents.len()).map(Literal::usize_unsuffixed).collect();
+    let all_bits = match idents.len() {
+        64 => u64::MAX,
+        n => (1 << n) - 1,
+    };
+    let all_bits = Literal::u64_unsuffixed(all_bits);
+
+    let mut usage = "Combine the values with `+` or `,`.".to_string();
+    let mut special = TokenStream::new();
+    for (value, res, what) in [
+        (&conf.all, quote! { Self::all() }, "all the values"),
+        (&conf.none, quote! { Self::new() }, "no values"),
+    ] {
+        if let Some(v) = value {
+            usage += &format!(" Use `{v}` for {what}.");
+            special.extend(quote! {
+                if arg.trim().eq_ignore_ascii_case(#v) {
+                    return Ok(#res);
+                }
+            });
+        }
+    }
+    let mut hint = format!("{usage} Valid options are: ");
     for v in variants {
         hint += &format!("`{v}`, ");
     }
     hint.pop();
     hint.pop();
     hint.push('.');
-    let hint = Literal::string(&hint).to_token_stream();
 
     quote! {
-        impl<'a> pareg::FromArg<'a> for #ident {
-            fn from_arg(arg: &'a str) -> pareg::Result<Self> {
-                match arg.trim().to_lowercase().as_str() {
-                    #res
-                    _ => {
-                        Err(pareg::ArgError::FailedToParse(pareg::ArgErrCtx {
-                            args: vec![arg.into()],
-                            error_idx: 0,
-                            error_span: 0..arg.len(),
-                            message: "Unknown option.".into(),
-                            long_message: Some(
-                                format!("Unknown option `{arg}`.").into()
-                            ),
-                            hint: Some(#hint.into()),
-                            color: Default::default(),
-                        }.into()))
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+        #vis struct #set(u64);
+
+        impl #set {
+            /// Creates empty set.
+            pub fn new() -> Self {
+                Self(0)
+            }
+
+            /// Creates set with all the values.
+            pub fn all() -> Self {
+                Self(#all_bits)
+            }
+
+            /// Checks whether the set contains the value.
+            pub fn contains(&self, value: #ident) -> bool {
+                self.0 & Self::bit(&value) != 0
+            }
+
+            /// Adds the value to the set. Returns `false` if it was already
+            /// in the set.
+            pub fn insert(&mut self, value: #ident) -> bool {
+                let bit = Self::bit(&value);
+                let res = self.0 & bit == 0;
+                self.0 |= bit;
+                res
+            }
+
+            /// Removes the value from the set. Returns `false` if it was not
+            /// in the set.
+            pub fn remove(&mut self, value: #ident) -> bool {
+                let bit = Self::bit(&value);
+                let res = self.0 & bit != 0;
+                self.0 &= !bit;
+                res
+            }
+
+            /// Gets the number of values in the set.
+            pub fn len(&self) -> usize {
+                self.0.count_ones() as usize
+            }
+
+            /// Checks whether the set is empty.
+            pub fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+
+            /// Gets the bits of the set. The bit `n` is the `n`th variant.
+            pub fn bits(&self) -> u64 {
+                self.0
+            }
+
+            /// Iterates over the values in the set in the order of the
+            /// variants.
+            pub fn iter(&self) -> impl Iterator<Item = #ident> {
+                let bits = self.0;
+                (0..64).filter(move |i| bits & (1 << i) != 0).filter_map(
+                    |i| match i {
+                        #(#bits => Some(#ident::#idents),)*
+                        _ => None,
                     },
+                )
+            }
+
+            fn bit(value: &#ident) -> u64 {
+                match value {
+                    #(#ident::#idents => 1 << #bits,)*
+                }
+            }
+        }
+
+        impl<'a> pareg::FromArg<'a> for #set {
+            fn from_arg(arg: &'a str) -> pareg::Result<Self> {
+                #special
+                let mut res = Self::new();
+                let mut start = 0;
+                for part in arg.split(['+', ',']) {
+                    let value = <#ident as pareg::FromArg<'a>>::from_arg(part)
+                        .map_err(|e| {
+                            e.shift_span(start, arg.into()).map_ctx(|c| {
+                                let hint = match &c.hint {
+                                    Some(h) => format!("{} {h}", #usage),
+                                    None => #usage.to_string(),
+                                };
+                                c.hint(hint)
+                            })
+                        })?;
+                    if !res.insert(value) {
+                        let trimmed = part.trim_start();
+                        let s = start + part.len() - trimmed.len();
+                        let trimmed = trimmed.trim_end();
+                        return Err(pareg::ArgError::InvalidValue(
+                            pareg::ArgErrCtx::from_msg(
+                                "Duplicate value.",
+                                arg.to_string(),
+                            )
+                            .spanned(s..s + trimmed.len())
+                            .main_msg(format!(
+                                "The value `{trimmed}` is given more than \
+                                once."
+                            ))
+                            .hint(#hint)
+                            .into(),
+                        ));
+                    }
+                    start += part.len() + 1;
+                }
+                Ok(res)
+            }
+
+            fn hint() -> Option<::std::borrow::Cow<'static, str>> {
+                Some(#hint.into())
+            }
+        }
+    }
+}
+
+/// Gets the configuration of the set from the enum attribute `#[arg(set)]`
+/// or `#[arg(set(all = "...", none = "..."))]`.
+fn set_conf(attrs: &[Attribute]) -> Option<SetConf> {
+    type Options = Punctuated<MetaNameValue, Token![,]>;
+    let mut res = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("arg")) {
+        match attr.parse_args::<Meta>() {
+            Ok(Meta::Path(p)) if p.is_ident("set") => {
+                res.get_or_insert_with(SetConf::default);
+            }
+            Ok(Meta::List(l)) if l.path.is_ident("set") => {
+                let conf = res.get_or_insert_with(SetConf::default);
+                let args = l
+                    .parse_args_with(Options::parse_terminated)
+                    .expect("Expected '#[arg(set(all = \"...\"))]'");
+                for arg in args {
+                    let Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    }) = arg.value
+                    else {
+                        panic!("Values in '#[arg(set(...))]' must be strings")
+                    };
+                    let value = Some(value.value().to_lowercase());
+                    if arg.path.is_ident("all") {
+                        conf.all = value;
+                    } else if arg.path.is_ident("none") {
+                        conf.none = value;
+                    } else {
+                        panic!(
+                            "Unknown set option. Expected 'all' or 'none' in \
+                            '#[arg(set(...))]'"
+                        )
+                    }
                 }
             }
+            _ => {}
         }
     }
+    res
+}
+
+/// Gets the maximum distance from the enum attribute `#[arg(fuzzy)]` or
+/// `#[arg(fuzzy(max_distance = N))]`. The default distance is 1.
+fn fuzzy_distance(attrs: &[Attribute]) -> Option<usize> {
+    let mut res = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("arg")) {
+        let meta: Meta = attr
+            .parse_args()
+            .expect("Invalid arguments to the attribute '#[arg(...)]'");
+        match meta {
+            Meta::Path(p) if p.is_ident("fuzzy") => res = Some(1),
+            Meta::Path(p)
+                if p.is_ident("numeric")
+                    || p.is_ident("flexible_punct")
+                    || p.is_ident("set") => {}
+            Meta::List(l) if l.path.is_ident("set") => {}
+            Meta::List(l) if l.path.is_ident("fuzzy") => {
+                let arg: MetaNameValue = l
+                    .parse_args()
+                    .expect("Expected '#[arg(fuzzy(max_distance = N))]'");
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Int(dist),
+                    ..
+                }) = arg.value
+                else {
+                    panic!("'max_distance' must be integer")
+                };
+                if !arg.path.is_ident("max_distance") {
+                    panic!("Expected '#[arg(fuzzy(max_distance = N))]'")
+                }
+                res = Some(
+                    dist.base10_parse()
+                        .expect("'max_distance' must be integer"),
+                );
+            }
+            _ => panic!(
+                "Unknown enum attribute. Expected '#[arg(fuzzy)]', \
+                '#[arg(numeric)]', '#[arg(flexible_punct)]' or '#[arg(set)]'"
+            ),
+        }
+    }
+    res
+}
+
+/// Checks whether the enum has the attribute `#[arg(<flag>)]` (e.g.
+/// `#[arg(numeric)]`).
+fn has_flag(attrs: &[Attribute], flag: &str) -> bool {
+    attrs.iter().filter(|a| a.path().is_ident("arg")).any(|a| {
+        a.parse_args::<Meta>()
+            .is_ok_and(|m| matches!(m, Meta::Path(p) if p.is_ident(flag)))
+    })
+}
+
+/// Gets the value of discriminant that is integer literal (e.g. `1` or
+/// `-1`).
+fn int_discriminant(disc: &Expr) -> Option<i128> {
+    match disc {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(i), ..
+        }) => i.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => int_discriminant(expr).map(|n: i128| -n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Only one variant")]
+    fn multiple_other() {
+        derive_from_arg(quote! {
+            enum Target {
+                #[arg(other)]
+                A(String),
+                #[arg(other)]
+                B(String),
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "must have one field")]
+    fn other_without_field() {
+        derive_from_arg(quote! {
+            enum Target {
+                All,
+                #[arg(other)]
+                Named,
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "must have one field")]
+    fn other_named_field() {
+        derive_from_arg(quote! {
+            enum Target {
+                #[arg(other)]
+                Named { name: String },
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown enum attribute")]
+    fn unknown_enum_attr() {
+        derive_from_arg(quote! {
+            #[arg(other)]
+            enum Target {
+                All,
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "max_distance = N")]
+    fn fuzzy_unknown_option() {
+        derive_from_arg(quote! {
+            #[arg(fuzzy(distance = 1))]
+            enum Target {
+                All,
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "must have explicit discriminant")]
+    fn numeric_missing_discriminant() {
+        derive_from_arg(quote! {
+            #[arg(numeric)]
+            enum Level {
+                Error = 1,
+                Warn,
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate discriminant `1`")]
+    fn numeric_duplicate_discriminant() {
+        derive_from_arg(quote! {
+            #[arg(numeric)]
+            enum Level {
+                Error = 1,
+                Warn = 0x1,
+            }
+        });
+    }
+
+    #[test]
+    fn numeric_attr() {
+        let attrs = |t: TokenStream| {
+            syn::parse2::<DeriveInput>(t)
+                .map(|i| has_flag(&i.attrs, "numeric"))
+        };
+        assert!(!attrs(quote! { #[arg(fuzzy)] enum A {} }).unwrap());
+        assert!(
+            attrs(quote! { #[arg(fuzzy)] #[arg(numeric)] enum A {} }).unwrap()
+        );
+    }
+
+    #[test]
+    fn flexible_punct_attr() {
+        let input: DeriveInput = syn::parse2(quote! {
+            #[arg(flexible_punct)]
+            #[arg(fuzzy)]
+            enum A {}
+        })
+        .unwrap();
+        assert!(has_flag(&input.attrs, "flexible_punct"));
+        assert!(!has_flag(&input.attrs, "numeric"));
+        assert_eq!(fuzzy_distance(&input.attrs), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be used with '#[arg(other)]'")]
+    fn set_with_other() {
+        derive_from_arg(quote! {
+            #[arg(set)]
+            enum Target {
+                All,
+                #[arg(other)]
+                Named(String),
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown set option")]
+    fn set_unknown_option() {
+        derive_from_arg(quote! {
+            #[arg(set(any = "any"))]
+            enum Target {
+                All,
+            }
+        });
+    }
+
+    #[test]
+    fn set_attr() {
+        let attrs = |t: TokenStream| {
+            syn::parse2::<DeriveInput>(t).map(|i| set_conf(&i.attrs))
+        };
+        assert_eq!(attrs(quote! { #[arg(fuzzy)] enum A {} }).unwrap(), None);
+        assert_eq!(
+            attrs(quote! { #[arg(set)] enum A {} }).unwrap(),
+            Some(SetConf::default())
+        );
+        assert_eq!(
+            attrs(quote! { #[arg(set(all = "ALL", none = "-"))] enum A {} })
+                .unwrap(),
+            Some(SetConf {
+                all: Some("all".into()),
+                none: Some("-".into()),
+            })
+        );
+        let out = derive_from_arg(quote! {
+            #[arg(set(all = "all"))]
+            pub(crate) enum Feature {
+                Audio,
+            }
+        })
+        .to_string();
+        assert!(out.contains("pub (crate) struct FeatureSet (u64)"));
+    }
+
+    #[test]
+    fn fuzzy_distance_attr() {
+        let attrs = |t: TokenStream| {
+            syn::parse2::<DeriveInput>(t).map(|i| fuzzy_distance(&i.attrs))
+        };
+        assert_eq!(attrs(quote! { enum A {} }).unwrap(), None);
+        assert_eq!(
+            attrs(quote! { #[arg(fuzzy)] enum A {} }).unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            attrs(quote! { #[arg(fuzzy(max_distance = 3))] enum A {} })
+                .unwrap(),
+            Some(3)
+        );
+    }
 }
@@ -1,7 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{
-    parse::Parser, parse2, punctuated::Punctuated, Expr, Ident, LitStr, Token,
+    parse::Parser, parse2, punctuated::Punctuated, Expr, LitStr, Token,
 };
 
 pub fn proc_parsef(args: TokenStream, part: bool) -> TokenStream {
@@ -50,12 +50,53 @@ pub fn proc_parsef(args: TokenStream, part: bool) -> TokenStream {
             panic!("Missing closing '}}'");
         };
 
-        if pos == 0 {
-            let arg = input.next();
+        // `{~text}` is literal matched case insensitively and with flexible
+        // whitespace.
+        if let Some(text) = p[..pos].strip_prefix('~') {
+            let lit = LitStr::new(text, span);
+            args.extend(quote! {
+                pareg::ParseFArg::StrFlex {
+                    text: #lit.into(),
+                    case_insensitive: true,
+                    flex_ws: true,
+                },
+            });
+            p = &p[pos + 1..];
+            continue;
+        }
+
+        let (name, fmt) = split_fmt(&p[..pos]);
+        if name == "_" {
+            let sink = skip_sink(fmt, &p[pos + 1..]);
+            args.extend(quote! { pareg::ParseFArg::Arg(&mut #sink), });
+            p = &p[pos + 1..];
+            continue;
+        }
+
+        let arg = if name.is_empty() {
+            input.next().to_token_stream()
+        } else {
+            // The name may be any place expression such as `res.adr.0` or
+            // `ip[1]`.
+            match syn::parse_str::<Expr>(name) {
+                Ok(e) => quote! { &mut #e },
+                Err(_) => {
+                    return syn::Error::new(
+                        span,
+                        format!("Invalid expression `{name}` in placeholder."),
+                    )
+                    .to_compile_error();
+                }
+            }
+        };
+
+        if fmt.is_empty() {
             args.extend(quote! { pareg::ParseFArg::Arg(#arg), });
         } else {
-            let id = Ident::new(&p[..pos], span);
-            args.extend(quote! { pareg::ParseFArg::Arg(&mut #id), });
+            let fmt = LitStr::new(fmt, span);
+            args.extend(quote! {
+                pareg::ParseFArg::FmtArg(#arg, pareg::ReadFmt::new(#fmt)),
+            });
         }
 
         p = &p[pos + 1..];
@@ -71,3 +112,78 @@ pub fn proc_parsef(args: TokenStream, part: bool) -> TokenStream {
         }
     }
 }
+
+/// Splits the placeholder content into the name and the format. The format
+/// is after the first `:` that is not part of `::`.
+fn split_fmt(s: &str) -> (&str, &str) {
+    let mut i = 0;
+    while let Some(p) = s[i..].find(':').map(|p| p + i) {
+        if s[p + 1..].starts_with(':') {
+            i = p + 2;
+        } else {
+            return (&s[..p], &s[p + 1..]);
+        }
+    }
+    (s, "")
+}
+
+/// Gets the sink from [`crate::skip`] for the placeholder `{_:fmt}`. `rest`
+/// is the rest of the pattern after the placeholder.
+fn skip_sink(fmt: &str, rest: &str) -> TokenStream {
+    if fmt.is_empty() {
+        let mut chars = rest.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c @ ('{' | '}')), Some(d)) if c == d => {
+                quote! { pareg::skip::Until(#c) }
+            }
+            (Some(c), _) if c != '{' && c != '}' => {
+                quote! { pareg::skip::Until(#c) }
+            }
+            _ => panic!(
+                "`{{_}}` must be followed by literal character. Use format \
+                (e.g. `{{_:ws}}`) to specify what to skip."
+            ),
+        };
+    }
+
+    if fmt == "ws" {
+        return quote! { pareg::skip::Ws };
+    }
+
+    if let Ok(n) = fmt.parse::<usize>() {
+        return quote! { pareg::skip::Chars(#n) };
+    }
+
+    if let Some(c) = fmt.strip_prefix("until=") {
+        let mut chars = c.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return quote! { pareg::skip::Until(#c) };
+        }
+    }
+
+    panic!(
+        "Invalid format `{fmt}` for `{{_}}`. Expected `ws`, number or \
+        `until=<char>`."
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_fmt_paths() {
+        assert_eq!(split_fmt("res.adr.0"), ("res.adr.0", ""));
+        assert_eq!(split_fmt("res.mask:x"), ("res.mask", "x"));
+        assert_eq!(split_fmt("a::B.c:strict"), ("a::B.c", "strict"));
+        assert_eq!(split_fmt(":x"), ("", "x"));
+    }
+
+    #[test]
+    fn invalid_expr() {
+        let res = proc_parsef(quote! { &mut r, "{res.}" }, false);
+        let res = res.to_string();
+        assert!(res.contains("compile_error"), "{res}");
+        assert!(res.contains("Invalid expression `res.`"), "{res}");
+    }
+}
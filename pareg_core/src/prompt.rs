@@ -0,0 +1,81 @@
+use std::io::{BufRead, Write};
+
+use crate::{from_arg::parse_hinted, ColorMode, FromArg, Result};
+
+/// Options for prompting for missing values. See
+/// [`crate::Pareg::next_arg_or_prompt_opts`].
+#[derive(Debug, Clone)]
+pub struct PromptOpts {
+    /// How many more times the user is prompted if the value is invalid.
+    pub retries: usize,
+    /// Write the read value to the output. This is useful if the input
+    /// doesn't echo itself (e.g. it is not terminal).
+    pub echo: bool,
+    /// Value that is used if the user enters empty line. It is shown in
+    /// brackets after the prompt.
+    pub default: Option<String>,
+    /// Color mode of the errors printed when the value is invalid.
+    pub color: ColorMode,
+}
+
+impl Default for PromptOpts {
+    fn default() -> Self {
+        Self {
+            retries: 2,
+            echo: false,
+            default: None,
+            color: ColorMode::default(),
+        }
+    }
+}
+
+/// Prompts for value on `output` and reads it from `input`. If the value is
+/// invalid, the error is printed and the user is prompted again. Returns
+/// [`None`] if the input ends.
+pub(crate) fn prompt<T>(
+    prompt: &str,
+    opts: &PromptOpts,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Option<T>>
+where
+    T: for<'a> FromArg<'a>,
+{
+    let mut line = String::new();
+    let mut attempt = 0;
+    loop {
+        match &opts.default {
+            Some(d) => write!(output, "{prompt} [{d}]: ")?,
+            None => write!(output, "{prompt}: ")?,
+        }
+        output.flush()?;
+
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            writeln!(output)?;
+            return Ok(None);
+        }
+        let value = line.strip_suffix('\n').unwrap_or(&line);
+        let value = value.strip_suffix('\r').unwrap_or(value);
+        if opts.echo {
+            writeln!(output, "{value}")?;
+        }
+        let value = match &opts.default {
+            Some(d) if value.is_empty() => d.as_str(),
+            _ => value,
+        };
+
+        let err = match parse_hinted(value) {
+            Ok(v) => return Ok(Some(v)),
+            Err(e) => e.color_mode(opts.color),
+        };
+        if attempt >= opts.retries {
+            return Err(err);
+        }
+        attempt += 1;
+        match err.ctx() {
+            Some(ctx) => write!(output, "{ctx:#}")?,
+            None => writeln!(output, "{err}")?,
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use alloc::string::String;
+
+/// Joins the arguments into single string that POSIX shell (e.g. `sh -c`)
+/// splits back into the same arguments. Arguments that contain only safe
+/// characters are not quoted. Other arguments are quoted with single quotes.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::shell_quote_join;
+///
+/// assert_eq!(
+///     "cargo build --release",
+///     shell_quote_join(&["cargo", "build", "--release"])
+/// );
+/// assert_eq!(
+///     r#"echo 'hello world' '' 'it'\''s'"#,
+///     shell_quote_join(&["echo", "hello world", "", "it's"])
+/// );
+/// ```
+pub fn shell_quote_join(args: &[impl AsRef<str>]) -> String {
+    let mut res = String::new();
+    for (i, a) in args.iter().enumerate() {
+        if i != 0 {
+            res.push(' ');
+        }
+        shell_quote(&mut res, a.as_ref());
+    }
+    res
+}
+
+/// Joins the arguments into single string that is split back into the same
+/// arguments by the MSVC runtime and `CommandLineToArgvW` on Windows.
+/// Arguments without whitespace and `"` are not quoted. Other arguments are
+/// quoted with `"` and backslashes are escaped only where it is necessary.
+///
+/// Note that the first argument (program name) is split by different rules
+/// on Windows and it cannot contain `"`.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::cmd_quote_join;
+///
+/// assert_eq!(
+///     r#"app C:\dir\ "a b" "" "say \"hi\"" "my dir\\""#,
+///     cmd_quote_join(&[
+///         "app",
+///         r"C:\dir\",
+///         "a b",
+///         "",
+///         r#"say "hi""#,
+///         r"my dir\",
+///     ])
+/// );
+/// ```
+pub fn cmd_quote_join(args: &[impl AsRef<str>]) -> String {
+    let mut res = String::new();
+    for (i, a) in args.iter().enumerate() {
+        if i != 0 {
+            res.push(' ');
+        }
+        cmd_quote(&mut res, a.as_ref());
+    }
+    res
+}
+
+/// Appends the argument quoted for POSIX shell to `res`.
+fn shell_quote(res: &mut String, arg: &str) {
+    let safe = |c: char| {
+        c.is_ascii_alphanumeric()
+            || matches!(c, '_' | '@' | '%' | '+' | '=' | ':' | ',' | '.')
+            || matches!(c, '/' | '-')
+    };
+    if !arg.is_empty() && arg.chars().all(safe) {
+        res.push_str(arg);
+        return;
+    }
+
+    res.push('\'');
+    for c in arg.chars() {
+        if c == '\'' {
+            res.push_str(r"'\''");
+        } else {
+            res.push(c);
+        }
+    }
+    res.push('\'');
+}
+
+/// Appends the argument quoted by the rules of `CommandLineToArgvW` to
+/// `res`.
+fn cmd_quote(res: &mut String, arg: &str) {
+    let special = |c| matches!(c, ' ' | '\t' | '\n' | '\x0b' | '"');
+    if !arg.is_empty() && !arg.contains(special) {
+        res.push_str(arg);
+        return;
+    }
+
+    res.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            // Backslashes before `"` are escaped and so is the `"`.
+            '"' => {
+                push_n(res, '\\', backslashes * 2 + 1);
+                res.push('"');
+                backslashes = 0;
+            }
+            c => {
+                push_n(res, '\\', backslashes);
+                res.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing `"` must be escaped.
+    push_n(res, '\\', backslashes * 2);
+    res.push('"');
+}
+
+/// Appends `c` `n` times to `res`.
+fn push_n(res: &mut String, c: char, n: usize) {
+    res.extend(core::iter::repeat_n(c, n));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    const ARGS: &[&[&str]] = &[
+        &["simple", "--flag=5", "a/b.c"],
+        &["with space", "", "it's", "'", "''"],
+        &["new\nline", "tab\there", "\"quoted\"", "$HOME", "`ls`", "*"],
+        &[r"C:\dir\", r"a\\b", r#"\""#, r#"\\""#, r"\", r"x\ y"],
+        &["ünïcödé", "žluťoučký kůň", "\\\n\\"],
+    ];
+
+    /// Splits the command line by the rules of POSIX shell (only quotes and
+    /// backslashes).
+    fn shell_split(s: &str) -> Vec<String> {
+        let mut res = vec![];
+        let mut cur: Option<String> = None;
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                ' ' | '\t' | '\n' => res.extend(cur.take()),
+                '\'' => {
+                    let cur = cur.get_or_insert_default();
+                    cur.extend(chars.by_ref().take_while(|c| *c != '\''));
+                }
+                '\\' => cur.get_or_insert_default().extend(chars.next()),
+                c => cur.get_or_insert_default().push(c),
+            }
+        }
+        res.extend(cur);
+        res
+    }
+
+    /// Splits the command line by the rules of `CommandLineToArgvW` (for
+    /// arguments after the program name).
+    fn cmd_split(s: &str) -> Vec<String> {
+        let mut res = vec![];
+        let mut cur: Option<String> = None;
+        let mut quoted = false;
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                ' ' | '\t' if !quoted => res.extend(cur.take()),
+                '\\' => {
+                    let mut n = 1;
+                    while chars.next_if_eq(&'\\').is_some() {
+                        n += 1;
+                    }
+                    let cur = cur.get_or_insert_default();
+                    if chars.peek() == Some(&'"') {
+                        push_n(cur, '\\', n / 2);
+                        if n % 2 == 1 {
+                            cur.push('"');
+                            chars.next();
+                        }
+                    } else {
+                        push_n(cur, '\\', n);
+                    }
+                }
+                '"' => {
+                    cur.get_or_insert_default();
+                    if quoted && chars.next_if_eq(&'"').is_some() {
+                        cur.get_or_insert_default().push('"');
+                    } else {
+                        quoted = !quoted;
+                    }
+                }
+                c => cur.get_or_insert_default().push(c),
+            }
+        }
+        res.extend(cur);
+        res
+    }
+
+    #[test]
+    fn shell_round_trip() {
+        for args in ARGS {
+            let joined = shell_quote_join(args);
+            assert_eq!(shell_split(&joined), *args, "{joined}");
+        }
+        assert_eq!(shell_quote_join(&[] as &[&str]), "");
+    }
+
+    #[test]
+    fn cmd_round_trip() {
+        for args in ARGS {
+            let joined = cmd_quote_join(args);
+            assert_eq!(cmd_split(&joined), *args, "{joined}");
+        }
+        assert_eq!(cmd_quote_join(&[r#"a\"b"#]), r#""a\\\"b""#);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn real_shell() {
+        use std::process::Command;
+
+        for args in ARGS {
+            let script =
+                "printf '%s\\0' ".to_string() + &shell_quote_join(args);
+            let out = Command::new("sh").arg("-c").arg(&script).output();
+            let Ok(out) = out else {
+                return;
+            };
+            let out = String::from_utf8(out.stdout).unwrap();
+            let split: Vec<_> = out.split_terminator('\0').collect();
+            assert_eq!(split, *args, "{script}");
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use alloc::{format, string::String};
+use core::str::FromStr;
+
+use crate::{
+    ArgError, FromArgStr, FromRead, ParseResult, ReadFmt, Reader, Result,
+    Tuple,
+};
+
+/// String in quotes `"` or `'`. The string ends at the matching quote that is
+/// not escaped. The supported escapes are `\"`, `\'`, `\\`, `\n` and `\t`.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{parsef, FromArg, ParseFArg, QuotedString};
+///
+/// let s = QuotedString::from_arg(r#""say \"hi\"\n""#).unwrap();
+/// assert_eq!("say \"hi\"\n", s.0);
+/// assert!(QuotedString::from_arg("'unterminated").is_err());
+///
+/// let mut name = QuotedString::default();
+/// parsef(
+///     &mut "name='John Doe'".into(),
+///     [ParseFArg::Str("name=".into()), ParseFArg::Arg(&mut name)],
+/// )
+/// .unwrap();
+/// assert_eq!("John Doe", name.0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuotedString(pub String);
+
+/// String that may be quoted. If it starts with quote, it is read as
+/// [`QuotedString`]. Otherwise it is read until one of the delimiters or
+/// until the end. The delimiters are the characters of the format (e.g.
+/// `{:,;}`). With empty format, the delimiters are whitespace characters.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{FromRead, MaybeQuoted};
+///
+/// let read = |s: &str, fmt: &str| {
+///     MaybeQuoted::from_read_fmt(&mut s.into(), &fmt.into()).res.unwrap().0
+/// };
+/// assert_eq!("a b", read("'a b' c", ""));
+/// assert_eq!("a", read("a b", ""));
+/// assert_eq!("a b", read("a b,c", ","));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MaybeQuoted(pub String);
+
+impl FromRead for QuotedString {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        let res = (|| {
+            let quote = match r.next().transpose()? {
+                Some(q @ ('"' | '\'')) => q,
+                _ => {
+                    return r
+                        .err_parse("Expected `\"` or `'`.")
+                        .hint("Quote the string (e.g. `\"value\"`).")
+                        .err()
+                }
+            };
+            let open = r.pos().unwrap_or_default();
+            read_quoted(r, quote, open).map(Self)
+        })();
+        ParseResult {
+            res: res.as_ref().ok().cloned(),
+            err: res.err(),
+        }
+    }
+}
+
+impl FromRead for MaybeQuoted {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        Self::from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn from_read_fmt(r: &mut Reader, fmt: &ReadFmt) -> ParseResult<Self> {
+        let res = (|| {
+            if matches!(r.peek()?, Some('"' | '\'')) {
+                return r.parse::<QuotedString>(fmt).map(|s| Self(s.0));
+            }
+
+            let delims = fmt.custom();
+            let is_delim = |c: char| {
+                if delims.is_empty() {
+                    c.is_whitespace()
+                } else {
+                    delims.contains(c)
+                }
+            };
+            let mut res = String::new();
+            while let Some(c) = r.peek()?.filter(|c| !is_delim(*c)) {
+                res.push(c);
+                _ = r.next();
+            }
+            Ok(Self(res))
+        })();
+        ParseResult {
+            res: res.as_ref().ok().cloned(),
+            err: res.err(),
+        }
+    }
+}
+
+impl FromStr for QuotedString {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for QuotedString {}
+
+impl FromStr for MaybeQuoted {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for MaybeQuoted {}
+
+/// Reads the rest of string that was opened with `quote` at position `open`.
+fn read_quoted(r: &mut Reader, quote: char, open: usize) -> Result<String> {
+    let unterminated = |r: &Reader| {
+        r.err_parse("Unterminated quoted string.")
+            .spanned(open..open + 1)
+            .inline_msg("The string starts here.")
+            .hint(format!("Close the string with `{quote}`."))
+            .err()
+    };
+
+    let mut res = String::new();
+    loop {
+        match r.next().transpose()? {
+            None => return unterminated(r),
+            Some(c) if c == quote => return Ok(res),
+            Some('\\') => {
+                let esc = r.pos().unwrap_or_default();
+                match r.next().transpose()? {
+                    Some(c @ ('"' | '\'' | '\\')) => res.push(c),
+                    Some('n') => res.push('\n'),
+                    Some('t') => res.push('\t'),
+                    Some(c) => {
+                        return r
+                            .err_parse(format!("Invalid escape `\\{c}`."))
+                            .spanned(esc..r.read_end())
+                            .inline_msg("Invalid escape.")
+                            .hint(
+                                "Valid escapes are `\\\"`, `\\'`, `\\\\`, \
+                                `\\n` and `\\t`.",
+                            )
+                            .err()
+                    }
+                    None => return unterminated(r),
+                }
+            }
+            Some(c) => res.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromArg;
+    use core::ops::Range;
+
+    fn span<T: for<'a> FromArg<'a>>(s: &str) -> Range<usize> {
+        match T::from_arg(s) {
+            Err(e) => e.span().unwrap(),
+            Ok(_) => panic!("{s} is valid."),
+        }
+    }
+
+    #[test]
+    fn quoted() {
+        let q = |s: &str| QuotedString::from_arg(s).unwrap().0;
+        assert_eq!(q(r#""hello""#), "hello");
+        assert_eq!(q("'hello'"), "hello");
+        assert_eq!(q(r#""it's""#), "it's");
+        assert_eq!(q(r#"'say "hi"'"#), "say \"hi\"");
+        assert_eq!(q("''"), "");
+        assert_eq!(q(r#""a\tb\nc""#), "a\tb\nc");
+        assert_eq!(q(r#""a \"b \\\"c\\\" d\"""#), r#"a "b \"c\" d""#);
+        assert_eq!(q(r#"'\\\''"#), r"\'");
+        assert_eq!(q("\"žluť\""), "žluť");
+    }
+
+    #[test]
+    fn quoted_errors() {
+        assert_eq!(span::<QuotedString>("'abc"), 0..1);
+        assert_eq!(span::<QuotedString>("ab\"c"), 0..1);
+        assert_eq!(span::<QuotedString>(r#""abc\""#), 0..1);
+        assert_eq!(span::<QuotedString>(r#""abc\"#), 0..1);
+        assert_eq!(span::<QuotedString>(r#""a\xb""#), 2..4);
+        assert_eq!(span::<QuotedString>("'a' b"), 3..4);
+
+        let err = QuotedString::from_arg("\"abc'").unwrap_err();
+        assert!(err.hint_str().unwrap().contains("`\"`"));
+    }
+
+    #[test]
+    fn maybe_quoted() {
+        let read = |s: &str, fmt: &str| {
+            let mut r: Reader = s.into();
+            let res = MaybeQuoted::from_read_fmt(&mut r, &fmt.into());
+            (res.res.unwrap().0, r.peek().unwrap())
+        };
+        assert_eq!(read("abc def", ""), ("abc".into(), Some(' ')));
+        assert_eq!(read("'a b' c", ""), ("a b".into(), Some(' ')));
+        assert_eq!(read("a b;c", ",;"), ("a b".into(), Some(';')));
+        assert_eq!(read("abc", ","), ("abc".into(), None));
+        assert_eq!(read(",", ","), ("".into(), Some(',')));
+        assert_eq!(span::<MaybeQuoted>("'a b"), 0..1);
+
+        let mut q = MaybeQuoted::default();
+        crate::parsef(
+            &mut "name=\"a, b\",x".into(),
+            [
+                crate::ParseFArg::Str("name=".into()),
+                crate::ParseFArg::FmtArg(&mut q, ",".into()),
+                crate::ParseFArg::Str(",x".into()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(q.0, "a, b");
+    }
+}
@@ -0,0 +1,276 @@
+use alloc::{format, string::String};
+use core::{
+    ops::{Range, RangeFrom, RangeInclusive, RangeTo},
+    str::FromStr,
+};
+
+use crate::{
+    from_read::read_elem, match_prefix, ArgErrCtx, ArgError, FromArg,
+    FromRead, ParseResult, Reader, Result, Tuple,
+};
+
+macro_rules! impl_range {
+    (
+        $(#[$att:meta])*
+        $t:ident, $sep:literal, $start:literal, $end:literal,
+        |$s:ident, $e:ident| $new:expr
+    ) => {
+        $(#[$att])*
+        impl<T: FromRead + PartialOrd> FromRead for $t<T> {
+            fn from_read(r: &mut Reader) -> ParseResult<Self> {
+                let res = read_range::<T>(r, $sep, $start, $end);
+                ParseResult {
+                    err: res.err,
+                    res: res.res.and_then(|($s, $e)| Some($new)),
+                }
+            }
+        }
+
+        /// Parses the range with [`FromRead`]. The whole argument must be
+        /// the range.
+        impl<'a, T: FromRead + PartialOrd> FromArg<'a> for $t<T> {
+            fn from_arg(arg: &'a str) -> Result<Self> {
+                Tuple::<Self>::from_str(arg).map(|t| t.0)
+            }
+        }
+    };
+}
+
+impl_range!(
+    /// Reads range in the format `start..end`. Range with `start` larger
+    /// than `end` is [`ArgError::InvalidValue`].
+    ///
+    /// If `T` is float, `start` must have digits after the decimal point
+    /// (`1.0..5`), because in `1..5` the first `.` is read as part of the
+    /// float. `1...5` is ambiguous and so it is error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::Range;
+    ///
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["2..10", "10..2"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(2..10, args.next_arg::<Range<usize>>().unwrap());
+    /// assert!(args.next_arg::<Range<usize>>().is_err());
+    /// ```
+    Range, "..", true, true, |s, e| s?..e?
+);
+
+impl_range!(
+    /// Reads range in the format `start..=end`. Range with `start` larger
+    /// than `end` is [`ArgError::InvalidValue`]. Floats have the same
+    /// limitation as with [`Range`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::RangeInclusive;
+    ///
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = ["1..=8", "1..8"];
+    /// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+    ///
+    /// assert_eq!(1..=8, args.next_arg::<RangeInclusive<u32>>().unwrap());
+    /// assert!(args.next_arg::<RangeInclusive<u32>>().is_err());
+    /// ```
+    RangeInclusive, "..=", true, true, |s, e| s?..=e?
+);
+
+impl_range!(
+    /// Reads range in the format `start..`. Floats have the same limitation
+    /// as with [`Range`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::RangeFrom;
+    ///
+    /// use pareg_core::FromArg;
+    ///
+    /// assert_eq!(2.., RangeFrom::<i32>::from_arg("2..").unwrap());
+    /// assert!(RangeFrom::<i32>::from_arg("2..5").is_err());
+    /// ```
+    RangeFrom, "..", true, false, |s, _e| s?..
+);
+
+impl_range!(
+    /// Reads range in the format `..end`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::RangeTo;
+    ///
+    /// use pareg_core::FromArg;
+    ///
+    /// assert_eq!(..5, RangeTo::<i32>::from_arg("..5").unwrap());
+    /// assert!(RangeTo::<i32>::from_arg("..").is_err());
+    /// ```
+    RangeTo, "..", false, true, |_s, e| ..e?
+);
+
+/// Reads range with the bounds separated by `sep`. `has_start` and `has_end`
+/// determine which of the bounds are read.
+fn read_range<T: FromRead + PartialOrd>(
+    r: &mut Reader,
+    sep: &str,
+    has_start: bool,
+    has_end: bool,
+) -> ParseResult<(Option<T>, Option<T>)> {
+    macro_rules! pass_or_exit {
+        ($v:expr) => {
+            match $v {
+                Ok(r) => r,
+                Err(e) => {
+                    return ParseResult {
+                        err: Some(e),
+                        res: None,
+                    }
+                }
+            }
+        };
+    }
+
+    _ = r.peek();
+    let start_pos = r.pos().unwrap_or_default();
+    let start = if has_start {
+        Some(pass_or_exit!(read_elem::<T>(r)))
+    } else {
+        None
+    };
+    _ = r.peek();
+    let sep_pos = r.pos().unwrap_or_default();
+    // `1...2` may be both `1.` `..` `2` and `1` `..` `.2`.
+    let dot_before = has_start && r.last_read() == Some('.');
+    pass_or_exit!(match_prefix(sep, r));
+    if dot_before
+        || (has_end && sep.ends_with('.') && matches!(r.peek(), Ok(Some('.'))))
+    {
+        return ParseResult {
+            err: Some(
+                r.err_parse(format!("Ambiguous `.` next to `{sep}`."))
+                    .span_start(sep_pos - dot_before as usize)
+                    .hint(format!(
+                        "Write the bounds with digits on both sides of the \
+                        decimal point (`1.0{sep}0.5`)."
+                    )),
+            ),
+            res: None,
+        };
+    }
+    if !has_end {
+        return ParseResult {
+            err: None,
+            res: Some((start, None)),
+        };
+    }
+
+    _ = r.peek();
+    let end_pos = r.pos().unwrap_or_default();
+    let end = T::from_read(r);
+    let Some(e) = end.res else {
+        return ParseResult {
+            err: Some(end.err.unwrap_or_else(|| {
+                r.err_parse("Failed to parse end of range.")
+                    .span_start(end_pos)
+            })),
+            res: None,
+        };
+    };
+
+    if start.as_ref().is_some_and(|s| *s > e) {
+        let err = ArgError::InvalidValue(
+            ArgErrCtx::from_msg("Start is larger than end.", String::new())
+                .into(),
+        );
+        return ParseResult {
+            err: Some(
+                r.map_err(err)
+                    .span_start(start_pos)
+                    .main_msg("Invalid range.")
+                    .hint(format!(
+                        "Start of the range `start{sep}end` must not be \
+                        larger than its end."
+                    )),
+            ),
+            res: None,
+        };
+    }
+
+    ParseResult {
+        err: end.err,
+        res: Some((start, Some(e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parsef, ParseFArg};
+
+    #[test]
+    fn reversed() {
+        let err = Range::<u32>::from_arg("10..2").unwrap_err();
+        let ArgError::InvalidValue(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 0..5);
+        assert!(ctx.hint.unwrap().contains("`start..end`"));
+
+        assert_eq!(RangeInclusive::<u8>::from_arg("3..=3").unwrap(), 3..=3);
+        assert_eq!(Range::<i8>::from_arg("-3..-3").unwrap(), -3..-3);
+        assert!(RangeInclusive::<i8>::from_arg("-3..=-4").is_err());
+    }
+
+    #[test]
+    fn missing_bounds() {
+        assert!(Range::<u32>::from_arg("2..").is_err());
+        assert!(Range::<u32>::from_arg("..2").is_err());
+        assert!(Range::<u32>::from_arg("2").is_err());
+        assert!(RangeInclusive::<u32>::from_arg("2..=").is_err());
+        assert!(RangeFrom::<u32>::from_arg("..").is_err());
+        assert_eq!(RangeFrom::<u32>::from_arg("2..").unwrap(), 2..);
+        assert_eq!(RangeTo::<u32>::from_arg("..2").unwrap(), ..2);
+    }
+
+    #[test]
+    fn float() {
+        assert_eq!(Range::<f64>::from_arg("1.0..2.5").unwrap(), 1.0..2.5);
+        assert_eq!(Range::<f64>::from_arg("-.5..1").unwrap(), -0.5..1.0);
+        assert_eq!(RangeTo::<f32>::from_arg("..1.5").unwrap(), ..1.5);
+        // Limitation: `1.` is read as float.
+        assert!(Range::<f64>::from_arg("1..2").is_err());
+        // `...` is ambiguous.
+        assert!(Range::<f64>::from_arg("1...2").is_err());
+        assert!(Range::<f64>::from_arg("1.0...2").is_err());
+        assert!(RangeInclusive::<f64>::from_arg("1...=2").is_err());
+        assert!(RangeFrom::<f64>::from_arg("1...").is_err());
+        assert!(RangeTo::<f64>::from_arg("...5").is_err());
+        assert_eq!(
+            RangeInclusive::<f64>::from_arg("0.1..=.5").unwrap(),
+            0.1..=0.5
+        );
+        let err = Range::<f64>::from_arg("1...2").unwrap_err();
+        assert_eq!(err.span(), Some(1..4));
+        let err = Range::<f64>::from_arg("1.0...2").unwrap_err();
+        assert_eq!(err.span(), Some(3..6));
+    }
+
+    #[test]
+    fn parsef_target() {
+        let mut pages = 0..0;
+        let mut threads = 0..=0;
+        parsef(
+            &mut "p=2..10,t=1..=8".into(),
+            [
+                ParseFArg::Str("p=".into()),
+                ParseFArg::Arg(&mut pages),
+                ParseFArg::Str(",t=".into()),
+                ParseFArg::Arg(&mut threads),
+            ],
+        )
+        .unwrap();
+        assert_eq!((pages, threads), (2..10, 1..=8));
+    }
+}
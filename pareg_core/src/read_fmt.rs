@@ -0,0 +1,142 @@
+use core::ops::RangeInclusive;
+
+/// Format specification for reading values with [`crate::parsef`]. It is
+/// given after `:` in the placeholder (e.g. `{:strict}` or `{name:strict}`).
+///
+/// Interpretation of the format is up to the type that is read. The format
+/// may start with flags: sign policy (`+` or `-`, see [`ReadFmt::sign`])
+/// followed by `i` (see [`ReadFmt::case_insensitive`]). Numbers accept width
+/// after the flags (see [`ReadFmt::length_range`]). The whole format is still
+/// available with [`ReadFmt::custom`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadFmt<'a> {
+    fmt: &'a str,
+}
+
+/// Whether numbers may have sign (see [`ReadFmt::sign`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SignPolicy {
+    /// The sign is optional.
+    #[default]
+    Allow,
+    /// The number must start with sign (flag `+`). Unsigned numbers must
+    /// start with `+`.
+    Require,
+    /// The number may not have sign (flag `-`).
+    Forbid,
+}
+
+impl<'a> ReadFmt<'a> {
+    /// Creates new format from its string representation.
+    pub fn new(fmt: &'a str) -> Self {
+        Self { fmt }
+    }
+
+    /// Gets the whole format string.
+    pub fn custom(&self) -> &'a str {
+        self.fmt
+    }
+
+    /// Checks whether the format is empty (default).
+    pub fn is_empty(&self) -> bool {
+        self.fmt.is_empty()
+    }
+
+    /// Gets the sign policy of numbers. It is given by the flag `+` (sign is
+    /// required) or `-` (sign is forbidden) at the start of the format. The
+    /// sign is optional by default.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ReadFmt, SignPolicy};
+    ///
+    /// assert_eq!(SignPolicy::Require, ReadFmt::new("+d").sign());
+    /// assert_eq!(SignPolicy::Forbid, ReadFmt::new("-4x").sign());
+    /// assert_eq!(SignPolicy::Allow, ReadFmt::new("4").sign());
+    /// ```
+    pub fn sign(&self) -> SignPolicy {
+        self.split_flags().0
+    }
+
+    /// Checks whether the format has the flag `i` (after the sign flag). It
+    /// makes matching of words case insensitive (e.g. `{:istrict}` for
+    /// [`bool`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::ReadFmt;
+    ///
+    /// assert!(ReadFmt::new("i").case_insensitive());
+    /// assert!(ReadFmt::new("+i2x").case_insensitive());
+    /// assert!(!ReadFmt::new("2i").case_insensitive());
+    /// ```
+    pub fn case_insensitive(&self) -> bool {
+        self.split_flags().1
+    }
+
+    /// Gets the range of the number of chars that may be read. It is given
+    /// after the flags as `N` (exactly `N` chars) or `..N` (at most `N`
+    /// chars). Returns [`None`] if there is no width.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::ReadFmt;
+    ///
+    /// assert_eq!(Some(4..=4), ReadFmt::new("4").length_range());
+    /// assert_eq!(Some(0..=2), ReadFmt::new("..2x").length_range());
+    /// assert_eq!(None, ReadFmt::new("x").length_range());
+    /// assert_eq!(Some(3..=3), ReadFmt::new("+i3").length_range());
+    /// ```
+    pub fn length_range(&self) -> Option<RangeInclusive<usize>> {
+        self.split_width().0
+    }
+
+    /// Gets the format without the flags and the width (see
+    /// [`ReadFmt::length_range`]).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::ReadFmt;
+    ///
+    /// assert_eq!("x", ReadFmt::new("2x").spec());
+    /// assert_eq!("strict", ReadFmt::new("strict").spec());
+    /// assert_eq!("strict", ReadFmt::new("istrict").spec());
+    /// ```
+    pub fn spec(&self) -> &'a str {
+        self.split_width().1
+    }
+
+    /// Splits the flags from the rest of the format.
+    fn split_flags(&self) -> (SignPolicy, bool, &'a str) {
+        let (sign, s) = match self.fmt.as_bytes().first() {
+            Some(b'+') => (SignPolicy::Require, &self.fmt[1..]),
+            Some(b'-') => (SignPolicy::Forbid, &self.fmt[1..]),
+            _ => (SignPolicy::Allow, self.fmt),
+        };
+        match s.strip_prefix('i') {
+            Some(s) => (sign, true, s),
+            None => (sign, false, s),
+        }
+    }
+
+    /// Splits the format (without flags) to width and the rest.
+    fn split_width(&self) -> (Option<RangeInclusive<usize>>, &'a str) {
+        let rest = self.split_flags().2;
+        let (at_most, s) = match rest.strip_prefix("..") {
+            Some(s) => (true, s),
+            None => (false, rest),
+        };
+        let len = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let Ok(max) = s[..len].parse() else {
+            return (None, rest);
+        };
+        let min = if at_most { 0 } else { max };
+        (Some(min..=max), &s[len..])
+    }
+}
+
+impl<'a> From<&'a str> for ReadFmt<'a> {
+    fn from(value: &'a str) -> Self {
+        Self::new(value)
+    }
+}
@@ -1,26 +1,157 @@
-use std::{borrow::Cow, io::Read};
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::VecDeque,
+    format,
+    string::{String, ToString},
+    sync::Arc,
+    vec,
+    vec::Vec,
+};
+use core::{
+    ops::Range,
+    str::{Lines, SplitWhitespace},
+};
+#[cfg(feature = "std")]
+use std::io::{ErrorKind, Read};
 
-use crate::{ArgError, Result};
+use crate::{ArgError, FromRead, ReadFmt, Result};
 
 enum ReaderSource<'a> {
+    #[cfg(feature = "std")]
     Io(Box<dyn Read + 'a>),
     Str(Cow<'a, str>),
+    Shared(Arc<str>),
     Iter(Box<dyn Iterator<Item = char> + 'a>),
     IterErr(Box<dyn Iterator<Item = Result<char>> + 'a>),
+    Chunks(Chunks<'a>),
+}
+
+/// Chars of string chunks with optional char between the chunks.
+struct Chunks<'a> {
+    chunks: Box<dyn Iterator<Item = &'a str> + 'a>,
+    /// Rest of the current chunk.
+    cur: &'a str,
+    join: Option<char>,
+    /// Whether the first chunk was already taken.
+    started: bool,
+}
+
+impl Iterator for Chunks<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        loop {
+            let mut chars = self.cur.chars();
+            if let Some(c) = chars.next() {
+                self.cur = chars.as_str();
+                return Some(c);
+            }
+
+            self.cur = self.chunks.next()?;
+            if core::mem::replace(&mut self.started, true) {
+                if let Some(j) = self.join {
+                    return Some(j);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.chunks.size_hint();
+        let joins = match self.join {
+            Some(_) if self.started => min,
+            Some(_) => min.saturating_sub(1),
+            None => 0,
+        };
+        let cur = self.cur.len();
+        (cur.div_ceil(4) + joins, (max == Some(0)).then_some(cur))
+    }
 }
 
 /// Struct that allows formated reading.
 pub struct Reader<'a> {
     source: ReaderSource<'a>,
     peek: Option<char>,
+    /// The last char that was read (not peeked).
+    last: Option<char>,
     pos: usize,
+    /// Number of chars that may be still read. [`None`] if not limited.
+    limit: Option<usize>,
+    /// The last chars read from source that has no whole text. They are
+    /// shown in errors.
+    window: VecDeque<char>,
+    /// Maximum number of chars in [`Self::window`].
+    window_size: usize,
+    /// End of the part of the string that may be read (see
+    /// [`Reader::slice`]). [`None`] if the whole string may be read.
+    end: Option<usize>,
 }
 
 impl<'a> Reader<'a> {
+    /// Creates reader that reads the chunks one after another. If `join` is
+    /// given, it is read between the chunks. Position of the reader is the
+    /// position in the chunks joined together.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{parsef, ParseFArg, Reader};
+    ///
+    /// let mut r = Reader::from_chunks("w  10\th".split_whitespace(), Some(' '));
+    /// let mut width = 0;
+    /// parsef(&mut r, [
+    ///     ParseFArg::Str("w ".into()),
+    ///     ParseFArg::Arg(&mut width),
+    ///     ParseFArg::Str(" h".into()),
+    /// ]).unwrap();
+    /// assert_eq!(10, width);
+    /// ```
+    pub fn from_chunks(
+        chunks: impl IntoIterator<Item = &'a str> + 'a,
+        join: Option<char>,
+    ) -> Self {
+        Self::new(ReaderSource::Chunks(Chunks {
+            chunks: Box::new(chunks.into_iter()),
+            cur: "",
+            join,
+            started: false,
+        }))
+    }
+
+    /// Sets the number of the last read chars that are shown in errors. This
+    /// is used only for sources that don't have the whole text (readers and
+    /// iterators of chars). The errors also say the byte offset of the
+    /// error in the input. The default is 64. Zero disables it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{parsef, ParseFArg, Reader};
+    ///
+    /// let data = Box::new("w=800,h=x00".chars());
+    /// let mut r = Reader::from(data as Box<dyn Iterator<Item = char>>)
+    ///     .with_context_window(5);
+    /// let (mut w, mut h) = (0_u32, 0_u32);
+    /// let err = parsef(&mut r, [
+    ///     ParseFArg::Str("w=".into()),
+    ///     ParseFArg::Arg(&mut w),
+    ///     ParseFArg::Str(",h=".into()),
+    ///     ParseFArg::Arg(&mut h),
+    /// ]).unwrap_err();
+    /// assert_eq!(Some("0,h=x"), err.arg());
+    /// assert!(err.to_string().contains("at byte offset 8 of input"));
+    /// ```
+    pub fn with_context_window(mut self, n: usize) -> Self {
+        self.window_size = n;
+        while self.window.len() > n {
+            self.window.pop_front();
+        }
+        self
+    }
+
     /// Read at most `max` chars to the given string.
     pub fn read_to(&mut self, s: &mut String, max: usize) -> Result<()> {
         s.reserve(self.bytes_size_hint().min(max));
-        let target = s.len() + max;
+        let target = s.len().saturating_add(max);
         for c in self {
             s.push(c?);
             if s.len() == target {
@@ -48,23 +179,139 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// Gets the position after the last char that was read (not peeked).
+    pub(crate) fn read_end(&self) -> usize {
+        self.pos - self.peek.map_or(0, |c| c.len_utf8())
+    }
+
+    /// Gets the last char that was read (not peeked).
+    pub(crate) fn last_read(&self) -> Option<char> {
+        self.last
+    }
+
+    /// Gets the number of bytes that were read (not peeked) since `start`.
+    /// `start` is the position of the first char of the value (e.g.
+    /// [`Reader::pos`] after [`Reader::peek`]).
+    ///
+    /// This is useful to check the length of the value in custom
+    /// [`FromRead`] implementations.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ReadFmt, Reader};
+    ///
+    /// let mut r: Reader = "12x".into();
+    /// r.peek()?;
+    /// let start = r.pos().unwrap_or_default();
+    /// assert_eq!(12, r.parse::<u32>(&ReadFmt::default())?);
+    /// assert_eq!(2, r.consumed_since(start));
+    /// # Ok::<_, pareg_core::ArgError>(())
+    /// ```
+    pub fn consumed_since(&self, start: usize) -> usize {
+        self.read_end().saturating_sub(start)
+    }
+
+    /// Creates copy of the reader at the same position. This is possible
+    /// only if the source is string. Returns [`None`] for readers and
+    /// iterators, because they cannot be read twice.
+    ///
+    /// Shared strings (`Arc<str>`) and borrowed strings are cloned cheaply,
+    /// owned strings are copied.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::{ReadFmt, Reader};
+    ///
+    /// let mut r: Reader = "1,2".into();
+    /// assert_eq!(1, r.parse::<u32>(&ReadFmt::default())?);
+    /// let mut r2 = r.try_clone().unwrap();
+    /// assert_eq!(r.collect::<Result<String, _>>()?, ",2");
+    /// assert_eq!(r2.collect::<Result<String, _>>()?, ",2");
+    /// # Ok::<_, pareg_core::ArgError>(())
+    /// ```
+    pub fn try_clone(&self) -> Option<Self> {
+        let source = match &self.source {
+            ReaderSource::Str(s) => ReaderSource::Str(s.clone()),
+            ReaderSource::Shared(s) => ReaderSource::Shared(s.clone()),
+            _ => return None,
+        };
+        Some(Self {
+            source,
+            peek: self.peek,
+            last: self.last,
+            pos: self.pos,
+            limit: self.limit,
+            window: self.window.clone(),
+            window_size: self.window_size,
+            end: self.end,
+        })
+    }
+
+    /// Creates reader that reads only the part `range` of the string. The
+    /// range is in bytes of the whole string (not relative to the current
+    /// position or to this slice). The new reader has its own position, but
+    /// the positions and the error spans are still relative to the whole
+    /// string, so errors point to the right place in the whole input.
+    ///
+    /// Returns [`None`] if the source is not string or if the range is not
+    /// valid range in the whole string.
+    ///
+    /// [`Reader`] cannot be sent to other threads. To parse parts of one
+    /// input in parallel, share the input as `Arc<str>` and slice it in each
+    /// thread.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::sync::Arc;
+    ///
+    /// use pareg_core::{ReadFmt, Reader};
+    ///
+    /// let data: Arc<str> = "10,x0".into();
+    /// let r = Reader::from(data);
+    /// let a: u32 = r.slice(0..2).unwrap().parse(&ReadFmt::default())?;
+    /// assert_eq!(10, a);
+    ///
+    /// let err = r.slice(3..5).unwrap().parse::<u32>(&ReadFmt::default());
+    /// let err = err.unwrap_err();
+    /// assert_eq!(Some("10,x0"), err.arg());
+    /// assert_eq!(Some(3..4), err.span());
+    /// # Ok::<_, pareg_core::ArgError>(())
+    /// ```
+    pub fn slice(&self, range: Range<usize>) -> Option<Self> {
+        let text = self.text()?;
+        text.get(range.clone())?;
+        let mut res = self.try_clone()?;
+        res.peek = None;
+        res.last = None;
+        res.pos = range.start;
+        res.limit = None;
+        res.window.clear();
+        res.end = Some(range.end);
+        Some(res)
+    }
+
     pub fn bytes_size_hint(&self) -> usize {
         match &self.source {
+            #[cfg(feature = "std")]
             ReaderSource::Io(_) => {
                 self.peek.map(|a| a.len_utf8()).unwrap_or_default()
             }
-            ReaderSource::Str(s) => s.len() - self.pos,
+            ReaderSource::Str(_) | ReaderSource::Shared(_) => {
+                self.text_end().saturating_sub(self.pos)
+            }
             ReaderSource::Iter(i) => i.size_hint().0,
             ReaderSource::IterErr(i) => i.size_hint().0,
+            ReaderSource::Chunks(c) => c.cur.len(),
         }
     }
 
     pub fn map_err(&self, e: ArgError) -> ArgError {
-        match &self.source {
-            ReaderSource::Str(s) => e
+        match (&self.source, self.text()) {
+            (_, Some(s)) => e
                 .shift_span(self.pos.saturating_sub(1), s.to_string())
                 .spanned(self.pos.saturating_sub(1)..self.pos),
-            _ => e,
+            (ReaderSource::Chunks(_), _) => e,
+            _ => self.window_err(e),
         }
     }
 
@@ -72,66 +319,235 @@ impl<'a> Reader<'a> {
         self.map_err(ArgError::parse_msg(msg, String::new()))
     }
 
+    /// Reads value of the given type with the given format. The rest of the
+    /// input is not checked.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Reader;
+    ///
+    /// let mut r: Reader = "800,0.5;1".into();
+    /// assert_eq!((800, 0.5), r.parse::<(u32, f32)>(&",".into()).unwrap());
+    /// assert_eq!(Some(';'), r.peek().unwrap());
+    /// ```
+    pub fn parse<T: FromRead>(&mut self, fmt: &ReadFmt) -> Result<T> {
+        self.peek()?;
+        let start = self.pos().unwrap_or_default();
+        let res = T::from_read_fmt(self, fmt);
+        res.res.ok_or_else(|| {
+            res.err.unwrap_or_else(|| {
+                self.err_parse("Failed to parse argument.")
+                    .span_start(start)
+            })
+        })
+    }
+
     pub fn peek(&mut self) -> Result<Option<char>> {
-        if let Some(c) = self.peek {
+        if self.limit == Some(0) {
+            Ok(None)
+        } else if let Some(c) = self.peek {
             Ok(Some(c))
         } else {
-            self.peek = self.next().transpose()?;
+            self.peek = self.read_source().transpose()?;
             Ok(self.peek)
         }
     }
 
-    fn res<T>(&self, res: Result<T>) -> Result<T> {
-        res.map_err(|e| self.map_err(e))
-    }
+    /// Reads the input until after the first occurence of `s`. Returns the
+    /// position where the occurence starts or [`None`] if the whole input
+    /// was read without finding `s`. The reader never looks more than one
+    /// char ahead, so the skipped input is just read.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Reader;
+    ///
+    /// let mut r: Reader = "ab-a-b".into();
+    /// assert_eq!(Some(3), r.find_forward("a-b").unwrap());
+    /// assert_eq!(None, r.peek().unwrap());
+    /// assert_eq!(None, r.find_forward("a").unwrap());
+    /// ```
+    pub fn find_forward(&mut self, s: &str) -> Result<Option<usize>> {
+        let pat: Vec<_> = s.chars().collect();
+        // Length of the longest proper prefix of `pat[..=i]` that is also
+        // its suffix.
+        let mut fail = vec![0; pat.len()];
+        let mut k = 0;
+        for i in 1..pat.len() {
+            while k > 0 && pat[i] != pat[k] {
+                k = fail[k - 1];
+            }
+            if pat[i] == pat[k] {
+                k += 1;
+            }
+            fail[i] = k;
+        }
 
-    fn new(source: ReaderSource<'a>) -> Self {
-        Self {
-            source,
-            pos: 0,
-            peek: None,
+        let mut k = 0;
+        while k < pat.len() {
+            let Some(c) = self.next().transpose()? else {
+                return Ok(None);
+            };
+            while k > 0 && c != pat[k] {
+                k = fail[k - 1];
+            }
+            if c == pat[k] {
+                k += 1;
+            }
         }
+        Ok(Some(self.read_end() - s.len()))
     }
-}
 
-impl Iterator for Reader<'_> {
-    type Item = Result<char>;
+    /// Runs `f` with the reader limited to read at most `max` chars. Returns
+    /// the result of `f` and the number of chars that were read.
+    pub(crate) fn with_limit<T>(
+        &mut self,
+        max: usize,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> (T, usize) {
+        let prev = self.limit;
+        let max = prev.map_or(max, |p| p.min(max));
+        self.limit = Some(max);
+        let res = f(self);
+        let read = max - self.limit.unwrap_or_default();
+        self.limit = prev.map(|p| p - read);
+        (res, read)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(r) = self.peek {
-            self.peek = None;
-            return Some(Ok(r));
-        }
+    /// Adds the window of the last read chars to the error. The last char is
+    /// the errornous one.
+    fn window_err(&self, e: ArgError) -> ArgError {
+        let Some(last) = self.window.back() else {
+            return e;
+        };
+        let text: String = self.window.iter().collect();
+        let start = text.len() - last.len_utf8();
+        let offset = self.pos - last.len_utf8();
+        e.shift_span(start, text.clone())
+            .spanned(start..text.len())
+            .map_ctx(|c| {
+                let msg = c.long_message.as_ref().unwrap_or(&c.message);
+                let msg = format!("{msg} (at byte offset {offset} of input)");
+                c.main_msg(msg)
+            })
+    }
 
+    /// Reads the next char from the source (ignoring the peeked char and the
+    /// limit).
+    fn read_source(&mut self) -> Option<Result<char>> {
         let r = match &mut self.source {
+            #[cfg(feature = "std")]
             ReaderSource::Io(io) => read_char(io.as_mut()),
-            ReaderSource::Str(s) => Ok(s[self.pos..].chars().next()),
+            ReaderSource::Str(_) | ReaderSource::Shared(_) => Ok(self
+                .text()
+                .and_then(|s| s.get(self.pos..self.text_end()))
+                .and_then(|s| s.chars().next())),
             ReaderSource::Iter(i) => Ok(i.next()),
             ReaderSource::IterErr(i) => i.next().transpose(),
+            ReaderSource::Chunks(c) => Ok(c.next()),
         };
 
         match r {
             Ok(Some(r)) => {
                 self.pos += r.len_utf8();
+                let windowed = !matches!(
+                    self.source,
+                    ReaderSource::Str(_)
+                        | ReaderSource::Shared(_)
+                        | ReaderSource::Chunks(_)
+                );
+                if windowed && self.window_size > 0 {
+                    if self.window.len() == self.window_size {
+                        self.window.pop_front();
+                    }
+                    self.window.push_back(r);
+                }
                 Some(Ok(r))
             }
             e => self.res(e).transpose(),
         }
     }
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
+    /// Size hint of the remaining chars without the limit.
+    fn source_size_hint(&self) -> (usize, Option<usize>) {
         match &self.source {
+            #[cfg(feature = "std")]
             ReaderSource::Io(_) => (self.peek.is_some() as usize, None),
-            ReaderSource::Str(s) => (
-                self.peek.is_some() as usize + (s.len() - self.pos) / 4,
-                Some(self.peek.is_some() as usize + s.len() - self.pos),
-            ),
+            ReaderSource::Str(_) | ReaderSource::Shared(_) => {
+                let rest = self.text_end().saturating_sub(self.pos);
+                let peek = self.peek.is_some() as usize;
+                (peek + rest / 4, Some(peek + rest))
+            }
             ReaderSource::Iter(i) => i.size_hint(),
             ReaderSource::IterErr(i) => i.size_hint(),
+            ReaderSource::Chunks(c) => c.size_hint(),
+        }
+    }
+
+    fn res<T>(&self, res: Result<T>) -> Result<T> {
+        res.map_err(|e| self.map_err(e))
+    }
+
+    fn new(source: ReaderSource<'a>) -> Self {
+        Self {
+            source,
+            pos: 0,
+            peek: None,
+            last: None,
+            limit: None,
+            window: VecDeque::new(),
+            window_size: 64,
+            end: None,
+        }
+    }
+
+    /// Gets the whole string if the source is string.
+    fn text(&self) -> Option<&str> {
+        match &self.source {
+            ReaderSource::Str(s) => Some(s),
+            ReaderSource::Shared(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Gets the end of the part of the string that may be read.
+    fn text_end(&self) -> usize {
+        let len = self.text().map_or(0, |s| s.len());
+        self.end.map_or(len, |e| e.min(len))
+    }
+}
+
+impl Iterator for Reader<'_> {
+    type Item = Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.limit == Some(0) {
+            return None;
+        }
+
+        let r = match self.peek.take() {
+            Some(c) => Some(Ok(c)),
+            None => self.read_source(),
+        };
+        if let Some(Ok(c)) = r {
+            self.last = Some(c);
+            if let Some(l) = &mut self.limit {
+                *l -= 1;
+            }
+        }
+        r
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (min, max) = self.source_size_hint();
+        match self.limit {
+            Some(l) => (min.min(l), Some(max.map_or(l, |m| m.min(l)))),
+            None => (min, max),
         }
     }
 }
 
+#[cfg(feature = "std")]
 fn read_char<R: Read + ?Sized>(r: &mut R) -> Result<Option<char>> {
     let mut bts = [0; 4];
     if r.read(&mut bts[..1])? != 1 {
@@ -141,11 +557,14 @@ fn read_char<R: Read + ?Sized>(r: &mut R) -> Result<Option<char>> {
     if len == 1 {
         return Ok(Some(res as u8 as char));
     }
-    if r.read(&mut bts[1..len])? != len - 1 {
-        return Err(ArgError::parse_msg(
-            "Utf8 expected more bytes.",
-            String::new(),
-        ));
+    // Readers may return less bytes than requested even if there are more.
+    if let Err(e) = r.read_exact(&mut bts[1..len]) {
+        return Err(match e.kind() {
+            ErrorKind::UnexpectedEof => {
+                ArgError::parse_msg("Utf8 expected more bytes.", String::new())
+            }
+            _ => e.into(),
+        });
     }
 
     if bts[0] == 0xC0
@@ -176,6 +595,7 @@ fn read_char<R: Read + ?Sized>(r: &mut R) -> Result<Option<char>> {
         .map(Some)
 }
 
+#[cfg(feature = "std")]
 fn utf8_len(b: u8) -> Result<(usize, u32)> {
     match b.leading_ones() {
         0 => Ok((1, b as u32)),
@@ -189,6 +609,7 @@ fn utf8_len(b: u8) -> Result<(usize, u32)> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a> From<Box<dyn Read + 'a>> for Reader<'a> {
     fn from(value: Box<dyn Read + 'a>) -> Self {
         Self::new(ReaderSource::Io(value))
@@ -201,6 +622,12 @@ impl<'a> From<Cow<'a, str>> for Reader<'a> {
     }
 }
 
+impl From<Arc<str>> for Reader<'_> {
+    fn from(value: Arc<str>) -> Self {
+        Self::new(ReaderSource::Shared(value))
+    }
+}
+
 impl<'a> From<&'a str> for Reader<'a> {
     fn from(value: &'a str) -> Self {
         Cow::Borrowed(value).into()
@@ -224,3 +651,197 @@ impl<'a> From<Box<dyn Iterator<Item = Result<char>> + 'a>> for Reader<'a> {
         Self::new(ReaderSource::IterErr(value))
     }
 }
+
+impl<'a> From<Box<dyn Iterator<Item = &'a str> + 'a>> for Reader<'a> {
+    fn from(value: Box<dyn Iterator<Item = &'a str> + 'a>) -> Self {
+        Self::from_chunks(value, None)
+    }
+}
+
+/// The words are joined with space.
+impl<'a> From<SplitWhitespace<'a>> for Reader<'a> {
+    fn from(value: SplitWhitespace<'a>) -> Self {
+        Self::from_chunks(value, Some(' '))
+    }
+}
+
+/// The lines are joined with `\n`.
+impl<'a> From<Lines<'a>> for Reader<'a> {
+    fn from(value: Lines<'a>) -> Self {
+        Self::from_chunks(value, Some('\n'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parsef, ParseFArg};
+
+    fn ipv4(r: &mut Reader) -> Result<[u8; 4]> {
+        let [mut a, mut b, mut c, mut d] = [0; 4];
+        parsef(
+            r,
+            [
+                ParseFArg::Arg(&mut a),
+                ParseFArg::Str(".".into()),
+                ParseFArg::Arg(&mut b),
+                ParseFArg::Str(".".into()),
+                ParseFArg::Arg(&mut c),
+                ParseFArg::Str(".".into()),
+                ParseFArg::Arg(&mut d),
+            ],
+        )?;
+        Ok([a, b, c, d])
+    }
+
+    #[test]
+    fn chunks_ipv4() {
+        let chunks = vec!["19", "2.1", "", "68.", "0.", "1"];
+        let mut r = Reader::from_chunks(chunks, None);
+        assert_eq!(ipv4(&mut r).unwrap(), [192, 168, 0, 1]);
+        assert_eq!(r.pos(), Some(10));
+
+        let mut r: Reader = "10 . 0 .\n0  . 7".split_whitespace().into();
+        let mut s = String::new();
+        r.read_all(&mut s).unwrap();
+        assert_eq!(s, "10 . 0 . 0 . 7");
+
+        let mut r = Reader::from_chunks(["10.0", "0.7"], Some('.'));
+        assert_eq!(ipv4(&mut r).unwrap(), [10, 0, 0, 7]);
+        let mut r: Reader = "10.0\n.0.7".lines().into();
+        assert!(ipv4(&mut r).is_err());
+    }
+
+    #[test]
+    fn chunks_error_pos() {
+        let mut r = Reader::from_chunks(["1.2", ".x", ".4"], None);
+        let err = ipv4(&mut r).unwrap_err();
+        assert!(matches!(err, ArgError::FailedToParse(_)));
+        // There is no whole string, so the error has no argument.
+        assert_eq!(err.arg(), Some(""));
+        assert_eq!(r.pos(), Some(4));
+
+        let mut r = Reader::from_chunks(["ab", "c"], Some(' '));
+        assert_eq!(r.size_hint(), (1, None));
+        _ = r.next();
+        assert_eq!(r.size_hint(), (2, None));
+        assert_eq!(r.collect::<Result<String>>().unwrap(), "b c");
+    }
+
+    #[test]
+    fn read_to_max() {
+        let mut r: Reader = "abc".into();
+        let mut s = "x".to_string();
+        r.read_to(&mut s, usize::MAX).unwrap();
+        assert_eq!(s, "xabc");
+        assert_eq!((r.bytes_size_hint(), r.size_hint()), (0, (0, Some(0))));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn shared_slices() {
+        let data: Arc<str> = "10.0.0.1 192.168.x.1".into();
+        let parse = |range: Range<usize>| {
+            let data = data.clone();
+            std::thread::spawn(move || {
+                let mut r = Reader::from(data).slice(range).unwrap();
+                ipv4(&mut r).map_err(|e| (e.arg().map(String::from), e.span()))
+            })
+        };
+        let (a, b) = (parse(0..8), parse(9..20));
+        assert_eq!(a.join().unwrap(), Ok([10, 0, 0, 1]));
+        assert_eq!(
+            b.join().unwrap(),
+            Err((Some("10.0.0.1 192.168.x.1".into()), Some(17..18)))
+        );
+
+        // The slice doesn't read after its end.
+        let r = Reader::from(data.clone());
+        let mut s = String::new();
+        r.slice(3..8).unwrap().read_all(&mut s).unwrap();
+        assert_eq!(s, "0.0.1");
+        assert!(r.slice(9..21).is_none() && r.slice(21..22).is_none());
+
+        let mut r = r.slice(9..12).unwrap();
+        assert_eq!(r.next().transpose().unwrap(), Some('1'));
+        let c = r.try_clone().unwrap();
+        assert_eq!((c.pos(), c.size_hint()), (Some(9), (0, Some(2))));
+        assert_eq!(c.collect::<Result<String>>().unwrap(), "92");
+        assert_eq!(r.collect::<Result<String>>().unwrap(), "92");
+
+        let io = Box::new(std::io::Cursor::new("1")) as Box<dyn Read>;
+        let r = Reader::from(io);
+        assert!(r.try_clone().is_none() && r.slice(0..1).is_none());
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", not(feature = "minimal-errors")))]
+    fn io_error_context() {
+        use std::io::Cursor;
+
+        let parse = |data: &'static str, window: usize| {
+            let data = Box::new(Cursor::new(data)) as Box<dyn Read>;
+            let mut r = Reader::from(data).with_context_window(window);
+            ipv4(&mut r).unwrap_err().no_color()
+        };
+
+        let err = parse("10.0.ž.1", 64);
+        assert_eq!(err.arg(), Some("10.0.ž"));
+        assert_eq!(err.span(), Some(5..7));
+        assert_eq!(
+            err.to_string(),
+            "\
+argument error: Invalid digit in string. (at byte offset 5 of input)
+--> arg1:5..7
+ |
+ $ 10.0.ž
+ |      ^ Invalid digit in string.
+"
+        );
+
+        let err = parse("192.168.1234.1", 4);
+        assert_eq!(err.arg(), Some("1234"));
+        assert!(err.to_string().contains("at byte offset 11 of input"));
+
+        // Without window the error has no context.
+        let err = parse("1.x", 0);
+        assert_eq!(err.arg(), Some(""));
+
+        let chars = Box::new("1.2.3.-".chars()) as Box<dyn Iterator<Item = _>>;
+        let err = ipv4(&mut chars.into()).unwrap_err();
+        assert_eq!(err.arg(), Some("1.2.3.-"));
+        assert_eq!(err.span(), Some(6..7));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn utf8_short_reads() {
+        /// Returns at most one byte from each read.
+        struct OneByte<'a>(&'a [u8]);
+
+        impl Read for OneByte<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let Some((b, rest)) = self.0.split_first() else {
+                    return Ok(0);
+                };
+                match buf.first_mut() {
+                    Some(d) => *d = *b,
+                    None => return Ok(0),
+                }
+                self.0 = rest;
+                Ok(1)
+            }
+        }
+
+        let read = |b: &'static [u8]| {
+            let mut r: Reader = (Box::new(OneByte(b)) as Box<dyn Read>).into();
+            let mut s = String::new();
+            r.read_all(&mut s).map(|_| s)
+        };
+        assert_eq!(read("ž日🎵".as_bytes()).unwrap(), "ž日🎵");
+        assert!(read(b"a\xc5").is_err());
+        assert!(read(b"\xf4\x80").is_err());
+        assert!(read(b"\xc0\x80").is_err());
+        assert!(read(b"\xed\xa0\x80").is_err());
+    }
+}
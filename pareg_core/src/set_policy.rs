@@ -0,0 +1,27 @@
+/// Determines what happens if option that sets single value is given more
+/// than once (see [`crate::Pareg::try_set_next_policy`] and
+/// [`crate::set_arg_with_policy`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SetPolicy {
+    /// The repeated option is [`crate::ArgError::TooManyArguments`].
+    #[default]
+    Error,
+    /// The first value is kept and the new value is ignored.
+    FirstWins,
+    /// The new value replaces the old value. This allows scripts to append
+    /// overrides to the arguments.
+    LastWins,
+}
+
+impl SetPolicy {
+    /// Sets `value` to `res` according to the policy. Returns `true` if
+    /// `res` already had value. Repeated value with [`SetPolicy::Error`]
+    /// must be handled by the caller.
+    pub(crate) fn set<T>(self, res: &mut Option<T>, value: T) -> bool {
+        let repeated = res.is_some();
+        if !repeated || self != SetPolicy::FirstWins {
+            *res = Some(value);
+        }
+        repeated
+    }
+}
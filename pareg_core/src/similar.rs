@@ -0,0 +1,426 @@
+use alloc::{borrow::Cow, format, string::String, vec, vec::Vec};
+
+/// Computes the number of edits needed to change `a` to `b`. One edit is
+/// inserting, removing or replacing one character or swapping two adjacent
+/// characters.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::edit_distance;
+///
+/// assert_eq!(0, edit_distance("always", "always"));
+/// assert_eq!(1, edit_distance("alway", "always"));
+/// assert_eq!(1, edit_distance("alwyas", "always"));
+/// assert_eq!(2, edit_distance("nver", "never!"));
+/// ```
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<_> = a.chars().collect();
+    let b: Vec<_> = b.chars().collect();
+
+    // Three last rows of the distance matrix.
+    let mut prev2 = vec![0; b.len() + 1];
+    let mut prev: Vec<_> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        (prev2, prev, cur) = (prev, cur, prev2);
+    }
+    prev[b.len()]
+}
+
+/// Removes the punctuation `-` and `_` from the value, so that names that
+/// differ only by the punctuation are equal (e.g. `auto-update`,
+/// `auto_update` and `autoupdate`).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::strip_punct;
+///
+/// assert_eq!("autoupdate", strip_punct("auto-update"));
+/// assert_eq!("dryrun", strip_punct("_dry__run-"));
+/// assert_eq!("all", strip_punct("all"));
+/// ```
+pub fn strip_punct(value: &str) -> Cow<'_, str> {
+    if value.contains(['-', '_']) {
+        value.replace(['-', '_'], "").into()
+    } else {
+        value.into()
+    }
+}
+
+/// Finds the variant that has name equal to `value` if case and the
+/// punctuation `-` and `_` are ignored (see [`strip_punct`]). Returns
+/// [`None`] if there is no such variant or if there are more of them.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::find_flexible_punct;
+///
+/// let variants: &[&[&str]] = &[&["auto"], &["autoupdate"], &["dry_run"]];
+///
+/// assert_eq!(Some(1), find_flexible_punct("Auto-Update", variants));
+/// assert_eq!(Some(2), find_flexible_punct("dry-run", variants));
+/// assert_eq!(None, find_flexible_punct("auto-up", variants));
+/// ```
+pub fn find_flexible_punct(
+    value: &str,
+    variants: &[&[&str]],
+) -> Option<usize> {
+    let value = strip_punct(&value.to_lowercase()).into_owned();
+    let mut found = variants.iter().enumerate().filter(|(_, names)| {
+        names
+            .iter()
+            .any(|n| strip_punct(&n.to_lowercase()) == value.as_str())
+    });
+    match (found.next(), found.next()) {
+        (Some((i, _)), None) => Some(i),
+        _ => None,
+    }
+}
+
+/// Finds the variants that have name that starts with `value` if case and
+/// the punctuation `-` and `_` are ignored (see [`strip_punct`]). Returns the
+/// first such name of each variant. Empty value matches nothing.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::prefix_matches;
+///
+/// let variants: &[&[&str]] =
+///     &[&["auto"], &["autoupdate", "update"], &["manual"]];
+///
+/// assert_eq!(vec!["autoupdate"], prefix_matches("auto-up", variants));
+/// assert_eq!(vec!["auto", "autoupdate"], prefix_matches("AU", variants));
+/// assert!(prefix_matches("x", variants).is_empty());
+/// ```
+pub fn prefix_matches<'a>(
+    value: &str,
+    variants: &[&[&'a str]],
+) -> Vec<&'a str> {
+    let value = strip_punct(&value.to_lowercase()).into_owned();
+    if value.is_empty() {
+        return vec![];
+    }
+    variants
+        .iter()
+        .filter_map(|names| {
+            names.iter().copied().find(|n| {
+                strip_punct(&n.to_lowercase()).starts_with(value.as_str())
+            })
+        })
+        .collect()
+}
+
+/// Result of matching misspelled value to variants (see
+/// [`FuzzyMatch::find`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FuzzyMatch<'a> {
+    /// Exactly one variant is within the maximum distance. Contains index of
+    /// the variant.
+    Unique(usize),
+    /// There is no variant or more than one variant within the maximum
+    /// distance. Contains the closest names of different variants. It is
+    /// empty if all the names are completely different from the value.
+    Closest(Vec<&'a str>),
+}
+
+impl<'a> FuzzyMatch<'a> {
+    /// Finds the variant that is within `max_distance` (see
+    /// [`edit_distance`]) from `value`. Each variant may have several
+    /// names.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::FuzzyMatch;
+    ///
+    /// let variants: &[&[&str]] =
+    ///     &[&["auto"], &["always", "yes"], &["never", "no"]];
+    ///
+    /// assert_eq!(
+    ///     FuzzyMatch::Unique(1),
+    ///     FuzzyMatch::find("alway", variants, 1)
+    /// );
+    /// assert_eq!(
+    ///     FuzzyMatch::Closest(vec!["yes"]),
+    ///     FuzzyMatch::find("ys", variants, 2)
+    /// );
+    /// assert_eq!(
+    ///     FuzzyMatch::Closest(vec!["never"]),
+    ///     FuzzyMatch::find("nevr!!", variants, 1)
+    /// );
+    /// ```
+    pub fn find(
+        value: &str,
+        variants: &[&[&'a str]],
+        max_distance: usize,
+    ) -> Self {
+        // The closest name and its distance for each variant.
+        let best: Vec<_> = variants
+            .iter()
+            .filter_map(|names| {
+                names
+                    .iter()
+                    .map(|n| (edit_distance(value, n), *n))
+                    .min_by_key(|(d, _)| *d)
+            })
+            .collect();
+
+        let mut within = best
+            .iter()
+            .enumerate()
+            .filter(|(_, (d, _))| *d <= max_distance);
+        if let (Some((i, _)), None) = (within.next(), within.next()) {
+            return Self::Unique(i);
+        }
+
+        let len = value.chars().count();
+        let Some(min) = best.iter().map(|(d, _)| *d).min() else {
+            return Self::Closest(vec![]);
+        };
+        if min >= len.max(1) {
+            return Self::Closest(vec![]);
+        }
+        Self::Closest(
+            best.iter()
+                .filter(|(d, _)| *d == min)
+                .map(|(_, n)| *n)
+                .collect(),
+        )
+    }
+
+    /// Creates hint that suggests the closest names (e.g. `Did you mean
+    /// `always`?`). Returns [`None`] if there are no close names.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::FuzzyMatch;
+    ///
+    /// assert_eq!(
+    ///     Some("Did you mean `yes` or `no`?".to_string()),
+    ///     FuzzyMatch::Closest(vec!["yes", "no"]).hint()
+    /// );
+    /// assert_eq!(None, FuzzyMatch::Unique(0).hint());
+    /// ```
+    pub fn hint(&self) -> Option<String> {
+        let Self::Closest(names) = self else {
+            return None;
+        };
+        let (last, rest) = names.split_last()?;
+        let mut res = String::from("Did you mean ");
+        for n in rest {
+            res += &format!("`{n}`, ");
+        }
+        if !rest.is_empty() {
+            res.truncate(res.len() - 2);
+            res += " or ";
+        }
+        res += &format!("`{last}`?");
+        Some(res)
+    }
+}
+
+/// Algorithm that suggests the names that the user may have meant when the
+/// given name is unknown (see [`crate::Pareg::set_suggester`]).
+pub trait Suggester: Send + Sync {
+    /// Gets the candidates that are similar to `input` in the order in which
+    /// they should be suggested. Returns empty vector if there is no similar
+    /// candidate.
+    fn suggest(&self, input: &str, candidates: &[&str]) -> Vec<String>;
+}
+
+/// Suggests the candidates with the smallest [`edit_distance`] from the
+/// input. Candidates that are completely different from the input are not
+/// suggested (the same as with [`FuzzyMatch::find`]). This is the default
+/// [`Suggester`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{EditDistanceSuggester, Suggester};
+///
+/// let flags = ["--color", "--colour", "--count"];
+/// assert_eq!(
+///     vec!["--color".to_string()],
+///     EditDistanceSuggester.suggest("--colr", &flags)
+/// );
+/// assert!(EditDistanceSuggester.suggest("-x", &flags).is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditDistanceSuggester;
+
+impl Suggester for EditDistanceSuggester {
+    fn suggest(&self, input: &str, candidates: &[&str]) -> Vec<String> {
+        let variants: Vec<_> =
+            candidates.iter().map(core::slice::from_ref).collect();
+        // Distance `0` makes sure that the closest candidates are returned
+        // even if only one of them is close.
+        match FuzzyMatch::find(input, &variants, 0) {
+            FuzzyMatch::Unique(i) => vec![candidates[i].into()],
+            FuzzyMatch::Closest(c) => c.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Suggests the candidates whose words start with the words of the input.
+/// Words are separated by `-` or `_` and their case is ignored. The words of
+/// the input must be in the same order as in the candidate, but the
+/// candidate may have more words. This works well for long kebab-case
+/// options (`--lat-ms` matches `--p99-latency-ms`) where the edit distance
+/// is large.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{Suggester, TokenPrefixSuggester};
+///
+/// let flags = ["--p99-latency-ms", "--p50-latency-ms", "--timeout-ms"];
+/// assert_eq!(
+///     vec!["--p99-latency-ms".to_string()],
+///     TokenPrefixSuggester.suggest("--p99-lat", &flags)
+/// );
+/// assert_eq!(
+///     vec!["--p99-latency-ms".to_string(), "--p50-latency-ms".to_string()],
+///     TokenPrefixSuggester.suggest("--latency", &flags)
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenPrefixSuggester;
+
+impl Suggester for TokenPrefixSuggester {
+    fn suggest(&self, input: &str, candidates: &[&str]) -> Vec<String> {
+        let tokens = |s: &str| -> Vec<String> {
+            s.split(['-', '_'])
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_lowercase())
+                .collect()
+        };
+        let input = tokens(input);
+        if input.is_empty() {
+            return vec![];
+        }
+        candidates
+            .iter()
+            .filter(|c| {
+                let mut cand = tokens(c).into_iter();
+                input
+                    .iter()
+                    .all(|i| cand.any(|c| c.starts_with(i.as_str())))
+            })
+            .map(|c| (*c).into())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ab", "ba"), 1);
+        assert_eq!(edit_distance("abcd", "badc"), 2);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+        assert_eq!(edit_distance("žluť", "zlut"), 2);
+        assert_eq!(edit_distance("never", "nevre"), 1);
+    }
+
+    #[test]
+    fn find() {
+        let variants: &[&[&str]] = &[&["all"], &["ale"], &["none", "nil"]];
+        let find = |v| FuzzyMatch::find(v, variants, 1);
+        assert_eq!(find("nne"), FuzzyMatch::Unique(2));
+        assert_eq!(find("nl"), FuzzyMatch::Unique(2));
+        assert_eq!(find("al"), FuzzyMatch::Closest(vec!["all", "ale"]));
+        assert_eq!(find("alll"), FuzzyMatch::Unique(0));
+        assert_eq!(find("xyz"), FuzzyMatch::Closest(vec![]));
+        assert_eq!(find(""), FuzzyMatch::Closest(vec![]));
+        assert_eq!(
+            FuzzyMatch::find("nothing", variants, 2),
+            FuzzyMatch::Closest(vec!["none"])
+        );
+        assert_eq!(FuzzyMatch::find("a", &[], 1), FuzzyMatch::Closest(vec![]));
+
+        let hint = FuzzyMatch::Closest(vec!["a", "b", "c"]).hint();
+        assert_eq!(hint.as_deref(), Some("Did you mean `a`, `b` or `c`?"));
+        assert_eq!(FuzzyMatch::Closest(vec![]).hint(), None);
+    }
+
+    #[test]
+    fn punct_and_prefix() {
+        let variants: &[&[&str]] =
+            &[&["auto"], &["autoupdate"], &["auto-run", "ar"], &["a_r"]];
+        assert_eq!(strip_punct("-a-_b_"), "ab");
+        assert!(matches!(strip_punct("ab"), Cow::Borrowed("ab")));
+
+        assert_eq!(find_flexible_punct("auto_update", variants), Some(1));
+        assert_eq!(find_flexible_punct("AUTORUN", variants), Some(2));
+        assert_eq!(find_flexible_punct("ar", variants), None);
+        assert_eq!(find_flexible_punct("", variants), None);
+        assert_eq!(find_flexible_punct("-", &[&["_"]]), Some(0));
+
+        assert_eq!(
+            prefix_matches("auto", variants),
+            ["auto", "autoupdate", "auto-run"]
+        );
+        assert_eq!(prefix_matches("autou", variants), ["autoupdate"]);
+        assert_eq!(prefix_matches("auto_r", variants), ["auto-run"]);
+        assert_eq!(
+            prefix_matches("A", variants),
+            ["auto", "autoupdate", "auto-run", "a_r"]
+        );
+        assert!(prefix_matches("-", variants).is_empty());
+        assert!(prefix_matches("autox", variants).is_empty());
+    }
+
+    #[test]
+    fn suggesters() {
+        let flags = [
+            "--p99-latency-ms",
+            "--p50-latency-ms",
+            "--max-latency-ms",
+            "--timeout-ms",
+            "--retry-count",
+            "--verbose",
+            "--version",
+        ];
+        let edit = |i| EditDistanceSuggester.suggest(i, &flags);
+        let token = |i| TokenPrefixSuggester.suggest(i, &flags);
+
+        // Typos are found by the edit distance, but not by the tokens.
+        assert_eq!(edit("--verbos"), ["--verbose"]);
+        assert!(token("--verbos").contains(&"--verbose".into()));
+        assert_eq!(edit("--retyr-count"), ["--retry-count"]);
+        assert!(token("--retyr-count").is_empty());
+
+        // Shortened words are found only by the tokens.
+        assert_eq!(
+            edit("--p99-lat"),
+            ["--p99-latency-ms", "--verbose", "--version"]
+        );
+        assert_eq!(token("--p99-lat"), ["--p99-latency-ms"]);
+        assert_eq!(
+            token("--latency-ms"),
+            ["--p99-latency-ms", "--p50-latency-ms", "--max-latency-ms"]
+        );
+        assert_eq!(token("--P50_LAT"), ["--p50-latency-ms"]);
+        assert_eq!(token("--ver"), ["--verbose", "--version"]);
+        assert_eq!(edit("--ver"), ["--verbose", "--version"]);
+
+        // Words must be in the same order.
+        assert!(token("--ms-latency").is_empty());
+        assert!(token("--").is_empty());
+        assert!(edit("-x").is_empty());
+        assert!(token("x").is_empty());
+        assert!(edit("x").is_empty());
+        assert!(EditDistanceSuggester.suggest("--a", &[]).is_empty());
+    }
+}
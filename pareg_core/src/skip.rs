@@ -0,0 +1,77 @@
+//! Sinks for [`crate::parsef`] that consume input without storing it.
+//!
+//! In the `parsef!` macro they may be used with the placeholder `{_}` that
+//! doesn't consume any argument:
+//! - `{_}`: [`Until`] the next literal character in the pattern.
+//! - `{_:ws}`: [`Ws`].
+//! - `{_:N}`: [`Chars`] with `N` chars.
+//! - `{_:until=C}`: [`Until`] the character `C`.
+//!
+//! # Examples
+//! ```rust
+//! use pareg_core::{parsef, skip, ParseFArg};
+//!
+//! let (mut a, mut b) = (0, 0);
+//! parsef(
+//!     &mut "5 \t 6".into(),
+//!     [
+//!         ParseFArg::Arg(&mut a),
+//!         ParseFArg::Arg(&mut skip::Ws),
+//!         ParseFArg::Arg(&mut b),
+//!     ],
+//! )
+//! .unwrap();
+//! assert_eq!((5, 6), (a, b));
+//! ```
+
+use alloc::format;
+
+use crate::{ArgError, ParseF, Reader, Result};
+
+/// Consumes zero or more whitespace characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ws;
+
+/// Consumes all characters until the given character. The character itself
+/// is not consumed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Until(pub char);
+
+/// Consumes exactly the given number of characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Chars(pub usize);
+
+impl ParseF for Ws {
+    fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        while r.peek()?.is_some_and(char::is_whitespace) {
+            r.next().transpose()?;
+        }
+        Ok(None)
+    }
+}
+
+impl ParseF for Until {
+    fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        while r.peek()?.is_some_and(|c| c != self.0) {
+            r.next().transpose()?;
+        }
+        Ok(None)
+    }
+}
+
+impl ParseF for Chars {
+    fn set_from_read(&mut self, r: &mut Reader) -> Result<Option<ArgError>> {
+        for i in 0..self.0 {
+            if r.next().transpose()?.is_none() {
+                return r
+                    .err_parse(format!(
+                        "Expected {} more characters.",
+                        self.0 - i
+                    ))
+                    .main_msg("Unexpected end of string.")
+                    .err();
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,29 @@
+use core::ops::Range;
+
+/// Parsed value together with its location in the arguments. This may be
+/// used to create errors that point to the value after it was parsed (see
+/// [`crate::Pareg::err_at`]).
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::Pareg;
+///
+/// let args = ["--min=5", "--max", "3"];
+/// let mut args = Pareg::new(args.iter().map(|a| a.to_string()).collect());
+///
+/// args.next();
+/// let min = args.cur_val_spanned::<u32>('=').unwrap();
+/// args.next();
+/// let max = args.next_arg_spanned::<u32>().unwrap();
+/// assert_eq!((min.value, min.idx, min.span), (5, 0, 6..7));
+/// assert_eq!((max.value, max.idx, max.span), (3, 2, 0..1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    /// The parsed value.
+    pub value: T,
+    /// Index of the argument with the value.
+    pub idx: usize,
+    /// Span of the value within the argument.
+    pub span: Range<usize>,
+}
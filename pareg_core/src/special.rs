@@ -0,0 +1,85 @@
+use alloc::{borrow::Cow, boxed::Box};
+use core::fmt::Display;
+
+/// Special argument that stops the parsing (see [`crate::Pareg::special`]
+/// and [`crate::Pareg::on_special`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Special {
+    /// `-h` or `--help`.
+    Help,
+    /// `-V` or `--version`.
+    Version,
+}
+
+/// Actions for special arguments that are intercepted by
+/// [`crate::Pareg::on_special`]. Special arguments without action are not
+/// intercepted.
+#[derive(Default)]
+pub struct SpecialArgs {
+    /// Called when the argument `-h` or `--help` is intercepted (e.g. to
+    /// print help).
+    pub help: Option<Box<dyn Fn() + Send + Sync>>,
+    /// Version that is printed to stdout when the argument `-V` or
+    /// `--version` is intercepted. It is printed only with the feature
+    /// `std`.
+    pub version: Option<Cow<'static, str>>,
+}
+
+impl Special {
+    /// Gets the special argument that `arg` is.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Special;
+    ///
+    /// assert_eq!(Some(Special::Help), Special::of("--help"));
+    /// assert_eq!(Some(Special::Version), Special::of("-V"));
+    /// assert_eq!(None, Special::of("-v"));
+    /// ```
+    pub fn of(arg: &str) -> Option<Self> {
+        match arg {
+            "-h" | "--help" => Some(Self::Help),
+            "-V" | "--version" => Some(Self::Version),
+            _ => None,
+        }
+    }
+}
+
+impl SpecialArgs {
+    /// Checks whether the special argument has action, so it should be
+    /// intercepted.
+    pub(crate) fn handles(&self, special: Special) -> bool {
+        match special {
+            Special::Help => self.help.is_some(),
+            Special::Version => self.version.is_some(),
+        }
+    }
+
+    /// Runs the action of the special argument.
+    pub(crate) fn run(&self, special: Special) {
+        match special {
+            Special::Help => {
+                if let Some(h) = &self.help {
+                    h();
+                }
+            }
+            #[cfg(feature = "std")]
+            Special::Version => {
+                if let Some(v) = &self.version {
+                    std::println!("{v}");
+                }
+            }
+            #[cfg(not(feature = "std"))]
+            Special::Version => {}
+        }
+    }
+}
+
+impl Display for Special {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Help => f.write_str("--help"),
+            Self::Version => f.write_str("--version"),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    fs::File,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{ArgError, FromArg, Result};
+
+/// Value that may be `-` which means that it should be read from stdin (e.g.
+/// `--file -`). Other values are parsed as `T`.
+///
+/// # Examples
+/// ```rust
+/// use std::path::PathBuf;
+///
+/// use pareg_core::{FromArg, StdinOr};
+///
+/// assert_eq!(StdinOr::Stdin, StdinOr::<PathBuf>::from_arg("-").unwrap());
+/// assert_eq!(
+///     StdinOr::Value(PathBuf::from("a.txt")),
+///     StdinOr::<PathBuf>::from_arg("a.txt").unwrap()
+/// );
+/// assert!(StdinOr::<u32>::from_arg("x").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StdinOr<T> {
+    /// The value is `-`.
+    Stdin,
+    /// Other value.
+    Value(T),
+}
+
+/// Input given by argument (see [`crate::Pareg::next_file_or_stdin`]). It is
+/// either opened file or stdin. Reading from it reads from the file or from
+/// stdin.
+#[derive(Debug)]
+pub enum Input {
+    /// File opened from the path.
+    File(PathBuf, File),
+    /// Standard input (the argument was `-`).
+    Stdin(io::Stdin),
+}
+
+impl<T> StdinOr<T> {
+    /// Checks whether the value is `-`.
+    pub fn is_stdin(&self) -> bool {
+        matches!(self, Self::Stdin)
+    }
+
+    /// Gets the value that is not `-`.
+    pub fn value(self) -> Option<T> {
+        match self {
+            Self::Stdin => None,
+            Self::Value(v) => Some(v),
+        }
+    }
+}
+
+impl<'a, T> FromArg<'a> for StdinOr<T>
+where
+    T: FromArg<'a>,
+{
+    fn from_arg(arg: &'a str) -> Result<Self> {
+        if arg == "-" {
+            Ok(Self::Stdin)
+        } else {
+            T::from_arg(arg).map(Self::Value)
+        }
+    }
+
+    fn try_from_arg(arg: &'a str) -> Option<Self> {
+        if arg == "-" {
+            Some(Self::Stdin)
+        } else {
+            T::try_from_arg(arg).map(Self::Value)
+        }
+    }
+}
+
+impl Input {
+    /// Gets the path of the file. Returns [`None`] for stdin.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File(p, _) => Some(p),
+            Self::Stdin(_) => None,
+        }
+    }
+
+    /// Checks whether this is stdin.
+    pub fn is_stdin(&self) -> bool {
+        matches!(self, Self::Stdin(_))
+    }
+}
+
+impl Read for Input {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::File(_, f) => f.read(buf),
+            Self::Stdin(s) => s.read(buf),
+        }
+    }
+}
+
+/// Reads value of the option `opt` from stdin. The whole input is read and
+/// trailing newline is removed. Errors are [`ArgError::Io`] with message
+/// that names the option.
+pub fn read_stdin_value(opt: &str) -> Result<String> {
+    read_value_from(opt, io::stdin().lock())
+}
+
+/// Same as [`read_stdin_value`], but the value is read from `input`.
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::read_value_from;
+///
+/// let value = read_value_from("--text", "hello\n".as_bytes()).unwrap();
+/// assert_eq!("hello", value);
+///
+/// let err = read_value_from("--text", &[0xff_u8][..]).unwrap_err();
+/// assert!(err.is_io());
+/// assert!(err.to_string().contains("`--text`"));
+/// ```
+pub fn read_value_from(opt: &str, mut input: impl Read) -> Result<String> {
+    let mut res = String::new();
+    if let Err(e) = input.read_to_string(&mut res) {
+        return Err(ArgError::Io(io::Error::new(
+            e.kind(),
+            format!("Failed to read the value of `{opt}` from stdin: {e}"),
+        )));
+    }
+    if res.ends_with('\n') {
+        res.pop();
+        if res.ends_with('\r') {
+            res.pop();
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Pareg;
+
+    /// Reader that always fails.
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+    }
+
+    #[test]
+    fn stdin_or() {
+        assert_eq!(StdinOr::<u8>::from_arg("-").unwrap(), StdinOr::Stdin);
+        assert_eq!(StdinOr::<u8>::from_arg("5").unwrap(), StdinOr::Value(5));
+        assert_eq!(StdinOr::<i8>::try_from_arg("-"), Some(StdinOr::Stdin));
+        assert_eq!(StdinOr::<u8>::try_from_arg("x"), None);
+        assert_eq!(
+            StdinOr::<&str>::from_arg("--").unwrap().value(),
+            Some("--")
+        );
+
+        let err = StdinOr::<u8>::from_arg("5x").unwrap_err();
+        assert_eq!(err.span(), Some(0..2));
+
+        let mut args = Pareg::new(vec!["-n".into(), "-".into(), "x".into()]);
+        args.next();
+        assert!(args.next_arg::<StdinOr<u8>>().unwrap().is_stdin());
+        let err = args.next_arg::<StdinOr<u8>>().unwrap_err();
+        assert_eq!(err.ctx().unwrap().error_idx, 2);
+    }
+
+    #[test]
+    fn read_value() {
+        let read = |s: &str| read_value_from("-v", Cursor::new(s)).unwrap();
+        assert_eq!(read("a b\n"), "a b");
+        assert_eq!(read("a\r\n"), "a");
+        assert_eq!(read("a\n\n"), "a\n");
+        assert_eq!(read(""), "");
+
+        let err = read_value_from("--text", Broken).unwrap_err();
+        let ArgError::Io(e) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(
+            err.to_string(),
+            "Failed to read the value of `--text` from stdin: closed"
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let mut args =
+            Pareg::new(vec!["--file".into(), "pareg-missing-file.txt".into()]);
+        args.next();
+        let err = args.next_file_or_stdin().unwrap_err();
+        let ArgError::InvalidValue(ctx) = &err else {
+            panic!("{err:?}");
+        };
+        assert_eq!((ctx.error_idx, ctx.error_span.clone()), (1, 0..22));
+        assert_eq!(ctx.owner.as_deref(), Some("--file"));
+        assert!(ctx
+            .long_message
+            .as_deref()
+            .unwrap()
+            .starts_with("Failed to open `pareg-missing-file.txt`: "));
+    }
+}
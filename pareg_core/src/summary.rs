@@ -0,0 +1,197 @@
+use core::{iter, ops::Range};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{arg_class, ArgClass, ArgSource};
+
+/// How was argument used (see [`crate::Pareg::summary`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum UseKind {
+    /// Option without value (e.g. `-v` or `--verbose`).
+    Flag,
+    /// Option with value in the same argument (e.g. `--color=always` or
+    /// `-j8`).
+    OptionWithInlineValue,
+    /// Option with value in the next argument (e.g. `--color always`). The
+    /// value is part of the same [`UsedArg`].
+    OptionValue,
+    /// Argument that is not option.
+    Positional,
+}
+
+/// Argument that was consumed by the parser (see [`crate::Pareg::summary`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UsedArg {
+    /// Index of the argument.
+    pub idx: usize,
+    /// How was the argument used.
+    pub kind: UseKind,
+    /// Name of the option without its value. This is the first of the names
+    /// given to [`crate::Pareg::opt_value`] if it was used to get the value.
+    /// [`None`] for positional arguments.
+    pub name: Option<String>,
+}
+
+/// Summary of the arguments that were consumed by the parser. It doesn't
+/// contain any values (only names of the options), so it may be used for
+/// example to collect which options are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ParseSummary {
+    /// The consumed arguments in the order of their position. Values of
+    /// options in separate argument are not listed separately.
+    pub args: Vec<UsedArg>,
+}
+
+impl ParseSummary {
+    /// Gets the names of all the options that were used (with duplicates).
+    pub fn options(&self) -> impl Iterator<Item = &str> {
+        self.args.iter().filter_map(|a| a.name.as_deref())
+    }
+
+    /// Gets the number of positional arguments.
+    pub fn positional_count(&self) -> usize {
+        self.args
+            .iter()
+            .filter(|a| a.kind == UseKind::Positional)
+            .count()
+    }
+}
+
+/// Use of argument recorded by the parsing methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum UseTag {
+    /// The argument is option with the given kind and canonical name.
+    Option(UseKind, Option<String>),
+    /// The argument is value of the previous argument.
+    Value,
+}
+
+/// Classifies argument without recorded use by its text. Returns the kind
+/// and the range of the option name in `arg` ([`None`] for positional
+/// arguments). Value attached to the option is never part of the name, so
+/// only the first option of short option cluster is used (e.g. `-p` for
+/// `-phunter2`). Negative numbers (e.g. `-5`) are positional.
+pub(crate) fn classify_arg(arg: &str) -> (UseKind, Option<Range<usize>>) {
+    let name_len = match arg_class(arg) {
+        ArgClass::DoubleDash => 2,
+        ArgClass::Flag => arg[2..]
+            .find(|c: char| !c.is_alphanumeric() && !"-_.".contains(c))
+            .map_or(arg.len(), |p| p + 2),
+        ArgClass::ShortCluster if !is_negative_number(arg) => {
+            arg[1..].chars().next().map_or(1, |c| c.len_utf8() + 1)
+        }
+        _ => return (UseKind::Positional, None),
+    };
+    let kind = if name_len == arg.len() {
+        UseKind::Flag
+    } else {
+        UseKind::OptionWithInlineValue
+    };
+    (kind, Some(0..name_len))
+}
+
+/// Checks whether the argument is negative number (e.g. `-5` or `-.5`).
+fn is_negative_number(arg: &str) -> bool {
+    let Some(num) = arg.strip_prefix('-') else {
+        return false;
+    };
+    let num = num.strip_prefix('.').unwrap_or(num);
+    num.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Uses of the arguments by their index (see [`crate::Pareg::summary`]).
+/// Arguments without tag are classified by their text.
+#[derive(Debug, Default)]
+pub(crate) struct ArgUses(Vec<Option<UseTag>>);
+
+impl ArgUses {
+    /// Gets the recorded use of the argument at `idx`.
+    pub(crate) fn get(&self, idx: usize) -> Option<&UseTag> {
+        self.0.get(idx).and_then(Option::as_ref)
+    }
+
+    /// Records how was the argument at `idx` used.
+    pub(crate) fn record(&mut self, idx: usize, tag: UseTag) {
+        if self.0.len() <= idx {
+            self.0.resize(idx + 1, None);
+        }
+        self.0[idx] = Some(tag);
+    }
+
+    /// Records that the argument before `cur` is value of the argument
+    /// before it if it looks like option without value.
+    pub(crate) fn record_value<S>(&mut self, args: &S, cur: usize)
+    where
+        S: ArgSource + ?Sized,
+    {
+        if let Some(idx) = cur.checked_sub(1) {
+            self.record_values(args, idx, 1);
+        }
+    }
+
+    /// Records that the `n` arguments from `start` are values of the
+    /// argument before them if it looks like option without value. Canonical
+    /// name recorded for the option is kept.
+    pub(crate) fn record_values<S>(&mut self, args: &S, start: usize, n: usize)
+    where
+        S: ArgSource + ?Sized,
+    {
+        let Some(idx) = start.checked_sub(1) else {
+            return;
+        };
+        let Some(owner) = args.get(idx) else {
+            return;
+        };
+        if !arg_class(owner).is_option() || owner.contains('=') {
+            return;
+        }
+        let name = match self.get(idx) {
+            Some(UseTag::Option(_, name)) => name.clone(),
+            _ => None,
+        };
+        self.record(idx, UseTag::Option(UseKind::OptionValue, name));
+        for i in start..start + n {
+            self.record(i, UseTag::Value);
+        }
+    }
+
+    /// Records that the argument before `cur` is option with value after
+    /// `sep`.
+    pub(crate) fn record_inline<S>(&mut self, args: &S, cur: usize, sep: char)
+    where
+        S: ArgSource + ?Sized,
+    {
+        let Some(idx) = cur.checked_sub(1) else {
+            return;
+        };
+        let Some(arg) = args.get(idx) else {
+            return;
+        };
+        let name = arg.split_once(sep).map(|(k, _)| k.into());
+        let kind = UseKind::OptionWithInlineValue;
+        self.record(idx, UseTag::Option(kind, name));
+    }
+
+    /// Removes the use of the argument at `idx`.
+    #[cfg(feature = "glob")]
+    pub(crate) fn remove(&mut self, idx: usize) {
+        if idx < self.0.len() {
+            self.0.remove(idx);
+        }
+    }
+
+    /// Inserts `cnt` arguments without use at `at`.
+    pub(crate) fn insert(&mut self, at: usize, cnt: usize) {
+        if at < self.0.len() {
+            self.0.splice(at..at, iter::repeat_n(None, cnt));
+        }
+    }
+
+    /// Forgets the uses of the arguments from `idx`.
+    pub(crate) fn truncate(&mut self, idx: usize) {
+        self.0.truncate(idx);
+    }
+}
@@ -0,0 +1,157 @@
+//! Helpers for testing command line parsing and the rendered errors. This is
+//! available with the feature `testing`.
+
+use alloc::string::{String, ToString};
+
+use crate::{ArgError, ColorMode, JoinedArgs, Pareg};
+
+/// Renders the error without color and with the `argument error:` header.
+/// The result doesn't depend on the terminal, so it may be compared in
+/// tests.
+///
+/// # Examples
+#[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+#[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+/// use pareg_core::{testing::render_error_plain, Pareg};
+///
+/// let mut args = Pareg::from_cmdline("prog --count x");
+/// args.next();
+/// args.next();
+/// let err = args.next_arg::<u32>().unwrap_err();
+/// assert!(render_error_plain(&err).starts_with("argument error: "));
+/// ```
+pub fn render_error_plain(err: &ArgError) -> String {
+    match err.ctx() {
+        Some(ctx) => ctx.clone().color_mode(ColorMode::Never).to_string(),
+        None => err.to_string(),
+    }
+}
+
+/// Removes whitespace at the end of each line and empty lines at the end.
+/// This is used by [`crate::assert_parse_err`] so that the expected
+/// rendering doesn't have to match the trailing whitespace.
+pub fn trim_trailing_ws(s: &str) -> String {
+    let mut res = String::new();
+    for l in s.trim_end().lines() {
+        res += l.trim_end();
+        res.push('\n');
+    }
+    res
+}
+
+/// Asserts that the result is error and that its plain rendering (see
+/// [`testing::render_error_plain`]) is the expected string. Trailing
+/// whitespace on each line is ignored (see [`testing::trim_trailing_ws`]).
+///
+/// [`testing::render_error_plain`]: crate::testing::render_error_plain
+/// [`testing::trim_trailing_ws`]: crate::testing::trim_trailing_ws
+///
+/// # Examples
+#[cfg_attr(not(feature = "minimal-errors"), doc = "```rust")]
+#[cfg_attr(feature = "minimal-errors", doc = "```ignore")]
+/// use pareg_core::{assert_parse_err, Pareg};
+///
+/// let mut args = Pareg::from_cmdline("prog --count x");
+/// args.next();
+/// args.next();
+/// assert_parse_err!(
+///     args.next_arg::<u32>(),
+///     "
+/// argument error: Invalid value for `--count`: invalid digit found in string
+/// --> arg2:0..1
+///  |
+///  $ prog --count x
+///  |              ^ invalid digit found in string
+/// "
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_parse_err {
+    ($res:expr, $expected:expr $(,)?) => {
+        match $res {
+            Ok(_) => panic!("Expected error, but the parsing succeeded."),
+            Err(e) => {
+                let e: $crate::ArgError = e.into();
+                let actual = $crate::testing::trim_trailing_ws(
+                    &$crate::testing::render_error_plain(&e),
+                );
+                let expected =
+                    $crate::testing::trim_trailing_ws($expected.trim_start());
+                assert_eq!(actual, expected, "Rendered error doesn't match.");
+            }
+        }
+    };
+}
+
+impl Pareg {
+    /// Creates [`Pareg`] from command line that is split by simple shell
+    /// like rules (see [`JoinedArgs`]). The first argument is NOT skipped,
+    /// but it is the program in errors (the second argument is `arg1`).
+    ///
+    /// # Panics
+    /// If there is unterminated quote or the command line ends with
+    /// backslash.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use pareg_core::Pareg;
+    ///
+    /// let args = Pareg::from_cmdline(r#"prog --name "John Doe" '' a\ b"#);
+    /// assert_eq!(args.all_args(), ["prog", "--name", "John Doe", "", "a b"]);
+    /// ```
+    pub fn from_cmdline(cmdline: &str) -> Self {
+        match JoinedArgs::new(cmdline) {
+            Ok(joined) => Self::from_joined(&joined),
+            Err(e) => panic!("{}", e.message().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Unterminated `'`")]
+    fn unterminated_single() {
+        Pareg::from_cmdline("prog 'abc");
+    }
+
+    #[test]
+    #[should_panic(expected = "Unterminated `\"`")]
+    fn unterminated_double() {
+        Pareg::from_cmdline("prog \"abc\\\"");
+    }
+
+    #[test]
+    #[should_panic(expected = "ends with")]
+    fn trailing_backslash() {
+        Pareg::from_cmdline("prog abc\\");
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn parse_err() {
+        let mut args = Pareg::from_cmdline("prog --count  'x y'");
+        args.next();
+        args.next();
+        assert_parse_err!(
+            args.next_arg::<u32>(),
+            "
+argument error: Invalid value for `--count`: invalid digit found in string
+--> arg2:0..3
+ |
+ $ prog --count x y
+ |              ^^^ invalid digit found in string
+"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Rendered error doesn't match")]
+    fn parse_err_mismatch() {
+        let mut args = Pareg::from_cmdline("prog x");
+        args.next();
+        assert_parse_err!(args.next_arg::<u32>(), "argument error: other");
+    }
+}
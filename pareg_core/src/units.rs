@@ -0,0 +1,297 @@
+use alloc::{format, string::String};
+use core::{ops::Range, str::FromStr};
+
+use crate::{
+    from_read::read_elem, ArgErrCtx, ArgError, FromArgStr, FromRead,
+    ParseResult, ReadFmt, Reader, Result, Tuple,
+};
+
+/// Percentage. The value is the ratio (`75%` is `0.75`).
+///
+/// By default reads both `NN%` and bare ratio (`0.75`). With the format `%`
+/// only `NN%` is accepted and with the format `ratio` only the bare ratio is
+/// accepted. Negative values are [`ArgError::InvalidValue`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{FromArg, Percent};
+///
+/// assert_eq!(Percent(0.75), Percent::from_arg("75%").unwrap());
+/// assert_eq!(Percent(0.5), Percent::from_arg("0.5").unwrap());
+/// assert!(Percent::from_arg("-5%").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Percent(pub f64);
+
+/// Size in bytes with optional suffix (e.g. `512`, `1.5GiB` or `512k`). The
+/// suffixes are case insensitive:
+/// - `B` or no suffix for bytes.
+/// - `kB`, `MB`, `GB`, `TB`, `PB` and `EB` are powers of 1000.
+/// - `KiB`, `MiB`, `GiB`, `TiB`, `PiB` and `EiB` are powers of 1024.
+/// - `K`, `M`, `G`, `T`, `P` and `E` are also powers of 1024.
+///
+/// Fractional sizes are rounded down to whole bytes. Sizes that don't fit
+/// into [`u64`] are [`ArgError::InvalidValue`].
+///
+/// # Examples
+/// ```rust
+/// use pareg_core::{ByteSize, FromArg};
+///
+/// assert_eq!(ByteSize(1000), ByteSize::from_arg("1kB").unwrap());
+/// assert_eq!(ByteSize(1024), ByteSize::from_arg("1KiB").unwrap());
+/// assert_eq!(ByteSize(524288), ByteSize::from_arg("512k").unwrap());
+/// assert_eq!(ByteSize(1610612736), ByteSize::from_arg("1.5GiB").unwrap());
+/// assert!(ByteSize::from_arg("16E").is_err());
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct ByteSize(pub u64);
+
+const SIZE_HINT: &str = "Valid suffixes are `B`, `kB`, `MB`, `GB`, `TB`, \
+    `PB` and `EB` (powers of 1000) and `K`, `M`, `G`, `T`, `P`, `E`, `KiB`, \
+    `MiB`, `GiB`, `TiB`, `PiB` and `EiB` (powers of 1024).";
+
+impl FromRead for Percent {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        Self::from_read_fmt(r, &ReadFmt::default())
+    }
+
+    fn from_read_fmt(r: &mut Reader, fmt: &ReadFmt) -> ParseResult<Self> {
+        let res = (|| {
+            _ = r.peek();
+            let start = r.pos().unwrap_or_default();
+            let v = read_elem::<f64>(r)?;
+            let percent = match fmt.custom() {
+                "ratio" => false,
+                "%" => match r.next().transpose()? {
+                    Some('%') => true,
+                    _ => {
+                        return r
+                            .err_parse("Expected `%`.")
+                            .hint("Write the percentage as `75%`.")
+                            .err()
+                    }
+                },
+                _ if r.peek()? == Some('%') => {
+                    _ = r.next();
+                    true
+                }
+                _ => false,
+            };
+
+            if v.is_sign_negative() || !v.is_finite() {
+                let span = start..r.read_end();
+                return Err(invalid_value(r, "Invalid percentage.", span)
+                    .hint(
+                        "Use percentage such as `75%` or ratio such as \
+                        `0.75`. It must not be negative.",
+                    ));
+            }
+            Ok(Self(if percent { v / 100. } else { v }))
+        })();
+        ParseResult {
+            res: res.as_ref().ok().copied(),
+            err: res.err(),
+        }
+    }
+}
+
+impl FromRead for ByteSize {
+    fn from_read(r: &mut Reader) -> ParseResult<Self> {
+        let res = (|| {
+            _ = r.peek();
+            let start = r.pos().unwrap_or_default();
+
+            let mut int = String::new();
+            let mut frac = String::new();
+            while let Some(c @ '0'..='9') = r.peek()? {
+                int.push(c);
+                _ = r.next();
+            }
+            if r.peek()? == Some('.') {
+                _ = r.next();
+                while let Some(c @ '0'..='9') = r.peek()? {
+                    frac.push(c);
+                    _ = r.next();
+                }
+            }
+            if int.is_empty() && frac.is_empty() {
+                return r
+                    .err_parse("Expected at least one digit.")
+                    .hint(
+                        "Write the size as number with optional suffix \
+                        (e.g. `512k`).",
+                    )
+                    .err();
+            }
+
+            _ = r.peek();
+            let suffix_start = r.pos().unwrap_or_default();
+            let mut suffix = String::new();
+            while let Some(c) = r.peek()?.filter(|c| c.is_ascii_alphabetic()) {
+                suffix.push(c.to_ascii_lowercase());
+                _ = r.next();
+            }
+            let end = r.read_end();
+
+            let Some(mult) = size_multiplier(&suffix) else {
+                return r
+                    .err_parse(format!("Unknown size suffix `{suffix}`."))
+                    .spanned(suffix_start..end)
+                    .inline_msg("Unknown suffix.")
+                    .hint(SIZE_HINT)
+                    .err();
+            };
+
+            // Digits after the 20th don't change the result.
+            frac.truncate(20);
+            let size = int
+                .parse::<u128>()
+                .ok()
+                .or(int.is_empty().then_some(0))
+                .and_then(|i| i.checked_mul(mult as u128))
+                .and_then(|i| {
+                    let f = frac.parse::<u128>().unwrap_or_default();
+                    let f = f * mult as u128 / 10_u128.pow(frac.len() as u32);
+                    i.checked_add(f)
+                })
+                .and_then(|s| u64::try_from(s).ok());
+            match size {
+                Some(s) => Ok(Self(s)),
+                None => {
+                    Err(invalid_value(r, "Size is too large.", start..end)
+                        .hint(format!(
+                            "Maximum size is `{}` bytes. {SIZE_HINT}",
+                            u64::MAX
+                        )))
+                }
+            }
+        })();
+        ParseResult {
+            res: res.as_ref().ok().copied(),
+            err: res.err(),
+        }
+    }
+}
+
+impl FromStr for Percent {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for Percent {}
+
+impl FromStr for ByteSize {
+    type Err = ArgError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Tuple::<Self>::from_str(s).map(|t| t.0)
+    }
+}
+
+impl FromArgStr for ByteSize {}
+
+/// Gets the multiplier for the lowercase size suffix.
+fn size_multiplier(suffix: &str) -> Option<u64> {
+    let (prefix, binary) = match suffix {
+        "" | "b" => return Some(1),
+        s if s.len() == 1 => (s, true),
+        s => match s.strip_suffix("ib") {
+            Some(p) => (p, true),
+            None => (s.strip_suffix('b')?, false),
+        },
+    };
+    let exp = match prefix {
+        "k" => 1,
+        "m" => 2,
+        "g" => 3,
+        "t" => 4,
+        "p" => 5,
+        "e" => 6,
+        _ => return None,
+    };
+    Some(if binary {
+        1 << (10 * exp)
+    } else {
+        1000_u64.pow(exp)
+    })
+}
+
+/// Creates [`ArgError::InvalidValue`] with the given span in the input of
+/// the reader.
+fn invalid_value(
+    r: &Reader,
+    msg: &'static str,
+    span: Range<usize>,
+) -> ArgError {
+    let err =
+        ArgError::InvalidValue(ArgErrCtx::from_msg(msg, String::new()).into());
+    r.map_err(err).spanned(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FromArg;
+
+    fn ctx(err: ArgError) -> (bool, Range<usize>) {
+        let invalid = matches!(err, ArgError::InvalidValue(_));
+        (invalid, err.span().unwrap())
+    }
+
+    #[test]
+    fn percent() {
+        assert_eq!(Percent::from_arg("100%").unwrap(), Percent(1.));
+        assert_eq!(Percent::from_arg("0.5").unwrap(), Percent(0.5));
+        assert_eq!(Percent::from_arg("0%").unwrap(), Percent(0.));
+        assert_eq!(Percent::from_arg("150%").unwrap(), Percent(1.5));
+        assert!(Percent::from_arg("50%%").is_err());
+
+        let err = Percent::from_arg("-5%").unwrap_err();
+        assert_eq!(ctx(err), (true, 0..3));
+        let err = Percent::from_arg("-0.5").unwrap_err();
+        assert!(err.hint_str().unwrap().contains("`75%`"));
+
+        let fmt = |s: &str, f: &str| {
+            Percent::from_read_fmt(&mut s.into(), &f.into()).res
+        };
+        assert_eq!(fmt("75%", "%"), Some(Percent(0.75)));
+        assert_eq!(fmt("0.75", "%"), None);
+        assert_eq!(fmt("0.75", "ratio"), Some(Percent(0.75)));
+        assert_eq!(fmt("75%", "ratio"), Some(Percent(75.)));
+    }
+
+    #[test]
+    fn byte_size() {
+        let size = |s: &str| ByteSize::from_arg(s).map(|b| b.0);
+        assert_eq!(size("1kB").unwrap(), 1000);
+        assert_eq!(size("1KiB").unwrap(), 1024);
+        assert_eq!(size("1kb").unwrap(), 1000);
+        assert_eq!(size("1KIB").unwrap(), 1024);
+        assert_eq!(size("1K").unwrap(), 1024);
+        assert_eq!(size("42").unwrap(), 42);
+        assert_eq!(size("42B").unwrap(), 42);
+        assert_eq!(size("1.5MB").unwrap(), 1_500_000);
+        assert_eq!(size(".5k").unwrap(), 512);
+        assert_eq!(size("1.0009kB").unwrap(), 1000);
+        assert_eq!(size("15E").unwrap(), 15 << 60);
+        assert_eq!(size("18446744073709551615").unwrap(), u64::MAX);
+
+        let err = size("16E").unwrap_err();
+        assert_eq!(ctx(err), (true, 0..3));
+        let err = size("18446744073709551616").unwrap_err();
+        assert!(err.hint_str().unwrap().contains("`KiB`"));
+        assert_eq!(ctx(err), (true, 0..20));
+
+        let err = size("12.5XB").unwrap_err();
+        assert_eq!(ctx(err), (false, 4..6));
+        let err = size("12 kB").unwrap_err();
+        assert!(matches!(err, ArgError::FailedToParse(_)));
+        assert!(size("kB").is_err());
+        assert!(size(".").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::arg_class;
+
+/// Determines what [`crate::Pareg::handle_or_defer`] does with unknown
+/// arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownSink {
+    /// Unknown arguments are errors.
+    #[default]
+    Error,
+    /// Unknown arguments are collected so that they may be forwarded. The
+    /// value determines whether unknown option consumes the following
+    /// argument as its value.
+    Collect(UnknownValue),
+}
+
+/// Policy that determines whether unknown option without `=` takes the
+/// following argument as its value. It is not possible to know this for
+/// unknown options, so it must be chosen explicitly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownValue {
+    /// The following argument is never taken.
+    #[default]
+    Never,
+    /// The following argument is taken if it doesn't look like option or
+    /// `--` (see [`crate::ArgClass::is_value`]).
+    IfNotOption,
+    /// The following argument is always taken (if there is one).
+    Always,
+}
+
+impl UnknownValue {
+    /// Checks whether the argument `next` should be taken as value of the
+    /// unknown option `arg`. Only option (see [`crate::ArgClass::is_option`])
+    /// without `=` may take value.
+    pub fn takes(&self, arg: &str, next: &str) -> bool {
+        if !arg_class(arg).is_option() || arg.contains('=') {
+            return false;
+        }
+        match self {
+            Self::Never => false,
+            Self::IfNotOption => arg_class(next).is_value(),
+            Self::Always => true,
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use proc_macro::TokenStream;
 
 /// Derives the [`pareg_core::FromArg`] macro for an enum. The enum must not be
-/// generic and the enum members cannot contain any  fields.
+/// generic and the enum members cannot contain any fields (except the
+/// variant with `#[arg(other)]`).
 ///
 /// The parsing is case insensitive.
 ///
@@ -28,6 +29,135 @@ use proc_macro::TokenStream;
 /// assert_eq!(ColorMode::Always, ColorMode::from_arg("oK").unwrap());
 /// assert_eq!(ColorMode::Never, ColorMode::from_arg("NO").unwrap());
 /// assert_eq!(ColorMode::Auto, ColorMode::from_arg("AuTo").unwrap());
+/// assert_eq!(["auto", "always", "never"], ColorMode::arg_variants());
+/// ```
+///
+/// One variant with single field may have the attribute `#[arg(other)]`.
+/// Values that don't match any other variant are parsed into its field with
+/// [`pareg_core::FromArg`] (the whitespace around the value is trimmed, but
+/// the case is kept).
+///
+/// ```
+/// use pareg_core::{self as pareg, FromArg};
+/// use pareg_proc::FromArg;
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// enum Target {
+///     All,
+///     None,
+///     #[arg(other)]
+///     Named(String),
+/// }
+///
+/// assert_eq!(Target::All, Target::from_arg("all").unwrap());
+/// assert_eq!(
+///     Target::Named("Main".into()),
+///     Target::from_arg(" Main ").unwrap()
+/// );
+/// ```
+///
+/// With the attribute `#[arg(fuzzy)]` on the enum, misspelled values are
+/// accepted if exactly one variant is within the edit distance (see
+/// [`pareg_core::edit_distance`]). The default distance is 1 and it may be
+/// set with `#[arg(fuzzy(max_distance = N))]`. Exact matches are always
+/// preferred. If the value is ambiguous or too far, the error hint suggests
+/// the closest names.
+///
+/// ```
+/// use pareg_core::{self as pareg, FromArg};
+/// use pareg_proc::FromArg;
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// #[arg(fuzzy(max_distance = 1))]
+/// enum ColorMode {
+///     Auto,
+///     Always,
+///     Never,
+/// }
+///
+/// assert_eq!(ColorMode::Always, ColorMode::from_arg("alwyas").unwrap());
+/// let err = ColorMode::from_arg("nevr!").unwrap_err();
+/// assert!(err.hint_str().unwrap().starts_with("Did you mean `never`?"));
+/// ```
+///
+/// If the value is not known, the error hint suggests the variants whose
+/// names start with the value (ignoring case and the punctuation `-` and
+/// `_`). With the attribute `#[arg(flexible_punct)]` on the enum, values that
+/// differ from a name only by the punctuation `-` and `_` are accepted (see
+/// [`pareg_core::find_flexible_punct`]). Exact matches are always preferred.
+///
+/// ```
+/// use pareg_core::{self as pareg, FromArg};
+/// use pareg_proc::FromArg;
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// enum Update {
+///     Auto,
+///     Scheduled,
+///     Manual,
+/// }
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// #[arg(flexible_punct)]
+/// enum Mode {
+///     DryRun,
+///     Run,
+/// }
+///
+/// let err = Update::from_arg("sched").unwrap_err();
+/// assert!(err.hint_str().unwrap().starts_with("Did you mean `scheduled`?"));
+/// assert_eq!(Mode::DryRun, Mode::from_arg("dry-run").unwrap());
+/// assert_eq!(Mode::DryRun, Mode::from_arg("Dry_Run").unwrap());
+/// ```
+///
+/// With the attribute `#[arg(numeric)]` on the enum, values may be also given
+/// as the explicit discriminants of the variants. All the variants (except
+/// the variant with `#[arg(other)]`) must have unique integer discriminant.
+///
+/// ```
+/// use pareg_core::{self as pareg, FromArg};
+/// use pareg_proc::FromArg;
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// #[arg(numeric)]
+/// enum LogLevel {
+///     Error = 1,
+///     Warn = 2,
+///     Info = 3,
+/// }
+///
+/// assert_eq!(LogLevel::Warn, LogLevel::from_arg("2").unwrap());
+/// assert_eq!(LogLevel::Warn, LogLevel::from_arg("warn").unwrap());
+/// assert!(LogLevel::from_arg("4").is_err());
+/// ```
+///
+/// With the attribute `#[arg(set)]` on the enum, set of the variants is also
+/// generated. Its name is the name of the enum with the suffix `Set` (e.g.
+/// `FeatureSet`) and it is parsed from values separated by `+` or `,`.
+/// Duplicate values are rejected. Value that means all the variants or no
+/// variants may be set with `#[arg(set(all = "all", none = "none"))]`. The
+/// enum may have at most 64 variants and it cannot have the variant with
+/// `#[arg(other)]`.
+///
+/// ```
+/// use pareg_core::{self as pareg, FromArg};
+/// use pareg_proc::FromArg;
+///
+/// #[derive(FromArg, PartialEq, Debug)]
+/// #[arg(set(all = "all"))]
+/// enum Feature {
+///     Audio,
+///     Video,
+///     Subs,
+/// }
+///
+/// let set = FeatureSet::from_arg("audio+video").unwrap();
+/// assert!(set.contains(Feature::Audio));
+/// assert!(!set.contains(Feature::Subs));
+/// assert_eq!(FeatureSet::all(), FeatureSet::from_arg("all").unwrap());
+///
+/// let err = FeatureSet::from_arg("audio,subs,audio").unwrap_err();
+/// assert_eq!(err.span(), Some(11..16));
 /// ```
 #[proc_macro_derive(FromArg, attributes(arg))]
 pub fn derive_from_arg(item: TokenStream) -> TokenStream {
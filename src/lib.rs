@@ -25,7 +25,7 @@
 //! ```rust
 //! use std::process::ExitCode;
 //!
-//! use pareg::{Result, Pareg, FromArg, starts_any};
+//! use pareg::{Result, Pareg, FromArg};
 //!
 //! // You can define enums, and have them automaticaly derive FromArg where each
 //! // enum variant will be parsed from case insensitive strings of the same name
@@ -46,7 +46,7 @@
 //! }
 //!
 //! impl Args {
-//!     // create function that takes the arguments as ArgIterator
+//!     // create function that takes the arguments as Pareg
 //!     pub fn parse(mut args: Pareg) -> Result<Self>
 //!     {
 //!         // initialize with default values
@@ -60,15 +60,20 @@
 //!             match arg {
 //!                 // when there is the argument `count`, parse the next value
 //!                 "-c" | "--count" => res.count = args.next_arg()?,
-//!                 // if the argument starts with either `--color` or
-//!                 // `--colour`, parse its value.
-//!                 a if starts_any!(a, "--color=", "--colour=") => {
-//!                     res.colors = args.cur_val('=')?;
-//!                 }
-//!                 // it seems that this is flag, but it is not recognized
 //!                 a if a.starts_with('-') => {
-//!                     Err(args.err_unknown_argument())?
-//!                 },
+//!                     // if the argument is either `--color` or `--colour`,
+//!                     // parse its value. The value may be given as
+//!                     // `--color=always` or `--color always`.
+//!                     if let Some(c) =
+//!                         args.opt_value_arg(&["--color", "--colour"])?
+//!                     {
+//!                         res.colors = c;
+//!                     } else {
+//!                         // it seems that this is flag, but it is not
+//!                         // recognized
+//!                         Err(args.err_unknown_argument())?
+//!                     }
+//!                 }
 //!                 // if the argument is unknown, just set it as name
 //!                 _ => res.name = arg.to_string(),
 //!             }
@@ -191,4 +196,714 @@ mod tests {
 
         assert_eq!(ip, (156, 189, 254, 5));
     }
+
+    #[test]
+    fn parsef_paths() -> Result<()> {
+        use pareg_proc::parsef;
+
+        #[derive(Default)]
+        struct Net {
+            adr: (u8, u8, u8, u8),
+            mask: u8,
+        }
+
+        #[derive(Default)]
+        struct Res {
+            net: Net,
+            ports: (u16, u16),
+        }
+
+        let mut res = Res::default();
+        parsef!(
+            &mut "10.0.12.1/24:80-8080".into(),
+            "{res.net.adr.0}.{res.net.adr.1}.{res.net.adr.2}.{res.net.adr.3}\
+            /{res.net.mask}:{res.ports.0}-{res.ports.1}"
+        )?;
+        assert_eq!(res.net.adr, (10, 0, 12, 1));
+        assert_eq!(res.net.mask, 24);
+        assert_eq!(res.ports, (80, 8080));
+
+        let mut masks = [0_u8; 3];
+        parsef!(&mut "/16".into(), "/{masks[1]}")?;
+        assert_eq!(masks, [0, 16, 0]);
+
+        let mut flags = (false, 0_u8);
+        parsef!(&mut "yes 5".into(), "{flags.0} {flags.1:x}")?;
+        assert_eq!(flags, (true, 5));
+        assert!(parsef!(&mut "yes 5".into(), "{flags.0:strict} {}", &mut 0)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsef_width() -> Result<()> {
+        use pareg_proc::parsef;
+
+        let (mut a, mut b) = (0_u32, 0_u32);
+        parsef!(&mut "0420-0069".into(), "{:4}-{:4}", &mut a, &mut b)?;
+        assert_eq!((a, b), (420, 69));
+        assert!(parsef!(&mut "420-0069".into(), "{a:4}-{b:4}").is_err());
+
+        let mut rgb = (0_u8, 0_u8, 0_u8);
+        parsef!(&mut "ff8010".into(), "{rgb.0:2x}{rgb.1:2x}{rgb.2:2x}")?;
+        assert_eq!(rgb, (255, 128, 16));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsef_exact_width() -> Result<()> {
+        use pareg_proc::parsef;
+
+        let (mut y, mut m, mut d) = (0_u32, 0_u32, 0_u32);
+        parsef!(&mut "20240115".into(), "{y:4}{m:2}{d:2}")?;
+        assert_eq!((y, m, d), (2024, 1, 15));
+
+        let span = |s: &str| {
+            let (mut y, mut m, mut d) = (0_u32, 0_u32, 0_u32);
+            let err = parsef!(&mut s.into(), "{y:4}{m:2}{d:2}").unwrap_err();
+            (err.span().unwrap(), err.message().unwrap().to_string())
+        };
+        let short = |n| format!("Expected {n} characters.");
+        // Short fields.
+        assert_eq!(span("20241x15"), (4..6, short(2)));
+        assert_eq!(span("2024011"), (6..8, short(2)));
+        assert_eq!(span("202401"), (6..8, short(2)));
+        assert_eq!(span("24-01-15"), (0..4, short(4)));
+        // The next field starts after the width even if it is longer.
+        assert_eq!(span("202401150").0, 8..9);
+        parsef!(&mut "2024123".into(), "{y:4}{m:2}{d:..2}")?;
+        assert_eq!((y, m, d), (2024, 12, 3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsef_quoted() -> Result<()> {
+        use pareg_core::{MaybeQuoted, QuotedString};
+        use pareg_proc::parsef;
+
+        let mut q = QuotedString::default();
+        parsef!(&mut r#"name="a \"b\"""#.into(), "name={}", &mut q)?;
+        assert_eq!(q.0, r#"a "b""#);
+        assert!(parsef!(&mut "name='a".into(), "name={}", &mut q).is_err());
+
+        let (mut a, mut b) = (MaybeQuoted::default(), MaybeQuoted::default());
+        parsef!(&mut "'x, y',z".into(), "{a:,},{b:,}")?;
+        assert_eq!((a.0.as_str(), b.0.as_str()), ("x, y", "z"));
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn from_read_bool() {
+        use pareg_core::FromRead;
+
+        for (s, v) in [
+            ("true", true),
+            ("False", false),
+            ("YES", true),
+            ("no", false),
+            ("On", true),
+            ("off", false),
+            ("1", true),
+            ("0", false),
+        ] {
+            let res = bool::from_read(&mut s.into());
+            assert!(res.err.is_none(), "{s}");
+            assert_eq!(res.res, Some(v), "{s}");
+        }
+
+        let res = bool::from_read(&mut "tr".into());
+        assert!(res.res.is_none());
+        let msg = res.err.unwrap().to_string();
+        assert!(msg.contains("Unexpected end of string."), "{msg}");
+        assert!(msg.contains("Expected `u`."), "{msg}");
+
+        let res = bool::from_read(&mut "o".into());
+        let msg = res.err.unwrap().to_string();
+        assert!(msg.contains("Expected `n` or `f`."), "{msg}");
+
+        assert!(bool::from_read(&mut "maybe".into()).res.is_none());
+    }
+
+    #[test]
+    fn parsef_bool() -> Result<()> {
+        use pareg_proc::{parsef, parsef_part};
+
+        let mut b = false;
+        parsef!(&mut "Yes".into(), "{}", &mut b)?;
+        assert!(b);
+        parsef!(&mut "b=off".into(), "b={b}")?;
+        assert!(!b);
+
+        parsef!(&mut "true".into(), "{:strict}", &mut b)?;
+        assert!(b);
+        parsef!(&mut "false".into(), "{b:strict}")?;
+        assert!(!b);
+        assert!(parsef!(&mut "yes".into(), "{b:strict}").is_err());
+        assert!(parsef!(&mut "True".into(), "{b:strict}").is_err());
+        parsef!(&mut "True".into(), "{b:istrict}")?;
+        assert!(b);
+
+        let (mut x, mut y) = (0_i32, 0_i32);
+        parsef!(&mut "+4,-2".into(), "{x:+d},{y:+}")?;
+        assert_eq!((x, y), (4, -2));
+        assert!(parsef!(&mut "4,2".into(), "{x:+d},{y}").is_err());
+        assert!(parsef!(&mut "-ff".into(), "{x:-x}").is_err());
+
+        let soft = parsef_part!(&mut "trueX".into(), "{}", &mut b)?;
+        assert!(b);
+        assert!(soft.is_some());
+        assert!(parsef!(&mut "trueX".into(), "{}", &mut b).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsef_skip() -> Result<()> {
+        use crate::{skip, ArgError};
+        use pareg_proc::parsef;
+
+        let (mut a, mut b) = (0_u32, 0_u32);
+        parsef!(&mut "5  ,\t6".into(), "{}{_:ws},{_:ws}{}", &mut a, &mut b)?;
+        assert_eq!((a, b), (5, 6));
+
+        parsef!(&mut " \n 7  ".into(), "{_:ws}{a}{_:ws}")?;
+        assert_eq!(a, 7);
+        parsef!(&mut "8".into(), "{_:ws}{a}{_:ws}")?;
+        assert_eq!(a, 8);
+
+        parsef!(&mut "name=10".into(), "{_}={a}")?;
+        assert_eq!(a, 10);
+        parsef!(&mut "ab{c}11".into(), "{_}{{{_}}}{a}")?;
+        assert_eq!(a, 11);
+        parsef!(&mut "abc12".into(), "{_:3}{a}")?;
+        assert_eq!(a, 12);
+        parsef!(&mut "x:y:13".into(), "{_:until=:}:{_:until=:}:{a}")?;
+        assert_eq!(a, 13);
+        parsef!(&mut "14 ".into(), "{a}{}", &mut skip::Ws)?;
+        assert_eq!(a, 14);
+
+        let err = parsef!(&mut "ab".into(), "{_:5}{a}").unwrap_err();
+        assert!(matches!(err, ArgError::FailedToParse(_)));
+
+        let err = parsef!(&mut "  \tx".into(), "{_:ws}{a}").unwrap_err();
+        let ArgError::FailedToParse(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 3..4);
+
+        let err = parsef!(&mut "skip=x".into(), "{_}={a}").unwrap_err();
+        let ArgError::FailedToParse(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 5..6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsef_flex() -> Result<()> {
+        use crate::{parsef, ArgError, ParseFArg};
+        use pareg_proc::parsef;
+
+        let (mut w, mut h) = (0_u32, 0_u32);
+        for res in ["1024x768", "1024 x 768", "1024  X\t768", "1024x 768"] {
+            parsef!(&mut res.into(), "{w}{~x}{h}")?;
+            assert_eq!((w, h), (1024, 768));
+        }
+        parsef!(&mut "1024 x 768".into(), "{w}{~ x }{h}")?;
+        assert_eq!((w, h), (1024, 768));
+
+        parsef!(&mut "12 px".into(), "{w}{~PX}")?;
+        assert_eq!(w, 12);
+        parsef!(&mut "13Px".into(), "{w}{~ px}")?;
+        assert_eq!(w, 13);
+        assert!(parsef!(&mut "14 p x".into(), "{w}{~px}").is_err());
+
+        let mut parse = |s: &str, case_insensitive, flex_ws| {
+            parsef(
+                &mut s.into(),
+                [
+                    ParseFArg::Arg(&mut w),
+                    ParseFArg::StrFlex {
+                        text: "x".into(),
+                        case_insensitive,
+                        flex_ws,
+                    },
+                    ParseFArg::Arg(&mut h),
+                ],
+            )
+        };
+        assert!(parse("1X2", true, false).is_ok());
+        assert!(parse("1 X 2", true, false).is_err());
+        assert!(parse("1 x 2", false, true).is_ok());
+        let err = parse("1 X 2", false, true).unwrap_err();
+        let ArgError::FailedToParse(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(ctx.error_span, 2..3);
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn type_hint() {
+        use std::borrow::Cow;
+
+        use pareg_core::{key_val_arg, ArgError};
+
+        #[derive(Debug)]
+        struct When;
+
+        impl FromArg<'_> for When {
+            fn from_arg(arg: &str) -> Result<Self> {
+                u32::from_arg(arg).map(|_| Self)
+            }
+
+            fn hint() -> Option<Cow<'static, str>> {
+                Some("See `--help` for the format of `--when`.".into())
+            }
+        }
+
+        let hint = |e: ArgError| {
+            let msg = e.no_color().to_string();
+            assert_eq!(msg.matches("hint:").count(), 1);
+            msg.split_once("hint: ").unwrap().1.trim_end().to_string()
+        };
+
+        // Nested failure gets the hint of the value type once.
+        let err = key_val_arg::<&str, When>("when=x", '=').unwrap_err();
+        assert_eq!(hint(err), "See `--help` for the format of `--when`.");
+        let err = key_val_arg::<&str, ColorMode>("c=x", '=').unwrap_err();
+        assert_eq!(hint(err), "Valid options are: `always`, `never`, `auto`.");
+
+        let mut args = Pareg::new(vec!["x".into(), "--when=y".into()]);
+        assert!(hint(args.next_arg::<When>().unwrap_err()).contains("when"));
+        let err = args.next_val::<Option<When>>('=').unwrap_err();
+        assert!(hint(err).contains("when"));
+
+        // Hint from the error is not replaced.
+        let err = Pareg::new(vec!["x".into()])
+            .next_arg::<Option<ColorMode>>()
+            .unwrap_err();
+        assert!(hint(err).starts_with("Valid options"));
+        assert!(When::from_arg("x").is_err());
+    }
+
+    #[test]
+    fn error_accessors() {
+        use crate::{ArgErrKind, ArgError};
+
+        #[derive(Debug, PartialEq)]
+        enum MyError {
+            MissingValue { arg: String, hint: Option<String> },
+            Other(String),
+        }
+
+        impl From<ArgError> for MyError {
+            fn from(e: ArgError) -> Self {
+                if !e.is_no_value() {
+                    return MyError::Other(e.to_string());
+                }
+                MyError::MissingValue {
+                    arg: e.arg().unwrap_or_default().to_string(),
+                    hint: e.hint_str().map(|h| h.to_string()),
+                }
+            }
+        }
+
+        let mut args = Pareg::new(vec!["--count".into(), "--seed=x".into()]);
+        let err = args.next_val::<u32>('=').unwrap_err();
+        assert_eq!(err.kind(), ArgErrKind::NoValue);
+        assert_eq!((err.error_idx(), err.span()), (Some(0), Some(0..7)));
+        assert_eq!(err.args(), ["--count", "--seed=x"]);
+        let MyError::MissingValue { arg, hint } = err.into() else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!(arg, "--count");
+        assert!(hint.is_some());
+
+        let err = args.next_val::<u32>('=').unwrap_err();
+        assert!(err.is_failed_to_parse() && !err.is_no_value());
+        assert_eq!(err.arg(), Some("--seed=x"));
+        assert_eq!(err.long_message(), None);
+        assert!(err.message().is_some());
+        let ctx = err.into_ctx().unwrap();
+        assert_eq!(ctx.error_span, 7..8);
+
+        let err = ArgError::no_last_argument();
+        assert_eq!((err.arg(), err.span(), err.args()), (None, None, &[][..]));
+        assert!(matches!(MyError::from(err), MyError::Other(_)));
+    }
+
+    #[test]
+    fn from_arg_other() {
+        #[derive(FromArg, PartialEq, Debug)]
+        enum Target {
+            All,
+            #[arg("nothing")]
+            None,
+            #[arg(other)]
+            Named(String),
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        enum Level {
+            Max,
+            #[arg(other)]
+            Num(u8),
+        }
+
+        assert_eq!(Target::from_arg(" ALL\t").unwrap(), Target::All);
+        assert_eq!(Target::from_arg("nothing").unwrap(), Target::None);
+        assert_eq!(
+            Target::from_arg("  My Name ").unwrap(),
+            Target::Named("My Name".into())
+        );
+        assert_eq!(Target::from_arg("").unwrap(), Target::Named("".into()));
+        assert_eq!(Target::arg_variants(), ["all", "none"]);
+        assert_eq!(
+            Target::hint().as_deref(),
+            Some("Valid options are: `all`, `none` or any custom name.")
+        );
+
+        assert_eq!(Level::from_arg(" 5 ").unwrap(), Level::Num(5));
+        assert_eq!(Level::from_arg("MAX").unwrap(), Level::Max);
+        let err = Level::from_arg("  5x").unwrap_err();
+        let crate::ArgError::FailedToParse(ctx) = err else {
+            panic!("Invalid error kind.");
+        };
+        assert_eq!((ctx.args[0].as_ref(), ctx.error_span), ("  5x", 2..4));
+    }
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn from_arg_numeric() {
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(numeric)]
+        enum LogLevel {
+            Error = 1,
+            #[arg("warning")]
+            Warn = 2,
+            Info = 3,
+            Trace = -1,
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(numeric)]
+        #[repr(u8)]
+        enum Jobs {
+            Auto = 0,
+            #[arg(other)]
+            Count(usize),
+        }
+
+        assert_eq!(LogLevel::from_arg("1").unwrap(), LogLevel::Error);
+        assert_eq!(LogLevel::from_arg(" 2 ").unwrap(), LogLevel::Warn);
+        assert_eq!(LogLevel::from_arg("+3").unwrap(), LogLevel::Info);
+        assert_eq!(LogLevel::from_arg("-1").unwrap(), LogLevel::Trace);
+        assert_eq!(LogLevel::from_arg("WARN").unwrap(), LogLevel::Warn);
+        assert_eq!(LogLevel::from_arg("warning").unwrap(), LogLevel::Warn);
+        assert_eq!(
+            LogLevel::arg_variants(),
+            ["error", "warn", "info", "trace"]
+        );
+
+        let err = LogLevel::from_arg("4").unwrap_err();
+        assert!(err.is_failed_to_parse());
+        assert_eq!(
+            err.no_color().anounce(true).to_string(),
+            "argument error: No option has the number `4`.
+--> arg1:0..1
+ |
+ $ 4
+ | ^ Unknown number.
+hint: Valid options are: `error` (1), `warn` (2), `info` (3), `trace` \
+(-1).
+"
+        );
+        let err = LogLevel::from_arg("debug").unwrap_err();
+        assert_eq!(err.long_message(), Some("Unknown option `debug`."));
+
+        assert_eq!(Jobs::from_arg("0").unwrap(), Jobs::Auto);
+        assert_eq!(Jobs::from_arg("auto").unwrap(), Jobs::Auto);
+        assert_eq!(Jobs::from_arg("4").unwrap(), Jobs::Count(4));
+        assert_eq!(
+            Jobs::hint().as_deref(),
+            Some("Valid options are: `auto` (0) or any custom name.")
+        );
+    }
+
+    #[cfg(not(feature = "minimal-errors"))]
+    #[test]
+    fn from_arg_suggestions() {
+        #[derive(FromArg, PartialEq, Debug)]
+        enum Color {
+            Auto,
+            Always,
+            Never,
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(fuzzy)]
+        enum Mode {
+            Fast,
+            Small,
+        }
+
+        let err = Color::from_arg("alwys").unwrap_err();
+        assert_eq!(err.suggestions(), ["auto", "always", "never"]);
+        assert_eq!(err.replacement_args(), None);
+        assert_eq!(
+            err.no_color().anounce(true).to_string(),
+            "argument error: Unknown option `alwys`.
+--> arg1:0..5
+ |
+ $ alwys
+ | ^^^^^ Unknown option.
+hint: Valid options are: `auto`, `always`, `never`.
+"
+        );
+
+        let args = ["prog", "--mode", "smol!"];
+        let mut args =
+            Pareg::new(args.iter().map(|a| a.to_string()).collect());
+        args.next();
+        args.next();
+        let err = args.next_arg::<Mode>().unwrap_err();
+        assert!(err.is_failed_to_parse());
+        assert_eq!(err.suggestions(), ["small"]);
+        assert_eq!(
+            err.replacement_args().unwrap(),
+            ["prog", "--mode", "small"]
+        );
+
+        let args = ["prog", "--mode= fsat!! "];
+        let mut args =
+            Pareg::new(args.iter().map(|a| a.to_string()).collect());
+        args.next();
+        args.next();
+        args.normalize(pareg::Normalize {
+            trim: true,
+            empty_is_missing: false,
+        });
+        let err = args.cur_val::<Mode>('=').unwrap_err();
+        assert_eq!(err.suggestions(), ["fast"]);
+        assert_eq!(err.replacement_args().unwrap(), ["prog", "--mode= fast "]);
+
+        let err = Mode::from_arg("xyz").unwrap_err();
+        assert_eq!(err.suggestions(), ["fast", "small"]);
+    }
+
+    #[test]
+    fn from_arg_fuzzy() {
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(fuzzy(max_distance = 1))]
+        enum Mode {
+            Auto,
+            Always,
+            Aways,
+            #[arg("no")]
+            Never,
+            Nevre,
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(fuzzy)]
+        enum Target {
+            All,
+            #[arg(other)]
+            Named(String),
+        }
+
+        let hint = |s: &str| {
+            Mode::from_arg(s).unwrap_err().hint_str().map(str::to_owned)
+        };
+
+        assert_eq!(Mode::from_arg("alway").unwrap(), Mode::Always);
+        assert_eq!(Mode::from_arg("aways").unwrap(), Mode::Aways);
+        assert_eq!(Mode::from_arg(" ALWAYZ ").unwrap(), Mode::Always);
+        assert_eq!(Mode::from_arg("uato").unwrap(), Mode::Auto);
+        assert_eq!(Mode::from_arg("nevre").unwrap(), Mode::Nevre);
+        assert_eq!(Mode::from_arg("never").unwrap(), Mode::Never);
+        assert_eq!(Mode::from_arg("n").unwrap(), Mode::Never);
+
+        assert_eq!(
+            hint("awa").as_deref(),
+            Some(
+                "Did you mean `aways`? Valid options are: `auto`, `always`, \
+                `aways`, `never`, `nevre`."
+            )
+        );
+        assert!(hint("nevr")
+            .unwrap()
+            .starts_with("Did you mean `never` or `nevre`? Valid"));
+        assert!(hint("alwaysss")
+            .unwrap()
+            .starts_with("Did you mean `always`?"));
+        assert!(hint("xyz").unwrap().starts_with("Valid options are:"));
+
+        assert_eq!(Target::from_arg("al").unwrap(), Target::All);
+        assert_eq!(Target::from_arg("ALL").unwrap(), Target::All);
+        assert_eq!(Target::from_arg("alls").unwrap(), Target::All);
+        assert_eq!(
+            Target::from_arg("alles").unwrap(),
+            Target::Named("alles".into())
+        );
+    }
+
+    #[test]
+    fn from_arg_only_other() {
+        #[derive(FromArg, PartialEq, Debug)]
+        enum Count {
+            #[arg(other)]
+            Exact(u32),
+        }
+
+        assert_eq!(Count::hint(), None);
+        assert!(Count::arg_variants().is_empty());
+        assert_eq!(Count::from_arg(" 5 ").unwrap(), Count::Exact(5));
+        let err = Count::from_arg(" x").unwrap_err();
+        assert!(!err.hint_str().unwrap_or("").contains("Valid options"));
+    }
+
+    #[test]
+    fn from_arg_prefix_and_punct() {
+        #[derive(FromArg, PartialEq, Debug)]
+        enum Policy {
+            Auto,
+            AutoUpdate,
+            Manual,
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(flexible_punct)]
+        enum Mode {
+            Auto,
+            AutoUpdate,
+            #[arg("dry_run")]
+            DryRun,
+            #[arg("a-u")]
+            Au,
+        }
+
+        let err = Policy::from_arg("auto-update").unwrap_err();
+        assert_eq!(
+            err.hint_str(),
+            Some(
+                "Did you mean `autoupdate`? Valid options are: `auto`, \
+                `autoupdate`, `manual`."
+            )
+        );
+        assert_eq!(err.suggestions(), ["autoupdate"]);
+        let err = Policy::from_arg("AU").unwrap_err();
+        assert!(err
+            .hint_str()
+            .unwrap()
+            .starts_with("Did you mean `auto` or `autoupdate`?"));
+        assert_eq!(err.suggestions(), ["auto", "autoupdate"]);
+        let err = Policy::from_arg("x").unwrap_err();
+        assert!(err.hint_str().unwrap().starts_with("Valid options are:"));
+        assert_eq!(err.suggestions(), ["auto", "autoupdate", "manual"]);
+        assert_eq!(Policy::from_arg("auto").unwrap(), Policy::Auto);
+
+        assert_eq!(Mode::from_arg("auto-update").unwrap(), Mode::AutoUpdate);
+        assert_eq!(Mode::from_arg(" Auto_Update ").unwrap(), Mode::AutoUpdate);
+        assert_eq!(Mode::from_arg("dry-run").unwrap(), Mode::DryRun);
+        assert_eq!(Mode::from_arg("dryrun").unwrap(), Mode::DryRun);
+        assert_eq!(Mode::from_arg("au").unwrap(), Mode::Au);
+        assert_eq!(Mode::from_arg("a-u").unwrap(), Mode::Au);
+        assert_eq!(Mode::from_arg("a_u").unwrap(), Mode::Au);
+        assert_eq!(Mode::from_arg("auto").unwrap(), Mode::Auto);
+        let err = Mode::from_arg("auto-up").unwrap_err();
+        assert_eq!(err.suggestions(), ["autoupdate"]);
+    }
+
+    #[test]
+    fn from_arg_set() {
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(set)]
+        enum Feature {
+            Audio,
+            Video,
+            #[arg("subtitles")]
+            Subs,
+        }
+
+        #[derive(FromArg, PartialEq, Debug)]
+        #[arg(set(all = "all", none = "None"))]
+        enum Level {
+            Low,
+            High,
+        }
+
+        let set = FeatureSet::from_arg("audio+video").unwrap();
+        assert!(set.contains(Feature::Audio));
+        assert!(set.contains(Feature::Video));
+        assert!(!set.contains(Feature::Subs));
+        assert_eq!((set.len(), set.bits()), (2, 0b011));
+        let set = FeatureSet::from_arg("Subtitles, audio").unwrap();
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [Feature::Audio, Feature::Subs]
+        );
+        assert_eq!(FeatureSet::from_arg("video").unwrap().len(), 1);
+
+        let mut set = FeatureSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(Feature::Subs));
+        assert!(!set.insert(Feature::Subs));
+        assert!(set.remove(Feature::Subs));
+        assert!(!set.remove(Feature::Subs));
+        assert_eq!(FeatureSet::all().len(), 3);
+
+        let err = FeatureSet::from_arg("audio+video,audio").unwrap_err();
+        assert!(err.is_invalid_value());
+        assert_eq!(err.span(), Some(12..17));
+        assert_eq!(err.arg(), Some("audio+video,audio"));
+        assert_eq!(
+            err.long_message(),
+            Some("The value `audio` is given more than once.")
+        );
+        let err = FeatureSet::from_arg("video+ Video ").unwrap_err();
+        assert_eq!(err.span(), Some(7..12));
+
+        let err = FeatureSet::from_arg("audio+vid+subs").unwrap_err();
+        assert!(err.is_failed_to_parse());
+        assert_eq!(err.span(), Some(6..9));
+        assert_eq!(err.arg(), Some("audio+vid+subs"));
+        assert_eq!(
+            err.hint_str(),
+            Some(
+                "Combine the values with `+` or `,`. Did you mean `video`? \
+                Valid options are: `audio`, `video`, `subs`."
+            )
+        );
+        assert_eq!(err.suggestions(), ["video"]);
+        assert_eq!(
+            FeatureSet::hint().as_deref(),
+            Some(
+                "Combine the values with `+` or `,`. Valid options are: \
+                `audio`, `video`, `subs`."
+            )
+        );
+        assert_eq!(
+            FeatureSet::from_arg("audio+").unwrap_err().span(),
+            Some(6..6)
+        );
+        assert!(FeatureSet::from_arg("all").is_err());
+
+        assert_eq!(LevelSet::from_arg("ALL").unwrap(), LevelSet::all());
+        assert_eq!(LevelSet::from_arg(" none").unwrap(), LevelSet::new());
+        assert_eq!(LevelSet::from_arg("high").unwrap().bits(), 0b10);
+        let err = LevelSet::from_arg("low+all").unwrap_err();
+        assert_eq!(err.span(), Some(4..7));
+        assert!(err.hint_str().unwrap().contains(
+            "Use `all` for all the values. Use `none` for no values."
+        ));
+    }
 }
@@ -0,0 +1,71 @@
+//! Checks the exit codes and the output of `main` that returns
+//! [`MainResult`]. The test runs itself as subprocess with the case in the
+//! environment variable, so it has its own `main` (`harness = false`).
+
+use std::{
+    env,
+    process::{Command, Output},
+};
+
+use pareg::{ArgErrCtx, ArgError, Exit, MainResult, Special};
+
+const CASE: &str = "PAREG_EXIT_CASE";
+
+fn main() -> MainResult {
+    match env::var(CASE) {
+        Ok(case) => child(&case),
+        Err(_) => {
+            parent();
+            Ok::<_, Exit>(()).into()
+        }
+    }
+}
+
+/// Returns the result of the case like `main` of real program.
+fn child(case: &str) -> MainResult {
+    match case {
+        "ok" => Ok::<_, ArgError>(()).into(),
+        "arg" => ArgError::InvalidValue(Box::new(
+            ArgErrCtx::from_msg("Invalid count.", "x".into())
+                .main_msg("Count must be number."),
+        ))
+        .into(),
+        "special" => ArgError::Special(Special::Help).into(),
+        "other" => Exit::other(7, "Failed to read file.").into(),
+        "silent" => Exit::other(4, "").into(),
+        _ => panic!("Unknown case `{case}`."),
+    }
+}
+
+fn run(case: &str) -> Output {
+    Command::new(env::current_exe().unwrap())
+        .env(CASE, case)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+fn parent() {
+    let check = |case: &str, code: i32, stderr: &str| {
+        let out = run(case);
+        let err = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(out.status.code(), Some(code), "{case}: {err}");
+        assert!(err.contains(stderr), "{case}: {err}");
+        assert!(!err.contains('\x1b'), "{case}: {err}");
+        if stderr.is_empty() {
+            assert!(err.is_empty(), "{case}: {err}");
+        } else {
+            // Minimal errors end with the position of the argument.
+            let end = cfg!(feature = "minimal-errors") && err.ends_with(")\n");
+            assert!(err.ends_with(".\n") || end);
+            assert!(!err.ends_with("\n\n"));
+        }
+    };
+
+    check("ok", 0, "");
+    check("arg", 2, "Count must be number.");
+    check("special", 0, "");
+    check("other", 7, "Failed to read file.");
+    check("silent", 4, "");
+    println!("exit codes: ok");
+}
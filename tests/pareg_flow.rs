@@ -0,0 +1,443 @@
+//! Command line interfaces in the style of real programs. Each scenario
+//! checks the successful parses and the exact rendering of the errors for
+//! malformed invocations.
+//!
+//! The errors are compared with the full renderer, so the scenarios don't
+//! run with the feature `minimal-errors`.
+#![cfg(not(feature = "minimal-errors"))]
+
+use std::collections::HashMap;
+
+use pareg::{assert_parse_err, DupPolicy, FromArg, Pareg, Result};
+
+// git-like subcommands
+
+#[derive(FromArg, Debug, PartialEq)]
+enum Cmd {
+    Clone,
+    Commit,
+    Log,
+}
+
+#[derive(Debug, PartialEq)]
+enum Git {
+    Clone { url: String, depth: Option<usize> },
+    Commit { message: String, amend: bool },
+    Log { count: usize, oneline: bool },
+}
+
+fn git(cmdline: &str) -> Result<Git> {
+    let mut args = Pareg::from_cmdline(cmdline);
+    args.next();
+    let cmd: Cmd = args.next_arg()?;
+    match cmd {
+        Cmd::Clone => {
+            let mut url = None;
+            let mut depth = None;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--depth" => depth = Some(args.next_arg()?),
+                    a if a.starts_with('-') => {
+                        return Err(args.err_unknown_argument())
+                    }
+                    _ if url.is_none() => url = Some(args.cur_arg()?),
+                    _ => return Err(args.err_unknown_argument()),
+                }
+            }
+            let url = url.ok_or_else(|| args.err_no_more_arguments())?;
+            Ok(Git::Clone { url, depth })
+        }
+        Cmd::Commit => {
+            let mut message = None;
+            let mut amend = false;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "-m" | "--message" => message = Some(args.next_arg()?),
+                    "--amend" => amend = true,
+                    _ => return Err(args.err_unknown_argument()),
+                }
+            }
+            let message =
+                message.ok_or_else(|| args.err_no_more_arguments())?;
+            Ok(Git::Commit { message, amend })
+        }
+        Cmd::Log => {
+            let mut count = 10;
+            let mut oneline = false;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "-n" | "--max-count" => count = args.next_arg()?,
+                    "--oneline" => oneline = true,
+                    _ => return Err(args.err_unknown_argument()),
+                }
+            }
+            Ok(Git::Log { count, oneline })
+        }
+    }
+}
+
+#[test]
+fn git_like() {
+    assert_eq!(
+        git("git clone --depth 1 https://x.y/r").unwrap(),
+        Git::Clone {
+            url: "https://x.y/r".into(),
+            depth: Some(1)
+        }
+    );
+    assert_eq!(
+        git("git commit --amend -m 'fix it'").unwrap(),
+        Git::Commit {
+            message: "fix it".into(),
+            amend: true
+        }
+    );
+    assert_eq!(
+        git("git log --oneline").unwrap(),
+        Git::Log {
+            count: 10,
+            oneline: true
+        }
+    );
+
+    assert_parse_err!(
+        git("git push"),
+        "
+argument error: Unknown option `push`.
+--> arg1:0..4
+ |
+ $ git push
+ |     ^^^^ Unknown option.
+hint: Valid options are: `clone`, `commit`, `log`.
+"
+    );
+    assert_parse_err!(
+        git("git"),
+        "
+argument error: Expected more arguments after `git`.
+--> arg0:3..3
+ |
+ $ git
+ |    ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        git("git clone --depth x url"),
+        "
+argument error: Invalid value for `--depth`: invalid digit found in string
+--> arg3:0..1
+ |
+ $ git clone --depth x url
+ |                   ^ invalid digit found in string
+"
+    );
+    assert_parse_err!(
+        git("git clone a b"),
+        "
+argument error: Unknown argument `b`.
+--> arg3:0..1
+ |
+ $ git clone a b
+ |             ^ Unknown argument.
+"
+    );
+    assert_parse_err!(
+        git("git commit -m"),
+        "
+argument error: Expected more arguments after the 2nd argument `-m`.
+--> arg2:2..2
+ |
+ $ git commit -m
+ |              ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        git("git log --online"),
+        "
+argument error: Unknown argument `--online`.
+--> arg2:0..8
+ |
+ $ git log --online
+ |         ^^^^^^^^ Unknown argument.
+"
+    );
+    assert_parse_err!(
+        git("git log -n -1"),
+        "
+argument error: Invalid value for `-n`: invalid digit found in string
+--> arg3:0..2
+ |
+ $ git log -n -1
+ |            ^^ invalid digit found in string
+"
+    );
+}
+
+// tar-like flag clusters
+
+#[derive(Debug, Default, PartialEq)]
+struct Tar {
+    create: bool,
+    extract: bool,
+    verbose: bool,
+    gzip: bool,
+    file: Option<String>,
+    members: Vec<String>,
+}
+
+fn tar(cmdline: &str) -> Result<Tar> {
+    let mut args = Pareg::from_cmdline(cmdline);
+    args.next();
+    let mut res = Tar::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--" => {
+                res.members.extend(args.take_rest());
+                break;
+            }
+            "-f" | "--file" => res.file = Some(args.next_arg()?),
+            a if a.starts_with("--") => {
+                return Err(args.err_unknown_argument())
+            }
+            a if a.starts_with('-') => {
+                let flags = a.to_string();
+                for (i, c) in flags.char_indices().skip(1) {
+                    match c {
+                        'c' => res.create = true,
+                        'x' => res.extract = true,
+                        'v' => res.verbose = true,
+                        'z' => res.gzip = true,
+                        'f' if i + 1 == flags.len() => {
+                            res.file = Some(args.next_arg()?)
+                        }
+                        _ => {
+                            return Err(args
+                                .err_invalid_span(i..i + c.len_utf8())
+                                .inline_msg("Unknown flag.")
+                                .main_msg(format!("Unknown flag `{c}`."))
+                                .hint(
+                                    "Valid flags are `c`, `x`, `v`, `z` \
+                                    and `f`.",
+                                ))
+                        }
+                    }
+                }
+            }
+            _ => res.members.push(args.cur_arg()?),
+        }
+    }
+    Ok(res)
+}
+
+#[test]
+fn tar_like() {
+    assert_eq!(
+        tar("tar -cvzf out.tgz a b").unwrap(),
+        Tar {
+            create: true,
+            verbose: true,
+            gzip: true,
+            file: Some("out.tgz".into()),
+            members: vec!["a".into(), "b".into()],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        tar("tar -x --file in.tar -- -weird").unwrap(),
+        Tar {
+            extract: true,
+            file: Some("in.tar".into()),
+            members: vec!["-weird".into()],
+            ..Default::default()
+        }
+    );
+
+    assert_parse_err!(
+        tar("tar -cvq a"),
+        "
+argument error: Unknown flag `q`.
+--> arg1:3..4
+ |
+ $ tar -cvq a
+ |        ^ Unknown flag.
+hint: Valid flags are `c`, `x`, `v`, `z` and `f`.
+"
+    );
+    assert_parse_err!(
+        tar("tar -cfv out a"),
+        "
+argument error: Unknown flag `f`.
+--> arg1:2..3
+ |
+ $ tar -cfv out a
+ |       ^ Unknown flag.
+hint: Valid flags are `c`, `x`, `v`, `z` and `f`.
+"
+    );
+    assert_parse_err!(
+        tar("tar -cf"),
+        "
+argument error: Expected more arguments after the 1st argument `-cf`.
+--> arg1:3..3
+ |
+ $ tar -cf
+ |        ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        tar("tar --create a"),
+        "
+argument error: Unknown argument `--create`.
+--> arg1:0..8
+ |
+ $ tar --create a
+ |     ^^^^^^^^ Unknown argument.
+"
+    );
+    assert_parse_err!(
+        tar("tar -c --file"),
+        "
+argument error: Expected more arguments after the 2nd argument `--file`.
+--> arg2:6..6
+ |
+ $ tar -c --file
+ |              ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        tar("tar -cvzž a"),
+        "
+argument error: Unknown flag `ž`.
+--> arg1:4..6
+ |
+ $ tar -cvzž a
+ |         ^ Unknown flag.
+hint: Valid flags are `c`, `x`, `v`, `z` and `f`.
+"
+    );
+}
+
+// kv-define heavy
+
+#[derive(Debug, Default, PartialEq)]
+struct Defines {
+    defs: HashMap<String, i64>,
+    opts: Vec<(String, String)>,
+    level: u8,
+}
+
+fn defines(cmdline: &str) -> Result<Defines> {
+    let mut args = Pareg::from_cmdline(cmdline);
+    args.next();
+    let mut res = Defines::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "-D" => {
+                args.next_key_val_insert('=', &mut res.defs, DupPolicy::Error)?
+            }
+            "-o" => res.opts.push(args.next_key_val('=')?),
+            a if a.starts_with("--opt-level") => {
+                res.level = args.cur_val_or_next('=')?;
+            }
+            _ => return Err(args.err_unknown_argument()),
+        }
+    }
+    args.require_exhausted()?;
+    Ok(res)
+}
+
+#[test]
+fn kv_define() {
+    assert_eq!(
+        defines("cc -D A=1 -o k=v -D B=-3 --opt-level 2").unwrap(),
+        Defines {
+            defs: HashMap::from([("A".into(), 1), ("B".into(), -3)]),
+            opts: vec![("k".into(), "v".into())],
+            level: 2,
+        }
+    );
+    assert_eq!(defines("cc --opt-level=3").unwrap().level, 3);
+    assert_parse_err!(
+        defines("cc -D A=1 -D A=2"),
+        "
+argument error: Key `A` was already given in the 2nd argument `A=1`.
+--> arg4:0..1
+ |
+ $ cc -D A=1 -D A=2
+ |              ^ Duplicate key.
+hint: Each key may be given only once.
+"
+    );
+    assert_parse_err!(
+        defines("cc -D A"),
+        "
+argument error: Missing separator `=` for key value pair.
+--> arg2:0..1
+ |
+ $ cc -D A
+ |       ^ Missing separator `=`.
+hint: Use the separator `=` to split the argument into key and value.
+"
+    );
+    assert_parse_err!(
+        defines("cc -D A=x"),
+        "
+argument error: invalid digit found in string
+--> arg2:2..3
+ |
+ $ cc -D A=x
+ |         ^ invalid digit found in string
+"
+    );
+    assert_parse_err!(
+        defines("cc -o k"),
+        "
+argument error: Missing separator `=` for key value pair.
+--> arg2:0..1
+ |
+ $ cc -o k
+ |       ^ Missing separator `=`.
+hint: Use the separator `=` to split the argument into key and value.
+"
+    );
+    assert_parse_err!(
+        defines("cc --opt-level=x"),
+        "
+argument error: Invalid value for `--opt-level`: invalid digit found in string
+--> arg1:12..13
+ |
+ $ cc --opt-level=x
+ |                ^ invalid digit found in string
+"
+    );
+    assert_parse_err!(
+        defines("cc -D"),
+        "
+argument error: Expected more arguments after the 1st argument `-D`.
+--> arg1:2..2
+ |
+ $ cc -D
+ |      ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        defines("cc -o"),
+        "
+argument error: Expected more arguments after the 1st argument `-o`.
+--> arg1:2..2
+ |
+ $ cc -o
+ |      ^ Expected more arguments.
+"
+    );
+    assert_parse_err!(
+        defines("cc --opt-level 2 x"),
+        "
+argument error: Unknown argument `x`.
+--> arg3:0..1
+ |
+ $ cc --opt-level 2 x
+ |                  ^ Unknown argument.
+"
+    );
+}